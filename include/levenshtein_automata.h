@@ -0,0 +1,45 @@
+/* C API of the levenshtein_automata crate, built with the `ffi` feature. */
+#ifndef LEVENSHTEIN_AUTOMATA_H
+#define LEVENSHTEIN_AUTOMATA_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct LevenshteinAutomatonBuilder LevenshteinAutomatonBuilder;
+typedef struct DFA DFA;
+
+/* If `is_exact` is false, `distance` is a lower bound of the distance. */
+typedef struct LevDistance {
+    bool is_exact;
+    uint8_t distance;
+} LevDistance;
+
+LevenshteinAutomatonBuilder *lev_builder_new(uint8_t max_distance, bool transposition_cost_one);
+void lev_builder_free(LevenshteinAutomatonBuilder *builder);
+
+/* Return NULL if the query is not valid utf-8, or if the automaton cannot be
+ * built, e.g. because it would exceed the maximum number of states. */
+DFA *lev_dfa_build(const LevenshteinAutomatonBuilder *builder, const uint8_t *query, size_t query_len);
+DFA *lev_dfa_build_prefix(const LevenshteinAutomatonBuilder *builder, const uint8_t *query, size_t query_len);
+void lev_dfa_free(DFA *dfa);
+
+LevDistance lev_dfa_eval(const DFA *dfa, const uint8_t *text, size_t text_len);
+
+uint32_t lev_dfa_initial_state(const DFA *dfa);
+/* An invalid `state` leads to the sink state 0 in `lev_dfa_transition`, to a
+ * distance of at least 255 (`is_exact` false, `distance` 255) in
+ * `lev_dfa_distance`, and to false in `lev_dfa_can_match`. */
+uint32_t lev_dfa_transition(const DFA *dfa, uint32_t state, uint8_t byte);
+LevDistance lev_dfa_distance(const DFA *dfa, uint32_t state);
+bool lev_dfa_can_match(const DFA *dfa, uint32_t state);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
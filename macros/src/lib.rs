@@ -0,0 +1,108 @@
+/*!
+
+Builds Levenshtein automata at compile time.
+
+The [levenshtein_dfa!] macro runs the
+[LevenshteinAutomatonBuilder](https://docs.rs/levenshtein_automata/latest/levenshtein_automata/struct.LevenshteinAutomatonBuilder.html)
+while compiling, and embeds the tables of the automaton as static data,
+so that no automaton is built at runtime.
+
+!*/
+
+use levenshtein_automata::{Distance, LevenshteinAutomatonBuilder};
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{parse_macro_input, LitBool, LitInt, LitStr, Token};
+
+// `"query", max_distance[, transposition_cost_one]`
+struct Input {
+    query: LitStr,
+    max_distance: LitInt,
+    transposition_cost_one: Option<LitBool>,
+}
+
+impl Parse for Input {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Input> {
+        let query = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let max_distance = input.parse()?;
+        let mut transposition_cost_one = None;
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            transposition_cost_one = Some(input.parse()?);
+            input.parse::<Option<Token![,]>>()?;
+        }
+        Ok(Input {
+            query,
+            max_distance,
+            transposition_cost_one,
+        })
+    }
+}
+
+/// Builds the automaton of a query at compile time, as a
+/// `levenshtein_automata::DfaRef<'static>` over static tables.
+///
+/// The arguments are the query, the maximum distance, and optionally whether
+/// transpositions cost `1`, which defaults to `true`.
+///
+/// The crate using the macro must also depend on `levenshtein_automata`.
+///
+/// ```rust
+/// use levenshtein_automata::{DfaRef, Distance};
+/// use levenshtein_automata_macros::levenshtein_dfa;
+///
+/// static KEYWORD: DfaRef<'static> = levenshtein_dfa!("select", 1);
+///
+/// assert_eq!(KEYWORD.eval("select"), Distance::Exact(0));
+/// assert_eq!(KEYWORD.eval("slect"), Distance::Exact(1));
+/// assert_eq!(KEYWORD.eval("delete"), Distance::AtLeast(2));
+///
+/// let no_transposition = levenshtein_dfa!("select", 1, false);
+/// assert_eq!(no_transposition.eval("slecet"), Distance::AtLeast(2));
+/// ```
+#[proc_macro]
+pub fn levenshtein_dfa(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as Input);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &Input) -> syn::Result<TokenStream2> {
+    let max_distance: u8 = input.max_distance.base10_parse()?;
+    let transposition_cost_one = input
+        .transposition_cost_one
+        .as_ref()
+        .map(|lit| lit.value)
+        .unwrap_or(true);
+    let dfa = LevenshteinAutomatonBuilder::new(max_distance, transposition_cost_one)
+        .try_build_dfa(&input.query.value())
+        .map_err(|err| syn::Error::new(input.query.span(), err))?;
+    let num_states = dfa.num_states();
+    let transitions = (0..num_states as u32)
+        .flat_map(|state_id| (0..=255u8).map(move |b| (state_id, b)))
+        .map(|(state_id, b)| dfa.transition(state_id, b));
+    let distances = (0..num_states as u32).map(|state_id| match dfa.distance(state_id) {
+        Distance::Exact(d) => quote!(::levenshtein_automata::Distance::Exact(#d)),
+        Distance::AtLeast(d) => quote!(::levenshtein_automata::Distance::AtLeast(#d)),
+    });
+    let num_transitions = num_states * 256;
+    let initial_state = dfa.initial_state();
+    Ok(quote! {
+        {
+            static TRANSITIONS: [u32; #num_transitions] = [#(#transitions),*];
+            static DISTANCES: [::levenshtein_automata::Distance; #num_states] = [#(#distances),*];
+            // The tables were built by the `LevenshteinAutomatonBuilder`.
+            unsafe {
+                ::levenshtein_automata::DfaRef::from_parts_unchecked(
+                    &TRANSITIONS,
+                    &DISTANCES,
+                    #initial_state,
+                )
+            }
+        }
+    })
+}
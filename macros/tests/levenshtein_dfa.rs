@@ -0,0 +1,19 @@
+use levenshtein_automata::{DfaRef, LevenshteinAutomatonBuilder};
+use levenshtein_automata_macros::levenshtein_dfa;
+
+static SUSHI: DfaRef<'static> = levenshtein_dfa!("寿司", 2, false);
+
+#[test]
+fn test_matches_runtime_dfa() {
+    let dfa = LevenshteinAutomatonBuilder::new(2, false).build_dfa("寿司");
+    assert_eq!(SUSHI.num_states(), dfa.num_states());
+    assert_eq!(SUSHI.initial_state(), dfa.initial_state());
+    for candidate in &["", "寿", "寿司", "司寿", "寿司屋", "sushi", "寿司寿司"] {
+        assert_eq!(SUSHI.eval(candidate), dfa.eval(candidate), "{}", candidate);
+    }
+    let dfa = LevenshteinAutomatonBuilder::new(1, true).build_dfa("");
+    let empty = levenshtein_dfa!("", 1,);
+    for candidate in &["", "a", "ab"] {
+        assert_eq!(empty.eval(candidate), dfa.eval(candidate), "{}", candidate);
+    }
+}
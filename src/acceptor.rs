@@ -0,0 +1,128 @@
+use super::{Distance, DFA};
+
+/// Automaton that only tells whether a string matches or not.
+///
+/// It is obtained by dropping the distances of a
+/// [DFA](./struct.DFA.html) with [DFA::into_acceptor](./struct.DFA.html#method.into_acceptor),
+/// and only keeps two bits per state: whether it is accepting, and whether
+/// an accepting state can be reached from it. State ids are left untouched.
+pub struct Acceptor {
+    // Flat transition table, as in `DFA`.
+    transitions: Vec<u32>,
+    accepting: Vec<u64>,
+    live: Vec<u64>,
+    initial_state: u32,
+}
+
+impl DFA {
+    /// Converts the automaton into an [Acceptor](./struct.Acceptor.html), for consumers
+    /// that do not need the distance.
+    pub fn into_acceptor(self) -> Acceptor {
+        let mut live = vec![0u64; self.num_states().div_ceil(64)];
+        for (state_id, &can_match) in self.live_states().iter().enumerate() {
+            if can_match {
+                live[state_id / 64] |= 1u64 << (state_id % 64);
+            }
+        }
+        let (transitions, distances, initial_state) = self.into_parts();
+        let mut accepting = vec![0u64; distances.len().div_ceil(64)];
+        for (state_id, distance) in distances.iter().enumerate() {
+            if let Distance::Exact(_) = distance {
+                accepting[state_id / 64] |= 1u64 << (state_id % 64);
+            }
+        }
+        Acceptor {
+            transitions,
+            accepting,
+            live,
+            initial_state,
+        }
+    }
+}
+
+impl Acceptor {
+    /// Returns the initial state
+    pub fn initial_state(&self) -> u32 {
+        self.initial_state
+    }
+
+    /// Returns the destination state reached after consuming a given byte.
+    pub fn transition(&self, from_state_id: u32, b: u8) -> u32 {
+        self.transitions[from_state_id as usize * 256 + b as usize]
+    }
+
+    /// Returns true iff the state is accepting.
+    pub fn is_accepting(&self, state_id: u32) -> bool {
+        let state_id = state_id as usize;
+        (self.accepting[state_id / 64] >> (state_id % 64)) & 1 == 1
+    }
+
+    /// Returns true iff an accepting state can be reached from the state.
+    pub fn can_match(&self, state_id: u32) -> bool {
+        let state_id = state_id as usize;
+        (self.live[state_id / 64] >> (state_id % 64)) & 1 == 1
+    }
+
+    /// Returns the number of states.
+    pub fn num_states(&self) -> usize {
+        self.transitions.len() / 256
+    }
+
+    /// Returns true iff `text` is accepted by the automaton.
+    ///
+    /// Returns early once no accepting state can be reached.
+    pub fn accepts<B: AsRef<[u8]>>(&self, text: B) -> bool {
+        let mut state = self.initial_state();
+        for &b in text.as_ref() {
+            state = self.transition(state, b);
+            if !self.can_match(state) {
+                return false;
+            }
+        }
+        self.is_accepting(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Distance, LevenshteinAutomatonBuilder, Utf8DFABuilder};
+
+    #[test]
+    fn test_acceptor() {
+        let builder = LevenshteinAutomatonBuilder::new(1, true);
+        let dfa = builder.build_dfa("abcdefghijklmnopqrstuvwxyz0123456789");
+        let texts = [
+            "abcdefghijklmnopqrstuvwxyz0123456789",
+            "abcdefghijklmnopqrstuvwxyz012345678",
+            "bacdefghijklmnopqrstuvwxyz0123456789",
+            "badcefghijklmnopqrstuvwxyz0123456789",
+            "",
+            "寿司",
+        ];
+        let expected: Vec<bool> = texts
+            .iter()
+            .map(|text| matches!(dfa.eval(text), Distance::Exact(_)))
+            .collect();
+        let num_states = dfa.num_states();
+        let acceptor = dfa.into_acceptor();
+        assert_eq!(acceptor.num_states(), num_states);
+        let accepted: Vec<bool> = texts.iter().map(|text| acceptor.accepts(text)).collect();
+        assert_eq!(accepted, expected);
+        assert_eq!(accepted, vec![true, true, true, false, false, false]);
+    }
+
+    #[test]
+    fn test_acceptor_through_accepting_state_zero() {
+        let mut dfa_builder = Utf8DFABuilder::with_max_num_states(2);
+        dfa_builder.add_state(0, Distance::Exact(1u8), 1);
+        dfa_builder.add_state(1, Distance::AtLeast(2u8), 0);
+        dfa_builder.set_initial_state(1u32);
+        let acceptor = dfa_builder.build().into_acceptor();
+        assert!(acceptor.can_match(0));
+        assert!(acceptor.accepts("a"));
+        assert!(acceptor.accepts("abc"));
+        assert!(acceptor.accepts("寿"));
+        assert!(!acceptor.accepts("ab"));
+        assert!(!acceptor.accepts(""));
+    }
+}
@@ -1,4 +1,5 @@
-use std::slice;
+use alloc::vec::Vec;
+use core::slice;
 
 #[derive(Clone)]
 pub struct FullCharacteristicVector(Vec<u32>);
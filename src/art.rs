@@ -0,0 +1,523 @@
+use super::{Distance, DFA};
+use std::mem;
+
+// Children of a node. As in the adaptive radix tree, the representation
+// grows and shrinks with the number of children.
+enum Children {
+    // Sorted keys, with at most 4 children.
+    Node4(Vec<u8>, Vec<Node>),
+    // Sorted keys, with at most 16 children.
+    Node16(Vec<u8>, Vec<Node>),
+    // `index[b]` is the slot of the child for `b`, plus one, or `0`.
+    Node48(Box<[u8; 256]>, Vec<Node>),
+    Node256(Vec<Option<Box<Node>>>),
+}
+
+impl Children {
+    fn len(&self) -> usize {
+        match self {
+            Children::Node4(keys, _) | Children::Node16(keys, _) => keys.len(),
+            Children::Node48(_, children) => children.len(),
+            Children::Node256(children) => children.iter().filter(|child| child.is_some()).count(),
+        }
+    }
+
+    fn get(&self, b: u8) -> Option<&Node> {
+        match self {
+            Children::Node4(keys, children) | Children::Node16(keys, children) => {
+                keys.binary_search(&b).ok().map(|slot| &children[slot])
+            }
+            Children::Node48(index, children) => match index[b as usize] {
+                0 => None,
+                slot => Some(&children[slot as usize - 1]),
+            },
+            Children::Node256(children) => children[b as usize].as_deref(),
+        }
+    }
+
+    fn get_mut(&mut self, b: u8) -> Option<&mut Node> {
+        match self {
+            Children::Node4(keys, children) | Children::Node16(keys, children) => {
+                match keys.binary_search(&b) {
+                    Ok(slot) => Some(&mut children[slot]),
+                    Err(_) => None,
+                }
+            }
+            Children::Node48(index, children) => match index[b as usize] {
+                0 => None,
+                slot => Some(&mut children[slot as usize - 1]),
+            },
+            Children::Node256(children) => children[b as usize].as_deref_mut(),
+        }
+    }
+
+    // Returns the children, ordered by byte.
+    fn iter(&self) -> Vec<(u8, &Node)> {
+        (0..=255u8)
+            .filter_map(|b| self.get(b).map(|child| (b, child)))
+            .collect()
+    }
+
+    // Inserts a child for a byte that does not have one yet.
+    fn insert(&mut self, b: u8, child: Node) {
+        self.grow();
+        match self {
+            Children::Node4(keys, children) | Children::Node16(keys, children) => {
+                let slot = keys.binary_search(&b).unwrap_err();
+                keys.insert(slot, b);
+                children.insert(slot, child);
+            }
+            Children::Node48(index, children) => {
+                children.push(child);
+                index[b as usize] = children.len() as u8;
+            }
+            Children::Node256(children) => children[b as usize] = Some(Box::new(child)),
+        }
+    }
+
+    fn remove(&mut self, b: u8) -> Option<Node> {
+        let removed = match self {
+            Children::Node4(keys, children) | Children::Node16(keys, children) => {
+                let slot = keys.binary_search(&b).ok()?;
+                keys.remove(slot);
+                Some(children.remove(slot))
+            }
+            Children::Node48(index, children) => {
+                let slot = match index[b as usize] {
+                    0 => return None,
+                    slot => slot as usize - 1,
+                };
+                index[b as usize] = 0;
+                let removed = children.swap_remove(slot);
+                if slot < children.len() {
+                    // The last child moved to the freed slot.
+                    let moved = index
+                        .iter()
+                        .position(|&i| i as usize == children.len() + 1)
+                        .unwrap();
+                    index[moved] = slot as u8 + 1;
+                }
+                Some(removed)
+            }
+            Children::Node256(children) => children[b as usize].take().map(|child| *child),
+        };
+        self.shrink();
+        removed
+    }
+
+    fn take_all(&mut self) -> Vec<(u8, Node)> {
+        match mem::replace(self, Children::Node4(Vec::new(), Vec::new())) {
+            Children::Node4(keys, children) | Children::Node16(keys, children) => {
+                keys.into_iter().zip(children).collect()
+            }
+            Children::Node48(index, mut children) => {
+                let mut slots: Vec<(u8, usize)> = (0..=255u8)
+                    .filter(|&b| index[b as usize] != 0)
+                    .map(|b| (b, index[b as usize] as usize - 1))
+                    .collect();
+                // Take the children from the last slot, so that slots stay valid.
+                slots.sort_by_key(|&(_, slot)| std::cmp::Reverse(slot));
+                let mut all: Vec<(u8, Node)> = slots
+                    .into_iter()
+                    .map(|(b, slot)| (b, children.swap_remove(slot)))
+                    .collect();
+                all.sort_by_key(|&(b, _)| b);
+                all
+            }
+            Children::Node256(children) => children
+                .into_iter()
+                .enumerate()
+                .filter_map(|(b, child)| child.map(|child| (b as u8, *child)))
+                .collect(),
+        }
+    }
+
+    fn rebuild(&mut self, all: Vec<(u8, Node)>, capacity: usize) {
+        *self = match capacity {
+            4 | 16 => {
+                let (keys, children) = all.into_iter().unzip();
+                if capacity == 4 {
+                    Children::Node4(keys, children)
+                } else {
+                    Children::Node16(keys, children)
+                }
+            }
+            48 => {
+                let mut index = Box::new([0u8; 256]);
+                let mut children = Vec::with_capacity(48);
+                for (b, child) in all {
+                    children.push(child);
+                    index[b as usize] = children.len() as u8;
+                }
+                Children::Node48(index, children)
+            }
+            _ => {
+                let mut children: Vec<Option<Box<Node>>> = (0..256).map(|_| None).collect();
+                for (b, child) in all {
+                    children[b as usize] = Some(Box::new(child));
+                }
+                Children::Node256(children)
+            }
+        };
+    }
+
+    // Switches to a larger representation if full.
+    fn grow(&mut self) {
+        let len = self.len();
+        let capacity = match self {
+            Children::Node4(..) if len == 4 => 16,
+            Children::Node16(..) if len == 16 => 48,
+            Children::Node48(..) if len == 48 => 256,
+            _ => return,
+        };
+        let all = self.take_all();
+        self.rebuild(all, capacity);
+    }
+
+    // Switches to a smaller representation if sparse enough. The thresholds
+    // are below the capacities, so that alternating inserts and removals do not
+    // switch representations back and forth.
+    fn shrink(&mut self) {
+        let len = self.len();
+        let capacity = match self {
+            Children::Node16(..) if len <= 3 => 4,
+            Children::Node48(..) if len <= 12 => 16,
+            Children::Node256(..) if len <= 40 => 48,
+            _ => return,
+        };
+        let all = self.take_all();
+        self.rebuild(all, capacity);
+    }
+}
+
+struct Node {
+    // Compressed path leading to the node from its parent's byte.
+    prefix: Vec<u8>,
+    is_word: bool,
+    children: Children,
+}
+
+impl Node {
+    fn new(prefix: &[u8], is_word: bool) -> Node {
+        Node {
+            prefix: prefix.to_vec(),
+            is_word,
+            children: Children::Node4(Vec::new(), Vec::new()),
+        }
+    }
+
+    fn insert(&mut self, key: &[u8]) -> bool {
+        let common_prefix_len = self
+            .prefix
+            .iter()
+            .zip(key)
+            .take_while(|(left, right)| left == right)
+            .count();
+        if common_prefix_len < self.prefix.len() {
+            // Split the compressed path.
+            let split_byte = self.prefix[common_prefix_len];
+            let mut split = mem::replace(self, Node::new(&key[..common_prefix_len], false));
+            split.prefix.drain(..=common_prefix_len);
+            self.children.insert(split_byte, split);
+            if common_prefix_len == key.len() {
+                self.is_word = true;
+            } else {
+                let leaf = Node::new(&key[common_prefix_len + 1..], true);
+                self.children.insert(key[common_prefix_len], leaf);
+            }
+            return true;
+        }
+        let rest = &key[common_prefix_len..];
+        let (&b, rest) = match rest.split_first() {
+            Some(split) => split,
+            None => return !mem::replace(&mut self.is_word, true),
+        };
+        match self.children.get_mut(b) {
+            Some(child) => child.insert(rest),
+            None => {
+                self.children.insert(b, Node::new(rest, true));
+                true
+            }
+        }
+    }
+
+    fn remove(&mut self, key: &[u8]) -> bool {
+        if !key.starts_with(&self.prefix) {
+            return false;
+        }
+        let rest = &key[self.prefix.len()..];
+        let (&b, rest) = match rest.split_first() {
+            Some(split) => split,
+            None => return mem::replace(&mut self.is_word, false),
+        };
+        let child = match self.children.get_mut(b) {
+            Some(child) => child,
+            None => return false,
+        };
+        if !child.remove(rest) {
+            return false;
+        }
+        if !child.is_word {
+            match child.children.len() {
+                0 => {
+                    self.children.remove(b);
+                }
+                1 => child.merge_single_child(),
+                _ => {}
+            }
+        }
+        true
+    }
+
+    // Merges a node that is not a word with its only child.
+    fn merge_single_child(&mut self) {
+        let (b, child) = self.children.take_all().pop().unwrap();
+        let Node {
+            prefix,
+            is_word,
+            children,
+        } = child;
+        self.prefix.push(b);
+        self.prefix.extend(prefix);
+        self.is_word = is_word;
+        self.children = children;
+    }
+
+    fn contains(&self, key: &[u8]) -> bool {
+        if !key.starts_with(&self.prefix) {
+            return false;
+        }
+        match key[self.prefix.len()..].split_first() {
+            None => self.is_word,
+            Some((&b, rest)) => self
+                .children
+                .get(b)
+                .map(|child| child.contains(rest))
+                .unwrap_or(false),
+        }
+    }
+
+    fn collect_words(&self, word: &mut Vec<u8>, words: &mut Vec<String>) {
+        let len = word.len();
+        word.extend_from_slice(&self.prefix);
+        if self.is_word {
+            words.push(String::from_utf8(word.clone()).unwrap());
+        }
+        for (b, child) in self.children.iter() {
+            word.push(b);
+            child.collect_words(word, words);
+            word.pop();
+        }
+        word.truncate(len);
+    }
+
+    fn search(
+        &self,
+        dfa: &DFA,
+        live_states: &[bool],
+        mut state: u32,
+        word: &mut Vec<u8>,
+        matches: &mut Vec<(String, u8)>,
+    ) {
+        for &b in &self.prefix {
+            state = dfa.transition(state, b);
+            if !live_states[state as usize] {
+                return;
+            }
+        }
+        let len = word.len();
+        word.extend_from_slice(&self.prefix);
+        if self.is_word {
+            if let Distance::Exact(d) = dfa.distance(state) {
+                matches.push((String::from_utf8(word.clone()).unwrap(), d));
+            }
+        }
+        for (b, child) in self.children.iter() {
+            let child_state = dfa.transition(state, b);
+            if live_states[child_state as usize] {
+                word.push(b);
+                child.search(dfa, live_states, child_state, word, matches);
+                word.pop();
+            }
+        }
+        word.truncate(len);
+    }
+}
+
+/// Mutable dictionary backed by an adaptive radix tree.
+///
+/// Unlike an `fst::Set`, words can be inserted and removed at any time,
+/// which suits vocabularies that change too often to rebuild an immutable
+/// structure. Paths are compressed, and nodes switch between
+/// representations for 4, 16, 48 and 256 children as they fill up.
+///
+/// Fuzzy lookups walk the tree and the Levenshtein DFA together, and prune
+/// the subtrees from which the DFA cannot match anymore.
+///
+/// ```rust
+/// # use levenshtein_automata::{ArtDictionary, LevenshteinAutomatonBuilder};
+/// let mut dictionary = ArtDictionary::new();
+/// for word in &["search", "seared", "sear", "research"] {
+///     dictionary.insert(word);
+/// }
+/// dictionary.remove("seared");
+/// let dfa = LevenshteinAutomatonBuilder::new(1, true).build_dfa("serach");
+/// assert_eq!(dictionary.search(&dfa), vec![("search".to_string(), 1)]);
+/// ```
+pub struct ArtDictionary {
+    root: Node,
+    len: usize,
+}
+
+impl Default for ArtDictionary {
+    fn default() -> ArtDictionary {
+        ArtDictionary::new()
+    }
+}
+
+impl ArtDictionary {
+    /// Creates an empty dictionary.
+    pub fn new() -> ArtDictionary {
+        ArtDictionary {
+            root: Node::new(&[], false),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of words.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the dictionary is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts a word. Returns false if it was already present.
+    pub fn insert(&mut self, word: &str) -> bool {
+        let inserted = self.root.insert(word.as_bytes());
+        if inserted {
+            self.len += 1;
+        }
+        inserted
+    }
+
+    /// Removes a word. Returns false if it was not present.
+    pub fn remove(&mut self, word: &str) -> bool {
+        let removed = self.root.remove(word.as_bytes());
+        if removed {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    /// Returns true if the dictionary contains `word`.
+    pub fn contains(&self, word: &str) -> bool {
+        self.root.contains(word.as_bytes())
+    }
+
+    /// Returns all of the words, in lexicographic order.
+    pub fn words(&self) -> Vec<String> {
+        let mut words = Vec::with_capacity(self.len);
+        self.root.collect_words(&mut Vec::new(), &mut words);
+        words
+    }
+
+    /// Returns the words accepted by `dfa` along with their distance,
+    /// in lexicographic order.
+    pub fn search(&self, dfa: &DFA) -> Vec<(String, u8)> {
+        let live_states = dfa.live_states();
+        let initial_state = dfa.initial_state();
+        let mut matches = Vec::new();
+        if live_states[initial_state as usize] {
+            self.root.search(
+                dfa,
+                live_states,
+                initial_state,
+                &mut Vec::new(),
+                &mut matches,
+            );
+        }
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ArtDictionary, Children};
+    use crate::{Distance, LevenshteinAutomatonBuilder};
+    use std::collections::BTreeSet;
+
+    fn words_and_search_agree(dictionary: &ArtDictionary, expected: &BTreeSet<String>) {
+        assert_eq!(dictionary.len(), expected.len());
+        let words: Vec<String> = expected.iter().cloned().collect();
+        assert_eq!(dictionary.words(), words);
+        let builder = LevenshteinAutomatonBuilder::new(1, true);
+        for query in &["", "ab", "寿司", "abcd"] {
+            let dfa = builder.build_dfa(query);
+            let expected_matches: Vec<(String, u8)> = words
+                .iter()
+                .filter_map(|word| match dfa.eval(word) {
+                    Distance::Exact(d) => Some((word.clone(), d)),
+                    Distance::AtLeast(_) => None,
+                })
+                .collect();
+            assert_eq!(dictionary.search(&dfa), expected_matches);
+        }
+    }
+
+    #[test]
+    fn test_art_insert_remove() {
+        let alphabet = ['a', 'b', 'c', 'd', '寿', '司'];
+        let mut dictionary = ArtDictionary::new();
+        let mut expected = BTreeSet::new();
+        let mut seed = 42u64;
+        for step in 0..2_000 {
+            seed = seed
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            let len = (seed >> 60) as usize % 5;
+            let word: String = (0..len)
+                .map(|i| alphabet[(seed >> (8 * i + 8)) as usize % alphabet.len()])
+                .collect();
+            if (seed >> 40) % 3 == 2 {
+                assert_eq!(dictionary.remove(&word), expected.remove(&word));
+            } else {
+                assert_eq!(dictionary.insert(&word), expected.insert(word.clone()));
+            }
+            assert!(dictionary.contains(&word) == expected.contains(&word));
+            if step % 100 == 0 {
+                words_and_search_agree(&dictionary, &expected);
+            }
+        }
+        words_and_search_agree(&dictionary, &expected);
+    }
+
+    #[test]
+    fn test_art_node_growth() {
+        let mut dictionary = ArtDictionary::new();
+        let mut expected = BTreeSet::new();
+        for b in 0x20u8..0x7f {
+            let word = format!("x{}", b as char);
+            dictionary.insert(&word);
+            expected.insert(word);
+        }
+        let x = dictionary.root.children.get(b'x').unwrap();
+        assert!(matches!(x.children, Children::Node256(_)));
+        words_and_search_agree(&dictionary, &expected);
+        for b in 0x20u8..0x70 {
+            let word = format!("x{}", b as char);
+            assert!(dictionary.remove(&word));
+            expected.remove(&word);
+        }
+        let x = dictionary.root.children.get(b'x').unwrap();
+        assert!(matches!(x.children, Children::Node48(..)));
+        words_and_search_agree(&dictionary, &expected);
+        for word in expected.iter() {
+            assert!(dictionary.remove(word));
+        }
+        assert!(dictionary.is_empty());
+        assert_eq!(dictionary.root.children.len(), 0);
+    }
+}
@@ -0,0 +1,160 @@
+use super::{Distance, DFA};
+
+// Number of candidates evaluated together.
+const LANES: usize = 8;
+
+// Number of candidates of the tasks of the parallel evaluations.
+#[cfg(feature = "rayon")]
+const PAR_CHUNK_LEN: usize = 1024;
+
+impl DFA {
+    /// Returns the distance of each of the `candidates`, in order.
+    ///
+    /// Candidates are evaluated by groups of eight, one byte of each
+    /// candidate at a time: the table lookups of the different candidates
+    /// are independent, so that the processor can keep several of them in
+    /// flight instead of waiting for each one in turn.
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::{Distance, LevenshteinAutomatonBuilder};
+    /// let dfa = LevenshteinAutomatonBuilder::new(1, false).build_dfa("abc");
+    /// assert_eq!(
+    ///     dfa.eval_batch(&["abc", "abd", "xyz"]),
+    ///     vec![Distance::Exact(0), Distance::Exact(1), Distance::AtLeast(2)]
+    /// );
+    /// ```
+    pub fn eval_batch<S: AsRef<[u8]>>(&self, candidates: &[S]) -> Vec<Distance> {
+        let mut distances = Vec::with_capacity(candidates.len());
+        let mut groups = candidates.chunks_exact(LANES);
+        for group in &mut groups {
+            let mut texts: [&[u8]; LANES] = [&[]; LANES];
+            for (text, candidate) in texts.iter_mut().zip(group) {
+                *text = candidate.as_ref();
+            }
+            let common_len = texts.iter().map(|text| text.len()).min().unwrap_or(0);
+            let mut states = [self.initial_state(); LANES];
+            // All of the lanes are busy up to the length of the shortest candidate.
+            for i in 0..common_len {
+                for (state, text) in states.iter_mut().zip(&texts) {
+                    *state = self.transition(*state, text[i]);
+                }
+            }
+            for (state, text) in states.iter_mut().zip(&texts) {
+                for &b in &text[common_len..] {
+                    *state = self.transition(*state, b);
+                }
+                distances.push(self.distance(*state));
+            }
+        }
+        distances.extend(
+            groups
+                .remainder()
+                .iter()
+                .map(|candidate| self.eval(candidate)),
+        );
+        distances
+    }
+
+    /// Same as [.eval_batch()](#method.eval_batch), with the candidates
+    /// evaluated in parallel.
+    #[cfg(feature = "rayon")]
+    pub fn par_eval_batch<S: AsRef<[u8]> + Sync>(&self, candidates: &[S]) -> Vec<Distance> {
+        use rayon::prelude::*;
+
+        candidates
+            .par_chunks(PAR_CHUNK_LEN)
+            .flat_map_iter(|chunk| self.eval_batch(chunk))
+            .collect()
+    }
+
+    /// Returns the index and the distance of the candidates within
+    /// `max_distance`, in order, evaluating the candidates in parallel.
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::LevenshteinAutomatonBuilder;
+    /// let dfa = LevenshteinAutomatonBuilder::new(2, false).build_dfa("abc");
+    /// assert_eq!(
+    ///     dfa.par_filter_within(&["abc", "xyz", "abd", "bd"], 1),
+    ///     vec![(0, 0), (2, 1)]
+    /// );
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn par_filter_within<S: AsRef<[u8]> + Sync>(
+        &self,
+        candidates: &[S],
+        max_distance: u8,
+    ) -> Vec<(usize, u8)> {
+        use rayon::prelude::*;
+
+        candidates
+            .par_chunks(PAR_CHUNK_LEN)
+            .enumerate()
+            .flat_map_iter(|(chunk_id, chunk)| {
+                self.eval_batch(chunk)
+                    .into_iter()
+                    .enumerate()
+                    .filter_map(move |(i, distance)| match distance {
+                        Distance::Exact(d) if d <= max_distance => {
+                            Some((chunk_id * PAR_CHUNK_LEN + i, d))
+                        }
+                        _ => None,
+                    })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::LevenshteinAutomatonBuilder;
+
+    #[test]
+    fn test_eval_batch() {
+        let dfa = LevenshteinAutomatonBuilder::new(2, true).build_dfa("levenshtein");
+        let candidates: Vec<String> = [
+            "",
+            "l",
+            "levenshtein",
+            "levenstein",
+            "leventshein",
+            "lvnshtn",
+            "寿司",
+            "abc",
+            "levenshteinlevenshtein",
+            "levenshtei",
+            "evenshtein",
+        ]
+        .iter()
+        .cycle()
+        .take(27)
+        .map(|candidate| candidate.to_string())
+        .collect();
+        for len in 0..candidates.len() {
+            let batch = &candidates[..len];
+            let expected: Vec<_> = batch.iter().map(|candidate| dfa.eval(candidate)).collect();
+            assert_eq!(dfa.eval_batch(batch), expected);
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_eval_batch() {
+        use crate::Distance;
+
+        let dfa = LevenshteinAutomatonBuilder::new(1, true).build_dfa("rayon");
+        let candidates: Vec<String> = (0..5000)
+            .map(|i| format!("{}{}", ["rayon", "ryaon", "crayon", "ray"][i % 4], i % 3))
+            .collect();
+        let expected = dfa.eval_batch(&candidates);
+        assert_eq!(dfa.par_eval_batch(&candidates), expected);
+        let within: Vec<(usize, u8)> = expected
+            .iter()
+            .enumerate()
+            .filter_map(|(i, distance)| match *distance {
+                Distance::Exact(d) => Some((i, d)),
+                Distance::AtLeast(_) => None,
+            })
+            .collect();
+        assert_eq!(dfa.par_filter_within(&candidates, 1), within);
+    }
+}
@@ -1,4 +1,4 @@
-use super::{LevenshteinNFA, ParametricDFA};
+use super::{LevenshteinAutomatonBuilder, LevenshteinNFA, ParametricDFA};
 use test::Bencher;
 
 #[bench]
@@ -115,3 +115,25 @@ fn bench_build_parametricdfa_damerau_perf_2_profile(b: &mut Bencher) {
         let _dfa = parametric_dfa.build_dfa("Levenshtein", false);
     });
 }
+
+fn eval_bench_text() -> Vec<u8> {
+    "Levenshtein automata are fast. Leveshtein, Lewenstein or Levenstein? "
+        .repeat(1 << 10)
+        .into_bytes()
+}
+
+#[bench]
+fn bench_eval(b: &mut Bencher) {
+    let dfa = LevenshteinAutomatonBuilder::new(2, true).build_dfa("Levenshtein");
+    let text = eval_bench_text();
+    b.bytes = text.len() as u64;
+    b.iter(|| dfa.eval(&text));
+}
+
+#[bench]
+fn bench_eval_unchecked(b: &mut Bencher) {
+    let dfa = LevenshteinAutomatonBuilder::new(2, true).build_dfa("Levenshtein");
+    let text = eval_bench_text();
+    b.bytes = text.len() as u64;
+    b.iter(|| unsafe { dfa.eval_unchecked(&text) });
+}
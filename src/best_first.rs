@@ -0,0 +1,235 @@
+use super::{Dawg, Distance, DFA};
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+/// Byte-labelled trie-like dictionary, such as a [Dawg] or an `fst::Set`,
+/// that can be walked by [best_first_search].
+pub trait Trie {
+    /// Handle on a node of the trie.
+    type Node;
+
+    /// Returns the root of the trie.
+    fn root(&self) -> Self::Node;
+
+    /// Returns true if the path leading to `node` is a word of the dictionary.
+    fn is_final(&self, node: &Self::Node) -> bool;
+
+    /// Returns the outgoing edges of `node`, as `(byte, child)` pairs.
+    fn children(&self, node: &Self::Node) -> Vec<(u8, Self::Node)>;
+}
+
+impl Trie for Dawg {
+    type Node = u32;
+
+    fn root(&self) -> u32 {
+        0
+    }
+
+    fn is_final(&self, node: &u32) -> bool {
+        self.is_final_state(*node)
+    }
+
+    fn children(&self, node: &u32) -> Vec<(u8, u32)> {
+        self.state_transitions(*node).to_vec()
+    }
+}
+
+#[cfg(feature = "fst")]
+impl<D: AsRef<[u8]>> Trie for fst::Set<D> {
+    type Node = fst::raw::CompiledAddr;
+
+    fn root(&self) -> fst::raw::CompiledAddr {
+        self.as_fst().root().addr()
+    }
+
+    fn is_final(&self, node: &fst::raw::CompiledAddr) -> bool {
+        self.as_fst().node(*node).is_final()
+    }
+
+    fn children(&self, node: &fst::raw::CompiledAddr) -> Vec<(u8, fst::raw::CompiledAddr)> {
+        self.as_fst()
+            .node(*node)
+            .transitions()
+            .map(|transition| (transition.inp, transition.addr))
+            .collect()
+    }
+}
+
+// Entry of the priority queue: either a node left to expand, whose priority is
+// the smallest distance reachable from its automaton state, or a word
+// that was found, whose priority is its distance.
+struct Entry<N> {
+    priority: u8,
+    path: Vec<u8>,
+    kind: EntryKind<N>,
+}
+
+enum EntryKind<N> {
+    Match,
+    Expand { node: N, state: u32 },
+}
+
+impl<N> Entry<N> {
+    fn key(&self) -> (u8, &[u8], bool) {
+        let is_expand = matches!(self.kind, EntryKind::Expand { .. });
+        (self.priority, &self.path, is_expand)
+    }
+}
+
+impl<N> PartialEq for Entry<N> {
+    fn eq(&self, other: &Entry<N>) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl<N> Eq for Entry<N> {}
+
+impl<N> PartialOrd for Entry<N> {
+    fn partial_cmp(&self, other: &Entry<N>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N> Ord for Entry<N> {
+    fn cmp(&self, other: &Entry<N>) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+/// Returns the `limit` words of `trie` closest to the query of `dfa`,
+/// along with their distance.
+///
+/// Words are sorted by distance, and then in lexicographic order.
+///
+/// Unlike an exhaustive traversal followed by a sort, the trie is walked
+/// best-first: nodes are expanded by increasing [DFA::min_distances], which is
+/// a lower bound of the distance of the words below them. The search therefore
+/// stops as soon as `limit` words are found, and the nodes that can only lead
+/// to worse words are never visited.
+///
+/// ```rust
+/// # use levenshtein_automata::{best_first_search, Dawg, LevenshteinAutomatonBuilder};
+/// let dawg = Dawg::from_sorted_words(&["cart", "cat", "cats", "coat", "dog"]).unwrap();
+/// let dfa = LevenshteinAutomatonBuilder::new(2, true).build_dfa("cat");
+/// assert_eq!(
+///     best_first_search(&dawg, &dfa, 3),
+///     vec![
+///         ("cat".to_string(), 0),
+///         ("cart".to_string(), 1),
+///         ("cats".to_string(), 1)
+///     ]
+/// );
+/// ```
+pub fn best_first_search<T: Trie>(trie: &T, dfa: &DFA, limit: usize) -> Vec<(String, u8)> {
+    let min_distances = dfa.min_distances();
+    let mut matches = Vec::new();
+    let mut queue = BinaryHeap::new();
+    let initial_state = dfa.initial_state();
+    if let Some(priority) = min_distances[initial_state as usize] {
+        queue.push(Reverse(Entry {
+            priority,
+            path: Vec::new(),
+            kind: EntryKind::Expand {
+                node: trie.root(),
+                state: initial_state,
+            },
+        }));
+    }
+    // As priorities never decrease along a path, entries are popped by
+    // increasing priority, and in lexicographic order for a given priority.
+    while matches.len() < limit {
+        let Reverse(entry) = match queue.pop() {
+            Some(entry) => entry,
+            None => break,
+        };
+        let (node, state) = match entry.kind {
+            EntryKind::Match => {
+                let word =
+                    String::from_utf8(entry.path).expect("The automaton only accepts valid utf-8");
+                matches.push((word, entry.priority));
+                continue;
+            }
+            EntryKind::Expand { node, state } => (node, state),
+        };
+        if trie.is_final(&node) {
+            if let Distance::Exact(d) = dfa.distance(state) {
+                queue.push(Reverse(Entry {
+                    priority: d,
+                    path: entry.path.clone(),
+                    kind: EntryKind::Match,
+                }));
+            }
+        }
+        for (b, child) in trie.children(&node) {
+            let child_state = dfa.transition(state, b);
+            if let Some(priority) = min_distances[child_state as usize] {
+                let mut path = entry.path.clone();
+                path.push(b);
+                queue.push(Reverse(Entry {
+                    priority,
+                    path,
+                    kind: EntryKind::Expand {
+                        node: child,
+                        state: child_state,
+                    },
+                }));
+            }
+        }
+    }
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::best_first_search;
+    use crate::{Dawg, Distance, LevenshteinAutomatonBuilder};
+
+    #[test]
+    fn test_best_first_search() {
+        let mut words = vec![
+            "",
+            "a",
+            "ab",
+            "abc",
+            "abd",
+            "bcd",
+            "abdc",
+            "cab",
+            "寿司",
+            "寿司屋",
+            "levenshtein",
+            "levenstein",
+        ];
+        words.sort_unstable();
+        let dawg = Dawg::from_sorted_words(&words).unwrap();
+        for &(max_distance, transposition_cost_one) in &[(0, false), (1, true), (2, false)] {
+            let builder = LevenshteinAutomatonBuilder::new(max_distance, transposition_cost_one);
+            for query in &["", "abc", "寿司", "levenshtain", "xyz"] {
+                let dfa = builder.build_dfa(query);
+                let mut expected: Vec<(String, u8)> = words
+                    .iter()
+                    .filter_map(|word| match dfa.eval(word) {
+                        Distance::Exact(d) => Some((word.to_string(), d)),
+                        Distance::AtLeast(_) => None,
+                    })
+                    .collect();
+                expected.sort_by(|left, right| (left.1, &left.0).cmp(&(right.1, &right.0)));
+                for limit in 0..=expected.len() + 1 {
+                    let expected_top = &expected[..limit.min(expected.len())];
+                    assert_eq!(best_first_search(&dawg, &dfa, limit), expected_top);
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "fst")]
+    #[test]
+    fn test_best_first_search_fst() {
+        let set = fst::Set::from_iter(vec!["cart", "cat", "cats", "coat", "dog"]).unwrap();
+        let dfa = LevenshteinAutomatonBuilder::new(1, false).build_dfa("cot");
+        assert_eq!(
+            best_first_search(&set, &dfa, 10),
+            vec![("cat".to_string(), 1), ("coat".to_string(), 1),]
+        );
+    }
+}
@@ -0,0 +1,153 @@
+//! Command line tool to match words and to inspect the automata.
+//!
+//! ```text
+//! lev-dfa match --query foo --distance 2 < words.txt
+//! lev-dfa dot --query foo --distance 1 | dot -Tsvg > foo.svg
+//! ```
+use levenshtein_automata::{LevenshteinAutomatonBuilder, DFA};
+use std::env;
+use std::io::{self, BufRead, Write};
+use std::process;
+
+const USAGE: &str = "\
+usage: lev-dfa <match|dot> --query <query> [options]
+
+commands:
+    match   prints the lines of the standard input within the distance of the query
+    dot     prints the automaton of the query in the Graphviz dot format
+
+options:
+    --query <query>         query to match
+    --distance <distance>   maximum distance, 1 by default
+    --prefix                matches the texts starting with the query
+    --no-transposition      counts transpositions as two edits
+";
+
+#[derive(Debug, Eq, PartialEq)]
+enum Command {
+    Match,
+    Dot,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct Args {
+    command: Command,
+    query: String,
+    distance: u8,
+    prefix: bool,
+    transposition_cost_one: bool,
+}
+
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Args, String> {
+    let command = match args.next().as_deref() {
+        Some("match") => Command::Match,
+        Some("dot") => Command::Dot,
+        Some(command) => return Err(format!("unknown command {:?}", command)),
+        None => return Err("missing command".to_string()),
+    };
+    let mut query = None;
+    let mut distance = 1;
+    let mut prefix = false;
+    let mut transposition_cost_one = true;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--query" => query = Some(args.next().ok_or("missing value of --query")?),
+            "--distance" => {
+                let value = args.next().ok_or("missing value of --distance")?;
+                distance = value
+                    .parse()
+                    .map_err(|_| format!("invalid distance {:?}", value))?;
+            }
+            "--prefix" => prefix = true,
+            "--no-transposition" => transposition_cost_one = false,
+            _ => return Err(format!("unknown option {:?}", arg)),
+        }
+    }
+    Ok(Args {
+        command,
+        query: query.ok_or("missing --query")?,
+        distance,
+        prefix,
+        transposition_cost_one,
+    })
+}
+
+fn build_dfa(args: &Args) -> DFA {
+    let builder = LevenshteinAutomatonBuilder::new(args.distance, args.transposition_cost_one);
+    if args.prefix {
+        builder.build_prefix_dfa(&args.query)
+    } else {
+        builder.build_dfa(&args.query)
+    }
+}
+
+fn run(args: &Args) -> io::Result<()> {
+    let dfa = build_dfa(args);
+    let stdout = io::stdout();
+    let mut out = io::BufWriter::new(stdout.lock());
+    match args.command {
+        Command::Match => {
+            let stdin = io::stdin();
+            let is_match = dfa.matcher::<[u8]>();
+            for line in stdin.lock().split(b'\n') {
+                let mut line = line?;
+                if line.last() == Some(&b'\r') {
+                    line.pop();
+                }
+                if is_match(&line) {
+                    out.write_all(&line)?;
+                    out.write_all(b"\n")?;
+                }
+            }
+        }
+        Command::Dot => out.write_all(dfa.to_dot().as_bytes())?,
+    }
+    out.flush()
+}
+
+fn main() {
+    let args = match parse_args(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            process::exit(2);
+        }
+    };
+    if let Err(err) = run(&args) {
+        if err.kind() != io::ErrorKind::BrokenPipe {
+            eprintln!("error: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_args, Args, Command};
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(
+            parse(&["match", "--query", "foo", "--distance", "2", "--prefix"]),
+            Ok(Args {
+                command: Command::Match,
+                query: "foo".to_string(),
+                distance: 2,
+                prefix: true,
+                transposition_cost_one: true,
+            })
+        );
+        let args = parse(&["dot", "--no-transposition", "--query", "寿司"]).unwrap();
+        assert_eq!(args.command, Command::Dot);
+        assert_eq!(args.distance, 1);
+        assert!(!args.transposition_cost_one);
+        assert!(parse(&["match"]).is_err());
+        assert!(parse(&["match", "--query", "foo", "--distance", "x"]).is_err());
+        assert!(parse(&["grep", "--query", "foo"]).is_err());
+        assert!(parse(&[]).is_err());
+    }
+}
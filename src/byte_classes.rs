@@ -0,0 +1,146 @@
+use super::{Distance, DFA};
+use std::collections::HashMap;
+use std::mem;
+
+/// [DFA](./struct.DFA.html) whose transition table is indexed by byte classes.
+///
+/// Two bytes are in the same class if all of the states have the same
+/// transition on them. Levenshtein automata only distinguish the bytes of
+/// the query, so that most bytes, and in particular most of the utf-8
+/// continuation bytes, end up in the same class. Each state then stores
+/// one transition per class instead of `256`, at the cost of an extra
+/// lookup in a `256` bytes table per transition.
+///
+/// ```rust
+/// # use levenshtein_automata::{Distance, LevenshteinAutomatonBuilder};
+/// let dfa = LevenshteinAutomatonBuilder::new(2, true).build_dfa("Levenshtein");
+/// let compressed = dfa.to_byte_classes();
+/// assert_eq!(compressed.eval("Levenshtain"), Distance::Exact(1));
+/// assert!(compressed.num_classes() <= 16);
+/// assert!(compressed.heap_size() * 10 < dfa.num_states() * 1024);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ByteClassDfa {
+    byte_classes: Box<[u8; 256]>,
+    num_classes: usize,
+    // `num_classes` transitions per state.
+    transitions: Vec<u32>,
+    distances: Vec<Distance>,
+    initial_state: u32,
+}
+
+impl DFA {
+    /// Builds the [ByteClassDfa](./struct.ByteClassDfa.html) equivalent to the automaton.
+    pub fn to_byte_classes(&self) -> ByteClassDfa {
+        let (byte_classes, representatives) = byte_classes(self);
+        let transitions = self
+            .states()
+            .flat_map(|state| {
+                representatives
+                    .iter()
+                    .map(move |&b| self.transition(state, b))
+            })
+            .collect();
+        ByteClassDfa {
+            byte_classes,
+            num_classes: representatives.len(),
+            transitions,
+            distances: self.states().map(|state| self.distance(state)).collect(),
+            initial_state: self.initial_state(),
+        }
+    }
+}
+
+// Returns the class of each byte, and a representative byte for each class.
+// Classes are numbered in the order of their smallest byte.
+pub(crate) fn byte_classes(dfa: &DFA) -> (Box<[u8; 256]>, Vec<u8>) {
+    let mut classes = Box::new([0u8; 256]);
+    let mut representatives: Vec<u8> = Vec::new();
+    let mut columns: HashMap<Vec<u32>, u8> = HashMap::new();
+    for b in 0..=255u8 {
+        let column: Vec<u32> = dfa.states().map(|state| dfa.transition(state, b)).collect();
+        let num_classes = columns.len() as u8;
+        classes[b as usize] = *columns.entry(column).or_insert_with(|| {
+            representatives.push(b);
+            num_classes
+        });
+    }
+    (classes, representatives)
+}
+
+impl ByteClassDfa {
+    /// Returns the initial state
+    pub fn initial_state(&self) -> u32 {
+        self.initial_state
+    }
+
+    /// Returns the number of states.
+    pub fn num_states(&self) -> usize {
+        self.distances.len()
+    }
+
+    /// Returns the number of byte classes, between `1` and `256`.
+    pub fn num_classes(&self) -> usize {
+        self.num_classes
+    }
+
+    /// Returns the class of a byte.
+    pub fn byte_class(&self, b: u8) -> u8 {
+        self.byte_classes[b as usize]
+    }
+
+    /// Returns the Levenshtein distance associated to the
+    /// current state.
+    pub fn distance(&self, state_id: u32) -> Distance {
+        self.distances[state_id as usize]
+    }
+
+    /// Returns the destination state reached after consuming a given byte.
+    pub fn transition(&self, from_state_id: u32, b: u8) -> u32 {
+        self.transitions
+            [from_state_id as usize * self.num_classes + self.byte_classes[b as usize] as usize]
+    }
+
+    /// Returns the Levenshtein distance computed by the automaton on `text`.
+    pub fn eval<B: AsRef<[u8]>>(&self, text: B) -> Distance {
+        let mut state = self.initial_state();
+        for &b in text.as_ref() {
+            state = self.transition(state, b);
+        }
+        self.distance(state)
+    }
+
+    /// Returns the number of bytes allocated on the heap by the tables.
+    pub fn heap_size(&self) -> usize {
+        mem::size_of::<[u8; 256]>()
+            + self.transitions.capacity() * mem::size_of::<u32>()
+            + self.distances.capacity() * mem::size_of::<Distance>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::LevenshteinAutomatonBuilder;
+
+    #[test]
+    fn test_byte_class_transitions() {
+        for &(max_distance, transposition_cost_one) in &[(0, false), (1, true), (2, false)] {
+            let builder = LevenshteinAutomatonBuilder::new(max_distance, transposition_cost_one);
+            for query in &["", "abc", "寿司", "levenshtein"] {
+                for dfa in &[builder.build_dfa(query), builder.build_prefix_dfa(query)] {
+                    let compressed = dfa.to_byte_classes();
+                    assert_eq!(compressed.num_states(), dfa.num_states());
+                    assert_eq!(compressed.initial_state(), dfa.initial_state());
+                    for state in dfa.states() {
+                        assert_eq!(compressed.distance(state), dfa.distance(state));
+                        for b in 0..=255u8 {
+                            assert_eq!(compressed.transition(state, b), dfa.transition(state, b));
+                        }
+                    }
+                    // Bytes that are not in the query share a class.
+                    assert_eq!(compressed.byte_class(b'x'), compressed.byte_class(b'z'));
+                }
+            }
+        }
+    }
+}
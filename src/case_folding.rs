@@ -0,0 +1,133 @@
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+
+/// Case folding applied by a
+/// [LevenshteinAutomatonBuilder](./struct.LevenshteinAutomatonBuilder.html).
+///
+/// Both the query and the texts are folded, so that chars differing only
+/// by their case match at no cost.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub enum CaseFolding {
+    /// Chars are compared as is.
+    #[default]
+    None,
+    /// ASCII letters are compared ignoring their case.
+    /// Other chars are compared as is.
+    Ascii,
+    /// Chars are compared after Unicode simple case folding, e.g. `Σ`, `σ`
+    /// and `ς` are equivalent, and so are `K`, `k` and the Kelvin sign `K`.
+    ///
+    /// Simple case folding maps each char to a single char: foldings
+    /// changing the length of the text, such as `ß` to `ss`, are not applied,
+    /// so that `ß` and `ss` remain two edits apart.
+    Unicode,
+}
+
+impl CaseFolding {
+    /// Returns the folded form of a char.
+    pub(crate) fn fold(self, chr: char) -> char {
+        match self {
+            CaseFolding::None => chr,
+            CaseFolding::Ascii => chr.to_ascii_lowercase(),
+            CaseFolding::Unicode => simple_fold(chr),
+        }
+    }
+
+    /// Returns the chars, other than `folded` itself,
+    /// whose folded form is `folded`.
+    pub(crate) fn variants(self, folded: char) -> Vec<char> {
+        match self {
+            CaseFolding::None => Vec::new(),
+            CaseFolding::Ascii if folded.is_ascii_lowercase() => {
+                vec![folded.to_ascii_uppercase()]
+            }
+            CaseFolding::Ascii => Vec::new(),
+            CaseFolding::Unicode => unfold(folded),
+        }
+    }
+}
+
+// Approximates the simple case folding of the Unicode character database
+// by the lowercase mapping, when it is a single char.
+fn simple_fold(chr: char) -> char {
+    match chr {
+        // Chars folded to another lowercase char.
+        'ς' => 'σ',
+        'ſ' => 's',
+        'ϐ' => 'β',
+        'ϑ' => 'θ',
+        'ϕ' => 'φ',
+        'ϖ' => 'π',
+        'ϰ' => 'κ',
+        'ϱ' => 'ρ',
+        'ϵ' => 'ε',
+        '\u{1E9B}' => '\u{1E61}',
+        '\u{1FBE}' => 'ι',
+        _ => {
+            let mut lowercase = chr.to_lowercase();
+            match (lowercase.next(), lowercase.next()) {
+                (Some(folded), None) => folded,
+                _ => chr,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+fn unfold(folded: char) -> Vec<char> {
+    unfold_table().get(&folded).cloned().unwrap_or_default()
+}
+
+// Without `std`, the table cannot be cached, so that all of the chars are
+// scanned on each call: callers should only call it once per char.
+#[cfg(not(feature = "std"))]
+fn unfold(folded: char) -> Vec<char> {
+    (0..=0x10_FFFF)
+        .filter_map(core::char::from_u32)
+        .filter(|&chr| chr != folded && simple_fold(chr) == folded)
+        .collect()
+}
+
+// Maps each folded char to the other chars folded to it.
+#[cfg(feature = "std")]
+fn unfold_table() -> &'static HashMap<char, Vec<char>> {
+    static UNFOLD_TABLE: OnceLock<HashMap<char, Vec<char>>> = OnceLock::new();
+    UNFOLD_TABLE.get_or_init(|| {
+        let mut table: HashMap<char, Vec<char>> = HashMap::new();
+        for chr in (0..=0x10_FFFF).filter_map(core::char::from_u32) {
+            let folded = simple_fold(chr);
+            if folded != chr {
+                table.entry(folded).or_default().push(chr);
+            }
+        }
+        table
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CaseFolding;
+
+    #[test]
+    fn test_unicode_variants() {
+        let mut variants = CaseFolding::Unicode.variants('k');
+        variants.sort_unstable();
+        assert_eq!(variants, vec!['K', '\u{212A}']);
+        let mut variants = CaseFolding::Unicode.variants('σ');
+        variants.sort_unstable();
+        assert_eq!(variants, vec!['Σ', 'ς']);
+        assert_eq!(CaseFolding::Unicode.variants('ß'), vec!['ẞ']);
+        assert!(CaseFolding::Unicode.variants('K').is_empty());
+        for chr in "aBcΣςİﬃ寿".chars() {
+            let folded = CaseFolding::Unicode.fold(chr);
+            assert_eq!(CaseFolding::Unicode.fold(folded), folded);
+            if folded != chr {
+                assert!(CaseFolding::Unicode.variants(folded).contains(&chr));
+            }
+        }
+    }
+}
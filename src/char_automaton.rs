@@ -0,0 +1,121 @@
+use super::dfa::Utf8DFABuilder;
+use super::{Distance, DFA};
+use alloc::vec::Vec;
+
+/// Deterministic automaton operating on unicode chars, that can
+/// be compiled into a [DFA](./struct.DFA.html) operating on utf-8 bytes.
+///
+/// States are identified by the integers `0..num_states()`. For each state,
+/// the automaton lists the chars having a specific destination. All of the
+/// other chars lead to the state's default transition.
+pub trait CharAutomaton {
+    /// Returns the number of states.
+    fn num_states(&self) -> u32;
+
+    /// Returns the initial state.
+    fn initial_state(&self) -> u32;
+
+    /// Returns the distance associated to a state.
+    ///
+    /// Accepting states have an `Exact` distance.
+    fn distance(&self, state: u32) -> Distance;
+
+    /// Returns the destination of all of the chars that are not
+    /// listed in `transitions`.
+    fn default_transition(&self, state: u32) -> u32;
+
+    /// Returns the chars having a specific destination.
+    ///
+    /// If a char is listed several times, the last destination wins.
+    fn transitions(&self, state: u32) -> Vec<(char, u32)>;
+}
+
+impl DFA {
+    /// Compiles a char-level automaton into a `DFA` operating on utf-8 bytes.
+    ///
+    /// State ids are not preserved, except for the state `0` which keeps
+    /// the id `0`. If that state is a sink, it therefore
+    /// is the [SINK_STATE](./constant.SINK_STATE.html) of the resulting `DFA`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the automaton refers to a state greater or equal to `num_states()`.
+    pub fn from_char_automaton<A: CharAutomaton + ?Sized>(automaton: &A) -> DFA {
+        let num_states = automaton.num_states();
+        let mut dfa_builder = Utf8DFABuilder::with_max_num_states(num_states as usize);
+        for state in 0..num_states {
+            let distance = automaton.distance(state);
+            let default_successor = automaton.default_transition(state);
+            let transitions = automaton.transitions(state);
+            assert!(
+                default_successor < num_states
+                    && transitions.iter().all(|&(_, to)| to < num_states),
+                "Transition to a state larger than num_states"
+            );
+            if default_successor == state && transitions.iter().all(|&(_, to)| to == state) {
+                dfa_builder.add_sink_state(state, distance);
+            } else {
+                let mut state_builder = dfa_builder.add_state(state, distance, default_successor);
+                for (chr, to) in transitions {
+                    state_builder.add_transition(chr, to);
+                }
+            }
+        }
+        dfa_builder.set_initial_state(automaton.initial_state());
+        dfa_builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CharAutomaton;
+    use crate::{Distance, DFA, SINK_STATE};
+
+    // Accepts the strings containing exactly one occurrence of a given char.
+    struct ExactlyOnce(char);
+
+    impl CharAutomaton for ExactlyOnce {
+        fn num_states(&self) -> u32 {
+            3
+        }
+
+        fn initial_state(&self) -> u32 {
+            1
+        }
+
+        fn distance(&self, state: u32) -> Distance {
+            if state == 2 {
+                Distance::Exact(0)
+            } else {
+                Distance::AtLeast(1)
+            }
+        }
+
+        fn default_transition(&self, state: u32) -> u32 {
+            state
+        }
+
+        fn transitions(&self, state: u32) -> Vec<(char, u32)> {
+            match state {
+                0 => vec![],
+                1 => vec![(self.0, 2)],
+                _ => vec![(self.0, 0)],
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_char_automaton() {
+        let dfa = DFA::from_char_automaton(&ExactlyOnce('司'));
+        assert_eq!(dfa.eval("寿司"), Distance::Exact(0));
+        assert_eq!(dfa.eval("司abc❤"), Distance::Exact(0));
+        assert_eq!(dfa.eval("寿"), Distance::AtLeast(1));
+        assert_eq!(dfa.eval("司司"), Distance::AtLeast(1));
+        let mut state = dfa.initial_state();
+        for &b in "司司".as_bytes() {
+            state = dfa.transition(state, b);
+        }
+        assert_eq!(state, SINK_STATE);
+        assert_eq!(dfa.successors(SINK_STATE), vec![SINK_STATE]);
+    }
+}
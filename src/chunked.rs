@@ -0,0 +1,312 @@
+use super::scan::StartBytes;
+use super::{Distance, DFA};
+use std::iter;
+
+impl DFA {
+    /// Same as [.eval(...)](#method.eval), for a text split into several chunks,
+    /// such as the chunks of a rope (`ropey::Rope::chunks()`) or a list of
+    /// `bytes::Bytes` received from the network.
+    ///
+    /// The state is carried from one chunk to the next, so that the text
+    /// does not need to be copied into a single buffer. Chunks may be split
+    /// in the middle of a char.
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::{Distance, LevenshteinAutomatonBuilder};
+    /// let dfa = LevenshteinAutomatonBuilder::new(1, true).build_dfa("寿司屋");
+    /// let text = "寿司尾".as_bytes();
+    /// let chunks = vec![&text[..2], &text[2..7], &text[7..]];
+    /// assert_eq!(dfa.eval_chunks(chunks), Distance::Exact(1));
+    /// ```
+    pub fn eval_chunks<I, C>(&self, chunks: I) -> Distance
+    where
+        I: IntoIterator<Item = C>,
+        C: AsRef<[u8]>,
+    {
+        let mut state = self.initial_state();
+        for chunk in chunks {
+            for &b in chunk.as_ref() {
+                state = self.transition(state, b);
+            }
+            if self.is_sink(state) {
+                break;
+            }
+        }
+        self.distance(state)
+    }
+
+    /// Unanchored approximate search: returns an iterator over the end offsets
+    /// of the approximate occurrences of the query in `text`, along with their
+    /// distance, when called on an automaton built by
+    /// [LevenshteinAutomatonBuilder::build_suffix_dfa](./struct.LevenshteinAutomatonBuilder.html#method.build_suffix_dfa).
+    ///
+    /// The suffix automaton is the determinized form of Sellers' algorithm:
+    /// the distance reported at an offset is the smallest distance between the
+    /// query and a substring of `text` ending there. The text is read once, at
+    /// most one transition per byte: the chars keeping the automaton in its
+    /// initial state are skipped, with `memchr` if the `memchr` feature is
+    /// enabled, as described in [.find_iter_chunks(...)](#method.find_iter_chunks).
+    /// Offsets are only reported at char boundaries.
+    ///
+    /// For other automata, it reports the prefixes of `text` that are accepted.
+    /// See [.find_iter_chunks(...)](#method.find_iter_chunks) for chunked texts.
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::LevenshteinAutomatonBuilder;
+    /// let dfa = LevenshteinAutomatonBuilder::new(1, true).build_suffix_dfa("needle");
+    /// let text = "haystack with a neelde and a needle";
+    /// let ends: Vec<(usize, u8)> = dfa.find_iter(text).collect();
+    /// assert_eq!(ends, vec![(22, 1), (34, 1), (35, 0)]);
+    /// ```
+    pub fn find_iter<'a, B: AsRef<[u8]> + ?Sized>(
+        &'a self,
+        text: &'a B,
+    ) -> ChunkedMatches<'a, iter::Once<&'a [u8]>, &'a [u8]> {
+        self.find_iter_chunks(iter::once(text.as_ref()))
+    }
+
+    /// Returns an iterator over the positions of a chunked text at which the
+    /// automaton is in an accepting state, along with the distance.
+    ///
+    /// Positions are byte offsets from the start of the text, and are only
+    /// reported at char boundaries. While the automaton is in its initial
+    /// state, the chars that keep it there are skipped at once, with `memchr`
+    /// if the `memchr` feature is enabled, provided that the chars leaving it
+    /// start with at most three distinct bytes. The text is then expected to
+    /// be valid utf-8.
+    /// Combined with
+    /// [LevenshteinAutomatonBuilder::build_suffix_dfa](./struct.LevenshteinAutomatonBuilder.html#method.build_suffix_dfa),
+    /// this reports the end of all of the approximate occurrences of the query
+    /// in the text, in a single pass and without copying the chunks.
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::LevenshteinAutomatonBuilder;
+    /// let dfa = LevenshteinAutomatonBuilder::new(1, false).build_suffix_dfa("fox");
+    /// let chunks = ["the quick brown f", "ox jumps over the bo", "x"];
+    /// let ends: Vec<(usize, u8)> = dfa.find_iter_chunks(chunks.iter()).collect();
+    /// assert_eq!(ends, vec![(18, 1), (19, 0), (20, 1), (38, 1)]);
+    /// ```
+    pub fn find_iter_chunks<I, C>(&self, chunks: I) -> ChunkedMatches<'_, I::IntoIter, C>
+    where
+        I: IntoIterator<Item = C>,
+        C: AsRef<[u8]>,
+    {
+        ChunkedMatches {
+            dfa: self,
+            chunks: chunks.into_iter(),
+            chunk: None,
+            chunk_offset: 0,
+            position: 0,
+            state: self.initial_state(),
+            start_bytes: StartBytes::leaving_initial_state(self),
+            remaining_continuation_bytes: 0,
+            start_reported: false,
+        }
+    }
+}
+
+/// Iterator over the accepting positions of a chunked text,
+/// returned by [DFA::find_iter_chunks](./struct.DFA.html#method.find_iter_chunks).
+pub struct ChunkedMatches<'a, I, C> {
+    dfa: &'a DFA,
+    chunks: I,
+    chunk: Option<C>,
+    // Position of the next byte in the current chunk.
+    chunk_offset: usize,
+    // Position of the next byte in the text.
+    position: usize,
+    state: u32,
+    // Bytes on which the automaton leaves its initial state.
+    start_bytes: StartBytes,
+    // Number of bytes left before the end of the current char.
+    remaining_continuation_bytes: u8,
+    start_reported: bool,
+}
+
+impl<'a, I, C> Iterator for ChunkedMatches<'a, I, C>
+where
+    I: Iterator<Item = C>,
+    C: AsRef<[u8]>,
+{
+    type Item = (usize, u8);
+
+    fn next(&mut self) -> Option<(usize, u8)> {
+        if !self.start_reported {
+            self.start_reported = true;
+            if let Distance::Exact(d) = self.dfa.distance(self.state) {
+                return Some((0, d));
+            }
+        }
+        loop {
+            let chunk = match self.chunk {
+                Some(ref chunk) if self.chunk_offset < chunk.as_ref().len() => chunk.as_ref(),
+                _ => {
+                    self.chunk = Some(self.chunks.next()?);
+                    self.chunk_offset = 0;
+                    continue;
+                }
+            };
+            while self.chunk_offset < chunk.len() {
+                if self.start_bytes != StartBytes::All && self.state == self.dfa.initial_state() {
+                    let next_start = match self.start_bytes.next_start(chunk, self.chunk_offset) {
+                        Some(next_start) => next_start,
+                        // The last char may be split with the next chunk.
+                        None => last_char_start(chunk).max(self.chunk_offset),
+                    };
+                    self.position += next_start - self.chunk_offset;
+                    self.chunk_offset = next_start;
+                    if next_start == chunk.len() {
+                        break;
+                    }
+                }
+                let b = chunk[self.chunk_offset];
+                self.chunk_offset += 1;
+                self.position += 1;
+                self.state = self.dfa.transition(self.state, b);
+                self.remaining_continuation_bytes =
+                    remaining_continuation_bytes(self.remaining_continuation_bytes, b);
+                if self.remaining_continuation_bytes > 0 {
+                    continue;
+                }
+                if let Distance::Exact(d) = self.dfa.distance(self.state) {
+                    return Some((self.position, d));
+                }
+            }
+        }
+    }
+}
+
+// Returns the position of the last char of `chunk` if it is not complete,
+// and the length of `chunk` otherwise.
+fn last_char_start(chunk: &[u8]) -> usize {
+    for (i, &b) in chunk.iter().enumerate().rev().take(4) {
+        if !(0x80..0xC0).contains(&b) {
+            if remaining_continuation_bytes(0, b) as usize > chunk.len() - i - 1 {
+                return i;
+            }
+            break;
+        }
+    }
+    chunk.len()
+}
+
+// Returns the number of bytes left before the end of the current char,
+// after reading `b`.
+fn remaining_continuation_bytes(remaining: u8, b: u8) -> u8 {
+    match b {
+        0xC0..=0xDF => 1,
+        0xE0..=0xEF => 2,
+        0xF0..=0xFF => 3,
+        _ => remaining.saturating_sub(1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scan::StartBytes;
+    use crate::{
+        damerau_levenshtein, levenshtein, Distance, LevenshteinAutomatonBuilder, Utf8DFABuilder,
+    };
+
+    #[test]
+    fn test_chunks_match_contiguous_text() {
+        let builder = LevenshteinAutomatonBuilder::new(1, true);
+        let text = "寿司 sushi 寿司屋 suhsi";
+        let bytes = text.as_bytes();
+        for query in &["sushi", "寿司屋", ""] {
+            let dfa = builder.build_dfa(query);
+            let suffix_dfa = builder.build_suffix_dfa(query);
+            let expected_ends: Vec<(usize, u8)> = (0..=bytes.len())
+                .filter(|&end| text.is_char_boundary(end))
+                .filter_map(|end| match suffix_dfa.eval(&bytes[..end]) {
+                    Distance::Exact(d) => Some((end, d)),
+                    Distance::AtLeast(_) => None,
+                })
+                .collect();
+            for chunk_len in 1..=bytes.len() {
+                let chunks: Vec<Vec<u8>> = bytes.chunks(chunk_len).map(|c| c.to_vec()).collect();
+                assert_eq!(dfa.eval_chunks(&chunks), dfa.eval(bytes));
+                let ends: Vec<(usize, u8)> = suffix_dfa.find_iter_chunks(&chunks).collect();
+                assert_eq!(ends, expected_ends);
+            }
+            assert_eq!(
+                suffix_dfa.find_iter(text).collect::<Vec<_>>(),
+                expected_ends
+            );
+            let no_chunks: Vec<&[u8]> = Vec::new();
+            assert_eq!(dfa.eval_chunks(&no_chunks), dfa.eval(""));
+        }
+    }
+
+    #[test]
+    fn test_find_iter_chunks_skipping_initial_state() {
+        let text = "寿司 sushi 寿司屋 suhsi 司屋";
+        let bytes = text.as_bytes();
+        for max_distance in 0..=1u8 {
+            let builder = LevenshteinAutomatonBuilder::new(max_distance, false);
+            for query in &["寿司", "su", "司屋", "hi"] {
+                let dfa = builder.build_suffix_dfa(query);
+                assert_ne!(StartBytes::leaving_initial_state(&dfa), StartBytes::All);
+                let expected_ends: Vec<(usize, u8)> = (0..=bytes.len())
+                    .filter(|&end| text.is_char_boundary(end))
+                    .filter_map(|end| match dfa.eval(&bytes[..end]) {
+                        Distance::Exact(d) => Some((end, d)),
+                        Distance::AtLeast(_) => None,
+                    })
+                    .collect();
+                assert!(!expected_ends.is_empty());
+                assert_eq!(dfa.find_iter(text).collect::<Vec<_>>(), expected_ends);
+                for chunk_len in 1..=bytes.len() {
+                    let ends: Vec<(usize, u8)> =
+                        dfa.find_iter_chunks(bytes.chunks(chunk_len)).collect();
+                    assert_eq!(ends, expected_ends, "{} {}", query, chunk_len);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_chunks_through_accepting_state_zero() {
+        let mut dfa_builder = Utf8DFABuilder::with_max_num_states(2);
+        dfa_builder.add_state(0, Distance::Exact(1u8), 1);
+        dfa_builder.add_state(1, Distance::Exact(0u8), 0);
+        dfa_builder.set_initial_state(1u32);
+        let dfa = dfa_builder.build();
+        for text in &["a", "aab", "aあb", "❤❤a"] {
+            let bytes = text.as_bytes();
+            for chunk_len in 1..=bytes.len() {
+                let chunks: Vec<&[u8]> = bytes.chunks(chunk_len).collect();
+                assert_eq!(dfa.eval_chunks(&chunks), dfa.eval(text), "{}", text);
+            }
+        }
+    }
+
+    #[test]
+    fn test_find_iter_substrings() {
+        let text = "abcab acb bca";
+        for &(max_distance, transposition_cost_one) in &[(0, false), (1, true), (2, false)] {
+            let builder = LevenshteinAutomatonBuilder::new(max_distance, transposition_cost_one);
+            for query in &["abc", "ba", "cabx"] {
+                let substring_distance = |end: usize| {
+                    (0..=end)
+                        .map(|start| {
+                            if transposition_cost_one {
+                                damerau_levenshtein(query, &text[start..end])
+                            } else {
+                                levenshtein(query, &text[start..end])
+                            }
+                        })
+                        .min()
+                        .unwrap()
+                };
+                let expected: Vec<(usize, u8)> = (0..=text.len())
+                    .map(|end| (end, substring_distance(end)))
+                    .filter(|&(_, d)| d <= max_distance as usize)
+                    .map(|(end, d)| (end, d as u8))
+                    .collect();
+                let dfa = builder.build_suffix_dfa(query);
+                assert_eq!(dfa.find_iter(text).collect::<Vec<_>>(), expected);
+            }
+        }
+    }
+}
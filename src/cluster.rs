@@ -0,0 +1,186 @@
+use super::{Distance, LevenshteinAutomatonBuilder, TrigramIndex};
+
+/// Prefilter narrowing down the pairs of strings compared by a [Clusterer].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ClusterPrefilter {
+    /// All of the pairs are compared. Only reasonable for small sets.
+    None,
+    /// Only the pairs whose lengths differ by at most the distance are compared.
+    Length,
+    /// Only the pairs sharing enough trigrams are compared, using a [TrigramIndex].
+    ///
+    /// This is the most selective prefilter, except for short strings and large
+    /// distances, for which most pairs need to be compared anyway.
+    Trigrams,
+}
+
+/// Groups strings into the connected components of the
+/// "within distance `k`" relation.
+///
+/// Since the relation is not transitive, two members of a cluster may be
+/// much further apart than `k`, as long as they are linked by a chain of
+/// close strings.
+///
+/// ```rust
+/// # use levenshtein_automata::Clusterer;
+/// let names = ["Acme Corp", "ACME Corp", "Acme Corp.", "Globex", "Globex Inc", "Acme Corp"];
+/// let clusters = Clusterer::new(1, true).cluster(&names);
+/// assert_eq!(clusters, vec![vec![0, 2, 5], vec![1], vec![3], vec![4]]);
+/// ```
+pub struct Clusterer {
+    builder: LevenshteinAutomatonBuilder,
+    max_distance: u8,
+    prefilter: ClusterPrefilter,
+}
+
+impl Clusterer {
+    /// Creates a clusterer linking the strings within `max_distance` of each other.
+    ///
+    /// The default prefilter is [ClusterPrefilter::Trigrams].
+    pub fn new(max_distance: u8, transposition_cost_one: bool) -> Clusterer {
+        Clusterer {
+            builder: LevenshteinAutomatonBuilder::new(max_distance, transposition_cost_one),
+            max_distance,
+            prefilter: ClusterPrefilter::Trigrams,
+        }
+    }
+
+    /// Sets the prefilter. It only affects performance, not the clusters.
+    pub fn prefilter(mut self, prefilter: ClusterPrefilter) -> Clusterer {
+        self.prefilter = prefilter;
+        self
+    }
+
+    /// Returns the clusters, as lists of positions in `strings`.
+    ///
+    /// Clusters are sorted by their smallest position,
+    /// and positions within a cluster are sorted.
+    pub fn cluster<S: AsRef<str>>(&self, strings: &[S]) -> Vec<Vec<usize>> {
+        let mut components = UnionFind::new(strings.len());
+        match self.prefilter {
+            ClusterPrefilter::Trigrams => {
+                let index = TrigramIndex::new(strings);
+                for (id, string) in strings.iter().enumerate() {
+                    for (other_id, _) in index.lookup(&self.builder, string.as_ref()) {
+                        components.union(id, other_id as usize);
+                    }
+                }
+            }
+            ClusterPrefilter::None | ClusterPrefilter::Length => {
+                let lens: Vec<usize> = strings
+                    .iter()
+                    .map(|string| string.as_ref().chars().count())
+                    .collect();
+                for (id, string) in strings.iter().enumerate() {
+                    let dfa = self.builder.build_dfa(string.as_ref());
+                    for other_id in id + 1..strings.len() {
+                        if self.prefilter == ClusterPrefilter::Length
+                            && lens[id].max(lens[other_id]) - lens[id].min(lens[other_id])
+                                > self.max_distance as usize
+                        {
+                            continue;
+                        }
+                        if components.find(id) == components.find(other_id) {
+                            continue;
+                        }
+                        if let Distance::Exact(_) = dfa.eval(strings[other_id].as_ref()) {
+                            components.union(id, other_id);
+                        }
+                    }
+                }
+            }
+        }
+        components.components()
+    }
+}
+
+struct UnionFind {
+    parents: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(len: usize) -> UnionFind {
+        UnionFind {
+            parents: (0..len).collect(),
+        }
+    }
+
+    fn find(&mut self, mut id: usize) -> usize {
+        while self.parents[id] != id {
+            self.parents[id] = self.parents[self.parents[id]];
+            id = self.parents[id];
+        }
+        id
+    }
+
+    fn union(&mut self, left: usize, right: usize) {
+        let (left, right) = (self.find(left), self.find(right));
+        // The smallest id is the root, so that roots are met in order.
+        if left < right {
+            self.parents[right] = left;
+        } else {
+            self.parents[left] = right;
+        }
+    }
+
+    fn components(&mut self) -> Vec<Vec<usize>> {
+        let mut component_ids: Vec<Option<usize>> = vec![None; self.parents.len()];
+        let mut components: Vec<Vec<usize>> = Vec::new();
+        for id in 0..self.parents.len() {
+            let root = self.find(id);
+            let component_id = *component_ids[root].get_or_insert_with(|| {
+                components.push(Vec::new());
+                components.len() - 1
+            });
+            components[component_id].push(id);
+        }
+        components
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ClusterPrefilter, Clusterer};
+
+    #[test]
+    fn test_cluster_chains() {
+        // "abc" and "abcde" are at distance 2, but linked by "abcd".
+        let strings = ["abcde", "xyz", "abc", "", "abcd", "x"];
+        let clusterer = Clusterer::new(1, false);
+        assert_eq!(
+            clusterer.cluster(&strings),
+            vec![vec![0, 2, 4], vec![1], vec![3, 5]]
+        );
+        assert!(clusterer.cluster::<&str>(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_cluster_prefilters_agree() {
+        let strings = [
+            "jonathan",
+            "jonathon",
+            "johnathan",
+            "jon",
+            "john",
+            "joan",
+            "jean",
+            "寿司",
+            "寿し",
+            "",
+            "a",
+            "ab",
+            "ba",
+        ];
+        for &(max_distance, transposition_cost_one) in &[(0, false), (1, true), (2, false)] {
+            let expected = Clusterer::new(max_distance, transposition_cost_one)
+                .prefilter(ClusterPrefilter::None)
+                .cluster(&strings);
+            for &prefilter in &[ClusterPrefilter::Length, ClusterPrefilter::Trigrams] {
+                let clusters = Clusterer::new(max_distance, transposition_cost_one)
+                    .prefilter(prefilter)
+                    .cluster(&strings);
+                assert_eq!(clusters, expected);
+            }
+        }
+    }
+}
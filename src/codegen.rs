@@ -0,0 +1,146 @@
+use super::{Distance, DFA, SINK_STATE};
+use std::fmt::Write;
+
+impl DFA {
+    /// Emits the Rust source of a standalone function `name` evaluating
+    /// the automaton, as a `match` based state machine.
+    ///
+    /// The function has the signature `fn name(text: &[u8]) -> Option<u8>`,
+    /// and returns the distance of the texts accepted by the automaton,
+    /// or `None`. It does not depend on this crate, so that it can be
+    /// vendored into projects that cannot afford building the automaton
+    /// at runtime.
+    ///
+    /// `name` must be a valid Rust identifier.
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::LevenshteinAutomatonBuilder;
+    /// let dfa = LevenshteinAutomatonBuilder::new(1, true).build_dfa("cat");
+    /// let source = dfa.codegen("is_cat");
+    /// assert!(source.contains("pub fn is_cat(text: &[u8]) -> Option<u8> {"));
+    /// ```
+    pub fn codegen(&self, name: &str) -> String {
+        let mut source = String::new();
+        writeln!(
+            source,
+            "// Generated by levenshtein_automata from an automaton with {} states.",
+            self.num_states()
+        )
+        .unwrap();
+        source.push('\n');
+        writeln!(
+            source,
+            "/// Returns the distance of `text`, or `None` if it is not accepted."
+        )
+        .unwrap();
+        writeln!(source, "pub fn {}(text: &[u8]) -> Option<u8> {{", name).unwrap();
+        writeln!(source, "    let mut state: u32 = {};", self.initial_state()).unwrap();
+        writeln!(source, "    for &b in text {{").unwrap();
+        writeln!(source, "        state = match state {{").unwrap();
+        for state_id in self.states() {
+            let edges: Vec<_> = self
+                .edges(state_id)
+                .filter(|edge| edge.to != SINK_STATE)
+                .collect();
+            if edges.is_empty() {
+                continue;
+            }
+            writeln!(source, "            {} => match b {{", state_id).unwrap();
+            for edge in &edges {
+                let pattern = if edge.start == edge.end {
+                    rust_byte(edge.start)
+                } else {
+                    format!("{}..={}", rust_byte(edge.start), rust_byte(edge.end))
+                };
+                writeln!(source, "                {} => {},", pattern, edge.to).unwrap();
+            }
+            // A catch-all arm after exhaustive ranges would be unreachable.
+            if edges.len() < self.edges(state_id).count() {
+                writeln!(source, "                _ => {},", SINK_STATE).unwrap();
+            }
+            writeln!(source, "            }},").unwrap();
+        }
+        writeln!(source, "            _ => {},", SINK_STATE).unwrap();
+        writeln!(source, "        }};").unwrap();
+        if !self.can_match(SINK_STATE) {
+            writeln!(source, "        if state == {} {{", SINK_STATE).unwrap();
+            writeln!(source, "            return None;").unwrap();
+            writeln!(source, "        }}").unwrap();
+        }
+        writeln!(source, "    }}").unwrap();
+        writeln!(source, "    match state {{").unwrap();
+        for state_id in self.states() {
+            if let Distance::Exact(d) = self.distance(state_id) {
+                writeln!(source, "        {} => Some({}),", state_id, d).unwrap();
+            }
+        }
+        writeln!(source, "        _ => None,").unwrap();
+        writeln!(source, "    }}").unwrap();
+        source.push_str("}\n");
+        source
+    }
+}
+
+// Renders a byte as a Rust byte literal, in hexadecimal unless it is a
+// printable ASCII char.
+fn rust_byte(b: u8) -> String {
+    match b {
+        b' '..=b'~' if b != b'\'' && b != b'\\' => format!("b'{}'", b as char),
+        _ => format!("0x{:02X}", b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rust_byte;
+    use crate::{Distance, ExhaustiveVerifier, LevenshteinAutomatonBuilder};
+
+    // Output of `codegen("is_ae")` for the automaton of `"aé"`, checked in
+    // so that the generated source is compiled and run by the tests.
+    mod generated {
+        include!("codegen/is_ae.rs");
+    }
+
+    #[test]
+    fn test_rust_byte() {
+        assert_eq!(rust_byte(b'a'), "b'a'");
+        assert_eq!(rust_byte(b' '), "b' '");
+        assert_eq!(rust_byte(b'\''), "0x27");
+        assert_eq!(rust_byte(b'\\'), "0x5C");
+        assert_eq!(rust_byte(0xE5), "0xE5");
+    }
+
+    #[test]
+    fn test_codegen() {
+        let dfa = LevenshteinAutomatonBuilder::new(0, false).build_dfa("ab");
+        let source = dfa.codegen("is_ab");
+        assert!(source.contains("pub fn is_ab(text: &[u8]) -> Option<u8> {"));
+        assert!(source.contains(&format!("let mut state: u32 = {};", dfa.initial_state())));
+        let after_a = dfa.transition(dfa.initial_state(), b'a');
+        assert!(source.contains(&format!("b'a' => {},", after_a)));
+        let accepting = dfa.transition(after_a, b'b');
+        assert!(source.contains(&format!("{} => Some(0),", accepting)));
+        assert_eq!(source.matches("Some(").count(), 1);
+        assert!(source.contains("return None;"));
+    }
+
+    #[test]
+    fn test_generated_source_matches_eval() {
+        let dfa = LevenshteinAutomatonBuilder::new(1, true).build_dfa("aé");
+        assert_eq!(dfa.codegen("is_ae"), include_str!("codegen/is_ae.rs"));
+        for text in ExhaustiveVerifier::new("aeé寿", 4, 1, true).strings() {
+            let expected = match dfa.eval(&text) {
+                Distance::Exact(d) => Some(d),
+                Distance::AtLeast(_) => None,
+            };
+            assert_eq!(generated::is_ae(text.as_bytes()), expected, "{}", text);
+        }
+        for bytes in [&b"\xC3"[..], b"a\xC3", b"\xA9a", b"a\xFF\xC3\xA9"] {
+            let expected = match dfa.eval(bytes) {
+                Distance::Exact(d) => Some(d),
+                Distance::AtLeast(_) => None,
+            };
+            assert_eq!(generated::is_ae(bytes), expected, "{:?}", bytes);
+        }
+    }
+}
@@ -0,0 +1,161 @@
+// Generated by levenshtein_automata from an automaton with 31 states.
+
+/// Returns the distance of `text`, or `None` if it is not accepted.
+pub fn is_ae(text: &[u8]) -> Option<u8> {
+    let mut state: u32 = 1;
+    for &b in text {
+        state = match state {
+            1 => match b {
+                0x00..=b'`' => 2,
+                b'a' => 6,
+                b'b'..=0xBF => 2,
+                0xC0..=0xC2 => 3,
+                0xC3 => 8,
+                0xC4..=0xDF => 3,
+                0xE0..=0xEF => 4,
+                0xF0..=0xFF => 5,
+            },
+            2 => match b {
+                b'a' => 12,
+                0xC0..=0xC2 => 9,
+                0xC3 => 14,
+                0xC4..=0xDF => 9,
+                0xE0..=0xEF => 10,
+                0xF0..=0xFF => 11,
+                _ => 0,
+            },
+            3 => match b {
+                0x00..=0xFF => 2,
+            },
+            4 => match b {
+                0x00..=0xFF => 3,
+            },
+            5 => match b {
+                0x00..=0xFF => 4,
+            },
+            6 => match b {
+                0x00..=0xBF => 15,
+                0xC0..=0xC2 => 16,
+                0xC3 => 20,
+                0xC4..=0xDF => 16,
+                0xE0..=0xEF => 17,
+                0xF0..=0xFF => 18,
+            },
+            7 => match b {
+                b'a' => 15,
+                0xC0..=0xC2 => 9,
+                0xC3 => 21,
+                0xC4..=0xDF => 9,
+                0xE0..=0xEF => 10,
+                0xF0..=0xFF => 11,
+                _ => 0,
+            },
+            8 => match b {
+                0x00..=0xA8 => 2,
+                0xA9 => 7,
+                0xAA..=0xFF => 2,
+            },
+            10 => match b {
+                0x00..=0xFF => 9,
+            },
+            11 => match b {
+                0x00..=0xFF => 10,
+            },
+            12 => match b {
+                0xC0..=0xC2 => 9,
+                0xC3 => 22,
+                0xC4..=0xDF => 9,
+                0xE0..=0xEF => 10,
+                0xF0..=0xFF => 11,
+                _ => 0,
+            },
+            13 => match b {
+                0xC0..=0xC2 => 9,
+                0xC3 => 23,
+                0xC4..=0xDF => 9,
+                0xE0..=0xEF => 10,
+                0xF0..=0xFF => 11,
+                _ => 0,
+            },
+            14 => match b {
+                0xA9 => 13,
+                _ => 0,
+            },
+            15 => match b {
+                0xC0..=0xC2 => 9,
+                0xC3 => 24,
+                0xC4..=0xDF => 9,
+                0xE0..=0xEF => 10,
+                0xF0..=0xFF => 11,
+                _ => 0,
+            },
+            16 => match b {
+                0x00..=0xFF => 15,
+            },
+            17 => match b {
+                0x00..=0xFF => 16,
+            },
+            18 => match b {
+                0x00..=0xFF => 17,
+            },
+            19 => match b {
+                0x00..=0xBF => 25,
+                0xC0..=0xC2 => 26,
+                0xC3 => 29,
+                0xC4..=0xDF => 26,
+                0xE0..=0xEF => 27,
+                0xF0..=0xFF => 28,
+            },
+            20 => match b {
+                0x00..=0xA8 => 15,
+                0xA9 => 19,
+                0xAA..=0xFF => 15,
+            },
+            21 => match b {
+                0xA9 => 13,
+                _ => 0,
+            },
+            22 => match b {
+                0xA9 => 13,
+                _ => 0,
+            },
+            24 => match b {
+                0xA9 => 13,
+                _ => 0,
+            },
+            25 => match b {
+                0xC0..=0xC2 => 9,
+                0xC3 => 30,
+                0xC4..=0xDF => 9,
+                0xE0..=0xEF => 10,
+                0xF0..=0xFF => 11,
+                _ => 0,
+            },
+            26 => match b {
+                0x00..=0xFF => 25,
+            },
+            27 => match b {
+                0x00..=0xFF => 26,
+            },
+            28 => match b {
+                0x00..=0xFF => 27,
+            },
+            29 => match b {
+                0x00..=0xFF => 25,
+            },
+            _ => 0,
+        };
+        if state == 0 {
+            return None;
+        }
+    }
+    match state {
+        6 => Some(1),
+        7 => Some(1),
+        13 => Some(1),
+        15 => Some(1),
+        19 => Some(0),
+        25 => Some(1),
+        _ => None,
+    }
+}
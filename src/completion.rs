@@ -0,0 +1,195 @@
+use super::{Distance, LevenshteinAutomatonBuilder, DFA};
+
+/// Splits an identifier into its `snake_case`, `kebab-case` and
+/// `camelCase` words.
+///
+/// Acronyms are kept together, so `HTTPServer` yields `["HTTP", "Server"]`.
+pub fn identifier_tokens(identifier: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let chars: Vec<(usize, char)> = identifier.char_indices().collect();
+    let mut start: Option<usize> = None;
+    for (i, &(offset, chr)) in chars.iter().enumerate() {
+        if !chr.is_alphanumeric() {
+            if let Some(token_start) = start.take() {
+                tokens.push(&identifier[token_start..offset]);
+            }
+            continue;
+        }
+        if let Some(token_start) = start {
+            let prev = chars[i - 1].1;
+            let next_is_lower = chars.get(i + 1).map(|&(_, c)| c.is_lowercase());
+            let boundary = chr.is_uppercase()
+                && (prev.is_lowercase()
+                    || prev.is_numeric()
+                    || (prev.is_uppercase() && next_is_lower == Some(true)));
+            if boundary {
+                tokens.push(&identifier[token_start..offset]);
+                start = Some(offset);
+            }
+        } else {
+            start = Some(offset);
+        }
+    }
+    if let Some(token_start) = start {
+        tokens.push(&identifier[token_start..]);
+    }
+    tokens
+}
+
+/// Score of a completion candidate. Lower is better.
+///
+/// Scores are ordered by total edit distance first, and then by
+/// the number of candidate words that had to be skipped.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct CompletionScore {
+    /// Sum of the prefix distances of every query word.
+    pub distance: u32,
+    /// Number of candidate words skipped before the last matched word.
+    pub skipped_tokens: u32,
+}
+
+/// Ranks identifier completions in a typo tolerant manner.
+///
+/// Both the query and the candidates are split using [identifier_tokens].
+/// Every query word must then match, in order, the prefix of a candidate
+/// word within `max_distance`. Comparisons are case insensitive.
+///
+/// ```rust
+/// # use levenshtein_automata::CompletionRanker;
+/// let ranker = CompletionRanker::new(1, true);
+/// let query = ranker.prepare("getUsre");
+/// let ranked = query.rank(&["get_user_name", "getUrl", "set_user"]);
+/// assert_eq!(ranked[0].0, "get_user_name");
+/// ```
+pub struct CompletionRanker {
+    builder: LevenshteinAutomatonBuilder,
+}
+
+impl CompletionRanker {
+    /// Creates a new ranker.
+    ///
+    /// `max_distance` is the typo budget of each query word.
+    pub fn new(max_distance: u8, transposition_cost_one: bool) -> CompletionRanker {
+        CompletionRanker {
+            builder: LevenshteinAutomatonBuilder::new(max_distance, transposition_cost_one),
+        }
+    }
+
+    /// Builds the automata for the words of the `query`.
+    pub fn prepare(&self, query: &str) -> CompletionQuery {
+        let token_dfas = identifier_tokens(query)
+            .into_iter()
+            .map(|token| self.builder.build_prefix_dfa(&token.to_lowercase()))
+            .collect();
+        CompletionQuery { token_dfas }
+    }
+}
+
+/// A query prepared by a [CompletionRanker].
+pub struct CompletionQuery {
+    token_dfas: Vec<DFA>,
+}
+
+impl CompletionQuery {
+    /// Scores a candidate identifier, returning `None` if it does not match.
+    pub fn score(&self, candidate: &str) -> Option<CompletionScore> {
+        let candidate_tokens: Vec<String> = identifier_tokens(candidate)
+            .into_iter()
+            .map(str::to_lowercase)
+            .collect();
+        // best[j] is the best score after matching the query words processed so far,
+        // the last of them being matched with the candidate word `j - 1`.
+        let mut best: Vec<Option<CompletionScore>> = vec![None; candidate_tokens.len() + 1];
+        best[0] = Some(CompletionScore {
+            distance: 0,
+            skipped_tokens: 0,
+        });
+        for dfa in &self.token_dfas {
+            let mut next: Vec<Option<CompletionScore>> = vec![None; candidate_tokens.len() + 1];
+            for (start, score) in best.iter().enumerate() {
+                let score = match *score {
+                    Some(score) => score,
+                    None => continue,
+                };
+                for (j, token) in candidate_tokens.iter().enumerate().skip(start) {
+                    if let Distance::Exact(d) = dfa.eval(token) {
+                        let candidate_score = CompletionScore {
+                            distance: score.distance + u32::from(d),
+                            skipped_tokens: score.skipped_tokens + (j - start) as u32,
+                        };
+                        if next[j + 1].map(|s| candidate_score < s).unwrap_or(true) {
+                            next[j + 1] = Some(candidate_score);
+                        }
+                    }
+                }
+            }
+            best = next;
+        }
+        best.into_iter().flatten().min()
+    }
+
+    /// Scores all of the `candidates` and returns the matching ones, best first.
+    pub fn rank<'a>(&self, candidates: &[&'a str]) -> Vec<(&'a str, CompletionScore)> {
+        let mut ranked: Vec<(&'a str, CompletionScore)> = candidates
+            .iter()
+            .filter_map(|&candidate| self.score(candidate).map(|score| (candidate, score)))
+            .collect();
+        ranked.sort_by_key(|&(candidate, score)| (score, candidate.len()));
+        ranked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{identifier_tokens, CompletionRanker, CompletionScore};
+
+    #[test]
+    fn test_identifier_tokens() {
+        assert_eq!(
+            identifier_tokens("get_user_name"),
+            vec!["get", "user", "name"]
+        );
+        assert_eq!(
+            identifier_tokens("getUserName"),
+            vec!["get", "User", "Name"]
+        );
+        assert_eq!(identifier_tokens("HTTPServer"), vec!["HTTP", "Server"]);
+        assert_eq!(identifier_tokens("__init__"), vec!["init"]);
+        assert_eq!(identifier_tokens("utf8Decode"), vec!["utf8", "Decode"]);
+        assert!(identifier_tokens("").is_empty());
+    }
+
+    #[test]
+    fn test_completion_score() {
+        let ranker = CompletionRanker::new(1, true);
+        let query = ranker.prepare("getUsr");
+        assert_eq!(
+            query.score("get_user_name"),
+            Some(CompletionScore {
+                distance: 1,
+                skipped_tokens: 0,
+            })
+        );
+        assert_eq!(
+            query.score("getTheUser"),
+            Some(CompletionScore {
+                distance: 1,
+                skipped_tokens: 1,
+            })
+        );
+        assert_eq!(query.score("userGet"), None);
+        assert_eq!(query.score("fetch"), None);
+    }
+
+    #[test]
+    fn test_completion_rank() {
+        let ranker = CompletionRanker::new(1, false);
+        let query = ranker.prepare("user");
+        let ranked: Vec<&str> = query
+            .rank(&["getUser", "UserName", "User", "usr", "item"])
+            .into_iter()
+            .map(|(candidate, _)| candidate)
+            .collect();
+        assert_eq!(ranked, vec!["User", "UserName", "getUser", "usr"]);
+    }
+}
@@ -0,0 +1,135 @@
+use super::{LevenshteinAutomatonBuilder, Trie};
+
+/// Node of a dictionary from which all of the completions match a typed prefix
+/// with the same distance, as returned by
+/// [LevenshteinAutomatonBuilder::prefix_continuations](./struct.LevenshteinAutomatonBuilder.html#method.prefix_continuations).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Continuation<N> {
+    /// Node of the dictionary.
+    pub node: N,
+    /// Path from the root of the dictionary to `node`.
+    pub prefix: String,
+    /// Distance between the typed prefix and `prefix`, and therefore
+    /// between the typed prefix and all of the completions of `node`.
+    pub distance: u8,
+    /// Number of edits that are still allowed, i.e. `max_distance - distance`.
+    pub remaining_budget: u8,
+}
+
+impl LevenshteinAutomatonBuilder {
+    /// Returns the frontier of the nodes of `trie` at which the prefix
+    /// automaton of `typed` has matched the whole typed prefix, along with the
+    /// remaining edit budget.
+    ///
+    /// The walk stops at these nodes: reading more chars cannot change the
+    /// distance anymore, so that an autocomplete engine can take over and
+    /// enumerate or rank the completions below each node with its own index,
+    /// without running the automaton. Nodes are returned in lexicographic order,
+    /// and none of them is a descendant of another.
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::{Dawg, LevenshteinAutomatonBuilder};
+    /// let dawg = Dawg::from_sorted_words(&["program", "progress", "project", "prose"]).unwrap();
+    /// let builder = LevenshteinAutomatonBuilder::new(1, true);
+    /// let frontier: Vec<(String, u8)> = builder
+    ///     .prefix_continuations(&dawg, "porg")
+    ///     .into_iter()
+    ///     .map(|continuation| (continuation.prefix, continuation.distance))
+    ///     .collect();
+    /// assert_eq!(frontier, vec![("prog".to_string(), 1)]);
+    /// ```
+    pub fn prefix_continuations<T: Trie>(
+        &self,
+        trie: &T,
+        typed: &str,
+    ) -> Vec<Continuation<T::Node>> {
+        let dfa = self.build_prefix_dfa(typed);
+        let live_states = dfa.live_states();
+        let settled_distances = dfa.settled_distances();
+        let mut continuations = Vec::new();
+        let initial_state = dfa.initial_state();
+        if !live_states[initial_state as usize] {
+            return continuations;
+        }
+        let mut stack = vec![(trie.root(), initial_state, Vec::new())];
+        while let Some((node, state, path)) = stack.pop() {
+            if let Some(distance) = settled_distances[state as usize] {
+                continuations.push(Continuation {
+                    node,
+                    prefix: String::from_utf8(path)
+                        .expect("The automaton only accepts valid utf-8"),
+                    distance,
+                    remaining_budget: self.max_distance - distance,
+                });
+                continue;
+            }
+            // Children are pushed in reverse order, so that they are popped in order.
+            for (b, child) in trie.children(&node).into_iter().rev() {
+                let child_state = dfa.transition(state, b);
+                if live_states[child_state as usize] {
+                    let mut child_path = path.clone();
+                    child_path.push(b);
+                    stack.push((child, child_state, child_path));
+                }
+            }
+        }
+        continuations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Dawg, Distance, LevenshteinAutomatonBuilder};
+
+    #[test]
+    fn test_prefix_continuations() {
+        let words = [
+            "",
+            "a",
+            "ab",
+            "abc",
+            "abcd",
+            "abd",
+            "acb",
+            "bcd",
+            "寿司",
+            "寿司屋",
+            "寿屋",
+        ];
+        let dawg = Dawg::from_sorted_words(&words).unwrap();
+        for &(max_distance, transposition_cost_one) in &[(0, false), (1, true), (1, false)] {
+            let builder = LevenshteinAutomatonBuilder::new(max_distance, transposition_cost_one);
+            for typed in &["", "ab", "abc", "寿司", "x"] {
+                let dfa = builder.build_prefix_dfa(typed);
+                let continuations = builder.prefix_continuations(&dawg, typed);
+                // The completions of the frontier have the distance of the frontier.
+                for word in &words {
+                    let continuation = continuations
+                        .iter()
+                        .find(|continuation| word.starts_with(&continuation.prefix));
+                    if let Some(continuation) = continuation {
+                        assert_eq!(dfa.eval(word), Distance::Exact(continuation.distance));
+                        assert_eq!(
+                            continuation.remaining_budget,
+                            max_distance - continuation.distance
+                        );
+                    }
+                }
+                let prefixes: Vec<&str> = continuations
+                    .iter()
+                    .map(|continuation| continuation.prefix.as_str())
+                    .collect();
+                let mut sorted_prefixes = prefixes.clone();
+                sorted_prefixes.sort_unstable();
+                assert_eq!(prefixes, sorted_prefixes);
+            }
+        }
+        let builder = LevenshteinAutomatonBuilder::new(1, true);
+        let prefixes: Vec<String> = builder
+            .prefix_continuations(&dawg, "abc")
+            .into_iter()
+            .map(|continuation| continuation.prefix)
+            .collect();
+        assert_eq!(prefixes, vec!["abc", "abd", "ac", "bc"]);
+    }
+}
@@ -0,0 +1,347 @@
+use super::{Distance, DFA};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Error returned when the words given to a [DawgBuilder] are not
+/// sorted in strictly increasing lexicographic order.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OutOfOrderWord {
+    /// The word that was inserted last.
+    pub previous: String,
+    /// The word that could not be inserted.
+    pub word: String,
+}
+
+impl fmt::Display for OutOfOrderWord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the word {:?} is not greater than the previous word {:?}",
+            self.word, self.previous
+        )
+    }
+}
+
+impl std::error::Error for OutOfOrderWord {}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+struct DawgState {
+    is_final: bool,
+    // Sorted by byte.
+    transitions: Vec<(u8, u32)>,
+}
+
+/// Builds a [Dawg] from a sorted list of words.
+///
+/// Words are added in increasing order, and the parts of the automaton that
+/// cannot change anymore are minimized as soon as possible, following
+/// Daciuk et al., so that memory stays proportional to the size of the
+/// minimal automaton.
+pub struct DawgBuilder {
+    states: Vec<DawgState>,
+    // Minimized states, which are final.
+    register: HashMap<DawgState, u32>,
+    // Path of the last word that has not been minimized yet,
+    // as `(parent, child)` edges.
+    unchecked: Vec<(u32, u32)>,
+    previous_word: Option<Vec<u8>>,
+}
+
+impl Default for DawgBuilder {
+    fn default() -> DawgBuilder {
+        DawgBuilder::new()
+    }
+}
+
+impl DawgBuilder {
+    /// Creates an empty builder.
+    pub fn new() -> DawgBuilder {
+        DawgBuilder {
+            states: vec![DawgState::default()],
+            register: HashMap::new(),
+            unchecked: Vec::new(),
+            previous_word: None,
+        }
+    }
+
+    /// Adds a word.
+    ///
+    /// Returns an error if `word` is not greater than the previously added word.
+    pub fn insert(&mut self, word: &str) -> Result<(), OutOfOrderWord> {
+        let word = word.as_bytes();
+        let common_prefix_len = match &self.previous_word {
+            Some(previous_word) => {
+                if word <= &previous_word[..] {
+                    return Err(OutOfOrderWord {
+                        previous: String::from_utf8(previous_word.clone()).unwrap(),
+                        word: String::from_utf8(word.to_vec()).unwrap(),
+                    });
+                }
+                previous_word
+                    .iter()
+                    .zip(word)
+                    .take_while(|(left, right)| left == right)
+                    .count()
+            }
+            None => 0,
+        };
+        self.minimize(common_prefix_len);
+        let mut state = self.unchecked.last().map(|&(_, child)| child).unwrap_or(0);
+        for &b in &word[common_prefix_len..] {
+            let child = self.states.len() as u32;
+            self.states.push(DawgState::default());
+            self.states[state as usize].transitions.push((b, child));
+            self.unchecked.push((state, child));
+            state = child;
+        }
+        self.states[state as usize].is_final = true;
+        self.previous_word = Some(word.to_vec());
+        Ok(())
+    }
+
+    // Replaces the states of the unchecked path deeper than `depth`
+    // by their equivalent registered states.
+    fn minimize(&mut self, depth: usize) {
+        while self.unchecked.len() > depth {
+            let (parent, child) = self.unchecked.pop().unwrap();
+            let child_state = self.states[child as usize].clone();
+            match self.register.get(&child_state) {
+                Some(&equivalent) => {
+                    // `child` is the last child of `parent`.
+                    self.states[parent as usize]
+                        .transitions
+                        .last_mut()
+                        .unwrap()
+                        .1 = equivalent;
+                }
+                None => {
+                    self.register.insert(child_state, child);
+                }
+            }
+        }
+    }
+
+    /// Minimizes the remaining states and returns the automaton.
+    pub fn finish(mut self) -> Dawg {
+        self.minimize(0);
+        // Renumber the reachable states, dropping the ones that were replaced.
+        let mut ids: Vec<Option<u32>> = vec![None; self.states.len()];
+        let mut order = vec![0u32];
+        ids[0] = Some(0);
+        let mut i = 0;
+        while i < order.len() {
+            let state = order[i];
+            for &(_, to) in &self.states[state as usize].transitions {
+                if ids[to as usize].is_none() {
+                    ids[to as usize] = Some(order.len() as u32);
+                    order.push(to);
+                }
+            }
+            i += 1;
+        }
+        let states = order
+            .iter()
+            .map(|&state| {
+                let state = &self.states[state as usize];
+                DawgState {
+                    is_final: state.is_final,
+                    transitions: state
+                        .transitions
+                        .iter()
+                        .map(|&(b, to)| (b, ids[to as usize].unwrap()))
+                        .collect(),
+                }
+            })
+            .collect();
+        Dawg { states }
+    }
+}
+
+/// Directed acyclic word graph, i.e. minimal acyclic automaton
+/// recognizing a set of words.
+///
+/// It is a lighter-weight alternative to an `fst::Set`: common prefixes and
+/// suffixes are shared, and it can be intersected with a Levenshtein [DFA]
+/// to retrieve the words within a given distance.
+///
+/// ```rust
+/// # use levenshtein_automata::{Dawg, LevenshteinAutomatonBuilder};
+/// let dawg = Dawg::from_sorted_words(&["tap", "taps", "top", "tops"]).unwrap();
+/// assert_eq!(dawg.num_states(), 5);
+/// let dfa = LevenshteinAutomatonBuilder::new(1, false).build_dfa("tip");
+/// assert_eq!(
+///     dawg.search(&dfa),
+///     vec![("tap".to_string(), 1), ("top".to_string(), 1)]
+/// );
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Dawg {
+    states: Vec<DawgState>,
+}
+
+impl Dawg {
+    /// Builds the automaton of words sorted in strictly increasing order.
+    pub fn from_sorted_words<S: AsRef<str>>(words: &[S]) -> Result<Dawg, OutOfOrderWord> {
+        let mut builder = DawgBuilder::new();
+        for word in words {
+            builder.insert(word.as_ref())?;
+        }
+        Ok(builder.finish())
+    }
+
+    /// Returns the number of states. The initial state is `0`.
+    pub fn num_states(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Returns true if the automaton contains `word`.
+    pub fn contains(&self, word: &str) -> bool {
+        let mut state = 0u32;
+        for b in word.bytes() {
+            let transitions = &self.states[state as usize].transitions;
+            match transitions.binary_search_by_key(&b, |&(b, _)| b) {
+                Ok(i) => state = transitions[i].1,
+                Err(_) => return false,
+            }
+        }
+        self.states[state as usize].is_final
+    }
+
+    /// Returns all of the words, in lexicographic order.
+    pub fn words(&self) -> Vec<String> {
+        let mut words = Vec::new();
+        self.walk(0, &mut Vec::new(), &mut |word, is_final| {
+            if is_final {
+                words.push(String::from_utf8(word.to_vec()).unwrap());
+            }
+            true
+        });
+        words
+    }
+
+    /// Returns the words accepted by `dfa` along with their distance,
+    /// in lexicographic order.
+    ///
+    /// Both automata are walked together; the branches from which the
+    /// DFA cannot match anymore are pruned.
+    pub fn search(&self, dfa: &DFA) -> Vec<(String, u8)> {
+        let live_states = dfa.live_states();
+        let mut matches = Vec::new();
+        let mut dfa_states = vec![dfa.initial_state()];
+        if !live_states[dfa_states[0] as usize] {
+            return matches;
+        }
+        self.walk(0, &mut Vec::new(), &mut |word, is_final| {
+            // `dfa_states[i]` is the state reached on the first `i` bytes of `word`.
+            dfa_states.truncate(word.len().max(1));
+            if let Some(&b) = word.last() {
+                let state = dfa.transition(dfa_states[word.len() - 1], b);
+                if !live_states[state as usize] {
+                    return false;
+                }
+                dfa_states.push(state);
+            }
+            if is_final {
+                if let Distance::Exact(d) = dfa.distance(dfa_states[word.len()]) {
+                    matches.push((String::from_utf8(word.to_vec()).unwrap(), d));
+                }
+            }
+            true
+        });
+        matches
+    }
+
+    pub(crate) fn is_final_state(&self, state: u32) -> bool {
+        self.states[state as usize].is_final
+    }
+
+    pub(crate) fn state_transitions(&self, state: u32) -> &[(u8, u32)] {
+        &self.states[state as usize].transitions
+    }
+
+    // Depth-first traversal in lexicographic order. `visit` is called with
+    // the path to each state and whether it is final, and returns false
+    // to skip the descendants of the state.
+    fn walk<F: FnMut(&[u8], bool) -> bool>(&self, state: u32, path: &mut Vec<u8>, visit: &mut F) {
+        let state = &self.states[state as usize];
+        if !visit(path, state.is_final) {
+            return;
+        }
+        for &(b, to) in &state.transitions {
+            path.push(b);
+            self.walk(to, path, visit);
+            path.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Dawg, DawgBuilder, OutOfOrderWord};
+    use crate::{Distance, LevenshteinAutomatonBuilder};
+
+    #[test]
+    fn test_dawg_is_minimal() {
+        let dawg = Dawg::from_sorted_words(&["", "ab", "abc", "bc", "bd", "cbc", "cbd"]).unwrap();
+        assert_eq!(
+            dawg.words(),
+            vec!["", "ab", "abc", "bc", "bd", "cbc", "cbd"]
+        );
+        assert!(dawg.contains(""));
+        assert!(dawg.contains("cbd"));
+        assert!(!dawg.contains("cb"));
+        // The initial state, `a`, `ab`, `c`, the state shared by `b` and `cb`,
+        // and the final state.
+        assert_eq!(dawg.num_states(), 6);
+        let empty = Dawg::from_sorted_words::<&str>(&[]).unwrap();
+        assert!(empty.words().is_empty());
+        assert_eq!(empty.num_states(), 1);
+    }
+
+    #[test]
+    fn test_dawg_out_of_order() {
+        let mut builder = DawgBuilder::new();
+        builder.insert("b").unwrap();
+        assert_eq!(
+            builder.insert("a"),
+            Err(OutOfOrderWord {
+                previous: "b".to_string(),
+                word: "a".to_string()
+            })
+        );
+        assert!(builder.insert("b").is_err());
+        assert!(builder.insert("ba").is_ok());
+    }
+
+    #[test]
+    fn test_dawg_search() {
+        let mut words = vec![
+            "",
+            "a",
+            "abc",
+            "abd",
+            "bcd",
+            "寿司",
+            "寿司屋",
+            "levenshtein",
+            "levenstein",
+            "abdc",
+        ];
+        words.sort_unstable();
+        let dawg = Dawg::from_sorted_words(&words).unwrap();
+        for &(max_distance, transposition_cost_one) in &[(0, false), (1, true), (2, false)] {
+            let builder = LevenshteinAutomatonBuilder::new(max_distance, transposition_cost_one);
+            for query in &["", "abc", "寿司", "levenshtain", "xyz"] {
+                let dfa = builder.build_dfa(query);
+                let expected: Vec<(String, u8)> = words
+                    .iter()
+                    .filter_map(|word| match dfa.eval(word) {
+                        Distance::Exact(d) => Some((word.to_string(), d)),
+                        Distance::AtLeast(_) => None,
+                    })
+                    .collect();
+                assert_eq!(dawg.search(&dfa), expected);
+            }
+        }
+    }
+}
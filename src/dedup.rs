@@ -0,0 +1,141 @@
+use super::{Distance, LevenshteinAutomatonBuilder, TrigramIndex, DFA};
+
+/// Approximate deduplication of strings.
+///
+/// Strings are processed in order, and each string within the distance of
+/// an already kept representative is dropped. Representatives are
+/// therefore the first occurrence of each group of near-duplicates.
+///
+/// By default, a string is compared to all of the representatives, narrowed down
+/// with a trigram prefilter. In consecutive mode, it is only compared to the
+/// last representative, like `uniq` ignoring small differences.
+///
+/// ```rust
+/// # use levenshtein_automata::Deduplicator;
+/// let logs = ["connection reset", "connection reset!", "timeout", "Connection reset"];
+/// let deduplicator = Deduplicator::new(1, true);
+/// assert_eq!(deduplicator.dedup(logs.iter().cloned()), vec!["connection reset", "timeout"]);
+/// let consecutive = Deduplicator::new(1, true).consecutive(true);
+/// assert_eq!(
+///     consecutive.dedup(logs.iter().cloned()),
+///     vec!["connection reset", "timeout", "Connection reset"]
+/// );
+/// ```
+pub struct Deduplicator {
+    builder: LevenshteinAutomatonBuilder,
+    consecutive: bool,
+}
+
+impl Deduplicator {
+    /// Creates a deduplicator dropping the strings within `max_distance`
+    /// of a representative.
+    pub fn new(max_distance: u8, transposition_cost_one: bool) -> Deduplicator {
+        Deduplicator {
+            builder: LevenshteinAutomatonBuilder::new(max_distance, transposition_cost_one),
+            consecutive: false,
+        }
+    }
+
+    /// If true, strings are only compared to the last representative.
+    pub fn consecutive(mut self, consecutive: bool) -> Deduplicator {
+        self.consecutive = consecutive;
+        self
+    }
+
+    /// Returns the representatives of `strings`, in order.
+    pub fn dedup<I>(&self, strings: I) -> Vec<I::Item>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        if self.consecutive {
+            self.dedup_consecutive(strings)
+        } else {
+            self.dedup_all(strings)
+        }
+    }
+
+    fn dedup_consecutive<I>(&self, strings: I) -> Vec<I::Item>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut representatives = Vec::new();
+        // Automaton of the last representative, built once per group.
+        let mut last_dfa: Option<DFA> = None;
+        for string in strings {
+            if let Some(dfa) = &last_dfa {
+                if let Distance::Exact(_) = dfa.eval(string.as_ref()) {
+                    continue;
+                }
+            }
+            last_dfa = Some(self.builder.build_dfa(string.as_ref()));
+            representatives.push(string);
+        }
+        representatives
+    }
+
+    fn dedup_all<I>(&self, strings: I) -> Vec<I::Item>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut representatives = Vec::new();
+        let mut index = TrigramIndex::default();
+        for string in strings {
+            if index.lookup(&self.builder, string.as_ref()).is_empty() {
+                index.insert(string.as_ref());
+                representatives.push(string);
+            }
+        }
+        representatives
+    }
+}
+
+/// Shortcut for [Deduplicator::dedup], comparing each string with all of
+/// the representatives, and without transpositions.
+///
+/// Creating a [Deduplicator] is not free: reuse one to deduplicate
+/// several collections.
+pub fn dedup_within<I>(strings: I, max_distance: u8) -> Vec<I::Item>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    Deduplicator::new(max_distance, false).dedup(strings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dedup_within, Deduplicator};
+
+    #[test]
+    fn test_dedup_within() {
+        let records = vec![
+            "Jonathan Smith".to_string(),
+            "Jonathon Smith".to_string(),
+            "Jane Doe".to_string(),
+            "Jonathan Smyth".to_string(),
+            "Jane Do".to_string(),
+            "J. Smith".to_string(),
+        ];
+        assert_eq!(
+            dedup_within(records.clone(), 1),
+            vec!["Jonathan Smith", "Jane Doe", "J. Smith"]
+        );
+        assert_eq!(dedup_within(records.clone(), 0), records);
+        assert!(dedup_within(Vec::<String>::new(), 1).is_empty());
+    }
+
+    #[test]
+    fn test_dedup_consecutive() {
+        let deduplicator = Deduplicator::new(1, false).consecutive(true);
+        // Each string is compared with its group's representative,
+        // not with the previous string.
+        let lines = ["abc", "abd", "abe", "xbe", "abc", "abc"];
+        assert_eq!(
+            deduplicator.dedup(lines.iter().cloned()),
+            vec!["abc", "xbe", "abc"]
+        );
+    }
+}
@@ -1,4 +1,12 @@
-use super::Distance;
+use super::{Distance, Error};
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::mem;
+use core::ops::RangeInclusive;
+use core::str;
+use once_cell::race::OnceBox;
 
 /// Sink state. See [DFA](./index.html)
 pub const SINK_STATE: u32 = 0u32;
@@ -9,7 +17,8 @@ pub const SINK_STATE: u32 = 0u32;
 /// The automaton does not validate `utf-8`.
 /// It will not return errors when fed with invalid `utf-8`
 ///
-/// The only `sink` state is guaranteed to be `SINK`.
+/// In the automata built by a [LevenshteinAutomatonBuilder](./struct.LevenshteinAutomatonBuilder.html),
+/// the only `sink` state is guaranteed to be `SINK`.
 ///
 /// This means that if you reach the sink state you are
 /// guaranteed that regardless of the sequence of bytes
@@ -17,7 +26,9 @@ pub const SINK_STATE: u32 = 0u32;
 /// remain in the same state.
 ///
 /// This property can be exploited to abort further
-/// evaluation.
+/// evaluation. The automata built by a [Utf8DFABuilder](./struct.Utf8DFABuilder.html)
+/// do not give any meaning to the state `0`: use
+/// [.can_match(...)](#method.can_match) to abort their evaluation.
 ///
 ///
 /// # Usage
@@ -43,12 +54,57 @@ pub const SINK_STATE: u32 = 0u32;
 /// # }
 //```
 pub struct DFA {
-    transitions: Vec<[u32; 256]>,
+    // The destination reached from `state` on the byte `b` is stored
+    // at `state * 256 + b`.
+    transitions: Vec<u32>,
     distances: Vec<Distance>,
     initial_state: u32,
+    // Whether an accepting state can be reached from each state,
+    // computed on the first call to `can_match`.
+    live: OnceBox<Vec<bool>>,
 }
 
 impl DFA {
+    /// Returns a borrowed view over the tables of the automaton.
+    pub fn as_dfa_ref(&self) -> DfaRef<'_> {
+        DfaRef {
+            transitions: &self.transitions,
+            distances: &self.distances,
+            initial_state: self.initial_state,
+        }
+    }
+
+    /// Inverse of [.into_parts()](#method.into_parts).
+    pub(crate) fn from_parts(
+        transitions: Vec<[u32; 256]>,
+        distances: Vec<Distance>,
+        initial_state: u32,
+    ) -> DFA {
+        DFA::from_flat_parts(transitions.into_flattened(), distances, initial_state)
+    }
+
+    /// Same as [DFA::from_parts], with a flat transition table.
+    pub(crate) fn from_flat_parts(
+        transitions: Vec<u32>,
+        distances: Vec<Distance>,
+        initial_state: u32,
+    ) -> DFA {
+        debug_assert_eq!(transitions.len(), distances.len() * 256);
+        DFA {
+            transitions,
+            distances,
+            initial_state,
+            live: OnceBox::new(),
+        }
+    }
+
+    /// Returns the flat transition table, the distance
+    /// of each state and the initial state.
+    #[cfg(feature = "std")]
+    pub(crate) fn into_parts(self) -> (Vec<u32>, Vec<Distance>, u32) {
+        (self.transitions, self.distances, self.initial_state)
+    }
+
     /// Returns the initial state
     pub fn initial_state(&self) -> u32 {
         self.initial_state
@@ -58,33 +114,679 @@ impl DFA {
     /// a sequence of bytes and returns the resulting
     /// distance.
     pub fn eval<B: AsRef<[u8]>>(&self, text: B) -> Distance {
+        self.as_dfa_ref().eval(text)
+    }
+
+    /// Same as [.eval(...)](#method.eval), without bounds checking
+    /// in the inner loop.
+    ///
+    /// # Safety
+    ///
+    /// The initial state and the destination of all of the transitions
+    /// must be lower than [.num_states()](#method.num_states).
+    /// This holds for all of the automata built by a
+    /// [LevenshteinAutomatonBuilder](./struct.LevenshteinAutomatonBuilder.html),
+    /// and for the automata built by a [Utf8DFABuilder](./struct.Utf8DFABuilder.html)
+    /// for which at least one state was defined.
+    pub unsafe fn eval_unchecked<B: AsRef<[u8]>>(&self, text: B) -> Distance {
+        self.as_dfa_ref().eval_unchecked(text)
+    }
+
+    /// Same as [.eval(...)](#method.eval), but consumes the bytes
+    /// from an iterator, which makes it possible to match
+    /// non-contiguous text without collecting it.
+    ///
+    /// The iterator is not consumed further once a rejecting sink state is reached.
+    pub fn eval_iter<I: IntoIterator<Item = u8>>(&self, bytes: I) -> Distance {
+        self.as_dfa_ref().eval_iter(bytes)
+    }
+
+    /// Returns the distance after each byte of `text`, i.e. the
+    /// distance of each non-empty prefix of `text`, shortest first.
+    ///
+    /// The distances at char boundaries can be picked with `char_indices`.
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::{Distance, LevenshteinAutomatonBuilder};
+    /// let dfa = LevenshteinAutomatonBuilder::new(1, false).build_dfa("寿司");
+    /// let text = "寿司屋";
+    /// let distances: Vec<Distance> = dfa.eval_prefixes(text).collect();
+    /// assert_eq!(distances.len(), text.len());
+    /// let char_distances: Vec<Distance> = text
+    ///     .char_indices()
+    ///     .map(|(offset, chr)| distances[offset + chr.len_utf8() - 1])
+    ///     .collect();
+    /// assert_eq!(
+    ///     char_distances,
+    ///     vec![Distance::Exact(1), Distance::Exact(0), Distance::Exact(1)]
+    /// );
+    /// ```
+    pub fn eval_prefixes<'a, B: AsRef<[u8]> + ?Sized>(
+        &'a self,
+        text: &'a B,
+    ) -> impl Iterator<Item = Distance> + 'a {
+        text.as_ref()
+            .iter()
+            .scan(self.initial_state, move |state, &b| {
+                *state = self.transition(*state, b);
+                Some(self.distance(*state))
+            })
+    }
+
+    /// Fallible version of [.eval_iter(...)](#method.eval_iter).
+    ///
+    /// The first error returned by the iterator is forwarded.
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::{LevenshteinAutomatonBuilder, Distance};
+    /// # use std::io::Read;
+    /// # let lev_automaton_builder = LevenshteinAutomatonBuilder::new(1, true);
+    /// let dfa = lev_automaton_builder.build_dfa("Levenshtein");
+    /// let reader = std::io::Cursor::new("Levenstein");
+    /// assert_eq!(dfa.try_eval_iter(reader.bytes()).unwrap(), Distance::Exact(1));
+    /// ```
+    pub fn try_eval_iter<E, I>(&self, bytes: I) -> Result<Distance, E>
+    where
+        I: IntoIterator<Item = Result<u8, E>>,
+    {
+        self.as_dfa_ref().try_eval_iter(bytes)
+    }
+
+    /// Returns a closure telling whether a text is within the
+    /// maximum distance of the automaton.
+    ///
+    /// It can be handed directly to higher-order functions.
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::LevenshteinAutomatonBuilder;
+    /// # let lev_automaton_builder = LevenshteinAutomatonBuilder::new(1, true);
+    /// let dfa = lev_automaton_builder.build_dfa("cat");
+    /// let mut words = vec!["cat".to_string(), "act".to_string(), "dog".to_string()];
+    /// words.retain(dfa.matcher());
+    /// assert_eq!(words, vec!["cat", "act"]);
+    /// let matches: Vec<&str> = ["bat", "bird"].iter().cloned().filter(dfa.matcher()).collect();
+    /// assert_eq!(matches, vec!["bat"]);
+    /// ```
+    pub fn matcher<S: AsRef<[u8]> + ?Sized>(&self) -> impl Fn(&S) -> bool + '_ {
+        self.as_dfa_ref().matcher()
+    }
+
+    /// Returns the Levenshtein distance associated to the
+    /// current state.
+    pub fn distance(&self, state_id: u32) -> Distance {
+        self.distances[state_id as usize]
+    }
+
+    /// Returns the number of states in the `DFA`.
+    pub fn num_states(&self) -> usize {
+        self.distances.len()
+    }
+
+    /// Returns the number of bytes allocated on the heap by the tables.
+    pub fn heap_size(&self) -> usize {
+        self.transitions.capacity() * mem::size_of::<u32>()
+            + self.distances.capacity() * mem::size_of::<Distance>()
+            + self.live.get().map_or(0, |live| live.capacity()) * mem::size_of::<bool>()
+    }
+
+    /// Returns the destination state reached after consuming a given byte.
+    pub fn transition(&self, from_state_id: u32, b: u8) -> u32 {
+        self.transitions[from_state_id as usize * 256 + b as usize]
+    }
+
+    /// Same as [.transition(...)](#method.transition), but returns an error
+    /// instead of panicking if `from_state_id` is not a valid state id.
+    pub fn try_transition(&self, from_state_id: u32, b: u8) -> Result<u32, Error> {
+        self.check_state_id(from_state_id)?;
+        Ok(self.transition(from_state_id, b))
+    }
+
+    /// Same as [.distance(...)](#method.distance), but returns an error
+    /// instead of panicking if `state_id` is not a valid state id.
+    pub fn try_distance(&self, state_id: u32) -> Result<Distance, Error> {
+        self.check_state_id(state_id)?;
+        Ok(self.distance(state_id))
+    }
+
+    fn check_state_id(&self, state_id: u32) -> Result<(), Error> {
+        if state_id as usize >= self.num_states() {
+            return Err(Error::InvalidStateId {
+                state_id,
+                num_states: self.num_states(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Same as [.transition(...)](#method.transition), without bounds checking.
+    ///
+    /// # Safety
+    ///
+    /// `from_state_id` must be lower than [.num_states()](#method.num_states).
+    pub unsafe fn transition_unchecked(&self, from_state_id: u32, b: u8) -> u32 {
+        debug_assert!((from_state_id as usize) < self.num_states());
+        *self
+            .transitions
+            .get_unchecked(from_state_id as usize * 256 + b as usize)
+    }
+
+    // Returns the destinations of the transitions of a state, by byte.
+    fn row(&self, state_id: u32) -> &[u32] {
+        let start = state_id as usize * 256;
+        &self.transitions[start..start + 256]
+    }
+
+    /// Returns true if an accepting state can be reached from `state_id`,
+    /// i.e. if some continuation of the bytes consumed so far is accepted.
+    ///
+    /// This is computed for all of the states on the first call, and cached,
+    /// so that building an automaton does not pay for it. Once it returns
+    /// false, the evaluation can be aborted, and the subtree of a trie pruned.
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::{LevenshteinAutomatonBuilder, SINK_STATE};
+    /// let dfa = LevenshteinAutomatonBuilder::new(1, false).build_dfa("abc");
+    /// let state = dfa.transition(dfa.initial_state(), b'x');
+    /// assert!(dfa.can_match(state));
+    /// let state = dfa.transition(state, b'y');
+    /// assert!(!dfa.can_match(state));
+    /// assert!(!dfa.can_match(SINK_STATE));
+    /// ```
+    pub fn can_match(&self, state_id: u32) -> bool {
+        self.live()[state_id as usize]
+    }
+
+    /// Returns, for each state, whether an accepting state
+    /// can be reached from it.
+    #[cfg(feature = "std")]
+    pub(crate) fn live_states(&self) -> &[bool] {
+        self.live()
+    }
+
+    fn live(&self) -> &[bool] {
+        self.live
+            .get_or_init(|| Box::new(live_states(&self.transitions, &self.distances)))
+    }
+
+    /// Returns true iff `state_id` is a rejecting [SINK_STATE](./constant.SINK_STATE.html),
+    /// looping on itself: the distance cannot change anymore once it is reached.
+    #[cfg(feature = "std")]
+    pub(crate) fn is_sink(&self, state_id: u32) -> bool {
+        self.as_dfa_ref().is_sink(state_id)
+    }
+
+    /// Returns the smallest distance of the accepting states reachable
+    /// from `state_id`, itself included, or `None` if no accepting state is reachable.
+    ///
+    /// It is a lower bound of the distance of any string going through
+    /// `state_id`, i.e. an admissible heuristic for best-first searches.
+    /// Use [.min_distances()](#method.min_distances) to compute it for all of the
+    /// states at once.
+    pub fn min_distance_from(&self, state_id: u32) -> Option<u8> {
+        let mut visited = vec![false; self.num_states()];
+        visited[state_id as usize] = true;
+        let mut stack = vec![state_id];
+        let mut min_distance: Option<u8> = None;
+        while let Some(state_id) = stack.pop() {
+            if let Distance::Exact(d) = self.distance(state_id) {
+                if min_distance
+                    .map(|min_distance| d < min_distance)
+                    .unwrap_or(true)
+                {
+                    min_distance = Some(d);
+                }
+            }
+            for &to_state_id in self.row(state_id) {
+                if !visited[to_state_id as usize] {
+                    visited[to_state_id as usize] = true;
+                    stack.push(to_state_id);
+                }
+            }
+        }
+        min_distance
+    }
+
+    /// Returns [.min_distance_from(state_id)](#method.min_distance_from)
+    /// for all of the states.
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::LevenshteinAutomatonBuilder;
+    /// let dfa = LevenshteinAutomatonBuilder::new(2, true).build_dfa("cat");
+    /// let min_distances = dfa.min_distances();
+    /// let state = dfa.transition(dfa.initial_state(), b'x');
+    /// // "x" is at distance 3, but "xat" is at distance 1.
+    /// assert_eq!(min_distances[state as usize], Some(1));
+    /// assert_eq!(min_distances[state as usize], dfa.min_distance_from(state));
+    /// ```
+    pub fn min_distances(&self) -> Vec<Option<u8>> {
+        let predecessors = predecessors(self.transitions.chunks_exact(256));
+        let mut accepting: Vec<(u8, u32)> = (0..self.num_states() as u32)
+            .filter_map(|state_id| match self.distance(state_id) {
+                Distance::Exact(d) => Some((d, state_id)),
+                Distance::AtLeast(_) => None,
+            })
+            .collect();
+        accepting.sort_unstable();
+        // States are reached backward from the accepting states, by increasing
+        // distance, so that the first distance assigned to a state is the smallest.
+        let mut min_distances: Vec<Option<u8>> = vec![None; self.num_states()];
+        for (d, state_id) in accepting {
+            if min_distances[state_id as usize].is_some() {
+                continue;
+            }
+            min_distances[state_id as usize] = Some(d);
+            mark_backward(&predecessors, state_id, |predecessor| {
+                let min_distance = &mut min_distances[predecessor as usize];
+                if min_distance.is_some() {
+                    return false;
+                }
+                *min_distance = Some(d);
+                true
+            });
+        }
+        min_distances
+    }
+
+    /// Returns, for each state, whether it is universally accepting, i.e.
+    /// whether every string of valid utf-8 leads from it to an accepting state.
+    ///
+    /// Once a dictionary traversal reaches such a state, whole subtrees of the
+    /// dictionary can be emitted without evaluating the automaton any further.
+    /// Their distance may still vary though, so it has to be evaluated if needed.
+    ///
+    /// Only the states reached at char boundaries are meaningful: the states
+    /// reached in the middle of a multi-byte char are never universally accepting.
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::LevenshteinAutomatonBuilder;
+    /// let dfa = LevenshteinAutomatonBuilder::new(1, true).build_prefix_dfa("cat");
+    /// let universally_accepting = dfa.universally_accepting_states();
+    /// let mut state = dfa.initial_state();
+    /// assert!(!universally_accepting[state as usize]);
+    /// for &b in b"ca" {
+    ///     state = dfa.transition(state, b);
+    /// }
+    /// // "ca" is at distance 1 of "cat", and so is any string starting with "ca".
+    /// assert!(universally_accepting[state as usize]);
+    /// ```
+    pub fn universally_accepting_states(&self) -> Vec<bool> {
+        let successors = self.char_successors();
+        let rejecting: Vec<bool> = self
+            .distances
+            .iter()
+            .map(|distance| !matches!(distance, Distance::Exact(_)))
+            .collect();
+        let reaching_rejecting = reaching_states(&predecessors(successors.iter()), rejecting);
+        reaching_rejecting
+            .into_iter()
+            .map(|reaching| !reaching)
+            .collect()
+    }
+
+    /// Returns an equivalent automaton without the states that are
+    /// unreachable from the initial state, and where all of the states from
+    /// which no accepting state can be reached are collapsed into
+    /// [SINK_STATE](./constant.SINK_STATE.html).
+    ///
+    /// States are renumbered compactly, in breadth-first order from the
+    /// initial state. Strings that reach the sink state are evaluated to the
+    /// smallest lower bound of the collapsed states, i.e. of the states
+    /// reachable from the initial state from which no accepting state can be
+    /// reached.
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::LevenshteinAutomatonBuilder;
+    /// let dfa = LevenshteinAutomatonBuilder::new(1, false).build_dfa("abc");
+    /// let pruned = dfa.prune();
+    /// assert!(pruned.num_states() <= dfa.num_states());
+    /// assert_eq!(pruned.eval("abd"), dfa.eval("abd"));
+    /// ```
+    pub fn prune(&self) -> DFA {
+        let mut ids: Vec<Option<u32>> = vec![None; self.num_states()];
+        let mut order: Vec<u32> = Vec::new();
+        // Dead states whose successors have not been visited yet.
+        let mut collapsed: Vec<u32> = Vec::new();
+        let mut visit = |state_id: u32, order: &mut Vec<u32>, collapsed: &mut Vec<u32>| {
+            if ids[state_id as usize].is_none() {
+                if self.can_match(state_id) {
+                    ids[state_id as usize] = Some(order.len() as u32 + 1);
+                    order.push(state_id);
+                } else {
+                    ids[state_id as usize] = Some(SINK_STATE);
+                    collapsed.push(state_id);
+                }
+            }
+            ids[state_id as usize].unwrap()
+        };
+        let initial_state = visit(self.initial_state, &mut order, &mut collapsed);
+        let mut transitions: Vec<[u32; 256]> = vec![[SINK_STATE; 256]];
+        let mut i = 0;
+        while i < order.len() {
+            let state_id = order[i];
+            let mut row = [SINK_STATE; 256];
+            for (b, to_state_id) in row.iter_mut().enumerate() {
+                *to_state_id = visit(
+                    self.transition(state_id, b as u8),
+                    &mut order,
+                    &mut collapsed,
+                );
+            }
+            transitions.push(row);
+            i += 1;
+        }
+        // All of the states reachable from a dead state are dead, and collapsed too.
+        let mut sink_distance: Option<u8> = None;
+        while let Some(state_id) = collapsed.pop() {
+            let d = self.distance(state_id).to_u8();
+            sink_distance = Some(sink_distance.map_or(d, |sink_d| sink_d.min(d)));
+            for &to_state_id in self.row(state_id) {
+                if ids[to_state_id as usize].is_none() {
+                    ids[to_state_id as usize] = Some(SINK_STATE);
+                    collapsed.push(to_state_id);
+                }
+            }
+        }
+        // If no state is collapsed, the sink is unreachable, and keeps the
+        // lower bound of the original one.
+        let sink_distance = sink_distance.unwrap_or_else(|| match self.distance(SINK_STATE) {
+            Distance::AtLeast(d) if !self.can_match(SINK_STATE) => d,
+            _ => 0,
+        });
+        let mut distances = vec![Distance::AtLeast(sink_distance)];
+        distances.extend(order.iter().map(|&state_id| self.distance(state_id)));
+        DFA::from_parts(transitions, distances, initial_state)
+    }
+
+    /// Returns, for each state, the distance of the state if all of the
+    /// states reachable from it through valid utf-8 are accepting and have the
+    /// same distance, and `None` otherwise.
+    ///
+    /// Once such a state is reached, reading more chars does not change the result.
+    /// In a prefix automaton, this typically happens once the whole query has been
+    /// matched.
+    #[cfg(feature = "std")]
+    pub(crate) fn settled_distances(&self) -> Vec<Option<u8>> {
+        let successors = self.char_successors();
+        let unsettled: Vec<bool> = (0..self.num_states())
+            .map(|state_id| match self.distances[state_id] {
+                Distance::Exact(_) => successors[state_id]
+                    .iter()
+                    .any(|&to_state_id| self.distance(to_state_id) != self.distances[state_id]),
+                Distance::AtLeast(_) => true,
+            })
+            .collect();
+        reaching_states(&predecessors(successors.iter()), unsettled)
+            .into_iter()
+            .zip(&self.distances)
+            .map(|(unsettled, distance)| match distance {
+                Distance::Exact(d) if !unsettled => Some(*d),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // Returns the states reached from each state after reading a whole char.
+    fn char_successors(&self) -> Vec<Vec<u32>> {
+        let sequences = utf8_sequences(0, 0x10_FFFF);
+        (0..self.num_states() as u32)
+            .map(|from_state_id| {
+                let mut successors: Vec<u32> = Vec::new();
+                for sequence in &sequences {
+                    let mut states = vec![from_state_id];
+                    for &(start, end) in sequence {
+                        let mut next_states: Vec<u32> = states
+                            .iter()
+                            .flat_map(|&state_id| {
+                                (start..=end).map(move |b| self.transition(state_id, b))
+                            })
+                            .collect();
+                        next_states.sort_unstable();
+                        next_states.dedup();
+                        states = next_states;
+                    }
+                    successors.extend(states);
+                }
+                successors.sort_unstable();
+                successors.dedup();
+                successors
+            })
+            .collect()
+    }
+}
+
+// Returns the predecessors of each state, given the successors of each state.
+fn predecessors<R: AsRef<[u32]>>(successors: impl ExactSizeIterator<Item = R>) -> Vec<Vec<u32>> {
+    let mut predecessors: Vec<Vec<u32>> = vec![Vec::new(); successors.len()];
+    for (from_state_id, to_state_ids) in successors.enumerate() {
+        for &to_state_id in to_state_ids.as_ref() {
+            let to_predecessors = &mut predecessors[to_state_id as usize];
+            if to_predecessors.last() != Some(&(from_state_id as u32)) {
+                to_predecessors.push(from_state_id as u32);
+            }
+        }
+    }
+    predecessors
+}
+
+// Walks backward from `state_id` through the states for which `mark`
+// returns true. `mark` is called on each predecessor of a visited state,
+// and should return false once a state has already been marked.
+fn mark_backward<F: FnMut(u32) -> bool>(predecessors: &[Vec<u32>], state_id: u32, mut mark: F) {
+    let mut stack = vec![state_id];
+    while let Some(state_id) = stack.pop() {
+        for &predecessor in &predecessors[state_id as usize] {
+            if mark(predecessor) {
+                stack.push(predecessor);
+            }
+        }
+    }
+}
+
+// Extends `marked` to all of the states from which a marked state can be reached.
+fn reaching_states(predecessors: &[Vec<u32>], mut marked: Vec<bool>) -> Vec<bool> {
+    let roots: Vec<u32> = (0..marked.len() as u32)
+        .filter(|&state_id| marked[state_id as usize])
+        .collect();
+    for state_id in roots {
+        mark_backward(predecessors, state_id, |predecessor| {
+            !mem::replace(&mut marked[predecessor as usize], true)
+        });
+    }
+    marked
+}
+
+// Returns, for each state, whether an accepting state can be reached from it.
+fn live_states(transitions: &[u32], distances: &[Distance]) -> Vec<bool> {
+    let accepting: Vec<bool> = distances
+        .iter()
+        .map(|distance| matches!(distance, Distance::Exact(_)))
+        .collect();
+    reaching_states(&predecessors(transitions.chunks_exact(256)), accepting)
+}
+
+/// Borrowed view over the tables of a [DFA](./struct.DFA.html).
+///
+/// It offers the same evaluation API as the `DFA` it was created from,
+/// without owning its tables. This makes it possible to embed automata
+/// in larger arena-allocated structures, or to evaluate tables that
+/// were loaded from somewhere else.
+///
+/// The transition table is flat: the destination reached from `state`
+/// on the byte `b` is stored at `state * 256 + b`.
+#[derive(Clone, Copy)]
+pub struct DfaRef<'a> {
+    transitions: &'a [u32],
+    distances: &'a [Distance],
+    initial_state: u32,
+}
+
+impl<'a> DfaRef<'a> {
+    /// Creates a view over some flat transition table and its distances.
+    ///
+    /// Returns `None` if the tables are inconsistent: the transition table
+    /// must have `256` entries per state, and all of the state ids
+    /// must be lower than the number of states.
+    pub fn from_parts(
+        transitions: &'a [u32],
+        distances: &'a [Distance],
+        initial_state: u32,
+    ) -> Option<DfaRef<'a>> {
+        let num_states = distances.len();
+        if transitions.len() != num_states * 256
+            || initial_state as usize >= num_states
+            || transitions
+                .iter()
+                .any(|&state_id| state_id as usize >= num_states)
+        {
+            return None;
+        }
+        Some(DfaRef {
+            transitions,
+            distances,
+            initial_state,
+        })
+    }
+
+    /// Same as [DfaRef::from_parts](#method.from_parts), without checking
+    /// the tables, so that it can be used to initialize statics.
+    ///
+    /// # Safety
+    ///
+    /// The tables must be consistent, as checked by
+    /// [DfaRef::from_parts](#method.from_parts): the unchecked evaluation
+    /// methods rely on it.
+    pub const unsafe fn from_parts_unchecked(
+        transitions: &'a [u32],
+        distances: &'a [Distance],
+        initial_state: u32,
+    ) -> DfaRef<'a> {
+        DfaRef {
+            transitions,
+            distances,
+            initial_state,
+        }
+    }
+
+    /// Copies the tables into an owned [DFA](./struct.DFA.html).
+    pub fn to_dfa(&self) -> DFA {
+        DFA::from_flat_parts(
+            self.transitions.to_vec(),
+            self.distances.to_vec(),
+            self.initial_state,
+        )
+    }
+
+    /// Returns the initial state
+    pub fn initial_state(&self) -> u32 {
+        self.initial_state
+    }
+
+    /// See [DFA::eval](./struct.DFA.html#method.eval).
+    pub fn eval<B: AsRef<[u8]>>(&self, text: B) -> Distance {
+        let mut state = self.initial_state();
+        for &b in text.as_ref() {
+            state = self.transition(state, b);
+        }
+        self.distance(state)
+    }
+
+    /// See [DFA::eval_unchecked](./struct.DFA.html#method.eval_unchecked).
+    ///
+    /// # Safety
+    ///
+    /// The initial state and the destination of all of the transitions
+    /// must be lower than [.num_states()](#method.num_states). This holds
+    /// for views created with [DfaRef::from_parts](#method.from_parts).
+    pub unsafe fn eval_unchecked<B: AsRef<[u8]>>(&self, text: B) -> Distance {
         let mut state = self.initial_state();
         for &b in text.as_ref() {
+            state = self.transition_unchecked(state, b);
+        }
+        *self.distances.get_unchecked(state as usize)
+    }
+
+    /// See [DFA::eval_iter](./struct.DFA.html#method.eval_iter).
+    pub fn eval_iter<I: IntoIterator<Item = u8>>(&self, bytes: I) -> Distance {
+        let mut state = self.initial_state();
+        for b in bytes {
             state = self.transition(state, b);
+            if self.is_sink(state) {
+                break;
+            }
         }
         self.distance(state)
     }
 
+    /// See [DFA::try_eval_iter](./struct.DFA.html#method.try_eval_iter).
+    pub fn try_eval_iter<E, I>(&self, bytes: I) -> Result<Distance, E>
+    where
+        I: IntoIterator<Item = Result<u8, E>>,
+    {
+        let mut state = self.initial_state();
+        for b in bytes {
+            state = self.transition(state, b?);
+            if self.is_sink(state) {
+                break;
+            }
+        }
+        Ok(self.distance(state))
+    }
+
+    /// See [DFA::matcher](./struct.DFA.html#method.matcher).
+    pub fn matcher<S: AsRef<[u8]> + ?Sized>(self) -> impl Fn(&S) -> bool + 'a {
+        move |text: &S| match self.eval(text.as_ref()) {
+            Distance::Exact(_) => true,
+            Distance::AtLeast(_) => false,
+        }
+    }
+
     /// Returns the Levenshtein distance associated to the
     /// current state.
     pub fn distance(&self, state_id: u32) -> Distance {
         self.distances[state_id as usize]
     }
 
-    /// Returns the number of states in the `DFA`.
+    /// Returns the number of states.
     pub fn num_states(&self) -> usize {
-        self.transitions.len()
+        self.distances.len()
+    }
+
+    /// See [DFA::is_sink](./struct.DFA.html#method.is_sink).
+    pub(crate) fn is_sink(&self, state_id: u32) -> bool {
+        state_id == SINK_STATE
+            && matches!(self.distance(state_id), Distance::AtLeast(_))
+            && self.transitions[..256].iter().all(|&to| to == SINK_STATE)
     }
 
     /// Returns the destination state reached after consuming a given byte.
     pub fn transition(&self, from_state_id: u32, b: u8) -> u32 {
-        self.transitions[from_state_id as usize][b as usize]
+        self.transitions[from_state_id as usize * 256 + b as usize]
+    }
+
+    /// See [DFA::transition_unchecked](./struct.DFA.html#method.transition_unchecked).
+    ///
+    /// # Safety
+    ///
+    /// `from_state_id` must be lower than [.num_states()](#method.num_states).
+    pub unsafe fn transition_unchecked(&self, from_state_id: u32, b: u8) -> u32 {
+        debug_assert!((from_state_id as usize) < self.num_states());
+        *self
+            .transitions
+            .get_unchecked(from_state_id as usize * 256 + b as usize)
     }
 }
 
-#[cfg(feature = "fst_automaton")]
-use fst;
-#[cfg(feature = "fst_automaton")]
+impl<'a> From<&'a DFA> for DfaRef<'a> {
+    fn from(dfa: &'a DFA) -> DfaRef<'a> {
+        dfa.as_dfa_ref()
+    }
+}
+
+/// Makes it possible to stream the terms of an `fst::Set` or `fst::Map`
+/// accepted by the automaton, e.g. with `set.search(&dfa)`.
+///
+/// Branches are pruned as soon as no accepting state can be reached.
+#[cfg(feature = "fst")]
 impl fst::Automaton for DFA {
     type State = u32;
 
@@ -93,14 +795,40 @@ impl fst::Automaton for DFA {
     }
 
     fn is_match(&self, state: &u32) -> bool {
-        match self.distance(*state) {
-            Distance::Exact(d) => true,
-            Distance::AtLeast(_) => false,
-        }
+        matches!(self.distance(*state), Distance::Exact(_))
+    }
+
+    fn can_match(&self, state: &u32) -> bool {
+        DFA::can_match(self, *state)
+    }
+
+    fn accept(&self, state: &u32, byte: u8) -> u32 {
+        self.transition(*state, byte)
+    }
+
+    fn accept_eof(&self, state: &u32) -> Option<u32> {
+        Some(*state)
+    }
+}
+
+/// Makes it possible to walk the term dictionary of `tantivy` with the
+/// automaton, e.g. to back a fuzzy term query.
+///
+/// Branches are pruned as soon as no accepting state can be reached.
+#[cfg(feature = "tantivy_automaton")]
+impl tantivy_fst::Automaton for DFA {
+    type State = u32;
+
+    fn start(&self) -> u32 {
+        self.initial_state()
+    }
+
+    fn is_match(&self, state: &u32) -> bool {
+        matches!(self.distance(*state), Distance::Exact(_))
     }
 
     fn can_match(&self, state: &u32) -> bool {
-        *state != SINK_STATE
+        DFA::can_match(self, *state)
     }
 
     fn accept(&self, state: &u32, byte: u8) -> u32 {
@@ -114,63 +842,220 @@ fn fill(dest: &mut [u32], val: u32) {
     }
 }
 
+/// Error returned when a byte sequence is not the
+/// utf-8 encoding of exactly one char.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct InvalidUtf8Sequence;
+
+impl fmt::Display for InvalidUtf8Sequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("the byte sequence is not the utf-8 encoding of a single char")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidUtf8Sequence {}
+
+/// Defines the transitions of a state.
+///
+/// See [Utf8DFABuilder::add_state](./struct.Utf8DFABuilder.html#method.add_state).
 pub struct Utf8DFAStateBuilder<'a> {
     dfa_builder: &'a mut Utf8DFABuilder,
     state_id: u32,
-    default_successor: [u32; 4],
 }
 
 impl<'a> Utf8DFAStateBuilder<'a> {
-    fn add_transition_id(&mut self, from_state_id: u32, b: u8, to_state_id: u32) {
-        self.dfa_builder.transitions[from_state_id as usize][b as usize] = to_state_id;
-    }
-
+    /// Adds a transition to `to_state_id` on the char `chr`.
     pub fn add_transition(&mut self, chr: char, to_state_id: u32) {
         let mut buffer = [0u8; 4];
-
-        // The char may translate into more than one bytes.
-        // We create a chain for this reason.
         let bytes: &[u8] = chr.encode_utf8(&mut buffer).as_bytes();
-        let mut from_state_id_decoded = self.state_id;
-        for (i, b) in bytes[..bytes.len() - 1].iter().cloned().enumerate() {
-            let remaining_num_bytes = bytes.len() - i as usize - 1 as usize;
-            let default_successor = self.default_successor[remaining_num_bytes];
-            let mut intermediary_state_id: u32 =
-                self.dfa_builder.transitions[from_state_id_decoded as usize][b as usize];
-            if intermediary_state_id == default_successor {
-                intermediary_state_id = self.dfa_builder.allocate();
-                fill(
-                    &mut self.dfa_builder.transitions[intermediary_state_id as usize],
-                    self.default_successor[remaining_num_bytes - 1],
-                );
-            }
-            self.add_transition_id(from_state_id_decoded, b, intermediary_state_id);
-            from_state_id_decoded = intermediary_state_id;
+        self.add_utf8_transition(bytes, to_state_id);
+    }
+
+    /// Adds a transition to `to_state_id` on an already utf-8 encoded char.
+    ///
+    /// Returns an error, and leaves the state untouched, if `bytes` is not
+    /// the utf-8 encoding of exactly one char.
+    pub fn add_sequence_transition(
+        &mut self,
+        bytes: &[u8],
+        to_state_id: u32,
+    ) -> Result<(), InvalidUtf8Sequence> {
+        let mut chars = str::from_utf8(bytes)
+            .map_err(|_| InvalidUtf8Sequence)?
+            .chars();
+        if chars.next().is_none() || chars.next().is_some() {
+            return Err(InvalidUtf8Sequence);
+        }
+        self.add_utf8_transition(bytes, to_state_id);
+        Ok(())
+    }
+
+    /// Adds a transition to `to_state_id` on all of the chars of `char_range`.
+    ///
+    /// This is equivalent to, but much cheaper than, calling
+    /// [.add_transition(...)](#method.add_transition) for each of these chars.
+    pub fn add_transition_range(&mut self, char_range: RangeInclusive<char>, to_state_id: u32) {
+        let to_state_id_decoded = self.decode_state_id(to_state_id);
+        for sequence in utf8_sequences(*char_range.start() as u32, *char_range.end() as u32) {
+            self.dfa_builder
+                .add_byte_ranges(self.state_id, &sequence, to_state_id_decoded);
         }
+    }
 
-        let to_state_id_decoded = self
-            .dfa_builder
-            .get_or_allocate(Utf8StateId::original(to_state_id));
-        self.add_transition_id(
-            from_state_id_decoded,
-            bytes[bytes.len() - 1],
+    fn add_utf8_transition(&mut self, bytes: &[u8], to_state_id: u32) {
+        let to_state_id_decoded = self.decode_state_id(to_state_id);
+        if let [b] = *bytes {
+            self.dfa_builder.transitions[self.state_id as usize][b as usize] = to_state_id_decoded;
+            return;
+        }
+        let mut byte_ranges = [(0u8, 0u8); 4];
+        for (byte_range, &b) in byte_ranges.iter_mut().zip(bytes) {
+            *byte_range = (b, b);
+        }
+        self.dfa_builder.add_byte_ranges(
+            self.state_id,
+            &byte_ranges[..bytes.len()],
             to_state_id_decoded,
         );
     }
+
+    fn decode_state_id(&mut self, to_state_id: u32) -> u32 {
+        assert!(
+            to_state_id < self.dfa_builder.max_num_states,
+            "State id is larger than max_num_states"
+        );
+        self.dfa_builder
+            .get_or_allocate(Utf8StateId::original(to_state_id))
+    }
+}
+
+// Splits the code points of `start..=end` into sequences of byte ranges,
+// such that the utf-8 encodings of these code points are exactly the
+// byte strings matched by one of the sequences.
+pub(crate) fn utf8_sequences(start: u32, end: u32) -> Vec<Vec<(u8, u8)>> {
+    let mut sequences = Vec::new();
+    let mut stack = Vec::new();
+    if start <= 0xD7FF && end >= 0xE000 {
+        // surrogates are not chars.
+        stack.push((0xE000, end));
+        stack.push((start, 0xD7FF));
+    } else {
+        stack.push((start, end));
+    }
+    'ranges: while let Some((start, mut end)) = stack.pop() {
+        if start > end {
+            continue;
+        }
+        // splits the range so that all of its chars have the same encoded length.
+        for &max_code_point in &[0x7Fu32, 0x7FF, 0xFFFF] {
+            if start <= max_code_point && max_code_point < end {
+                stack.push((max_code_point + 1, end));
+                stack.push((start, max_code_point));
+                continue 'ranges;
+            }
+        }
+        // splits the range so that each continuation byte covers a full range.
+        for i in 1..4 {
+            let mask = (1u32 << (6 * i)) - 1;
+            if start & !mask != end & !mask {
+                if start & mask != 0 {
+                    stack.push(((start | mask) + 1, end));
+                    stack.push((start, start | mask));
+                    continue 'ranges;
+                }
+                if end & mask != mask {
+                    stack.push((end & !mask, end));
+                    end = (end & !mask) - 1;
+                    stack.push((start, end));
+                    continue 'ranges;
+                }
+            }
+        }
+        let mut start_buffer = [0u8; 4];
+        let mut end_buffer = [0u8; 4];
+        let start_bytes = encode_code_point(start, &mut start_buffer);
+        let end_bytes = encode_code_point(end, &mut end_buffer);
+        sequences.push(
+            start_bytes
+                .iter()
+                .cloned()
+                .zip(end_bytes.iter().cloned())
+                .collect(),
+        );
+    }
+    sequences
+}
+
+fn encode_code_point(code_point: u32, buffer: &mut [u8; 4]) -> &[u8] {
+    let chr = char::from_u32(code_point).expect("Surrogates are filtered out");
+    chr.encode_utf8(buffer).as_bytes()
+}
+
+/// Custom default successor for a range of lead bytes.
+///
+/// See [Utf8DFABuilder::add_state_with_ranges](./struct.Utf8DFABuilder.html#method.add_state_with_ranges).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DefaultSuccessorRange {
+    /// Lead bytes covered by the range.
+    pub bytes: RangeInclusive<u8>,
+    /// Number of bytes, whatever they are, following the lead byte. At most 3.
+    pub num_trailing_bytes: u8,
+    /// State reached after the trailing bytes.
+    pub successor: u32,
 }
 
 /// `Utf8DFABuilder` makes it possible to define a DFA
 /// that takes unicode character, and build a `DFA`
 /// that operates on utf-8 encoded `&[u8]`.
+///
+/// States are identified by the integers `0..max_num_states`.
+/// They are renumbered in the resulting `DFA`, as intermediary states
+/// are created for multi-bytes chars. The first state defined gets the id `0`.
+///
+/// Unlike in the automata of a [LevenshteinAutomatonBuilder](./struct.LevenshteinAutomatonBuilder.html),
+/// the state `0` is not necessarily a rejecting sink state, as below: the
+/// methods of the `DFA` only stop early on [SINK_STATE](./constant.SINK_STATE.html)
+/// if it rejects and loops on itself.
+///
+/// ```rust
+/// # use levenshtein_automata::{Distance, Utf8DFABuilder};
+/// // Computes the parity of the number of chars.
+/// let mut dfa_builder = Utf8DFABuilder::with_max_num_states(2);
+/// dfa_builder.add_state(0, Distance::Exact(0), 1);
+/// dfa_builder.add_state(1, Distance::Exact(1), 0);
+/// dfa_builder.set_initial_state(0);
+/// let dfa = dfa_builder.build();
+/// assert_eq!(dfa.eval("寿司"), Distance::Exact(0));
+/// assert_eq!(dfa.eval("寿"), Distance::Exact(1));
+/// ```
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Utf8DFABuilder {
     index: Vec<Option<u32>>,
     distances: Vec<Distance>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::flat_rows"))]
     transitions: Vec<[u32; 256]>,
+    kinds: Vec<StateKind>,
     initial_state: u32,
     num_states: u32,
     max_num_states: u32,
 }
 
+// Largest `max_num_states` of a `Utf8DFABuilder`, so that the ids of the
+// intermediary states of the utf-8 encodings fit in a `u32`.
+const MAX_UTF8_NUM_STATES: usize = (u32::MAX as usize - 3) / 4;
+
+// States that are not `Original` are intermediary states of the encoding
+// of a char, and can only be reached from a single state. `Intermediary`
+// states are, in addition, reached via a single byte.
+#[derive(Eq, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum StateKind {
+    Original,
+    Intermediary,
+    SharedIntermediary,
+}
+
 #[derive(Eq, PartialEq, Hash, Clone, Copy)]
 struct Utf8StateId(u32);
 impl Utf8StateId {
@@ -184,19 +1069,31 @@ impl Utf8StateId {
 }
 
 impl Utf8DFABuilder {
-    /// Creates a new dictionary.
+    /// Creates a new builder.
     ///
     /// The `builder` will only accept `state_id` that are
     /// lower than `max_num_states`.
     pub fn with_max_num_states(max_num_states: usize) -> Utf8DFABuilder {
-        Utf8DFABuilder {
+        Utf8DFABuilder::try_with_max_num_states(max_num_states)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as [Utf8DFABuilder::with_max_num_states], but returns an error
+    /// if the states, along with the intermediary states of the utf-8
+    /// encodings, cannot be addressed with `u32` state ids.
+    pub fn try_with_max_num_states(max_num_states: usize) -> Result<Utf8DFABuilder, Error> {
+        if max_num_states > MAX_UTF8_NUM_STATES {
+            return Err(Error::TooManyStates { max_num_states });
+        }
+        Ok(Utf8DFABuilder {
             index: vec![None; max_num_states * 4 + 3],
             distances: Vec::with_capacity(100),
             transitions: Vec::with_capacity(100),
+            kinds: Vec::with_capacity(100),
             initial_state: 0u32,
             num_states: 0u32,
             max_num_states: max_num_states as u32,
-        }
+        })
     }
 
     fn allocate(&mut self) -> u32 {
@@ -205,9 +1102,103 @@ impl Utf8DFABuilder {
         self.distances
             .resize(new_state as usize + 1, Distance::AtLeast(255));
         self.transitions.resize(new_state as usize + 1, [0u32; 256]);
+        self.kinds
+            .resize(new_state as usize + 1, StateKind::Original);
+        new_state
+    }
+
+    // Allocates a state in the middle of the encoding of a char,
+    // that behaves as `like_state_id` until a transition is added to it.
+    fn allocate_intermediary(&mut self, like_state_id: u32, kind: StateKind) -> u32 {
+        let new_state = self.allocate();
+        self.kinds[new_state as usize] = kind;
+        self.transitions[new_state as usize] = self.transitions[like_state_id as usize];
         new_state
     }
 
+    // Copies an intermediary state and all of the intermediary states it points to.
+    fn deep_copy_intermediary(&mut self, state_id: u32, kind: StateKind) -> u32 {
+        let new_state = self.allocate_intermediary(state_id, kind);
+        let mut copies: Vec<(u32, u32)> = Vec::new();
+        for b in 0..256 {
+            let successor = self.transitions[new_state as usize][b];
+            let successor_kind = self.kinds[successor as usize];
+            if successor_kind == StateKind::Original {
+                continue;
+            }
+            let copy = match copies.iter().find(|&&(orig, _)| orig == successor) {
+                Some(&(_, copy)) => copy,
+                None => {
+                    let copy = self.deep_copy_intermediary(successor, successor_kind);
+                    copies.push((successor, copy));
+                    copy
+                }
+            };
+            self.transitions[new_state as usize][b] = copy;
+        }
+        new_state
+    }
+
+    // Makes all of the byte strings matching the sequence of `byte_ranges`
+    // lead from `from_state_id` to `to_state_id`.
+    //
+    // The char may translate into more than one bytes. We create a chain of
+    // intermediary states for this reason. These intermediary states are only
+    // reachable from `from_state_id`, so that they can be updated in place,
+    // unless they are shared with bytes outside of the range.
+    fn add_byte_ranges(&mut self, from_state_id: u32, byte_ranges: &[(u8, u8)], to_state_id: u32) {
+        let (start, end) = byte_ranges[0];
+        if byte_ranges.len() == 1 {
+            fill(
+                &mut self.transitions[from_state_id as usize][start as usize..=end as usize],
+                to_state_id,
+            );
+            return;
+        }
+        let kind = if start == end {
+            StateKind::Intermediary
+        } else {
+            StateKind::SharedIntermediary
+        };
+        let mut updated_successors: Vec<(u32, u32)> = Vec::new();
+        for b in start..=end {
+            let successor = self.transitions[from_state_id as usize][b as usize];
+            let updated_successor = match updated_successors
+                .iter()
+                .find(|&&(orig, _)| orig == successor)
+            {
+                Some(&(_, updated_successor)) => updated_successor,
+                None => {
+                    let updated_successor = match self.kinds[successor as usize] {
+                        StateKind::Original => self.allocate_intermediary(successor, kind),
+                        StateKind::Intermediary => successor,
+                        StateKind::SharedIntermediary => {
+                            if self.transitions[from_state_id as usize]
+                                .iter()
+                                .enumerate()
+                                .any(|(c, &s)| {
+                                    s == successor && (c < start as usize || c > end as usize)
+                                })
+                            {
+                                self.deep_copy_intermediary(successor, kind)
+                            } else {
+                                successor
+                            }
+                        }
+                    };
+                    self.add_byte_ranges(updated_successor, &byte_ranges[1..], to_state_id);
+                    if start == end {
+                        self.transitions[from_state_id as usize][b as usize] = updated_successor;
+                        return;
+                    }
+                    updated_successors.push((successor, updated_successor));
+                    updated_successor
+                }
+            };
+            self.transitions[from_state_id as usize][b as usize] = updated_successor;
+        }
+    }
+
     fn get_or_allocate(&mut self, state: Utf8StateId) -> u32 {
         let state_bucket = state.0 as usize;
         if let Some(state) = self.index[state_bucket] {
@@ -218,18 +1209,57 @@ impl Utf8DFABuilder {
         new_state
     }
 
+    /// Sets the initial state of the automaton.
     pub fn set_initial_state(&mut self, initial_state: u32) {
+        assert!(
+            initial_state < self.max_num_states,
+            "State id is larger than max_num_states"
+        );
         let state_id_decoded = self.get_or_allocate(Utf8StateId::original(initial_state));
         self.initial_state = state_id_decoded
     }
 
+    /// Define a new state, looping onto itself whatever the byte.
+    pub fn add_sink_state(&mut self, state: u32, distance: Distance) {
+        assert!(
+            state < self.max_num_states,
+            "State id is larger than max_num_states"
+        );
+        let state_id = self.get_or_allocate(Utf8StateId::original(state));
+        self.distances[state_id as usize] = distance;
+        fill(&mut self.transitions[state_id as usize], state_id);
+    }
+
     /// Define a new state.
+    ///
+    /// All chars lead to `default_successor_orig`, except for the
+    /// transitions added with the returned `Utf8DFAStateBuilder`.
     pub fn add_state(
         &mut self,
         state: u32,
         distance: Distance,
         default_successor_orig: u32,
-    ) -> Utf8DFAStateBuilder {
+    ) -> Utf8DFAStateBuilder<'_> {
+        self.add_state_with_ranges(state, distance, default_successor_orig, &[])
+    }
+
+    /// Define a new state, with custom default successors for some ranges of lead bytes.
+    ///
+    /// By default, as in [.add_state(...)](#method.add_state), the lead byte
+    /// of a utf-8 encoded char tells how many bytes follow it.
+    /// Each of the `ranges` overrides this for its lead bytes, so that
+    /// framings other than utf-8 (e.g. escape bytes) can be expressed.
+    /// Later ranges take precedence over earlier ones.
+    ///
+    /// Transitions added with the returned `Utf8DFAStateBuilder` take precedence
+    /// over the ranges.
+    pub fn add_state_with_ranges(
+        &mut self,
+        state: u32,
+        distance: Distance,
+        default_successor_orig: u32,
+        ranges: &[DefaultSuccessorRange],
+    ) -> Utf8DFAStateBuilder<'_> {
         assert!(
             state < self.max_num_states,
             "State id is larger than max_num_states"
@@ -237,24 +1267,7 @@ impl Utf8DFABuilder {
         let state_id = self.get_or_allocate(Utf8StateId::original(state));
         self.distances[state_id as usize] = distance;
 
-        let default_successor_id =
-            self.get_or_allocate(Utf8StateId::original(default_successor_orig));
-
-        // creates a chain of states of predecessors of `default_successor_orig`.
-        // Accepting k-bytes (whatever the bytes are) from `predecessor_states[k-1]`
-        // leads to the `default_successor_orig` state.
-        let mut predecessor_states = [default_successor_id; 4];
-
-        {
-            for num_bytes in 1..4 {
-                let predecessor_state =
-                    Utf8StateId::predecessor(default_successor_orig, num_bytes as u8);
-                let predecessor_state_id = self.get_or_allocate(predecessor_state);
-                predecessor_states[num_bytes] = predecessor_state_id;
-                let succ = predecessor_states[num_bytes - 1];
-                fill(&mut self.transitions[predecessor_state_id as usize], succ);
-            }
-        }
+        let predecessor_states = self.predecessor_states(default_successor_orig);
 
         {
             let transitions = &mut self.transitions[state_id as usize];
@@ -268,19 +1281,149 @@ impl Utf8DFABuilder {
             fill(&mut transitions[240..256], predecessor_states[3]);
         }
 
+        for range in ranges {
+            assert!(
+                range.num_trailing_bytes < 4,
+                "At most 3 bytes can follow a lead byte"
+            );
+            let range_predecessor_states = self.predecessor_states(range.successor);
+            let start = *range.bytes.start() as usize;
+            let end = *range.bytes.end() as usize;
+            if start <= end {
+                fill(
+                    &mut self.transitions[state_id as usize][start..=end],
+                    range_predecessor_states[range.num_trailing_bytes as usize],
+                );
+            }
+        }
+
         Utf8DFAStateBuilder {
             dfa_builder: self,
             state_id,
-            default_successor: predecessor_states,
         }
     }
 
+    // creates a chain of states of predecessors of `successor_orig`.
+    // Accepting k-bytes (whatever the bytes are) from `predecessor_states[k]`
+    // leads to the `successor_orig` state.
+    fn predecessor_states(&mut self, successor_orig: u32) -> [u32; 4] {
+        assert!(
+            successor_orig < self.max_num_states,
+            "State id is larger than max_num_states"
+        );
+        let successor_id = self.get_or_allocate(Utf8StateId::original(successor_orig));
+        let mut predecessor_states = [successor_id; 4];
+        for num_bytes in 1..4 {
+            let predecessor_state = Utf8StateId::predecessor(successor_orig, num_bytes as u8);
+            let predecessor_state_id = self.get_or_allocate(predecessor_state);
+            predecessor_states[num_bytes] = predecessor_state_id;
+            let succ = predecessor_states[num_bytes - 1];
+            fill(&mut self.transitions[predecessor_state_id as usize], succ);
+        }
+        predecessor_states
+    }
+
+    // Returns the number of states allocated so far, intermediary states included.
+    pub(crate) fn num_states(&self) -> usize {
+        self.num_states as usize
+    }
+
+    /// Builds the `DFA`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no state was defined.
     pub fn build(self) -> DFA {
-        DFA {
-            transitions: self.transitions,
-            distances: self.distances,
-            initial_state: self.initial_state,
+        self.try_build().unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as [.build()](#method.build), but returns an error if no state
+    /// was defined.
+    pub fn try_build(self) -> Result<DFA, Error> {
+        if self.num_states == 0 {
+            return Err(Error::NoStates);
         }
+        Ok(DFA::from_parts(
+            self.transitions,
+            self.distances,
+            self.initial_state,
+        ))
+    }
+
+    /// Builds the `DFA`, copying the tables so that the builder can be
+    /// [reset](#method.reset) and reused.
+    ///
+    /// The tables of the automaton are allocated to their exact size at once,
+    /// while the buffers of the builder keep their capacity across queries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no state was defined.
+    pub fn build_by_ref(&self) -> DFA {
+        self.try_build_by_ref()
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as [.build_by_ref()](#method.build_by_ref), but returns an error
+    /// if no state was defined.
+    pub fn try_build_by_ref(&self) -> Result<DFA, Error> {
+        if self.num_states == 0 {
+            return Err(Error::NoStates);
+        }
+        Ok(DFA::from_flat_parts(
+            self.transitions.as_flattened().to_vec(),
+            self.distances.clone(),
+            self.initial_state,
+        ))
+    }
+
+    /// Removes all of the states, keeping the allocated buffers, so that the
+    /// builder is ready for a new automaton with at most `max_num_states`
+    /// states, as if it had been created with
+    /// [Utf8DFABuilder::with_max_num_states].
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::{Distance, Utf8DFABuilder};
+    /// let mut dfa_builder = Utf8DFABuilder::with_max_num_states(3);
+    /// for &chr in &['a', 'b'] {
+    ///     dfa_builder.reset(3);
+    ///     dfa_builder.add_state(0, Distance::AtLeast(1), 0);
+    ///     dfa_builder
+    ///         .add_state(1, Distance::AtLeast(1), 0)
+    ///         .add_transition(chr, 2);
+    ///     dfa_builder.add_state(2, Distance::Exact(0), 0);
+    ///     dfa_builder.set_initial_state(1);
+    ///     let dfa = dfa_builder.build_by_ref();
+    ///     assert_eq!(dfa.eval(chr.to_string()), Distance::Exact(0));
+    ///     assert_eq!(dfa.eval("c"), Distance::AtLeast(1));
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics in the same cases as [Utf8DFABuilder::try_with_max_num_states]
+    /// returns an error.
+    pub fn reset(&mut self, max_num_states: usize) {
+        self.try_reset(max_num_states)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as [.reset(...)](#method.reset), but returns an error, leaving
+    /// the builder untouched, if the states, along with the intermediary
+    /// states of the utf-8 encodings, cannot be addressed with `u32` state ids.
+    pub fn try_reset(&mut self, max_num_states: usize) -> Result<(), Error> {
+        if max_num_states > MAX_UTF8_NUM_STATES {
+            return Err(Error::TooManyStates { max_num_states });
+        }
+        self.index.clear();
+        self.index.resize(max_num_states * 4 + 3, None);
+        self.distances.clear();
+        self.transitions.clear();
+        self.kinds.clear();
+        self.initial_state = 0;
+        self.num_states = 0;
+        self.max_num_states = max_num_states as u32;
+        Ok(())
     }
 }
 
@@ -288,7 +1431,63 @@ impl Utf8DFABuilder {
 mod tests {
 
     use super::Distance;
-    use super::Utf8DFABuilder;
+    use super::{DefaultSuccessorRange, DfaRef, InvalidUtf8Sequence, Utf8DFABuilder, DFA};
+    use crate::{Error, LevenshteinAutomatonBuilder, SINK_STATE};
+
+    #[test]
+    fn test_utf8_dfa_builder_reset() {
+        fn add_states(dfa_builder: &mut Utf8DFABuilder, chr: char) {
+            dfa_builder.add_state(0, Distance::AtLeast(2), 0);
+            dfa_builder
+                .add_state(1, Distance::Exact(1), 0)
+                .add_transition(chr, 1);
+            dfa_builder.set_initial_state(1);
+        }
+        let mut reused = Utf8DFABuilder::with_max_num_states(1);
+        for &(max_num_states, chr) in &[(4, 'é'), (2, '寿'), (5, 'z')] {
+            let mut dfa_builder = Utf8DFABuilder::with_max_num_states(max_num_states);
+            add_states(&mut dfa_builder, chr);
+            let expected = dfa_builder.build();
+            reused.reset(max_num_states);
+            add_states(&mut reused, chr);
+            let dfa = reused.build_by_ref();
+            assert_eq!(dfa.num_states(), expected.num_states());
+            for text in &["", "é", "éé", "寿", "z", "ab"] {
+                assert_eq!(dfa.eval(text), expected.eval(text));
+            }
+        }
+    }
+
+    #[test]
+    fn test_utf8_dfa_builder_try_reset() {
+        let mut dfa_builder = Utf8DFABuilder::with_max_num_states(1);
+        dfa_builder.add_state(0, Distance::Exact(0), 0);
+        let max_num_states = usize::MAX / 4;
+        assert_eq!(
+            dfa_builder.try_reset(max_num_states),
+            Err(Error::TooManyStates { max_num_states })
+        );
+        assert_eq!(dfa_builder.build_by_ref().eval("a"), Distance::Exact(0));
+        assert_eq!(dfa_builder.try_reset(2), Ok(()));
+        assert_eq!(dfa_builder.try_build_by_ref().err(), Some(Error::NoStates));
+        assert_eq!(dfa_builder.try_build().err(), Some(Error::NoStates));
+    }
+
+    #[test]
+    fn test_build_dfa_reusing() {
+        let builder = LevenshteinAutomatonBuilder::new(1, true);
+        let mut dfa_builder = Utf8DFABuilder::with_max_num_states(0);
+        for query in &["abc", "", "寿司屋", "ab"] {
+            let dfa = builder.build_dfa_reusing(query, &mut dfa_builder);
+            let prefix_dfa = builder.build_prefix_dfa_reusing(query, &mut dfa_builder);
+            let expected = builder.build_dfa(query);
+            let expected_prefix = builder.build_prefix_dfa(query);
+            for text in &["", "a", "abc", "acb", "寿司", "寿司屋x", "abd"] {
+                assert_eq!(dfa.eval(text), expected.eval(text));
+                assert_eq!(prefix_dfa.eval(text), expected_prefix.eval(text));
+            }
+        }
+    }
 
     #[test]
     fn test_utf8_dfa_builder() {
@@ -307,4 +1506,337 @@ mod tests {
         assert_eq!(parity_num_letters("あ"), 1u8);
         assert_eq!(parity_num_letters("ああ"), 0u8);
     }
+
+    #[test]
+    fn test_dfa_ref() {
+        let mut dfa_builder = Utf8DFABuilder::with_max_num_states(2);
+        dfa_builder.add_state(0, Distance::Exact(1u8), 1);
+        dfa_builder.add_state(1, Distance::Exact(0u8), 0);
+        dfa_builder.set_initial_state(1u32);
+        let dfa = dfa_builder.build();
+        let dfa_ref = dfa.as_dfa_ref();
+        assert_eq!(dfa_ref.num_states(), dfa.num_states());
+        for text in &["", "a", "aあ", "❤❤", "ああa"] {
+            assert_eq!(dfa_ref.eval(text), dfa.eval(text));
+        }
+        let copy = dfa_ref.to_dfa();
+        assert_eq!(copy.eval("ああa"), Distance::Exact(1u8));
+
+        let transitions: Vec<u32> = (0..512).map(|i| 1 - (i / 256)).collect();
+        let distances = [Distance::Exact(0u8), Distance::AtLeast(1u8)];
+        let arena_dfa = DfaRef::from_parts(&transitions, &distances, 0).unwrap();
+        assert_eq!(arena_dfa.eval("aa"), Distance::Exact(0u8));
+        assert_eq!(arena_dfa.eval("aaa"), Distance::AtLeast(1u8));
+        assert!(DfaRef::from_parts(&transitions[..256], &distances, 0).is_none());
+        assert!(DfaRef::from_parts(&transitions, &distances, 2).is_none());
+        assert!(DfaRef::from_parts(&transitions, &distances[..1], 0).is_none());
+    }
+
+    #[test]
+    fn test_eval_iter_through_accepting_state_zero() {
+        let mut dfa_builder = Utf8DFABuilder::with_max_num_states(2);
+        dfa_builder.add_state(0, Distance::Exact(1u8), 1);
+        dfa_builder.add_state(1, Distance::Exact(0u8), 0);
+        dfa_builder.set_initial_state(1u32);
+        let dfa = dfa_builder.build();
+        for text in &["", "a", "aab", "aあ", "❤❤a"] {
+            let bytes = text.bytes();
+            assert_eq!(dfa.eval_iter(bytes.clone()), dfa.eval(text), "{}", text);
+            let results = bytes.map(Ok::<u8, ()>);
+            assert_eq!(dfa.try_eval_iter(results), Ok(dfa.eval(text)), "{}", text);
+        }
+        assert_eq!(dfa.eval_iter("aab".bytes()), Distance::Exact(1u8));
+    }
+
+    #[test]
+    fn test_add_sequence_transition() {
+        let mut dfa_builder = Utf8DFABuilder::with_max_num_states(3);
+        dfa_builder.add_sink_state(0, Distance::AtLeast(1u8));
+        dfa_builder
+            .add_state(1, Distance::AtLeast(1u8), 0)
+            .add_sequence_transition("寿".as_bytes(), 2)
+            .unwrap();
+        {
+            let mut state_builder = dfa_builder.add_state(2, Distance::Exact(0u8), 0);
+            assert_eq!(
+                state_builder.add_sequence_transition(b"\xE5\xAF", 2),
+                Err(InvalidUtf8Sequence)
+            );
+            assert_eq!(
+                state_builder.add_sequence_transition(b"ab", 2),
+                Err(InvalidUtf8Sequence)
+            );
+            assert_eq!(
+                state_builder.add_sequence_transition(b"", 2),
+                Err(InvalidUtf8Sequence)
+            );
+        }
+        dfa_builder.set_initial_state(1);
+        let dfa = dfa_builder.build();
+        assert_eq!(dfa.eval("寿"), Distance::Exact(0u8));
+        assert_eq!(dfa.eval("寿寿"), Distance::AtLeast(1u8));
+        assert_eq!(dfa.eval("寿a"), Distance::AtLeast(1u8));
+        assert!(matches!(dfa.eval([0xE5u8, 0xAF]), Distance::AtLeast(_)));
+    }
+
+    #[test]
+    fn test_add_state_with_ranges() {
+        // Computes the parity of the number of escape sequences,
+        // made of `0x1B` followed by any byte.
+        let mut dfa_builder = Utf8DFABuilder::with_max_num_states(2);
+        for state in 0..2 {
+            let escape = DefaultSuccessorRange {
+                bytes: 0x1B..=0x1B,
+                num_trailing_bytes: 1,
+                successor: 1 - state,
+            };
+            dfa_builder.add_state_with_ranges(
+                state,
+                Distance::Exact(state as u8),
+                state,
+                &[escape],
+            );
+        }
+        dfa_builder.set_initial_state(0);
+        let dfa = dfa_builder.build();
+        assert_eq!(dfa.eval(b"abc"), Distance::Exact(0u8));
+        assert_eq!(dfa.eval(b"a\x1B\xFFb"), Distance::Exact(1u8));
+        assert_eq!(dfa.eval(b"\x1B\x1B"), Distance::Exact(1u8));
+        assert_eq!(dfa.eval(b"\x1B\x1B\x1Bz"), Distance::Exact(0u8));
+        assert_eq!(dfa.eval("寿\x1B司".as_bytes()), Distance::Exact(1u8));
+    }
+
+    #[test]
+    fn test_min_distances() {
+        for &(max_distance, transposition_cost_one) in &[(0, false), (1, true), (2, false)] {
+            let builder = LevenshteinAutomatonBuilder::new(max_distance, transposition_cost_one);
+            for query in &["", "abc", "寿司", "abcabc"] {
+                for dfa in &[builder.build_dfa(query), builder.build_prefix_dfa(query)] {
+                    let min_distances = dfa.min_distances();
+                    assert_eq!(min_distances[0], None);
+                    for state_id in 0..dfa.num_states() as u32 {
+                        assert_eq!(
+                            min_distances[state_id as usize],
+                            dfa.min_distance_from(state_id)
+                        );
+                        assert_eq!(
+                            dfa.can_match(state_id),
+                            min_distances[state_id as usize].is_some()
+                        );
+                    }
+                    assert_eq!(min_distances[dfa.initial_state() as usize], Some(0));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_can_match_is_computed_lazily() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<DFA>();
+        let dfa = LevenshteinAutomatonBuilder::new(1, true).build_dfa("abc");
+        let heap_size = dfa.heap_size();
+        assert!(dfa.can_match(dfa.initial_state()));
+        assert_eq!(dfa.heap_size(), heap_size + dfa.num_states());
+    }
+
+    #[test]
+    fn test_eval_prefixes() {
+        let dfa = LevenshteinAutomatonBuilder::new(2, true).build_dfa("abcd");
+        for text in &["", "a", "abdc", "xxabcd", "abcdxyz"] {
+            let distances: Vec<Distance> = dfa.eval_prefixes(*text).collect();
+            let expected: Vec<Distance> =
+                (1..=text.len()).map(|len| dfa.eval(&text[..len])).collect();
+            assert_eq!(distances, expected);
+        }
+    }
+
+    #[test]
+    fn test_universally_accepting_states() {
+        let builder = LevenshteinAutomatonBuilder::new(1, false);
+        let dfa = builder.build_dfa("abc");
+        assert!(!dfa.universally_accepting_states().contains(&true));
+        let dfa = builder.build_prefix_dfa("寿司");
+        let universally_accepting = dfa.universally_accepting_states();
+        for (text, expected) in &[("", false), ("寿", true), ("寿x", true), ("x", false)] {
+            let state = text
+                .bytes()
+                .fold(dfa.initial_state(), |state, b| dfa.transition(state, b));
+            assert_eq!(universally_accepting[state as usize], *expected);
+        }
+        // In the middle of a char.
+        let state = dfa.transition(dfa.initial_state(), "寿".as_bytes()[0]);
+        assert!(!universally_accepting[state as usize]);
+    }
+
+    #[cfg(feature = "fst")]
+    #[test]
+    fn test_fst_automaton() {
+        use fst::{IntoStreamer, Streamer};
+        let set = fst::Set::from_iter(vec!["cat", "cats", "coat", "dog", "寿司"]).unwrap();
+        let dfa = LevenshteinAutomatonBuilder::new(1, true).build_dfa("cta");
+        let mut stream = set.search(&dfa).into_stream();
+        let mut matches = Vec::new();
+        while let Some(key) = stream.next() {
+            matches.push(String::from_utf8(key.to_vec()).unwrap());
+        }
+        assert_eq!(matches, vec!["cat"]);
+        let dfa = LevenshteinAutomatonBuilder::new(0, false).build_prefix_dfa("寿");
+        assert_eq!(
+            set.search(&dfa).into_stream().into_strs().unwrap(),
+            vec!["寿司"]
+        );
+    }
+
+    #[cfg(feature = "tantivy_automaton")]
+    #[test]
+    fn test_tantivy_fst_automaton() {
+        use tantivy_fst::{Automaton, IntoStreamer, Map, Streamer};
+
+        let words = ["cat", "cats", "coat", "dog", "寿司"];
+        let map =
+            Map::from_iter(words.iter().enumerate().map(|(i, word)| (word, i as u64))).unwrap();
+        let dfa = LevenshteinAutomatonBuilder::new(1, true).build_dfa("cta");
+        let mut stream = map.search(&dfa).into_stream();
+        let mut matches = Vec::new();
+        while let Some((key, value)) = stream.next() {
+            matches.push((String::from_utf8(key.to_vec()).unwrap(), value));
+        }
+        assert_eq!(matches, vec![("cat".to_string(), 0)]);
+        let dfa = LevenshteinAutomatonBuilder::new(0, false).build_prefix_dfa("ca");
+        assert!(!Automaton::can_match(
+            &dfa,
+            &Automaton::accept(&dfa, &dfa.start(), b'd')
+        ));
+        let mut stream = map.search(&dfa).into_stream();
+        let mut count = 0;
+        while stream.next().is_some() {
+            count += 1;
+        }
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn test_utf8_sequences() {
+        let chars = [
+            '\0',
+            'a',
+            '\u{7F}',
+            '\u{80}',
+            'é',
+            '\u{7FF}',
+            '\u{800}',
+            '寿',
+            '\u{D7FF}',
+            '\u{E000}',
+            '\u{FFFF}',
+            '\u{10000}',
+            '😀',
+            '\u{10FFFF}',
+        ];
+        for &start in &chars {
+            for &end in &chars {
+                let sequences = super::utf8_sequences(start as u32, end as u32);
+                for &chr in &chars {
+                    let mut buffer = [0u8; 4];
+                    let bytes = chr.encode_utf8(&mut buffer).as_bytes();
+                    let num_matches = sequences
+                        .iter()
+                        .filter(|sequence| {
+                            sequence.len() == bytes.len()
+                                && sequence
+                                    .iter()
+                                    .zip(bytes)
+                                    .all(|(&(lo, hi), &b)| lo <= b && b <= hi)
+                        })
+                        .count();
+                    let expected = usize::from(start <= chr && chr <= end);
+                    assert_eq!(num_matches, expected, "{:?}..={:?} {:?}", start, end, chr);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_add_transition_range() {
+        let mut dfa_builder = Utf8DFABuilder::with_max_num_states(4);
+        dfa_builder.add_sink_state(0, Distance::AtLeast(1u8));
+        {
+            let mut state_builder = dfa_builder.add_state(1, Distance::AtLeast(1u8), 0);
+            state_builder.add_transition_range('a'..='z', 2);
+            state_builder.add_transition_range('\u{80}'..='\u{7FF}', 2);
+            state_builder.add_transition_range('\u{800}'..='\u{10FFFF}', 2);
+            // individual transitions added afterwards take precedence.
+            state_builder.add_transition('é', 3);
+            state_builder.add_transition('寿', 3);
+            state_builder.add_transition('m', 3);
+        }
+        dfa_builder.add_state(2, Distance::Exact(0u8), 0);
+        dfa_builder.add_state(3, Distance::Exact(1u8), 0);
+        dfa_builder.set_initial_state(1);
+        let dfa = dfa_builder.build();
+        for chr in [
+            'a', 'z', 'è', 'ß', 'ǅ', '\u{7FF}', '司', '€', '\u{FFFF}', '😀',
+        ] {
+            assert_eq!(dfa.eval(chr.to_string()), Distance::Exact(0u8), "{:?}", chr);
+        }
+        for chr in ['é', '寿', 'm'] {
+            assert_eq!(dfa.eval(chr.to_string()), Distance::Exact(1u8), "{:?}", chr);
+        }
+        for chr in ['A', '\0', '\u{7F}'] {
+            assert_eq!(
+                dfa.eval(chr.to_string()),
+                Distance::AtLeast(1u8),
+                "{:?}",
+                chr
+            );
+        }
+        assert_eq!(dfa.eval("ab"), Distance::AtLeast(1u8));
+    }
+
+    #[test]
+    fn test_prune() {
+        // 1 --a--> 2 --b--> 3 (accepting)
+        //   --c--> 4 --*--> 5, 4 and 5 cannot accept.
+        // 6 is unreachable.
+        let mut transitions = vec![[0u32; 256]; 7];
+        transitions[1][b'a' as usize] = 2;
+        transitions[1][b'c' as usize] = 4;
+        transitions[2][b'b' as usize] = 3;
+        transitions[4] = [5; 256];
+        transitions[6] = [3; 256];
+        let distances = vec![
+            Distance::AtLeast(3),
+            Distance::AtLeast(2),
+            Distance::AtLeast(1),
+            Distance::Exact(0),
+            Distance::AtLeast(2),
+            Distance::AtLeast(1),
+            Distance::Exact(1),
+        ];
+        let dfa = DFA::from_parts(transitions, distances, 1);
+        let pruned = dfa.prune();
+        assert_eq!(pruned.num_states(), 4);
+        assert_eq!(pruned.initial_state(), 1);
+        assert_eq!(pruned.transition(1, b'c'), SINK_STATE);
+        // The lower bound of 5, only reached through 4.
+        assert_eq!(pruned.distance(SINK_STATE), Distance::AtLeast(1));
+        assert!(!pruned.can_match(SINK_STATE));
+        for text in &["", "a", "ab", "abc", "c", "cx", "cxy"] {
+            match dfa.eval(text) {
+                Distance::Exact(d) => assert_eq!(pruned.eval(text), Distance::Exact(d)),
+                Distance::AtLeast(d) => {
+                    assert!(
+                        matches!(pruned.eval(text), Distance::AtLeast(pruned_d) if pruned_d <= d)
+                    )
+                }
+            }
+        }
+        let dfa = LevenshteinAutomatonBuilder::new(2, true).build_dfa("levenshtein");
+        let pruned = dfa.prune();
+        assert!(pruned.num_states() <= dfa.num_states());
+        for text in &["levenshtein", "levenstein", "leventhsein", "lev", "abc"] {
+            assert_eq!(pruned.eval(text), dfa.eval(text));
+        }
+    }
 }
@@ -0,0 +1,277 @@
+use super::{Distance, LevenshteinAutomatonBuilder, DFA};
+
+/// Returns the words of a sorted list accepted by the automaton, along with
+/// their distance, in the order of the list.
+///
+/// The list is traversed as a trie: the states reached on a common prefix
+/// are shared between consecutive words, and all of the words starting with
+/// a prefix that cannot match anymore are skipped at once, by a binary
+/// search. This is much faster than evaluating every word on its own.
+///
+/// The words must be sorted by bytes, as with `sort()`: otherwise, some of
+/// the accepted words may be missed.
+///
+/// ```rust
+/// # use levenshtein_automata::{fuzzy_lookup, LevenshteinAutomatonBuilder};
+/// let words = ["apple", "applet", "apply", "banana", "maple"];
+/// let dfa = LevenshteinAutomatonBuilder::new(1, true).build_dfa("aple");
+/// assert_eq!(fuzzy_lookup(&dfa, &words), vec![("apple", 1), ("maple", 1)]);
+/// ```
+pub fn fuzzy_lookup<'a, S: AsRef<str>>(dfa: &DFA, sorted_words: &'a [S]) -> Vec<(&'a str, u8)> {
+    traverse_sorted(dfa, sorted_words, |word| word.as_ref().as_bytes())
+        .into_iter()
+        .map(|(i, d)| (sorted_words[i].as_ref(), d))
+        .collect()
+}
+
+/// Same as [fuzzy_lookup](./fn.fuzzy_lookup.html), for words that are not
+/// in a slice.
+///
+/// The states reached on the prefix shared with the previous word are
+/// reused, but words cannot be skipped. The words do not need to be sorted,
+/// although sorting them maximizes the shared prefixes.
+pub fn fuzzy_lookup_iter<'a, I>(dfa: &DFA, words: I) -> Vec<(&'a str, u8)>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let live_states = dfa.live_states();
+    let mut matches = Vec::new();
+    // states[i] is the state reached on the first `i` bytes of `previous_word`,
+    // up to the first state that cannot match.
+    let mut states: Vec<u32> = vec![dfa.initial_state()];
+    let mut previous_word: &[u8] = &[];
+    for word in words {
+        let bytes = word.as_bytes();
+        let common_prefix_len = common_prefix_len(previous_word, bytes).min(states.len() - 1);
+        states.truncate(common_prefix_len + 1);
+        previous_word = bytes;
+        let mut is_dead = false;
+        for &b in &bytes[common_prefix_len..] {
+            let state = dfa.transition(states[states.len() - 1], b);
+            if !live_states[state as usize] {
+                is_dead = true;
+                break;
+            }
+            states.push(state);
+        }
+        if !is_dead {
+            if let Distance::Exact(d) = dfa.distance(states[states.len() - 1]) {
+                matches.push((word, d));
+            }
+        }
+    }
+    matches
+}
+
+/// Finds the entries of a sorted list closest to a query.
+///
+/// The entries within a distance of `0` are looked up first, then within a
+/// distance of `1`, and so on: as soon as `k` entries are found, the larger
+/// distances are not explored. Since the automata grow with the distance,
+/// the few best corrections of a query are found much faster than by
+/// enumerating all of the entries within the maximum distance.
+///
+/// ```rust
+/// # use levenshtein_automata::NearestNeighbors;
+/// let words = ["cart", "cast", "cat", "coat", "dog"];
+/// let neighbors = NearestNeighbors::new(2, true);
+/// assert_eq!(neighbors.find_top_k(&words, "cat", 1), vec![("cat", 0)]);
+/// assert_eq!(
+///     neighbors.find_top_k(&words, "cat", 3),
+///     vec![("cat", 0), ("cart", 1), ("cast", 1)]
+/// );
+/// ```
+pub struct NearestNeighbors {
+    // builders[d] builds the automata of the distance `d`.
+    builders: Vec<LevenshteinAutomatonBuilder>,
+}
+
+impl NearestNeighbors {
+    /// Creates the finder of the entries within `max_distance` of the queries.
+    ///
+    /// This builds the automaton builders of all of the distances up to
+    /// `max_distance`, which is expensive beyond a distance of `3`.
+    pub fn new(max_distance: u8, transposition_cost_one: bool) -> NearestNeighbors {
+        NearestNeighbors {
+            builders: (0..=max_distance)
+                .map(|d| LevenshteinAutomatonBuilder::new(d, transposition_cost_one))
+                .collect(),
+        }
+    }
+
+    /// Returns the `k` entries of `sorted_words` closest to `query`, along
+    /// with their distance, closest first.
+    ///
+    /// Entries at the same distance keep their order in the list. Fewer than
+    /// `k` entries are returned if there are not enough of them within the
+    /// maximum distance. As with [fuzzy_lookup](./fn.fuzzy_lookup.html), the
+    /// words must be sorted by bytes.
+    pub fn find_top_k<'a, S: AsRef<str>>(
+        &self,
+        sorted_words: &'a [S],
+        query: &str,
+        k: usize,
+    ) -> Vec<(&'a str, u8)> {
+        let mut neighbors = Vec::new();
+        if k == 0 {
+            return neighbors;
+        }
+        for builder in &self.builders {
+            neighbors = fuzzy_lookup(&builder.build_dfa(query), sorted_words);
+            if neighbors.len() >= k {
+                break;
+            }
+        }
+        neighbors.sort_by_key(|&(_, d)| d);
+        neighbors.truncate(k);
+        neighbors
+    }
+}
+
+/// Shortcut for [NearestNeighbors::find_top_k], up to a distance of `2`,
+/// with transpositions counting as one edit.
+///
+/// Creating a [NearestNeighbors] is not free: reuse one for several queries.
+pub fn find_top_k<'a, S: AsRef<str>>(
+    sorted_words: &'a [S],
+    query: &str,
+    k: usize,
+) -> Vec<(&'a str, u8)> {
+    NearestNeighbors::new(2, true).find_top_k(sorted_words, query, k)
+}
+
+fn common_prefix_len(left: &[u8], right: &[u8]) -> usize {
+    left.iter()
+        .zip(right)
+        .take_while(|(left, right)| left == right)
+        .count()
+}
+
+// Evaluates the automaton on sorted terms, sharing the work on common prefixes.
+// Returns the positions of the accepted terms along with their distance.
+pub(crate) fn traverse_sorted<T, F>(dfa: &DFA, sorted_terms: &[T], key: F) -> Vec<(usize, u8)>
+where
+    F: Fn(&T) -> &[u8],
+{
+    let live_states = dfa.live_states();
+    let mut matches = Vec::new();
+    // states[i] is the state reached on the first `i` bytes of `previous_term`.
+    let mut states: Vec<u32> = vec![dfa.initial_state()];
+    let mut previous_term: &[u8] = &[];
+    let mut i = 0;
+    while i < sorted_terms.len() {
+        let term = key(&sorted_terms[i]);
+        let common_prefix_len = common_prefix_len(previous_term, term).min(states.len() - 1);
+        states.truncate(common_prefix_len + 1);
+        previous_term = term;
+        let mut dead_prefix_len = None;
+        for &b in &term[common_prefix_len..] {
+            let state = dfa.transition(states[states.len() - 1], b);
+            if !live_states[state as usize] {
+                dead_prefix_len = Some(states.len());
+                break;
+            }
+            states.push(state);
+        }
+        match dead_prefix_len {
+            Some(len) => {
+                // None of the terms starting with this prefix can match.
+                let dead_prefix = &term[..len];
+                i += sorted_terms[i..].partition_point(|term| key(term).starts_with(dead_prefix));
+            }
+            None => {
+                if let Distance::Exact(d) = dfa.distance(states[states.len() - 1]) {
+                    matches.push((i, d));
+                }
+                i += 1;
+            }
+        }
+    }
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_top_k, fuzzy_lookup, fuzzy_lookup_iter, NearestNeighbors};
+    use crate::{Distance, LevenshteinAutomatonBuilder};
+
+    const WORDS: &[&str] = &[
+        "",
+        "a",
+        "ab",
+        "abc",
+        "abcd",
+        "abd",
+        "abdc",
+        "b",
+        "bc",
+        "levenstein",
+        "xyz",
+        "寿",
+        "寿司",
+        "寿司屋",
+    ];
+
+    fn naive_lookup<'a>(
+        query: &str,
+        words: &[&'a str],
+        max_distance: u8,
+        prefix: bool,
+    ) -> Vec<(&'a str, u8)> {
+        let builder = LevenshteinAutomatonBuilder::new(max_distance, true);
+        let dfa = if prefix {
+            builder.build_prefix_dfa(query)
+        } else {
+            builder.build_dfa(query)
+        };
+        words
+            .iter()
+            .filter_map(|&word| match dfa.eval(word) {
+                Distance::Exact(d) => Some((word, d)),
+                Distance::AtLeast(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fuzzy_lookup() {
+        for max_distance in 0..=2u8 {
+            let builder = LevenshteinAutomatonBuilder::new(max_distance, true);
+            for query in &["", "abc", "寿司", "levenshtein", "xy"] {
+                let dfa = builder.build_dfa(query);
+                let expected = naive_lookup(query, WORDS, max_distance, false);
+                assert_eq!(fuzzy_lookup(&dfa, WORDS), expected);
+                assert_eq!(fuzzy_lookup_iter(&dfa, WORDS.iter().cloned()), expected);
+                let prefix_dfa = builder.build_prefix_dfa(query);
+                let expected = naive_lookup(query, WORDS, max_distance, true);
+                assert_eq!(fuzzy_lookup(&prefix_dfa, WORDS), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fuzzy_lookup_iter_unsorted() {
+        let mut words = WORDS.to_vec();
+        words.reverse();
+        let dfa = LevenshteinAutomatonBuilder::new(1, true).build_dfa("abc");
+        assert_eq!(
+            fuzzy_lookup_iter(&dfa, words.iter().cloned()),
+            naive_lookup("abc", &words, 1, false)
+        );
+    }
+
+    #[test]
+    fn test_find_top_k() {
+        let neighbors = NearestNeighbors::new(2, true);
+        for query in &["abc", "寿司", "b", "levenshtein"] {
+            let mut expected = naive_lookup(query, WORDS, 2, false);
+            expected.sort_by_key(|&(_, d)| d);
+            for k in 0..=WORDS.len() {
+                let top_k = neighbors.find_top_k(WORDS, query, k);
+                assert_eq!(top_k, &expected[..k.min(expected.len())], "{} {}", query, k);
+            }
+        }
+        assert_eq!(find_top_k(WORDS, "abe", 2), vec![("ab", 1), ("abc", 1)]);
+        assert!(find_top_k(WORDS, "qwerty", 3).is_empty());
+    }
+}
@@ -0,0 +1,491 @@
+use super::{CaseFolding, Distance};
+use std::mem;
+
+/// Returns the Levenshtein distance between two strings, computed over chars.
+///
+/// This relies on dynamic programming rather than on an automaton,
+/// which is cheaper when comparing a single pair of strings.
+///
+/// ```rust
+/// # use levenshtein_automata::levenshtein;
+/// assert_eq!(levenshtein("kitten", "sitting"), 3);
+/// assert_eq!(levenshtein("寿司", "寿"), 1);
+/// ```
+pub fn levenshtein(left: &str, right: &str) -> usize {
+    edit_distance(left, right, false, usize::MAX)
+}
+
+/// Same as [levenshtein](./fn.levenshtein.html), with transpositions of
+/// contiguous chars counting as one edit.
+///
+/// As for the automata built with `transposition_cost_one`, this is the
+/// *optimal string alignment* distance: a substring cannot be edited more than once.
+///
+/// ```rust
+/// # use levenshtein_automata::damerau_levenshtein;
+/// assert_eq!(damerau_levenshtein("abcd", "acbd"), 1);
+/// assert_eq!(damerau_levenshtein("ca", "abc"), 3);
+/// ```
+pub fn damerau_levenshtein(left: &str, right: &str) -> usize {
+    edit_distance(left, right, true, usize::MAX)
+}
+
+/// Returns the Hamming distance between two strings, i.e. the number of
+/// positions at which their chars differ, or `None` if they do not have
+/// the same number of chars.
+///
+/// ```rust
+/// # use levenshtein_automata::hamming;
+/// assert_eq!(hamming("karolin", "kathrin"), Some(3));
+/// assert_eq!(hamming("寿司", "寿"), None);
+/// ```
+pub fn hamming(left: &str, right: &str) -> Option<usize> {
+    let mut right_chars = right.chars();
+    let mut distance = 0;
+    for left_chr in left.chars() {
+        match right_chars.next() {
+            Some(right_chr) => distance += usize::from(left_chr != right_chr),
+            None => return None,
+        }
+    }
+    match right_chars.next() {
+        Some(_) => None,
+        None => Some(distance),
+    }
+}
+
+/// Returns the indel distance between two strings, i.e. the number of
+/// chars to insert or delete, without substitutions, to turn one into the
+/// other.
+///
+/// This is the number of chars that are not part of a longest common
+/// subsequence of the two strings.
+///
+/// ```rust
+/// # use levenshtein_automata::indel;
+/// assert_eq!(indel("kitten", "sitting"), 5);
+/// assert_eq!(indel("寿司", "司"), 1);
+/// ```
+pub fn indel(left: &str, right: &str) -> usize {
+    let left: Vec<char> = left.chars().collect();
+    let right: Vec<char> = right.chars().collect();
+    // `row[j]` is the length of the longest common subsequence of the
+    // first `j` chars of `left` and the prefix of `right` read so far.
+    let mut previous_row: Vec<usize> = vec![0; left.len() + 1];
+    let mut current_row: Vec<usize> = vec![0; left.len() + 1];
+    for &right_chr in &right {
+        for j in 1..=left.len() {
+            current_row[j] = if left[j - 1] == right_chr {
+                previous_row[j - 1] + 1
+            } else {
+                previous_row[j].max(current_row[j - 1])
+            };
+        }
+        mem::swap(&mut previous_row, &mut current_row);
+    }
+    left.len() + right.len() - 2 * previous_row[left.len()]
+}
+
+/// Returns the Levenshtein distance between two strings,
+/// as computed by an automaton with the given `max_distance`.
+///
+/// The computation stops as soon as the distance is known
+/// to exceed `max_distance`.
+///
+/// ```rust
+/// # use levenshtein_automata::{levenshtein_within, Distance, LevenshteinAutomatonBuilder};
+/// assert_eq!(levenshtein_within("kitten", "sitting", 3), Distance::Exact(3));
+/// assert_eq!(levenshtein_within("kitten", "sitting", 2), Distance::AtLeast(3));
+/// let dfa = LevenshteinAutomatonBuilder::new(2, false).build_dfa("kitten");
+/// assert_eq!(dfa.eval("sitting"), levenshtein_within("kitten", "sitting", 2));
+/// ```
+pub fn levenshtein_within(left: &str, right: &str, max_distance: u8) -> Distance {
+    distance_within(left, right, max_distance, false, CaseFolding::None)
+}
+
+/// Same as [levenshtein_within](./fn.levenshtein_within.html), with
+/// transpositions of contiguous chars counting as one edit.
+pub fn damerau_levenshtein_within(left: &str, right: &str, max_distance: u8) -> Distance {
+    distance_within(left, right, max_distance, true, CaseFolding::None)
+}
+
+/// Returns the distance between two strings, as computed by a DFA built by
+/// `LevenshteinAutomatonBuilder::new(max_distance, transposition_cost_one)`
+/// with the given case folding.
+///
+/// Only the options of
+/// [LevenshteinAutomatonBuilder](./struct.LevenshteinAutomatonBuilder.html)
+/// are covered: the costs of a
+/// [WeightedAutomatonBuilder](./struct.WeightedAutomatonBuilder.html)
+/// are not.
+///
+/// ```rust
+/// # use levenshtein_automata::{distance_within, CaseFolding, Distance, LevenshteinAutomatonBuilder};
+/// assert_eq!(distance_within("Kitten", "kitten", 1, false, CaseFolding::Ascii), Distance::Exact(0));
+/// let dfa = LevenshteinAutomatonBuilder::new(1, true)
+///     .case_folding(CaseFolding::Ascii)
+///     .build_dfa("Kitten");
+/// assert_eq!(dfa.eval("iktten"), distance_within("Kitten", "iktten", 1, true, CaseFolding::Ascii));
+/// ```
+pub fn distance_within(
+    left: &str,
+    right: &str,
+    max_distance: u8,
+    transposition_cost_one: bool,
+    case_folding: CaseFolding,
+) -> Distance {
+    let left: Vec<char> = left.chars().map(|chr| case_folding.fold(chr)).collect();
+    let right: Vec<char> = right.chars().map(|chr| case_folding.fold(chr)).collect();
+    let distance = edit_distance_chars(
+        &left,
+        &right,
+        transposition_cost_one,
+        max_distance as usize,
+        false,
+    );
+    if distance > max_distance as usize {
+        Distance::AtLeast(max_distance + 1)
+    } else {
+        Distance::Exact(distance as u8)
+    }
+}
+
+// Returns the distance between `left` and `right`, or any value
+// greater than `max_distance` if it exceeds `max_distance`.
+fn edit_distance(
+    left: &str,
+    right: &str,
+    transposition_cost_one: bool,
+    max_distance: usize,
+) -> usize {
+    let left: Vec<char> = left.chars().collect();
+    let right: Vec<char> = right.chars().collect();
+    edit_distance_chars(&left, &right, transposition_cost_one, max_distance, false)
+}
+
+// Distance over chars. Rows are the prefixes of `right`.
+//
+// With `prefix`, returns the minimum distance between `left` and the prefixes of `right`.
+pub(crate) fn edit_distance_chars(
+    left: &[char],
+    right: &[char],
+    transposition_cost_one: bool,
+    max_distance: usize,
+    prefix: bool,
+) -> usize {
+    let width = left.len() + 1;
+    let mut before_previous_row: Vec<usize> = vec![0; width];
+    let mut previous_row: Vec<usize> = (0..width).collect();
+    let mut current_row: Vec<usize> = vec![0; width];
+    let mut best_prefix_distance = previous_row[left.len()];
+    for i in 1..=right.len() {
+        current_row[0] = i;
+        for j in 1..width {
+            let substitution = usize::from(right[i - 1] != left[j - 1]);
+            let mut d = (previous_row[j - 1] + substitution)
+                .min(previous_row[j] + 1)
+                .min(current_row[j - 1] + 1);
+            if transposition_cost_one
+                && i > 1
+                && j > 1
+                && right[i - 1] == left[j - 2]
+                && right[i - 2] == left[j - 1]
+            {
+                d = d.min(before_previous_row[j - 2] + 1);
+            }
+            current_row[j] = d;
+        }
+        best_prefix_distance = best_prefix_distance.min(current_row[left.len()]);
+        let current_min = current_row.iter().cloned().min().unwrap_or(0);
+        let previous_min = previous_row.iter().cloned().min().unwrap_or(0);
+        // The following rows cannot go below `lower_bound`:
+        // transpositions skip a row, at the cost of one edit.
+        let lower_bound = current_min.min(previous_min + 1);
+        if prefix && best_prefix_distance <= lower_bound {
+            // the following prefixes cannot get any closer.
+            return best_prefix_distance;
+        }
+        if lower_bound > max_distance {
+            // the following rows can only be further.
+            return max_distance + 1;
+        }
+        mem::swap(&mut before_previous_row, &mut previous_row);
+        mem::swap(&mut previous_row, &mut current_row);
+    }
+    if prefix {
+        best_prefix_distance
+    } else {
+        previous_row[left.len()]
+    }
+}
+
+/// Kind of edit operation.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum EditOp {
+    /// The chars are equal.
+    Match,
+    /// A char of the query is replaced by a char of the candidate.
+    Substitution,
+    /// A char of the candidate is absent from the query.
+    Insertion,
+    /// A char of the query is absent from the candidate.
+    Deletion,
+    /// Two contiguous chars of the query are swapped in the candidate.
+    Transposition,
+}
+
+/// Step of the alignment of a query and a candidate.
+///
+/// Positions are char indices.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Edit {
+    /// Kind of edit.
+    pub op: EditOp,
+    /// Position of the first char of the query concerned by the edit.
+    /// For insertions, the position before which the char is inserted.
+    pub query_position: usize,
+    /// Position of the first char of the candidate concerned by the edit.
+    /// For deletions, the position before which the char is deleted.
+    pub candidate_position: usize,
+}
+
+/// Returns an optimal alignment of `query` and `candidate`, as the sequence
+/// of edits turning the query into the candidate, in order.
+///
+/// Matches are included, so that the edits cover both strings entirely,
+/// and the number of edits other than [EditOp::Match](./enum.EditOp.html#variant.Match)
+/// is the distance. With `transposition_cost_one`, two swapped contiguous chars
+/// are a single [EditOp::Transposition](./enum.EditOp.html#variant.Transposition),
+/// as for the automata.
+///
+/// Ties are broken by preferring matches and substitutions, then deletions, then insertions.
+///
+/// ```rust
+/// # use levenshtein_automata::{edit_script, Edit, EditOp};
+/// let edits: Vec<Edit> = edit_script("kitten", "sitting", false)
+///     .into_iter()
+///     .filter(|edit| edit.op != EditOp::Match)
+///     .collect();
+/// assert_eq!(
+///     edits,
+///     vec![
+///         Edit { op: EditOp::Substitution, query_position: 0, candidate_position: 0 },
+///         Edit { op: EditOp::Substitution, query_position: 4, candidate_position: 4 },
+///         Edit { op: EditOp::Insertion, query_position: 6, candidate_position: 6 },
+///     ]
+/// );
+/// ```
+pub fn edit_script(query: &str, candidate: &str, transposition_cost_one: bool) -> Vec<Edit> {
+    let query: Vec<char> = query.chars().collect();
+    let candidate: Vec<char> = candidate.chars().collect();
+    edit_script_chars(&query, &candidate, transposition_cost_one)
+}
+
+pub(crate) fn edit_script_chars(
+    query: &[char],
+    candidate: &[char],
+    transposition_cost_one: bool,
+) -> Vec<Edit> {
+    let width = query.len() + 1;
+    // `table[i * width + j]` is the distance between `candidate[..i]` and `query[..j]`.
+    let mut table: Vec<usize> = vec![0; (candidate.len() + 1) * width];
+    for i in 0..=candidate.len() {
+        for j in 0..=query.len() {
+            table[i * width + j] = if i == 0 || j == 0 {
+                i + j
+            } else {
+                let substitution = usize::from(candidate[i - 1] != query[j - 1]);
+                let mut d = (table[(i - 1) * width + j - 1] + substitution)
+                    .min(table[(i - 1) * width + j] + 1)
+                    .min(table[i * width + j - 1] + 1);
+                if is_transposition(query, candidate, transposition_cost_one, i, j) {
+                    d = d.min(table[(i - 2) * width + j - 2] + 1);
+                }
+                d
+            };
+        }
+    }
+    let mut edits = Vec::new();
+    let (mut i, mut j) = (candidate.len(), query.len());
+    while i > 0 || j > 0 {
+        let d = table[i * width + j];
+        let edit = |op, i: usize, j: usize| Edit {
+            op,
+            query_position: j,
+            candidate_position: i,
+        };
+        if i > 0 && j > 0 && candidate[i - 1] == query[j - 1] && table[(i - 1) * width + j - 1] == d
+        {
+            edits.push(edit(EditOp::Match, i - 1, j - 1));
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && j > 0 && table[(i - 1) * width + j - 1] + 1 == d {
+            edits.push(edit(EditOp::Substitution, i - 1, j - 1));
+            i -= 1;
+            j -= 1;
+        } else if j > 0 && table[i * width + j - 1] + 1 == d {
+            edits.push(edit(EditOp::Deletion, i, j - 1));
+            j -= 1;
+        } else if i > 0 && table[(i - 1) * width + j] + 1 == d {
+            edits.push(edit(EditOp::Insertion, i - 1, j));
+            i -= 1;
+        } else {
+            debug_assert!(is_transposition(
+                query,
+                candidate,
+                transposition_cost_one,
+                i,
+                j
+            ));
+            edits.push(edit(EditOp::Transposition, i - 2, j - 2));
+            i -= 2;
+            j -= 2;
+        }
+    }
+    edits.reverse();
+    edits
+}
+
+fn is_transposition(
+    query: &[char],
+    candidate: &[char],
+    transposition_cost_one: bool,
+    i: usize,
+    j: usize,
+) -> bool {
+    transposition_cost_one
+        && i > 1
+        && j > 1
+        && candidate[i - 1] == query[j - 2]
+        && candidate[i - 2] == query[j - 1]
+        && candidate[i - 1] != candidate[i - 2]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::distance_within;
+    use super::{damerau_levenshtein, damerau_levenshtein_within, levenshtein, levenshtein_within};
+    use super::{edit_script_chars, EditOp};
+    use crate::{CaseFolding, Distance, LevenshteinAutomatonBuilder};
+
+    #[test]
+    fn test_levenshtein() {
+        for &(left, right) in &[
+            ("", ""),
+            ("", "abc"),
+            ("kitten", "sitting"),
+            ("寿司", "司寿"),
+            ("abcd", "acbd"),
+            ("flaw", "lawn"),
+        ] {
+            assert_eq!(
+                levenshtein(left, right),
+                ::levenshtein::levenshtein(left, right)
+            );
+            assert_eq!(levenshtein(right, left), levenshtein(left, right));
+        }
+        assert_eq!(damerau_levenshtein("寿司", "司寿"), 1);
+        assert_eq!(damerau_levenshtein("abcd", "badc"), 2);
+        assert_eq!(damerau_levenshtein("ca", "abc"), 3);
+    }
+
+    #[test]
+    fn test_within_matches_automata() {
+        let texts = [
+            "abc", "acb", "ab", "abcd", "bca", "", "xyz", "cab", "aabbcc",
+        ];
+        for max_distance in 0..=2u8 {
+            for &transposition_cost_one in &[false, true] {
+                let builder =
+                    LevenshteinAutomatonBuilder::new(max_distance, transposition_cost_one);
+                for query in &texts {
+                    let dfa = builder.build_dfa(query);
+                    for text in &texts {
+                        let distance = if transposition_cost_one {
+                            damerau_levenshtein_within(query, text, max_distance)
+                        } else {
+                            levenshtein_within(query, text, max_distance)
+                        };
+                        assert_eq!(distance, dfa.eval(text));
+                    }
+                }
+            }
+        }
+        assert_eq!(
+            levenshtein_within(&"a".repeat(100), "", 2),
+            Distance::AtLeast(3)
+        );
+    }
+
+    #[test]
+    fn test_distance_within_matches_folding_automata() {
+        let texts = ["Abc", "aCB", "ab", "ΣΑ", "σα", "ας", "", "xyz", "CAB"];
+        for &case_folding in &[CaseFolding::None, CaseFolding::Ascii, CaseFolding::Unicode] {
+            for &transposition_cost_one in &[false, true] {
+                let builder = LevenshteinAutomatonBuilder::new(2, transposition_cost_one)
+                    .case_folding(case_folding);
+                for query in &texts {
+                    let dfa = builder.build_dfa(query);
+                    for text in &texts {
+                        assert_eq!(
+                            distance_within(query, text, 2, transposition_cost_one, case_folding),
+                            dfa.eval(text)
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_edit_script() {
+        let chars = |text: &str| text.chars().collect::<Vec<char>>();
+        let ops = |query: &str, candidate: &str, transposition_cost_one: bool| {
+            edit_script_chars(&chars(query), &chars(candidate), transposition_cost_one)
+                .iter()
+                .map(|edit| edit.op)
+                .collect::<Vec<EditOp>>()
+        };
+        use self::EditOp::*;
+        assert_eq!(
+            ops("kitten", "sitting", false),
+            vec![
+                Substitution,
+                Match,
+                Match,
+                Match,
+                Substitution,
+                Match,
+                Insertion
+            ]
+        );
+        assert_eq!(ops("abcd", "acbd", true), vec![Match, Transposition, Match]);
+        assert_eq!(
+            ops("abcd", "acbd", false),
+            vec![Match, Substitution, Substitution, Match]
+        );
+        assert_eq!(ops("abc", "ac", false), vec![Match, Deletion, Match]);
+        assert_eq!(ops("", "", false), vec![]);
+        let script = edit_script_chars(&chars("寿司"), &chars("x寿司"), false);
+        assert_eq!(script.len(), 3);
+        assert_eq!(script[1].op, Match);
+        assert_eq!(
+            (script[1].query_position, script[1].candidate_position),
+            (0, 1)
+        );
+        for &(query, candidate) in &[("kitten", "sitting"), ("abcd", "badc"), ("ca", "abc")] {
+            for &transposition_cost_one in &[false, true] {
+                let num_edits = ops(query, candidate, transposition_cost_one)
+                    .iter()
+                    .filter(|&&op| op != Match)
+                    .count();
+                let expected = if transposition_cost_one {
+                    damerau_levenshtein(query, candidate)
+                } else {
+                    levenshtein(query, candidate)
+                };
+                assert_eq!(num_edits, expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use super::byte_classes::byte_classes;
+use super::{Distance, DFA, SINK_STATE};
+use std::mem;
+
+// Marks the free slots of `check`.
+const FREE: u32 = u32::MAX;
+
+/// Compact representation of a [DFA](./struct.DFA.html), using a
+/// double-array in the spirit of darts.
+///
+/// Bytes are first mapped to classes, two bytes being in the same class if
+/// all of the states have the same transition on them. Each state then keeps
+/// a default destination, the most frequent one among its transitions.
+/// The other transitions are stored in two arrays shared by all of the states:
+/// the transition of `state` on the class `c` is stored at `base[state] + c`,
+/// provided `check[base[state] + c] == state`. Bases are chosen so that the
+/// transitions of the different states interleave.
+///
+/// Lookups take a couple of memory accesses more than a dense `DFA`, but
+/// Levenshtein automata are very sparse, so that the tables are usually more
+/// than ten times smaller. State ids are left untouched.
+///
+/// ```rust
+/// # use levenshtein_automata::{Distance, LevenshteinAutomatonBuilder};
+/// let dfa = LevenshteinAutomatonBuilder::new(2, true).build_dfa("Levenshtein");
+/// let double_array = dfa.to_double_array();
+/// assert_eq!(double_array.eval("Levenshtain"), Distance::Exact(1));
+/// assert!(double_array.heap_size() * 10 < dfa.num_states() * 1024);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DoubleArrayDfa {
+    byte_classes: Box<[u8; 256]>,
+    base: Vec<u32>,
+    defaults: Vec<u32>,
+    check: Vec<u32>,
+    next: Vec<u32>,
+    distances: Vec<Distance>,
+    initial_state: u32,
+}
+
+impl DFA {
+    /// Builds the [DoubleArrayDfa](./struct.DoubleArrayDfa.html) equivalent to the automaton.
+    pub fn to_double_array(&self) -> DoubleArrayDfa {
+        let (byte_classes, representatives) = byte_classes(self);
+        let mut double_array = DoubleArrayDfa {
+            byte_classes,
+            base: Vec::with_capacity(self.num_states()),
+            defaults: Vec::with_capacity(self.num_states()),
+            check: Vec::new(),
+            next: Vec::new(),
+            distances: self.states().map(|state| self.distance(state)).collect(),
+            initial_state: self.initial_state(),
+        };
+        // All of the slots below are used.
+        let mut first_free = 0;
+        for state in self.states() {
+            let destinations: Vec<u32> = representatives
+                .iter()
+                .map(|&b| self.transition(state, b))
+                .collect();
+            let default = most_frequent(&destinations);
+            let exceptions: Vec<usize> = (0..destinations.len())
+                .filter(|&class| destinations[class] != default)
+                .collect();
+            let base = match exceptions.first() {
+                Some(&first_class) => {
+                    while first_free < double_array.check.len()
+                        && double_array.check[first_free] != FREE
+                    {
+                        first_free += 1;
+                    }
+                    // The first exception lands on the first free slot, or after.
+                    let mut base = first_free.saturating_sub(first_class);
+                    while !exceptions
+                        .iter()
+                        .all(|&class| double_array.is_free(base + class))
+                    {
+                        base += 1;
+                    }
+                    base
+                }
+                None => 0,
+            };
+            for &class in &exceptions {
+                let slot = base + class;
+                if slot >= double_array.check.len() {
+                    double_array.check.resize(slot + 1, FREE);
+                    double_array.next.resize(slot + 1, SINK_STATE);
+                }
+                double_array.check[slot] = state;
+                double_array.next[slot] = destinations[class];
+            }
+            double_array.base.push(base as u32);
+            double_array.defaults.push(default);
+        }
+        double_array
+    }
+}
+
+fn most_frequent(destinations: &[u32]) -> u32 {
+    let mut destinations: Vec<u32> = destinations.to_vec();
+    destinations.sort_unstable();
+    let mut best = (0, SINK_STATE);
+    let mut start = 0;
+    while start < destinations.len() {
+        let mut end = start + 1;
+        while end < destinations.len() && destinations[end] == destinations[start] {
+            end += 1;
+        }
+        if end - start > best.0 {
+            best = (end - start, destinations[start]);
+        }
+        start = end;
+    }
+    best.1
+}
+
+impl DoubleArrayDfa {
+    fn is_free(&self, slot: usize) -> bool {
+        slot >= self.check.len() || self.check[slot] == FREE
+    }
+
+    /// Returns the initial state
+    pub fn initial_state(&self) -> u32 {
+        self.initial_state
+    }
+
+    /// Returns the number of states.
+    pub fn num_states(&self) -> usize {
+        self.base.len()
+    }
+
+    /// Returns the Levenshtein distance associated to the
+    /// current state.
+    pub fn distance(&self, state_id: u32) -> Distance {
+        self.distances[state_id as usize]
+    }
+
+    /// Returns the destination state reached after consuming a given byte.
+    pub fn transition(&self, from_state_id: u32, b: u8) -> u32 {
+        let class = self.byte_classes[b as usize];
+        let slot = self.base[from_state_id as usize] as usize + class as usize;
+        match self.check.get(slot) {
+            Some(&owner) if owner == from_state_id => self.next[slot],
+            _ => self.defaults[from_state_id as usize],
+        }
+    }
+
+    /// Returns the Levenshtein distance computed by the automaton on `text`.
+    pub fn eval<B: AsRef<[u8]>>(&self, text: B) -> Distance {
+        let mut state = self.initial_state();
+        for &b in text.as_ref() {
+            state = self.transition(state, b);
+        }
+        self.distance(state)
+    }
+
+    /// Returns the number of bytes allocated on the heap by the tables.
+    pub fn heap_size(&self) -> usize {
+        mem::size_of::<[u8; 256]>()
+            + (self.base.capacity() + self.defaults.capacity()) * mem::size_of::<u32>()
+            + (self.check.capacity() + self.next.capacity()) * mem::size_of::<u32>()
+            + self.distances.capacity() * mem::size_of::<Distance>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::LevenshteinAutomatonBuilder;
+
+    #[test]
+    fn test_double_array_transitions() {
+        for &(max_distance, transposition_cost_one) in &[(0, false), (1, true), (2, false)] {
+            let builder = LevenshteinAutomatonBuilder::new(max_distance, transposition_cost_one);
+            for query in &["", "abc", "寿司", "levenshtein"] {
+                for dfa in &[builder.build_dfa(query), builder.build_prefix_dfa(query)] {
+                    let double_array = dfa.to_double_array();
+                    assert_eq!(double_array.num_states(), dfa.num_states());
+                    assert_eq!(double_array.initial_state(), dfa.initial_state());
+                    for state in dfa.states() {
+                        assert_eq!(double_array.distance(state), dfa.distance(state));
+                        for b in 0..=255u8 {
+                            assert_eq!(double_array.transition(state, b), dfa.transition(state, b));
+                        }
+                    }
+                    assert!(double_array.heap_size() < dfa.num_states() * 1024);
+                }
+            }
+        }
+    }
+}
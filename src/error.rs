@@ -0,0 +1,97 @@
+use core::fmt;
+
+/// Error returned by the fallible variants of the methods of the crate,
+/// e.g. [DFA::try_transition](./struct.DFA.html#method.try_transition) or
+/// [LevenshteinAutomatonBuilder::try_build_dfa](./struct.LevenshteinAutomatonBuilder.html#method.try_build_dfa).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// A state id is not lower than the number of states.
+    InvalidStateId {
+        /// The invalid state id.
+        state_id: u32,
+        /// The number of states.
+        num_states: usize,
+    },
+    /// An automaton cannot be built because it has no state at all.
+    NoStates,
+    /// An automaton would have more states than the budget set with
+    /// [LevenshteinAutomatonBuilder::max_num_states](./struct.LevenshteinAutomatonBuilder.html#method.max_num_states).
+    StateBudgetExceeded {
+        /// The maximum number of states.
+        max_num_states: usize,
+    },
+    /// An automaton would have more states than `u32` state ids can address.
+    TooManyStates {
+        /// The requested maximum number of states.
+        max_num_states: usize,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidStateId {
+                state_id,
+                num_states,
+            } => write!(
+                f,
+                "the state id {} is not lower than the number of states {}",
+                state_id, num_states
+            ),
+            Error::NoStates => f.write_str("the automaton has no state"),
+            Error::StateBudgetExceeded { max_num_states } => write!(
+                f,
+                "the automaton has more than the maximum of {} states",
+                max_num_states
+            ),
+            Error::TooManyStates { max_num_states } => write!(
+                f,
+                "{} states cannot be addressed with u32 state ids",
+                max_num_states
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::Error;
+    use crate::{Distance, LevenshteinAutomatonBuilder, Utf8DFABuilder};
+
+    #[test]
+    fn test_errors() {
+        let dfa = LevenshteinAutomatonBuilder::new(1, false)
+            .try_build_dfa("abc")
+            .unwrap();
+        let num_states = dfa.num_states();
+        assert_eq!(
+            dfa.try_transition(dfa.initial_state(), b'a'),
+            Ok(dfa.transition(dfa.initial_state(), b'a'))
+        );
+        assert_eq!(dfa.try_distance(0), Ok(dfa.distance(0)));
+        let invalid = Err(Error::InvalidStateId {
+            state_id: num_states as u32,
+            num_states,
+        });
+        assert_eq!(dfa.try_transition(num_states as u32, b'a'), invalid);
+        assert_eq!(dfa.try_distance(num_states as u32).map(|_| 0), invalid);
+        assert_eq!(
+            Utf8DFABuilder::with_max_num_states(1)
+                .try_build()
+                .map(|_| ()),
+            Err(Error::NoStates)
+        );
+        assert_eq!(
+            Utf8DFABuilder::try_with_max_num_states(usize::MAX).map(|_| ()),
+            Err(Error::TooManyStates {
+                max_num_states: usize::MAX
+            })
+        );
+        let mut dfa_builder = Utf8DFABuilder::try_with_max_num_states(1).unwrap();
+        dfa_builder.add_state(0, Distance::Exact(0), 0);
+        assert!(dfa_builder.try_build().is_ok());
+    }
+}
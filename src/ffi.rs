@@ -0,0 +1,273 @@
+//! C API, to embed the automata in C or C++ programs.
+//!
+//! The crate has to be compiled as a static or dynamic library with the
+//! `ffi` feature, e.g. with
+//! `cargo rustc --release --features ffi --crate-type staticlib`.
+//! The matching declarations are in `include/levenshtein_automata.h`.
+//!
+//! Builders and automata are handed out as opaque pointers, which must be
+//! released with [lev_builder_free] and [lev_dfa_free] respectively.
+//! Texts are passed as a pointer to their utf-8 bytes along with their length.
+//!
+//! ```rust
+//! # use levenshtein_automata::ffi::*;
+//! unsafe {
+//!     let builder = lev_builder_new(1, true);
+//!     let query = "cat";
+//!     let dfa = lev_dfa_build(builder, query.as_ptr(), query.len());
+//!     let text = "cart";
+//!     let distance = lev_dfa_eval(dfa, text.as_ptr(), text.len());
+//!     assert!(distance.is_exact);
+//!     assert_eq!(distance.distance, 1);
+//!     lev_dfa_free(dfa);
+//!     lev_builder_free(builder);
+//! }
+//! ```
+use super::{Distance, LevenshteinAutomatonBuilder, DFA, SINK_STATE};
+use std::ptr;
+use std::slice;
+use std::str;
+
+/// Distance of a text, as returned to C.
+///
+/// If `is_exact` is false, `distance` is a lower bound of the distance,
+/// as with `Distance::AtLeast`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LevDistance {
+    /// True if the distance is exact.
+    pub is_exact: bool,
+    /// The distance, or a lower bound of it.
+    pub distance: u8,
+}
+
+impl From<Distance> for LevDistance {
+    fn from(distance: Distance) -> LevDistance {
+        LevDistance {
+            is_exact: matches!(distance, Distance::Exact(_)),
+            distance: distance.to_u8(),
+        }
+    }
+}
+
+// Returns the bytes pointed to, which may be null if `len` is 0.
+unsafe fn bytes<'a>(ptr: *const u8, len: usize) -> &'a [u8] {
+    if len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(ptr, len)
+    }
+}
+
+/// Creates a builder, see [LevenshteinAutomatonBuilder::new].
+///
+/// The builder must be released with [lev_builder_free].
+#[no_mangle]
+pub extern "C" fn lev_builder_new(
+    max_distance: u8,
+    transposition_cost_one: bool,
+) -> *mut LevenshteinAutomatonBuilder {
+    Box::into_raw(Box::new(LevenshteinAutomatonBuilder::new(
+        max_distance,
+        transposition_cost_one,
+    )))
+}
+
+/// Releases a builder. Does nothing if `builder` is null.
+///
+/// # Safety
+///
+/// `builder` must be null or have been returned by [lev_builder_new],
+/// and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn lev_builder_free(builder: *mut LevenshteinAutomatonBuilder) {
+    if !builder.is_null() {
+        drop(Box::from_raw(builder));
+    }
+}
+
+/// Builds the automaton of a query, see
+/// [LevenshteinAutomatonBuilder::build_dfa].
+///
+/// Returns null if the query is not valid utf-8, or if the automaton
+/// cannot be built, as with [LevenshteinAutomatonBuilder::try_build_dfa].
+/// The automaton must be released with [lev_dfa_free].
+///
+/// # Safety
+///
+/// `builder` must be a valid builder, and `query` must point to `query_len`
+/// readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn lev_dfa_build(
+    builder: *const LevenshteinAutomatonBuilder,
+    query: *const u8,
+    query_len: usize,
+) -> *mut DFA {
+    let query = match str::from_utf8(bytes(query, query_len)) {
+        Ok(query) => query,
+        Err(_) => return ptr::null_mut(),
+    };
+    match (*builder).try_build_dfa(query) {
+        Ok(dfa) => Box::into_raw(Box::new(dfa)),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Builds the prefix automaton of a query, see
+/// [LevenshteinAutomatonBuilder::build_prefix_dfa].
+///
+/// Returns null if the query is not valid utf-8, or if the automaton
+/// cannot be built, as with [LevenshteinAutomatonBuilder::try_build_prefix_dfa].
+/// The automaton must be released with [lev_dfa_free].
+///
+/// # Safety
+///
+/// `builder` must be a valid builder, and `query` must point to `query_len`
+/// readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn lev_dfa_build_prefix(
+    builder: *const LevenshteinAutomatonBuilder,
+    query: *const u8,
+    query_len: usize,
+) -> *mut DFA {
+    let query = match str::from_utf8(bytes(query, query_len)) {
+        Ok(query) => query,
+        Err(_) => return ptr::null_mut(),
+    };
+    match (*builder).try_build_prefix_dfa(query) {
+        Ok(dfa) => Box::into_raw(Box::new(dfa)),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Releases an automaton. Does nothing if `dfa` is null.
+///
+/// # Safety
+///
+/// `dfa` must be null or have been returned by one of the `lev_dfa_build`
+/// functions, and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn lev_dfa_free(dfa: *mut DFA) {
+    if !dfa.is_null() {
+        drop(Box::from_raw(dfa));
+    }
+}
+
+/// Returns the distance of a text, see [DFA::eval].
+///
+/// # Safety
+///
+/// `dfa` must be a valid automaton, and `text` must point to `text_len`
+/// readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn lev_dfa_eval(
+    dfa: *const DFA,
+    text: *const u8,
+    text_len: usize,
+) -> LevDistance {
+    (*dfa).eval(bytes(text, text_len)).into()
+}
+
+/// Returns the initial state of an automaton.
+///
+/// # Safety
+///
+/// `dfa` must be a valid automaton.
+#[no_mangle]
+pub unsafe extern "C" fn lev_dfa_initial_state(dfa: *const DFA) -> u32 {
+    (*dfa).initial_state()
+}
+
+/// Returns the state reached from `state` after reading `byte`.
+///
+/// Returns the sink state `0` if `state` is not a valid state.
+///
+/// # Safety
+///
+/// `dfa` must be a valid automaton.
+#[no_mangle]
+pub unsafe extern "C" fn lev_dfa_transition(dfa: *const DFA, state: u32, byte: u8) -> u32 {
+    (*dfa).try_transition(state, byte).unwrap_or(SINK_STATE)
+}
+
+/// Returns the distance of the texts leading to `state`.
+///
+/// Returns `AtLeast(255)`, i.e. `is_exact` false and `distance` 255, if
+/// `state` is not a valid state.
+///
+/// # Safety
+///
+/// `dfa` must be a valid automaton.
+#[no_mangle]
+pub unsafe extern "C" fn lev_dfa_distance(dfa: *const DFA, state: u32) -> LevDistance {
+    (*dfa)
+        .try_distance(state)
+        .unwrap_or(Distance::AtLeast(u8::MAX))
+        .into()
+}
+
+/// Returns false if no text within the maximum distance can be reached from
+/// `state`, so that the evaluation can stop early.
+///
+/// Returns false if `state` is not a valid state.
+///
+/// # Safety
+///
+/// `dfa` must be a valid automaton.
+#[no_mangle]
+pub unsafe extern "C" fn lev_dfa_can_match(dfa: *const DFA, state: u32) -> bool {
+    (state as usize) < (*dfa).num_states() && (*dfa).can_match(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    #[test]
+    fn test_ffi_stepping() {
+        unsafe {
+            let builder = lev_builder_new(1, false);
+            let query = "寿司";
+            let dfa = lev_dfa_build_prefix(builder, query.as_ptr(), query.len());
+            assert!(!dfa.is_null());
+            let mut state = lev_dfa_initial_state(dfa);
+            for &b in "寿司屋".as_bytes() {
+                state = lev_dfa_transition(dfa, state, b);
+            }
+            assert_eq!(
+                lev_dfa_distance(dfa, state),
+                LevDistance {
+                    is_exact: true,
+                    distance: 0
+                }
+            );
+            assert!(lev_dfa_can_match(dfa, state));
+            assert_eq!(lev_dfa_transition(dfa, u32::MAX, b'a'), SINK_STATE);
+            assert_eq!(
+                lev_dfa_distance(dfa, u32::MAX),
+                LevDistance {
+                    is_exact: false,
+                    distance: 255
+                }
+            );
+            assert!(!lev_dfa_can_match(dfa, u32::MAX));
+            let distance = lev_dfa_eval(dfa, ptr::null(), 0);
+            assert_eq!(distance.distance, 2);
+            assert!(!distance.is_exact);
+            lev_dfa_free(dfa);
+            let invalid = [0xFFu8];
+            assert!(lev_dfa_build(builder, invalid.as_ptr(), invalid.len()).is_null());
+            lev_builder_free(builder);
+            let builder = Box::into_raw(Box::new(
+                LevenshteinAutomatonBuilder::new(2, true).max_num_states(10),
+            ));
+            let query = "levenshtein";
+            assert!(lev_dfa_build(builder, query.as_ptr(), query.len()).is_null());
+            assert!(lev_dfa_build_prefix(builder, query.as_ptr(), query.len()).is_null());
+            lev_builder_free(builder);
+            lev_builder_free(ptr::null_mut());
+            lev_dfa_free(ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use super::{Distance, LevenshteinAutomatonBuilder, DFA};
+use arbitrary::{Arbitrary, Unstructured};
+
+/// Parameters of a [LevenshteinAutomatonBuilder](./struct.LevenshteinAutomatonBuilder.html),
+/// generated from structured random data.
+///
+/// Generated configurations never exceed a `max_distance` of 2, as
+/// building the builder for larger distances is too slow to be
+/// done for every fuzzing input.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct BuilderConfig {
+    /// Maximum distance considered by the automaton.
+    pub max_distance: u8,
+    /// Assign a distance of 1 for transposition.
+    pub transposition_cost_one: bool,
+}
+
+impl BuilderConfig {
+    /// Creates the builder described by the configuration.
+    pub fn builder(&self) -> LevenshteinAutomatonBuilder {
+        LevenshteinAutomatonBuilder::new(self.max_distance, self.transposition_cost_one)
+    }
+}
+
+impl<'a> Arbitrary<'a> for BuilderConfig {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<BuilderConfig> {
+        Ok(BuilderConfig {
+            max_distance: u.int_in_range(0..=2)?,
+            transposition_cost_one: u.arbitrary()?,
+        })
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        arbitrary::size_hint::and(u8::size_hint(depth), bool::size_hint(depth))
+    }
+}
+
+/// A query, and a text to evaluate the automaton of the query on.
+///
+/// The text is an arbitrary byte string. It is not necessarily valid utf-8.
+#[derive(Arbitrary, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct QueryInput {
+    /// Query the automaton is built for.
+    pub query: String,
+    /// If true, builds a prefix automaton.
+    pub prefix: bool,
+    /// Text evaluated by the automaton.
+    pub text: Vec<u8>,
+}
+
+impl QueryInput {
+    /// Builds the automaton of the query.
+    pub fn build_dfa(&self, builder: &LevenshteinAutomatonBuilder) -> DFA {
+        if self.prefix {
+            builder.build_prefix_dfa(&self.query)
+        } else {
+            builder.build_dfa(&self.query)
+        }
+    }
+
+    /// Builds the automaton of the query, and evaluates it on the text.
+    pub fn eval(&self, builder: &LevenshteinAutomatonBuilder) -> Distance {
+        self.build_dfa(builder).eval(&self.text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BuilderConfig, QueryInput};
+    use crate::Distance;
+    use arbitrary::{Arbitrary, Unstructured};
+
+    #[test]
+    fn test_arbitrary_inputs() {
+        let data: Vec<u8> = (0..4096u32)
+            .map(|i| (i.wrapping_mul(2_654_435_761) >> 13) as u8)
+            .collect();
+        let mut num_checked = 0;
+        for chunk in data.chunks(256) {
+            let mut u = Unstructured::new(chunk);
+            let config = BuilderConfig::arbitrary(&mut u).unwrap();
+            assert!(config.max_distance <= 2);
+            let builder = config.builder();
+            while !u.is_empty() {
+                let input = QueryInput::arbitrary(&mut u).unwrap();
+                let distance = input.eval(&builder);
+                let text = match std::str::from_utf8(&input.text) {
+                    Ok(text) if !input.prefix && !config.transposition_cost_one => text,
+                    _ => continue,
+                };
+                let expected = levenshtein::levenshtein(&input.query, text);
+                let expected = if expected > config.max_distance as usize {
+                    Distance::AtLeast(config.max_distance + 1)
+                } else {
+                    Distance::Exact(expected as u8)
+                };
+                assert_eq!(distance, expected);
+                num_checked += 1;
+            }
+        }
+        assert!(num_checked > 0);
+    }
+}
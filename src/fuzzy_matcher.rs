@@ -0,0 +1,247 @@
+use super::{Distance, LevenshteinAutomatonBuilder, DFA};
+use fuzzy_matcher::FuzzyMatcher;
+use std::sync::{Arc, RwLock};
+
+/// Adapter implementing [`fuzzy_matcher::FuzzyMatcher`](https://docs.rs/fuzzy-matcher),
+/// the matcher trait of skim, on top of Levenshtein automata.
+///
+/// Skim-style fuzzy finders call the matcher once per candidate with the
+/// same pattern, so the automata of the last pattern are cached. The cache
+/// belongs to the matcher, and is behind a read-write lock: the threads
+/// matching candidates against the same pattern only share a read lock,
+/// and the lock is never held while the automata of a new pattern are built.
+///
+/// A candidate matches if one of its substrings is within `max_distance`
+/// of the pattern. The score is `max_distance - distance`, so exact
+/// matches rank first, and the returned indices are the char positions of
+/// the candidate that are aligned with a character of the pattern.
+///
+/// nucleo does not define a matcher trait: its `Matcher` is a concrete
+/// scoring engine, so there is no nucleo adapter.
+pub struct LevenshteinMatcher {
+    builder: LevenshteinAutomatonBuilder,
+    max_distance: u8,
+    last_pattern: RwLock<Option<Arc<CompiledPattern>>>,
+}
+
+struct CompiledPattern {
+    pattern: String,
+    // Smallest distance between the pattern and the substrings of the
+    // candidate ending at each position.
+    suffix_dfa: DFA,
+    // Distance to the reversed pattern, to find where a substring starts
+    // by reading the candidate backward from its end.
+    reversed_dfa: DFA,
+}
+
+impl LevenshteinMatcher {
+    /// Creates a new matcher.
+    ///
+    /// See [LevenshteinAutomatonBuilder::new](./struct.LevenshteinAutomatonBuilder.html#method.new)
+    /// for the meaning of the arguments.
+    pub fn new(max_distance: u8, transposition_cost_one: bool) -> LevenshteinMatcher {
+        LevenshteinMatcher {
+            builder: LevenshteinAutomatonBuilder::new(max_distance, transposition_cost_one),
+            max_distance,
+            last_pattern: RwLock::new(None),
+        }
+    }
+
+    fn compile(&self, pattern: &str) -> Arc<CompiledPattern> {
+        if let Some(ref cached) = *self.last_pattern.read().unwrap() {
+            if cached.pattern == pattern {
+                return cached.clone();
+            }
+        }
+        let reversed_pattern: String = pattern.chars().rev().collect();
+        let compiled = Arc::new(CompiledPattern {
+            pattern: pattern.to_string(),
+            suffix_dfa: self.builder.build_suffix_dfa(pattern),
+            reversed_dfa: self.builder.build_dfa(&reversed_pattern),
+        });
+        *self.last_pattern.write().unwrap() = Some(compiled.clone());
+        compiled
+    }
+}
+
+/// Returns the `(start, end, distance)` of the best matching window of `choice`,
+/// expressed in bytes: the shortest of the closest substrings ending first.
+///
+/// The candidate is read once to find the end of the window, and the
+/// window is then read backward to find its start.
+fn best_window(compiled: &CompiledPattern, choice: &str) -> Option<(usize, usize, u8)> {
+    let mut best: Option<(usize, u8)> = None;
+    for (end, d) in compiled.suffix_dfa.find_iter(choice) {
+        if best.map(|(_, best_d)| d < best_d).unwrap_or(true) {
+            best = Some((end, d));
+            if d == 0 {
+                break;
+            }
+        }
+    }
+    let (end, d) = best?;
+    let start = window_start(&compiled.reversed_dfa, &choice[..end], d)?;
+    Some((start, end, d))
+}
+
+/// Returns the largest position of `text` from which the rest of `text` is
+/// at distance `d`, reading the chars of `text` backward with the automaton
+/// of the reversed pattern.
+fn window_start(reversed_dfa: &DFA, text: &str, d: u8) -> Option<usize> {
+    let mut state = reversed_dfa.initial_state();
+    if reversed_dfa.distance(state) == Distance::Exact(d) {
+        return Some(text.len());
+    }
+    let mut buffer = [0u8; 4];
+    for (start, chr) in text.char_indices().rev() {
+        for &b in chr.encode_utf8(&mut buffer).as_bytes() {
+            state = reversed_dfa.transition(state, b);
+        }
+        if reversed_dfa.distance(state) == Distance::Exact(d) {
+            return Some(start);
+        }
+        if !reversed_dfa.can_match(state) {
+            break;
+        }
+    }
+    None
+}
+
+/// Aligns `window` with `pattern` and returns the positions in `window`
+/// of the characters matched by the alignment.
+fn aligned_indices(window: &[char], pattern: &[char]) -> Vec<usize> {
+    let width = pattern.len() + 1;
+    let mut table = vec![0u32; (window.len() + 1) * width];
+    for (j, cell) in table[..width].iter_mut().enumerate() {
+        *cell = j as u32;
+    }
+    for i in 1..=window.len() {
+        table[i * width] = i as u32;
+        for j in 1..width {
+            let substitution = u32::from(window[i - 1] != pattern[j - 1]);
+            table[i * width + j] = (table[(i - 1) * width + j - 1] + substitution)
+                .min(table[(i - 1) * width + j] + 1)
+                .min(table[i * width + j - 1] + 1);
+        }
+    }
+    let mut indices = Vec::with_capacity(pattern.len());
+    let (mut i, mut j) = (window.len(), pattern.len());
+    while i > 0 && j > 0 {
+        let current = table[i * width + j];
+        if window[i - 1] == pattern[j - 1] && current == table[(i - 1) * width + j - 1] {
+            indices.push(i - 1);
+            i -= 1;
+            j -= 1;
+        } else if current == table[(i - 1) * width + j - 1] + 1 {
+            i -= 1;
+            j -= 1;
+        } else if current == table[(i - 1) * width + j] + 1 {
+            i -= 1;
+        } else {
+            j -= 1;
+        }
+    }
+    indices.reverse();
+    indices
+}
+
+impl FuzzyMatcher for LevenshteinMatcher {
+    fn fuzzy_indices(&self, choice: &str, pattern: &str) -> Option<(i64, Vec<usize>)> {
+        let compiled = self.compile(pattern);
+        let (start, end, d) = best_window(&compiled, choice)?;
+        let char_start = choice[..start].chars().count();
+        let window_chars: Vec<char> = choice[start..end].chars().collect();
+        let pattern_chars: Vec<char> = pattern.chars().collect();
+        let indices = aligned_indices(&window_chars, &pattern_chars)
+            .into_iter()
+            .map(|i| char_start + i)
+            .collect();
+        Some((i64::from(self.max_distance - d), indices))
+    }
+
+    fn fuzzy_match(&self, choice: &str, pattern: &str) -> Option<i64> {
+        let compiled = self.compile(pattern);
+        best_window(&compiled, choice).map(|(_, _, d)| i64::from(self.max_distance - d))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LevenshteinMatcher;
+    use crate::{damerau_levenshtein, levenshtein, ExhaustiveVerifier};
+    use fuzzy_matcher::FuzzyMatcher;
+
+    #[test]
+    fn test_fuzzy_match() {
+        let matcher = LevenshteinMatcher::new(1, true);
+        assert_eq!(
+            matcher.fuzzy_match("src/levenshtein.rs", "levenshtein"),
+            Some(1)
+        );
+        assert_eq!(
+            matcher.fuzzy_match("src/levenshtien.rs", "levenshtein"),
+            Some(0)
+        );
+        assert_eq!(matcher.fuzzy_match("src/lib.rs", "levenshtein"), None);
+    }
+
+    #[test]
+    fn test_fuzzy_exact_match() {
+        let matcher = LevenshteinMatcher::new(0, false);
+        assert_eq!(
+            matcher.fuzzy_indices("src/dfa/dfa.rs", "dfa."),
+            Some((0, vec![8, 9, 10, 11]))
+        );
+        assert_eq!(matcher.fuzzy_match("src/dfa.rs", "dfx"), None);
+    }
+
+    #[test]
+    fn test_fuzzy_indices() {
+        let matcher = LevenshteinMatcher::new(1, false);
+        assert_eq!(
+            matcher.fuzzy_indices("src/dfa.rs", "dfa"),
+            Some((1, vec![4, 5, 6]))
+        );
+        assert_eq!(
+            matcher.fuzzy_indices("src/dxa.rs", "dfa"),
+            Some((0, vec![4, 6]))
+        );
+        assert_eq!(
+            matcher.fuzzy_indices("寿司は焦げられない", "焦げられ"),
+            Some((1, vec![3, 4, 5, 6]))
+        );
+    }
+
+    #[test]
+    fn test_fuzzy_match_closest_substring() {
+        for &transposition_cost_one in &[false, true] {
+            let matcher = LevenshteinMatcher::new(1, transposition_cost_one);
+            let distance = if transposition_cost_one {
+                damerau_levenshtein
+            } else {
+                levenshtein
+            };
+            for pattern in &["ab", "abé"] {
+                for choice in ExhaustiveVerifier::new("abé", 4, 0, false).strings() {
+                    let chars: Vec<char> = choice.chars().collect();
+                    let closest = (0..=chars.len())
+                        .flat_map(|start| (start..=chars.len()).map(move |end| (start, end)))
+                        .map(|(start, end)| {
+                            let substring: String = chars[start..end].iter().collect();
+                            distance(pattern, &substring)
+                        })
+                        .min()
+                        .unwrap();
+                    let expected = if closest <= 1 {
+                        Some(1 - closest as i64)
+                    } else {
+                        None
+                    };
+                    assert_eq!(matcher.fuzzy_match(&choice, pattern), expected);
+                    let indices = matcher.fuzzy_indices(&choice, pattern);
+                    assert_eq!(indices.map(|(score, _)| score), expected);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,274 @@
+use super::{Distance, DFA, SINK_STATE};
+use std::fmt::Write;
+use std::ops::Range;
+
+/// Edge of the graph of a [DFA](./struct.DFA.html).
+///
+/// All of the bytes in `start..=end` lead from the state `from`
+/// to the state `to`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Edge {
+    /// Source state.
+    pub from: u32,
+    /// Destination state.
+    pub to: u32,
+    /// First byte of the range, inclusive.
+    pub start: u8,
+    /// Last byte of the range, inclusive.
+    pub end: u8,
+}
+
+/// Iterator over the outgoing edges of a state,
+/// ordered by byte.
+///
+/// Consecutive bytes sharing a destination are coalesced
+/// into a single edge, so that the `256` transitions of a state
+/// typically translate into a handful of edges.
+pub struct Edges<'a> {
+    dfa: &'a DFA,
+    from: u32,
+    next_byte: usize,
+}
+
+impl<'a> Iterator for Edges<'a> {
+    type Item = Edge;
+
+    fn next(&mut self) -> Option<Edge> {
+        if self.next_byte > 255 {
+            return None;
+        }
+        let start = self.next_byte as u8;
+        let to = self.dfa.transition(self.from, start);
+        let mut end = start;
+        while end < 255 && self.dfa.transition(self.from, end + 1) == to {
+            end += 1;
+        }
+        self.next_byte = end as usize + 1;
+        Some(Edge {
+            from: self.from,
+            to,
+            start,
+            end,
+        })
+    }
+}
+
+impl DFA {
+    /// Returns the ids of all of the states of the automaton.
+    ///
+    /// State ids are dense and stable: they can be used directly to
+    /// index the side tables of graph algorithms.
+    pub fn states(&self) -> Range<u32> {
+        0..self.num_states() as u32
+    }
+
+    /// Returns the outgoing edges of a given state.
+    pub fn edges(&self, from_state_id: u32) -> Edges<'_> {
+        Edges {
+            dfa: self,
+            from: from_state_id,
+            next_byte: 0,
+        }
+    }
+
+    /// Returns all of the edges of the automaton, ordered by source state.
+    pub fn all_edges(&self) -> impl Iterator<Item = Edge> + '_ {
+        self.states().flat_map(move |state_id| self.edges(state_id))
+    }
+
+    /// Returns the distinct successors of a given state, in increasing order.
+    pub fn successors(&self, from_state_id: u32) -> Vec<u32> {
+        let mut successors: Vec<u32> = self.edges(from_state_id).map(|edge| edge.to).collect();
+        successors.sort_unstable();
+        successors.dedup();
+        successors
+    }
+
+    /// Renders the automaton in the Graphviz `dot` format.
+    ///
+    /// Accepting states are drawn with a double circle, and labeled with
+    /// their distance. The edges leading to the sink state are omitted, unless
+    /// it can match.
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::LevenshteinAutomatonBuilder;
+    /// let dfa = LevenshteinAutomatonBuilder::new(0, false).build_dfa("a");
+    /// assert!(dfa.to_dot().contains("[label=\"a\"]"));
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph dfa {\n    rankdir=LR;\n");
+        writeln!(dot, "    start [shape=point];").unwrap();
+        writeln!(dot, "    start -> {};", self.initial_state()).unwrap();
+        for state_id in self.states() {
+            match self.distance(state_id) {
+                Distance::Exact(d) => writeln!(
+                    dot,
+                    "    {} [shape=doublecircle, label=\"{}\\nd={}\"];",
+                    state_id, state_id, d
+                ),
+                Distance::AtLeast(_) => writeln!(dot, "    {} [shape=circle];", state_id),
+            }
+            .unwrap();
+        }
+        let sink_can_match = self.can_match(SINK_STATE);
+        for edge in self
+            .all_edges()
+            .filter(|edge| edge.to != SINK_STATE || sink_can_match)
+        {
+            let mut label = dot_byte(edge.start);
+            if edge.end != edge.start {
+                label.push('-');
+                label.push_str(&dot_byte(edge.end));
+            }
+            writeln!(
+                dot,
+                "    {} -> {} [label=\"{}\"];",
+                edge.from, edge.to, label
+            )
+            .unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+// Renders a byte in a `dot` label, escaping the non printable ASCII chars.
+fn dot_byte(b: u8) -> String {
+    match b {
+        b' '..=b'~' if b != b'"' && b != b'\\' => (b as char).to_string(),
+        _ => format!("\\\\x{:02X}", b),
+    }
+}
+
+#[cfg(feature = "petgraph")]
+impl DFA {
+    /// Exports the automaton as a `petgraph` directed graph.
+    ///
+    /// Node `i` is the state `i` and is weighted by its distance.
+    /// Edges are labeled with the range of bytes they accept, as
+    /// returned by [.edges(...)](#method.edges).
+    pub fn to_petgraph(
+        &self,
+    ) -> petgraph::graph::DiGraph<super::Distance, std::ops::RangeInclusive<u8>> {
+        let mut graph = petgraph::graph::DiGraph::with_capacity(self.num_states(), 0);
+        for state_id in self.states() {
+            graph.add_node(self.distance(state_id));
+        }
+        for edge in self.all_edges() {
+            graph.add_edge(
+                petgraph::graph::NodeIndex::new(edge.from as usize),
+                petgraph::graph::NodeIndex::new(edge.to as usize),
+                edge.start..=edge.end,
+            );
+        }
+        graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Edge;
+    use crate::{LevenshteinAutomatonBuilder, Utf8DFABuilder, SINK_STATE};
+
+    #[test]
+    fn test_sink_edges() {
+        let dfa = LevenshteinAutomatonBuilder::new(1, false).build_dfa("abc");
+        let edges: Vec<Edge> = dfa.edges(SINK_STATE).collect();
+        assert_eq!(
+            edges,
+            vec![Edge {
+                from: SINK_STATE,
+                to: SINK_STATE,
+                start: 0,
+                end: 255,
+            }]
+        );
+        assert_eq!(dfa.successors(SINK_STATE), vec![SINK_STATE]);
+    }
+
+    #[test]
+    fn test_edges_cover_all_bytes() {
+        let dfa = LevenshteinAutomatonBuilder::new(2, true).build_dfa("寿司abc");
+        for state_id in dfa.states() {
+            let mut next_byte = 0usize;
+            for edge in dfa.edges(state_id) {
+                assert_eq!(edge.from, state_id);
+                assert_eq!(edge.start as usize, next_byte);
+                for b in edge.start..=edge.end {
+                    assert_eq!(dfa.transition(state_id, b), edge.to);
+                }
+                next_byte = edge.end as usize + 1;
+            }
+            assert_eq!(next_byte, 256);
+        }
+    }
+
+    #[test]
+    fn test_all_states_reachable() {
+        let dfa = LevenshteinAutomatonBuilder::new(1, false).build_dfa("abc");
+        let mut visited = vec![false; dfa.num_states()];
+        let mut stack = vec![dfa.initial_state()];
+        visited[dfa.initial_state() as usize] = true;
+        while let Some(state_id) = stack.pop() {
+            for successor in dfa.successors(state_id) {
+                if !visited[successor as usize] {
+                    visited[successor as usize] = true;
+                    stack.push(successor);
+                }
+            }
+        }
+        assert!(visited.iter().all(|&v| v));
+        assert_eq!(
+            dfa.all_edges()
+                .filter(|edge| edge.from == SINK_STATE)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_to_dot() {
+        use crate::Distance;
+
+        let dfa = LevenshteinAutomatonBuilder::new(1, false).build_dfa("ab");
+        let dot = dfa.to_dot();
+        assert!(dot.starts_with("digraph dfa {"));
+        assert!(dot.ends_with("}\n"));
+        let num_accepting = dfa
+            .states()
+            .filter(|&state_id| matches!(dfa.distance(state_id), Distance::Exact(_)))
+            .count();
+        assert_eq!(dot.matches("doublecircle").count(), num_accepting);
+        assert!(!dot.contains(&format!("-> {} ", SINK_STATE)));
+        assert!(dot.contains("[label=\"\\\\x00-`\"]"));
+
+        // The edges to an accepting state `0` are kept.
+        let mut dfa_builder = Utf8DFABuilder::with_max_num_states(2);
+        dfa_builder.add_state(0, Distance::Exact(0), 1);
+        dfa_builder.add_state(1, Distance::AtLeast(1), 0);
+        dfa_builder.set_initial_state(0);
+        let dot = dfa_builder.build().to_dot();
+        assert!(dot.contains(&format!("-> {} ", SINK_STATE)));
+    }
+
+    #[cfg(feature = "petgraph")]
+    #[test]
+    fn test_to_petgraph() {
+        use crate::Distance;
+        use petgraph::graph::NodeIndex;
+
+        let dfa = LevenshteinAutomatonBuilder::new(1, false).build_dfa("ab");
+        let graph = dfa.to_petgraph();
+        assert_eq!(graph.node_count(), dfa.num_states());
+        assert_eq!(graph.edge_count(), dfa.all_edges().count());
+        let sink = NodeIndex::new(SINK_STATE as usize);
+        assert_eq!(graph[sink], Distance::AtLeast(2));
+        let sink_edge = graph.find_edge(sink, sink).unwrap();
+        assert_eq!(graph[sink_edge], 0..=255);
+        let initial = NodeIndex::new(dfa.initial_state() as usize);
+        assert!(petgraph::algo::has_path_connecting(
+            &graph, initial, sink, None
+        ));
+        assert_eq!(petgraph::algo::tarjan_scc(&graph).len(), dfa.num_states());
+    }
+}
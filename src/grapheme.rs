@@ -0,0 +1,111 @@
+use super::{Distance, LevenshteinAutomatonBuilder, DFA};
+use std::collections::HashMap;
+use unicode_segmentation::UnicodeSegmentation;
+
+// Graphemes of the query are mapped to the chars of the
+// supplementary private use area A.
+const FIRST_SYMBOL: u32 = 0xF_0000;
+const MAX_NUM_SYMBOLS: usize = 0xFFFE;
+// Stands for all of the graphemes absent from the query.
+const OTHER_SYMBOL: char = '\u{10FFFD}';
+
+/// Levenshtein automaton whose edits operate on extended grapheme clusters
+/// rather than on chars.
+///
+/// An emoji with a skin tone modifier, a flag, or a letter followed by
+/// combining accents, are made of several chars but are displayed as a single
+/// grapheme: inserting, deleting or replacing one of them costs a single edit.
+///
+/// Since grapheme boundaries depend on the chars that follow, texts are
+/// segmented before being evaluated: each grapheme is mapped to a symbol,
+/// and the symbols are fed to a [DFA](./struct.DFA.html).
+///
+/// ```rust
+/// # use levenshtein_automata::{Distance, LevenshteinAutomatonBuilder};
+/// let builder = LevenshteinAutomatonBuilder::new(1, false);
+/// let dfa = builder.build_grapheme_dfa("👍🏽 ok");
+/// assert_eq!(dfa.eval("👍 ok"), Distance::Exact(1));
+/// assert_eq!(dfa.eval("👍🏿 ok"), Distance::Exact(1));
+/// assert_eq!(dfa.eval(" ok"), Distance::Exact(1));
+/// // Chars are two edits apart.
+/// assert_eq!(builder.build_dfa("👍🏽 ok").eval(" ok"), Distance::AtLeast(2));
+/// ```
+pub struct GraphemeDfa {
+    dfa: DFA,
+    symbols: HashMap<String, char>,
+}
+
+impl LevenshteinAutomatonBuilder {
+    /// Builds an automaton computing the levenshtein distance to `query`,
+    /// counted in extended grapheme clusters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the query contains more than `65534` distinct graphemes.
+    pub fn build_grapheme_dfa(&self, query: &str) -> GraphemeDfa {
+        let mut symbols: HashMap<String, char> = HashMap::new();
+        let mut symbolic_query = String::new();
+        for grapheme in query.graphemes(true) {
+            let num_symbols = symbols.len();
+            let symbol = *symbols.entry(grapheme.to_string()).or_insert_with(|| {
+                assert!(
+                    num_symbols < MAX_NUM_SYMBOLS,
+                    "Too many distinct graphemes in the query"
+                );
+                std::char::from_u32(FIRST_SYMBOL + num_symbols as u32).unwrap()
+            });
+            symbolic_query.push(symbol);
+        }
+        GraphemeDfa {
+            dfa: self.build_dfa(&symbolic_query),
+            symbols,
+        }
+    }
+}
+
+impl GraphemeDfa {
+    /// Returns the number of states of the underlying automaton.
+    pub fn num_states(&self) -> usize {
+        self.dfa.num_states()
+    }
+
+    /// Returns the Levenshtein distance between the query and `text`,
+    /// counted in extended grapheme clusters.
+    pub fn eval(&self, text: &str) -> Distance {
+        let mut buffer = [0u8; 4];
+        let mut state = self.dfa.initial_state();
+        for grapheme in text.graphemes(true) {
+            let symbol = self.symbols.get(grapheme).cloned().unwrap_or(OTHER_SYMBOL);
+            for &b in symbol.encode_utf8(&mut buffer).as_bytes() {
+                state = self.dfa.transition(state, b);
+            }
+            if !self.dfa.can_match(state) {
+                break;
+            }
+        }
+        self.dfa.distance(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Distance, LevenshteinAutomatonBuilder};
+
+    #[test]
+    fn test_grapheme_dfa() {
+        let builder = LevenshteinAutomatonBuilder::new(1, true);
+        let dfa = builder.build_grapheme_dfa("e\u{301}te\u{301}");
+        assert_eq!(dfa.eval("e\u{301}te\u{301}"), Distance::Exact(0));
+        assert_eq!(dfa.eval("ete\u{301}"), Distance::Exact(1));
+        assert_eq!(dfa.eval("te\u{301}e\u{301}"), Distance::Exact(1));
+        assert_eq!(dfa.eval("e\u{301}t"), Distance::Exact(1));
+        assert_eq!(dfa.eval("ete"), Distance::AtLeast(2));
+        // Private use chars are graphemes like any others.
+        assert_eq!(dfa.eval("\u{F0000}te\u{301}"), Distance::Exact(1));
+        let dfa = builder.build_grapheme_dfa("🇫🇷");
+        assert_eq!(dfa.eval("🇫🇷"), Distance::Exact(0));
+        assert_eq!(dfa.eval("🇩🇪"), Distance::Exact(1));
+        assert_eq!(dfa.eval("🇫🇷🇩🇪"), Distance::Exact(1));
+        assert_eq!(dfa.eval(""), Distance::Exact(1));
+    }
+}
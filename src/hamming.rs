@@ -0,0 +1,150 @@
+use super::{CharAutomaton, Distance, DFA, SINK_STATE};
+
+/// Builds automata computing the Hamming distance to a query: the number of
+/// chars to substitute to turn a text into the query.
+///
+/// Insertions and deletions are not allowed, so that the texts that do not
+/// have as many chars as the query are rejected. The automata have a state
+/// per position in the query and per number of substitutions, and are much
+/// cheaper to build than Levenshtein automata, e.g. to match fixed-width
+/// codes or identifiers.
+///
+/// ```rust
+/// # use levenshtein_automata::{Distance, HammingAutomatonBuilder};
+/// let dfa = HammingAutomatonBuilder::new(1).build_dfa("AB-1234");
+/// assert_eq!(dfa.eval("AB-1234"), Distance::Exact(0));
+/// assert_eq!(dfa.eval("AB-1284"), Distance::Exact(1));
+/// assert_eq!(dfa.eval("AB-1294"), Distance::Exact(1));
+/// assert_eq!(dfa.eval("AB-2284"), Distance::AtLeast(2));
+/// assert_eq!(dfa.eval("AB-123"), Distance::AtLeast(2));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct HammingAutomatonBuilder {
+    max_distance: u8,
+}
+
+// States are identified by the number of chars read and the number of
+// substitutions so far, after the sink state.
+struct HammingAutomaton<'a> {
+    query: &'a [char],
+    max_distance: u8,
+}
+
+impl<'a> HammingAutomaton<'a> {
+    fn state(&self, num_chars: usize, num_substitutions: u8) -> u32 {
+        (1 + num_chars * (self.max_distance as usize + 1) + num_substitutions as usize) as u32
+    }
+
+    // Returns the number of chars read and the number of substitutions
+    // of a state other than the sink state.
+    fn position(&self, state: u32) -> (usize, u8) {
+        let stride = self.max_distance as usize + 1;
+        let index = state as usize - 1;
+        (index / stride, (index % stride) as u8)
+    }
+}
+
+impl<'a> CharAutomaton for HammingAutomaton<'a> {
+    fn num_states(&self) -> u32 {
+        self.state(self.query.len(), self.max_distance) + 1
+    }
+
+    fn initial_state(&self) -> u32 {
+        self.state(0, 0)
+    }
+
+    fn distance(&self, state: u32) -> Distance {
+        if state != SINK_STATE {
+            let (num_chars, num_substitutions) = self.position(state);
+            if num_chars == self.query.len() {
+                return Distance::Exact(num_substitutions);
+            }
+        }
+        Distance::AtLeast(self.max_distance + 1)
+    }
+
+    fn default_transition(&self, state: u32) -> u32 {
+        if state == SINK_STATE {
+            return SINK_STATE;
+        }
+        let (num_chars, num_substitutions) = self.position(state);
+        if num_chars == self.query.len() || num_substitutions == self.max_distance {
+            SINK_STATE
+        } else {
+            self.state(num_chars + 1, num_substitutions + 1)
+        }
+    }
+
+    fn transitions(&self, state: u32) -> Vec<(char, u32)> {
+        if state == SINK_STATE {
+            return Vec::new();
+        }
+        let (num_chars, num_substitutions) = self.position(state);
+        match self.query.get(num_chars) {
+            Some(&chr) => vec![(chr, self.state(num_chars + 1, num_substitutions))],
+            None => Vec::new(),
+        }
+    }
+}
+
+impl HammingAutomatonBuilder {
+    /// Creates a builder for automata accepting up to `max_distance`
+    /// substitutions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_distance` is `255`.
+    pub fn new(max_distance: u8) -> HammingAutomatonBuilder {
+        assert!(
+            max_distance < u8::MAX,
+            "The maximum distance should be at most 254"
+        );
+        HammingAutomatonBuilder { max_distance }
+    }
+
+    /// Builds the automaton computing the Hamming distance to `query`.
+    ///
+    /// Rejected texts are evaluated to `Distance::AtLeast(max_distance + 1)`.
+    pub fn build_dfa(&self, query: &str) -> DFA {
+        let query: Vec<char> = query.chars().collect();
+        DFA::from_char_automaton(&HammingAutomaton {
+            query: &query,
+            max_distance: self.max_distance,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HammingAutomatonBuilder;
+    use crate::{hamming, Distance};
+
+    #[test]
+    fn test_hamming_dfa() {
+        let candidates = [
+            "", "a", "abc", "abd", "xbc", "xyc", "xyz", "abcd", "寿bc", "寿司c", "ab寿",
+        ];
+        for &max_distance in &[0, 1, 2] {
+            let builder = HammingAutomatonBuilder::new(max_distance);
+            for query in &["", "abc", "寿司c"] {
+                let dfa = builder.build_dfa(query);
+                for candidate in &candidates {
+                    let expected = match hamming(query, candidate) {
+                        Some(d) if d <= max_distance as usize => Distance::Exact(d as u8),
+                        _ => Distance::AtLeast(max_distance + 1),
+                    };
+                    assert_eq!(dfa.eval(candidate), expected, "{} {}", query, candidate);
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "The maximum distance should be at most 254")]
+    fn test_max_distance_255() {
+        let dfa = HammingAutomatonBuilder::new(254).build_dfa("a");
+        assert_eq!(dfa.eval("b"), Distance::Exact(1));
+        assert_eq!(dfa.eval("ab"), Distance::AtLeast(255));
+        HammingAutomatonBuilder::new(255);
+    }
+}
@@ -0,0 +1,165 @@
+use super::{CharAutomaton, Distance, DFA, SINK_STATE};
+use std::collections::HashMap;
+
+/// Builds automata computing the indel distance to a query: the number of
+/// chars to insert or delete to turn a text into the query.
+///
+/// Substitutions are not allowed, so that replacing a char counts as a
+/// deletion and an insertion, i.e. two edits. The distance between two
+/// strings is the number of chars they do not have in their longest common
+/// subsequence, as in the output of `diff`.
+///
+/// ```rust
+/// # use levenshtein_automata::{Distance, IndelAutomatonBuilder};
+/// let dfa = IndelAutomatonBuilder::new(2).build_dfa("kitten");
+/// assert_eq!(dfa.eval("kitten"), Distance::Exact(0));
+/// assert_eq!(dfa.eval("kiten"), Distance::Exact(1));
+/// assert_eq!(dfa.eval("sitten"), Distance::Exact(2));
+/// assert_eq!(dfa.eval("sittin"), Distance::AtLeast(3));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct IndelAutomatonBuilder {
+    max_distance: u8,
+}
+
+// States are the columns of the dynamic programming table, where
+// `column[x]` is the distance between the first `x` chars of the query and
+// the text read so far, capped at `max_distance + 1`.
+struct IndelAutomaton {
+    distances: Vec<Distance>,
+    transitions: Vec<Vec<(char, u32)>>,
+    default_transitions: Vec<u32>,
+}
+
+impl CharAutomaton for IndelAutomaton {
+    fn num_states(&self) -> u32 {
+        self.distances.len() as u32
+    }
+
+    fn initial_state(&self) -> u32 {
+        1
+    }
+
+    fn distance(&self, state: u32) -> Distance {
+        self.distances[state as usize]
+    }
+
+    fn default_transition(&self, state: u32) -> u32 {
+        self.default_transitions[state as usize]
+    }
+
+    fn transitions(&self, state: u32) -> Vec<(char, u32)> {
+        self.transitions[state as usize].clone()
+    }
+}
+
+impl IndelAutomatonBuilder {
+    /// Creates a builder for automata accepting up to `max_distance`
+    /// insertions and deletions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_distance` is `255`.
+    pub fn new(max_distance: u8) -> IndelAutomatonBuilder {
+        assert!(
+            max_distance < u8::MAX,
+            "The maximum distance should be at most 254"
+        );
+        IndelAutomatonBuilder { max_distance }
+    }
+
+    /// Builds the automaton computing the indel distance to `query`.
+    ///
+    /// Rejected texts are evaluated to `Distance::AtLeast(max_distance + 1)`.
+    pub fn build_dfa(&self, query: &str) -> DFA {
+        let query: Vec<char> = query.chars().collect();
+        let mut alphabet = query.clone();
+        alphabet.sort_unstable();
+        alphabet.dedup();
+        let cap = self.max_distance + 1;
+        let initial: Vec<u8> = (0..=query.len())
+            .map(|x| x.min(cap as usize) as u8)
+            .collect();
+        let mut automaton = IndelAutomaton {
+            distances: vec![Distance::AtLeast(cap)],
+            transitions: vec![Vec::new()],
+            default_transitions: vec![SINK_STATE],
+        };
+        let mut ids: HashMap<Vec<u8>, u32> = HashMap::new();
+        let mut states: Vec<Vec<u8>> = vec![initial.clone()];
+        ids.insert(initial, 1);
+        let mut i = 0;
+        while i < states.len() {
+            let column = states[i].clone();
+            let mut state_id = |next: Vec<u8>| -> u32 {
+                if next.iter().all(|&d| d == cap) {
+                    return SINK_STATE;
+                }
+                let next_id = ids.len() as u32 + 1;
+                *ids.entry(next).or_insert_with_key(|next| {
+                    states.push(next.clone());
+                    next_id
+                })
+            };
+            let transitions: Vec<(char, u32)> = alphabet
+                .iter()
+                .map(|&chr| (chr, state_id(next_column(&column, &query, Some(chr), cap))))
+                .collect();
+            let default_transition = state_id(next_column(&column, &query, None, cap));
+            let d = column[query.len()];
+            automaton.distances.push(if d < cap {
+                Distance::Exact(d)
+            } else {
+                Distance::AtLeast(cap)
+            });
+            automaton.transitions.push(transitions);
+            automaton.default_transitions.push(default_transition);
+            i += 1;
+        }
+        DFA::from_char_automaton(&automaton)
+    }
+}
+
+// Returns the column following `column` after reading `chr`, `None` standing
+// for any char absent from the query.
+fn next_column(column: &[u8], query: &[char], chr: Option<char>, cap: u8) -> Vec<u8> {
+    let mut next = vec![0u8; column.len()];
+    next[0] = column[0].saturating_add(1).min(cap);
+    for x in 1..column.len() {
+        let mut d = column[x]
+            .saturating_add(1)
+            .min(next[x - 1].saturating_add(1));
+        if Some(query[x - 1]) == chr {
+            d = d.min(column[x - 1]);
+        }
+        next[x] = d.min(cap);
+    }
+    next
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IndelAutomatonBuilder;
+    use crate::{indel, Distance, ExhaustiveVerifier};
+
+    #[test]
+    fn test_indel_dfa() {
+        for max_distance in 0..=3u8 {
+            let builder = IndelAutomatonBuilder::new(max_distance);
+            let verifier = ExhaustiveVerifier::new("abé", 5, max_distance, false);
+            for query in &["", "a", "abé", "abéé", "ébab"] {
+                let dfa = builder.build_dfa(query);
+                let result = verifier.verify_dfa_with(query, &dfa, indel);
+                assert_eq!(result, Ok(()));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "The maximum distance should be at most 254")]
+    fn test_max_distance_255() {
+        let dfa = IndelAutomatonBuilder::new(254).build_dfa("a");
+        assert_eq!(dfa.eval("b"), Distance::Exact(2));
+        IndelAutomatonBuilder::new(255);
+    }
+}
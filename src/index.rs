@@ -1,6 +1,10 @@
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use core::hash::Hash;
+#[cfg(not(feature = "std"))]
+use hashbrown::HashMap;
+#[cfg(feature = "std")]
 use std::collections::HashMap;
-use std::fmt::Debug;
-use std::hash::Hash;
 
 pub(crate) struct Index<I: Eq + Hash + Clone> {
     index: HashMap<I, u32>,
@@ -0,0 +1,162 @@
+use super::dictionary::traverse_sorted;
+use super::LevenshteinAutomatonBuilder;
+
+/// Fuzzy join between two collections of strings.
+///
+/// An automaton is built once per term of the left side. The right side is
+/// sorted once, and traversed as a trie: the states reached on a common prefix
+/// are shared between consecutive terms, and all of the terms starting with
+/// a prefix that cannot match anymore are skipped at once.
+///
+/// With the `rayon` feature, [FuzzyJoiner::par_join] processes the left
+/// terms in parallel.
+///
+/// ```rust
+/// # use levenshtein_automata::FuzzyJoiner;
+/// let left = ["color", "center"];
+/// let right = ["colour", "centre", "colon", "central"];
+/// let pairs = FuzzyJoiner::new(1, true).join(&left, &right);
+/// assert_eq!(pairs, vec![(0, 0, 1), (0, 2, 1), (1, 1, 1)]);
+/// ```
+pub struct FuzzyJoiner {
+    builder: LevenshteinAutomatonBuilder,
+}
+
+impl FuzzyJoiner {
+    /// Creates a joiner pairing the strings within `max_distance` of each other.
+    pub fn new(max_distance: u8, transposition_cost_one: bool) -> FuzzyJoiner {
+        FuzzyJoiner {
+            builder: LevenshteinAutomatonBuilder::new(max_distance, transposition_cost_one),
+        }
+    }
+
+    /// Returns the `(left_id, right_id, distance)` of all of the pairs within
+    /// the distance, ids being positions in `left` and `right`.
+    ///
+    /// Pairs are sorted by left id, and then by right id.
+    pub fn join<L: AsRef<str>, R: AsRef<str>>(
+        &self,
+        left: &[L],
+        right: &[R],
+    ) -> Vec<(usize, usize, u8)> {
+        let sorted_right = sort_terms(right);
+        let mut pairs = Vec::new();
+        for (left_id, left_term) in left.iter().enumerate() {
+            self.join_term(left_id, left_term.as_ref(), &sorted_right, &mut pairs);
+        }
+        pairs
+    }
+
+    /// Same as [FuzzyJoiner::join], with the left terms processed in parallel.
+    #[cfg(feature = "rayon")]
+    pub fn par_join<L, R>(&self, left: &[L], right: &[R]) -> Vec<(usize, usize, u8)>
+    where
+        L: AsRef<str> + Sync,
+        R: AsRef<str> + Sync,
+    {
+        use rayon::prelude::*;
+
+        let sorted_right = sort_terms(right);
+        left.par_iter()
+            .enumerate()
+            .flat_map_iter(|(left_id, left_term)| {
+                let mut pairs = Vec::new();
+                self.join_term(left_id, left_term.as_ref(), &sorted_right, &mut pairs);
+                pairs
+            })
+            .collect()
+    }
+
+    fn join_term(
+        &self,
+        left_id: usize,
+        left_term: &str,
+        sorted_right: &[(&[u8], usize)],
+        pairs: &mut Vec<(usize, usize, u8)>,
+    ) {
+        let dfa = self.builder.build_dfa(left_term);
+        let start = pairs.len();
+        for (i, d) in traverse_sorted(&dfa, sorted_right, |&(term, _)| term) {
+            pairs.push((left_id, sorted_right[i].1, d));
+        }
+        pairs[start..].sort_unstable();
+    }
+}
+
+/// Shortcut for [FuzzyJoiner::join], without transpositions.
+///
+/// Creating a [FuzzyJoiner] is not free: reuse one for several joins.
+pub fn fuzzy_join<L: AsRef<str>, R: AsRef<str>>(
+    left: &[L],
+    right: &[R],
+    max_distance: u8,
+) -> Vec<(usize, usize, u8)> {
+    FuzzyJoiner::new(max_distance, false).join(left, right)
+}
+
+fn sort_terms<R: AsRef<str>>(terms: &[R]) -> Vec<(&[u8], usize)> {
+    let mut sorted: Vec<(&[u8], usize)> = terms
+        .iter()
+        .enumerate()
+        .map(|(id, term)| (term.as_ref().as_bytes(), id))
+        .collect();
+    sorted.sort_unstable();
+    sorted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fuzzy_join, FuzzyJoiner};
+    use crate::{Distance, LevenshteinAutomatonBuilder};
+
+    const LEFT: &[&str] = &["", "a", "abc", "abd", "bcd", "寿司", "levenshtein"];
+    const RIGHT: &[&str] = &[
+        "abc",
+        "ab",
+        "abcd",
+        "",
+        "b",
+        "bc",
+        "寿",
+        "寿司",
+        "寿司屋",
+        "abc",
+        "levenstein",
+        "xyz",
+        "abdc",
+    ];
+
+    fn naive_join(max_distance: u8, transposition_cost_one: bool) -> Vec<(usize, usize, u8)> {
+        let builder = LevenshteinAutomatonBuilder::new(max_distance, transposition_cost_one);
+        let mut pairs = Vec::new();
+        for (left_id, left_term) in LEFT.iter().enumerate() {
+            let dfa = builder.build_dfa(left_term);
+            for (right_id, right_term) in RIGHT.iter().enumerate() {
+                if let Distance::Exact(d) = dfa.eval(right_term) {
+                    pairs.push((left_id, right_id, d));
+                }
+            }
+        }
+        pairs
+    }
+
+    #[test]
+    fn test_fuzzy_join() {
+        for &(max_distance, transposition_cost_one) in &[(0, false), (1, true), (2, false)] {
+            let joiner = FuzzyJoiner::new(max_distance, transposition_cost_one);
+            assert_eq!(
+                joiner.join(LEFT, RIGHT),
+                naive_join(max_distance, transposition_cost_one)
+            );
+        }
+        assert_eq!(fuzzy_join(LEFT, RIGHT, 1), naive_join(1, false));
+        assert!(fuzzy_join(LEFT, &[] as &[&str], 1).is_empty());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_join() {
+        let joiner = FuzzyJoiner::new(1, true);
+        assert_eq!(joiner.par_join(LEFT, RIGHT), joiner.join(LEFT, RIGHT));
+    }
+}
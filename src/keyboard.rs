@@ -0,0 +1,141 @@
+use super::{EditCosts, EditOp};
+
+const QWERTY_ROWS: [&str; 4] = ["1234567890", "qwertyuiop", "asdfghjkl", "zxcvbnm"];
+
+/// Edit costs where substituting a char by a neighbouring key of a QWERTY
+/// keyboard is cheaper than other edits.
+///
+/// Two keys are adjacent if they are next to each other on a row, or touch
+/// each other on the previous or next row, rows being staggered as on an
+/// actual keyboard. Letters are compared ignoring ASCII case.
+///
+/// With the default costs, adjacent substitutions cost `1` and the other
+/// edits cost `2`, so that a maximum distance of `2` accepts one arbitrary
+/// typo or two fat-finger typos.
+///
+/// ```rust
+/// # use levenshtein_automata::{Distance, KeyboardCosts, WeightedAutomatonBuilder};
+/// let builder = WeightedAutomatonBuilder::new(2, KeyboardCosts::default());
+/// let dfa = builder.build_dfa("keyboard");
+/// assert_eq!(dfa.eval("keyboard"), Distance::Exact(0));
+/// assert_eq!(dfa.eval("keybosrd"), Distance::Exact(1));
+/// assert_eq!(dfa.eval("jeybosrd"), Distance::Exact(2));
+/// assert_eq!(dfa.eval("keybxard"), Distance::Exact(2));
+/// assert_eq!(dfa.eval("kebyoard"), Distance::Exact(2));
+/// assert_eq!(dfa.eval("kebyoadr"), Distance::AtLeast(3));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct KeyboardCosts {
+    /// Cost of the substitution of a key by an adjacent key.
+    pub adjacent_substitution: u8,
+    /// Cost of the other substitutions.
+    pub substitution: u8,
+    /// Cost of an insertion.
+    pub insertion: u8,
+    /// Cost of a deletion.
+    pub deletion: u8,
+    /// Cost of a transposition.
+    pub transposition: u8,
+}
+
+impl Default for KeyboardCosts {
+    fn default() -> KeyboardCosts {
+        KeyboardCosts {
+            adjacent_substitution: 1,
+            substitution: 2,
+            insertion: 2,
+            deletion: 2,
+            transposition: 2,
+        }
+    }
+}
+
+impl KeyboardCosts {
+    /// Returns true if `a` and `b` are distinct adjacent keys.
+    pub fn are_adjacent(a: char, b: char) -> bool {
+        match (key_position(a), key_position(b)) {
+            (Some((row_a, col_a)), Some((row_b, col_b))) => {
+                let (row_a, col_a, row_b, col_b) = (
+                    row_a as isize,
+                    col_a as isize,
+                    row_b as isize,
+                    col_b as isize,
+                );
+                if row_a == row_b {
+                    (col_a - col_b).abs() == 1
+                } else if row_b == row_a + 1 {
+                    // The next row is shifted to the right.
+                    col_b == col_a || col_b == col_a - 1
+                } else if row_b == row_a - 1 {
+                    col_b == col_a || col_b == col_a + 1
+                } else {
+                    false
+                }
+            }
+            _ => false,
+        }
+    }
+}
+
+// Returns the row and column of a key.
+fn key_position(chr: char) -> Option<(usize, usize)> {
+    let chr = chr.to_ascii_lowercase();
+    QWERTY_ROWS.iter().enumerate().find_map(|(row, keys)| {
+        keys.chars()
+            .position(|key| key == chr)
+            .map(|col| (row, col))
+    })
+}
+
+impl EditCosts for KeyboardCosts {
+    fn cost(&self, op: EditOp, a: char, b: char) -> u8 {
+        match op {
+            EditOp::Match => 0,
+            EditOp::Substitution if KeyboardCosts::are_adjacent(a, b) => self.adjacent_substitution,
+            EditOp::Substitution => self.substitution,
+            EditOp::Insertion => self.insertion,
+            EditOp::Deletion => self.deletion,
+            EditOp::Transposition => self.transposition,
+        }
+    }
+
+    fn alphabet(&self) -> Vec<char> {
+        QWERTY_ROWS
+            .iter()
+            .flat_map(|keys| keys.chars())
+            .flat_map(|key| vec![key, key.to_ascii_uppercase()])
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KeyboardCosts;
+
+    #[test]
+    fn test_qwerty_adjacency() {
+        for &(a, b) in &[
+            ('q', 'w'),
+            ('q', 'a'),
+            ('a', 'z'),
+            ('g', 't'),
+            ('g', 'y'),
+            ('g', 'b'),
+        ] {
+            assert!(KeyboardCosts::are_adjacent(a, b));
+            assert!(KeyboardCosts::are_adjacent(b, a));
+        }
+        assert!(KeyboardCosts::are_adjacent('G', 'h'));
+        assert!(KeyboardCosts::are_adjacent('1', 'q'));
+        for &(a, b) in &[
+            ('q', 'q'),
+            ('q', 'e'),
+            ('g', 'r'),
+            ('g', 'n'),
+            ('a', 'x'),
+            ('a', 'é'),
+        ] {
+            assert!(!KeyboardCosts::are_adjacent(a, b));
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use super::dfa::utf8_sequences;
+use super::{Distance, DFA};
+use std::collections::VecDeque;
+use std::str;
+
+impl DFA {
+    /// Returns the strings accepted by the automaton with at most `max_len`
+    /// chars, along with their distance, in lexicographic order.
+    ///
+    /// Since an automaton typically accepts any char at some positions,
+    /// listing all of the accepted strings is hopeless: the chars on which
+    /// the automaton behaves the same way are represented by the smallest
+    /// of them. For instance, in a Levenshtein automaton, all of the chars
+    /// absent from the query are represented by `'\0'`.
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::{Distance, LevenshteinAutomatonBuilder};
+    /// let dfa = LevenshteinAutomatonBuilder::new(1, false).build_dfa("ab");
+    /// let strings: Vec<(String, Distance)> = dfa.strings(1).collect();
+    /// assert_eq!(
+    ///     strings,
+    ///     vec![
+    ///         ("a".to_string(), Distance::Exact(1)),
+    ///         ("b".to_string(), Distance::Exact(1)),
+    ///     ]
+    /// );
+    /// assert!(dfa.strings(3).any(|(string, _)| string == "a\0b"));
+    /// ```
+    pub fn strings(&self, max_len: usize) -> impl Iterator<Item = (String, Distance)> + '_ {
+        let mut transitions: Vec<Option<Vec<(char, u32)>>> = vec![None; self.num_states()];
+        let mut stack: Vec<(u32, String, usize)> = Vec::new();
+        if self.can_match(self.initial_state()) {
+            stack.push((self.initial_state(), String::new(), 0));
+        }
+        std::iter::from_fn(move || {
+            while let Some((state_id, string, len)) = stack.pop() {
+                if len < max_len {
+                    let state_transitions = transitions[state_id as usize]
+                        .get_or_insert_with(|| self.char_transitions(state_id));
+                    for &(chr, to_state_id) in state_transitions.iter().rev() {
+                        if self.can_match(to_state_id) {
+                            let mut to_string = string.clone();
+                            to_string.push(chr);
+                            stack.push((to_state_id, to_string, len + 1));
+                        }
+                    }
+                }
+                if let distance @ Distance::Exact(_) = self.distance(state_id) {
+                    return Some((string, distance));
+                }
+            }
+            None
+        })
+    }
+
+    /// Returns true if the automaton does not accept any string.
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::LevenshteinAutomatonBuilder;
+    /// let builder = LevenshteinAutomatonBuilder::new(1, false);
+    /// let dfa = builder.build_dfa("abc");
+    /// assert!(!dfa.is_empty());
+    /// assert!(dfa.intersect(&builder.build_dfa("xyz")).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        !self.can_match(self.initial_state())
+    }
+
+    /// Returns one of the shortest strings accepted by the automaton,
+    /// or `None` if it does not accept any string.
+    ///
+    /// Among the accepted strings with the fewest chars, the returned string
+    /// is made of the chars chosen as in [.strings()](#method.strings),
+    /// and is the smallest of them.
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::LevenshteinAutomatonBuilder;
+    /// let builder = LevenshteinAutomatonBuilder::new(1, false);
+    /// let dfa = builder.build_dfa("abc");
+    /// assert_eq!(dfa.shortest_accepted(), Some("ab".to_string()));
+    /// let dfa = dfa.intersect(&builder.build_dfa("xbc"));
+    /// assert_eq!(dfa.shortest_accepted(), Some("bc".to_string()));
+    /// ```
+    pub fn shortest_accepted(&self) -> Option<String> {
+        if self.is_empty() {
+            return None;
+        }
+        // Breadth-first search, remembering the char and the state each
+        // state is first reached from.
+        let mut parents: Vec<Option<(char, u32)>> = vec![None; self.num_states()];
+        let mut visited = vec![false; self.num_states()];
+        visited[self.initial_state() as usize] = true;
+        let mut queue = VecDeque::new();
+        queue.push_back(self.initial_state());
+        while let Some(state_id) = queue.pop_front() {
+            if let Distance::Exact(_) = self.distance(state_id) {
+                let mut chars = Vec::new();
+                let mut state_id = state_id;
+                while let Some((chr, parent)) = parents[state_id as usize] {
+                    chars.push(chr);
+                    state_id = parent;
+                }
+                return Some(chars.into_iter().rev().collect());
+            }
+            for (chr, to_state_id) in self.char_transitions(state_id) {
+                if self.can_match(to_state_id) && !visited[to_state_id as usize] {
+                    visited[to_state_id as usize] = true;
+                    parents[to_state_id as usize] = Some((chr, state_id));
+                    queue.push_back(to_state_id);
+                }
+            }
+        }
+        None
+    }
+
+    // Returns the distinct states reached from `state_id` after reading a
+    // whole char, each along with the smallest char leading to it,
+    // by increasing char.
+    fn char_transitions(&self, state_id: u32) -> Vec<(char, u32)> {
+        let mut transitions: Vec<(char, u32)> = Vec::new();
+        for sequence in utf8_sequences(0, 0x10_FFFF) {
+            // The distinct states reached on a prefix of the sequence, along
+            // with the smallest bytes leading to them, in increasing order.
+            let mut frontier: Vec<(u32, Vec<u8>)> = vec![(state_id, Vec::new())];
+            for &(start, end) in &sequence {
+                let mut next_frontier: Vec<(u32, Vec<u8>)> = Vec::new();
+                for (from_state_id, bytes) in &frontier {
+                    for b in start..=end {
+                        let to_state_id = self.transition(*from_state_id, b);
+                        if next_frontier.iter().all(|&(state, _)| state != to_state_id) {
+                            let mut bytes = bytes.clone();
+                            bytes.push(b);
+                            next_frontier.push((to_state_id, bytes));
+                        }
+                    }
+                }
+                frontier = next_frontier;
+            }
+            for (to_state_id, bytes) in frontier {
+                if transitions.iter().all(|&(_, state)| state != to_state_id) {
+                    let chr = str::from_utf8(&bytes).unwrap().chars().next().unwrap();
+                    transitions.push((chr, to_state_id));
+                }
+            }
+        }
+        transitions.sort_unstable();
+        transitions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Distance, LevenshteinAutomatonBuilder};
+
+    #[test]
+    fn test_strings() {
+        let dfa = LevenshteinAutomatonBuilder::new(1, true).build_dfa("寿司");
+        let strings: Vec<(String, Distance)> = dfa.strings(3).collect();
+        let mut sorted = strings.clone();
+        sorted.sort_by(|left, right| left.0.cmp(&right.0));
+        assert_eq!(strings, sorted);
+        for (string, distance) in &strings {
+            assert!(string.chars().count() <= 3);
+            assert_eq!(dfa.eval(string), *distance);
+        }
+        for expected in &[
+            "寿司", "司寿", "寿", "司", "\0司", "寿\0", "寿\0司", "寿司\0",
+        ] {
+            assert!(
+                strings.iter().any(|(string, _)| string == expected),
+                "{}",
+                expected
+            );
+        }
+        let dfa = LevenshteinAutomatonBuilder::new(0, false).build_dfa("abc");
+        let strings: Vec<(String, Distance)> = dfa.strings(10).collect();
+        assert_eq!(strings, vec![("abc".to_string(), Distance::Exact(0))]);
+        assert_eq!(dfa.strings(2).count(), 0);
+    }
+
+    #[test]
+    fn test_shortest_accepted() {
+        let builder = LevenshteinAutomatonBuilder::new(2, true);
+        for query in &["", "a", "abcd", "寿司屋"] {
+            let dfa = builder.build_dfa(query);
+            let shortest = dfa.shortest_accepted().unwrap();
+            assert_eq!(
+                shortest.chars().count(),
+                query.chars().count().saturating_sub(2)
+            );
+            assert!(matches!(dfa.eval(&shortest), Distance::Exact(_)));
+            let expected = dfa.strings(shortest.chars().count()).next();
+            assert_eq!(Some(shortest), expected.map(|(string, _)| string));
+        }
+        let empty = builder
+            .build_dfa("abcdef")
+            .intersect(&builder.build_dfa("uvwxyz"));
+        assert!(empty.is_empty());
+        assert_eq!(empty.shortest_accepted(), None);
+        assert_eq!(empty.strings(10).count(), 0);
+    }
+}
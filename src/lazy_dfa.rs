@@ -0,0 +1,370 @@
+use super::nfa::{LevenshteinNfa, NfaState};
+use super::{Distance, SINK_STATE};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+const NUM_SHARDS: usize = 16;
+
+// States of a `LevenshteinNfa` determinized so far, shared by both lazy
+// automata. The state `0` is the sink state, and `1` the initial state.
+struct LazyStates {
+    states: Vec<(NfaState, Distance)>,
+    ids: HashMap<NfaState, u32>,
+}
+
+impl LazyStates {
+    fn new(nfa: &LevenshteinNfa) -> LazyStates {
+        let mut states = LazyStates {
+            states: Vec::new(),
+            ids: HashMap::new(),
+        };
+        states.state_id(nfa, NfaState::dead());
+        states.state_id(nfa, nfa.initial_state());
+        states
+    }
+
+    fn state(&self, state_id: u32) -> &NfaState {
+        &self.states[state_id as usize].0
+    }
+
+    fn distance(&self, state_id: u32) -> Distance {
+        self.states[state_id as usize].1
+    }
+
+    fn state_id(&mut self, nfa: &LevenshteinNfa, state: NfaState) -> u32 {
+        if let Some(&state_id) = self.ids.get(&state) {
+            return state_id;
+        }
+        let state_id = self.states.len() as u32;
+        let distance = nfa.distance(&state);
+        self.ids.insert(state.clone(), state_id);
+        self.states.push((state, distance));
+        state_id
+    }
+}
+
+/// Levenshtein automaton determinized on demand, which can be shared
+/// by several threads.
+///
+/// Unlike a [DFA](./struct.DFA.html), nothing is computed up front: the
+/// states and transitions are determinized the first time they are used,
+/// and cached. The cache is shared by all of the threads using the automaton,
+/// so that a state is only determinized once. Its transitions are split into
+/// shards, each protected by its own lock, to limit contention.
+///
+/// Transitions operate on chars rather than bytes. Since the chars absent from
+/// the query all behave the same, the cache stays small whatever the texts are.
+/// The states are determinized as in a [LazyDfa](./struct.LazyDfa.html).
+///
+/// ```rust
+/// # use levenshtein_automata::{ConcurrentLazyDfa, Distance};
+/// # use std::sync::Arc;
+/// let dfa = Arc::new(ConcurrentLazyDfa::new("Levenshtein", 2, true));
+/// let handles: Vec<_> = ["Levenshtain", "Lveenshtein", "Einstein"]
+///     .iter()
+///     .map(|text| {
+///         let dfa = dfa.clone();
+///         std::thread::spawn(move || dfa.eval(text))
+///     })
+///     .collect();
+/// let distances: Vec<Distance> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+/// assert_eq!(
+///     distances,
+///     vec![Distance::Exact(1), Distance::Exact(1), Distance::AtLeast(3)]
+/// );
+/// ```
+pub struct ConcurrentLazyDfa {
+    nfa: LevenshteinNfa,
+    states: RwLock<LazyStates>,
+    // Transitions from a state, indexed by the characteristic vector of the char.
+    // The transitions of `state` live in the shard `state % NUM_SHARDS`.
+    transitions: Vec<RwLock<HashMap<(u32, u64), u32>>>,
+}
+
+impl ConcurrentLazyDfa {
+    /// Creates the automaton computing the distance to `query`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_distance` is greater than `31`, as
+    /// [LevenshteinNfa::new](./struct.LevenshteinNfa.html#method.new).
+    pub fn new(query: &str, max_distance: u8, transposition_cost_one: bool) -> ConcurrentLazyDfa {
+        let nfa = LevenshteinNfa::new(query, max_distance, transposition_cost_one);
+        let states = LazyStates::new(&nfa);
+        ConcurrentLazyDfa {
+            nfa,
+            states: RwLock::new(states),
+            transitions: (0..NUM_SHARDS)
+                .map(|_| RwLock::new(HashMap::new()))
+                .collect(),
+        }
+    }
+
+    /// Returns the initial state
+    pub fn initial_state(&self) -> u32 {
+        1
+    }
+
+    /// Returns the number of states determinized so far.
+    pub fn num_states(&self) -> usize {
+        self.states.read().unwrap().states.len()
+    }
+
+    /// Returns the Levenshtein distance associated to the
+    /// current state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the state was not returned by this automaton.
+    pub fn distance(&self, state_id: u32) -> Distance {
+        self.states.read().unwrap().distance(state_id)
+    }
+
+    /// Returns the destination state reached after consuming a given char,
+    /// determinizing it if needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the state was not returned by this automaton.
+    pub fn transition(&self, from_state_id: u32, chr: char) -> u32 {
+        if from_state_id == SINK_STATE {
+            return SINK_STATE;
+        }
+        let (chi, to_state) = {
+            let states = self.states.read().unwrap();
+            let from_state = states.state(from_state_id);
+            let chi = self.nfa.characteristic_vector(from_state, chr);
+            let shard = &self.transitions[from_state_id as usize % NUM_SHARDS];
+            if let Some(&to_state_id) = shard.read().unwrap().get(&(from_state_id, chi)) {
+                return to_state_id;
+            }
+            (chi, self.nfa.transition_chi(from_state, chi))
+        };
+        let to_state_id = self.states.write().unwrap().state_id(&self.nfa, to_state);
+        self.transitions[from_state_id as usize % NUM_SHARDS]
+            .write()
+            .unwrap()
+            .insert((from_state_id, chi), to_state_id);
+        to_state_id
+    }
+
+    /// Returns the Levenshtein distance between the query and `text`.
+    pub fn eval(&self, text: &str) -> Distance {
+        let mut state = self.initial_state();
+        for chr in text.chars() {
+            state = self.transition(state, chr);
+            if state == SINK_STATE {
+                break;
+            }
+        }
+        self.distance(state)
+    }
+}
+
+/// Levenshtein automaton determinized on demand.
+///
+/// States of the [LevenshteinNfa](./struct.LevenshteinNfa.html) are
+/// determinized the first time they are reached, and the transitions are
+/// cached, so that evaluating a few texts only pays for the states they visit
+/// instead of the whole [DFA](./struct.DFA.html). Repeated evaluations get
+/// faster as the cache fills up.
+///
+/// Transitions operate on chars, and the chars absent from the query share
+/// their transitions. The state `0` is the sink state.
+/// Use a [ConcurrentLazyDfa](./struct.ConcurrentLazyDfa.html) to share
+/// the cache between threads.
+///
+/// ```rust
+/// # use levenshtein_automata::{Distance, LazyDfa};
+/// let mut dfa = LazyDfa::new("Levenshtein", 3, true);
+/// assert_eq!(dfa.eval("Levenshtain"), Distance::Exact(1));
+/// assert_eq!(dfa.eval("Lveenshtien"), Distance::Exact(2));
+/// assert_eq!(dfa.eval("Einstein"), Distance::AtLeast(4));
+/// ```
+pub struct LazyDfa {
+    nfa: LevenshteinNfa,
+    states: LazyStates,
+    // Transitions from a state, indexed by the characteristic vector of the char.
+    transitions: HashMap<(u32, u64), u32>,
+}
+
+impl LazyDfa {
+    /// Creates the automaton computing the distance to `query`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_distance` is greater than `31`, as
+    /// [LevenshteinNfa::new](./struct.LevenshteinNfa.html#method.new).
+    pub fn new(query: &str, max_distance: u8, transposition_cost_one: bool) -> LazyDfa {
+        let nfa = LevenshteinNfa::new(query, max_distance, transposition_cost_one);
+        let states = LazyStates::new(&nfa);
+        LazyDfa {
+            nfa,
+            states,
+            transitions: HashMap::new(),
+        }
+    }
+
+    /// Returns the initial state
+    pub fn initial_state(&self) -> u32 {
+        1
+    }
+
+    /// Returns the number of states determinized so far.
+    pub fn num_states(&self) -> usize {
+        self.states.states.len()
+    }
+
+    /// Returns the Levenshtein distance associated to the
+    /// current state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the state was not returned by this automaton.
+    pub fn distance(&self, state_id: u32) -> Distance {
+        self.states.distance(state_id)
+    }
+
+    /// Returns the destination state reached after consuming a given char,
+    /// determinizing it if needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the state was not returned by this automaton.
+    pub fn transition(&mut self, from_state_id: u32, chr: char) -> u32 {
+        if from_state_id == SINK_STATE {
+            return SINK_STATE;
+        }
+        let from_state = self.states.state(from_state_id);
+        let chi = self.nfa.characteristic_vector(from_state, chr);
+        if let Some(&to_state_id) = self.transitions.get(&(from_state_id, chi)) {
+            return to_state_id;
+        }
+        let to_state = self.nfa.transition_chi(from_state, chi);
+        let to_state_id = self.states.state_id(&self.nfa, to_state);
+        self.transitions.insert((from_state_id, chi), to_state_id);
+        to_state_id
+    }
+
+    /// Returns the Levenshtein distance between the query and `text`.
+    pub fn eval(&mut self, text: &str) -> Distance {
+        let mut state = self.initial_state();
+        for chr in text.chars() {
+            state = self.transition(state, chr);
+            if state == SINK_STATE {
+                break;
+            }
+        }
+        self.distance(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConcurrentLazyDfa, LazyDfa};
+    use crate::{damerau_levenshtein, Distance, LevenshteinAutomatonBuilder};
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn test_lazy_dfa_matches_dfa() {
+        let texts = [
+            "", "a", "abc", "acb", "bac", "abcabc", "寿司", "寿a司", "xyz", "cba",
+        ];
+        for &(max_distance, transposition_cost_one) in
+            &[(0, false), (1, true), (2, false), (2, true)]
+        {
+            let builder = LevenshteinAutomatonBuilder::new(max_distance, transposition_cost_one);
+            for query in &["", "abc", "寿司", "abcabc"] {
+                let dfa = builder.build_dfa(query);
+                let lazy_dfa = ConcurrentLazyDfa::new(query, max_distance, transposition_cost_one);
+                for text in &texts {
+                    assert_eq!(lazy_dfa.eval(text), dfa.eval(text));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lazy_dfa_first_char_absent_from_query() {
+        for &(max_distance, transposition_cost_one) in &[(1, false), (2, true)] {
+            let builder = LevenshteinAutomatonBuilder::new(max_distance, transposition_cost_one);
+            for query in &["ab", "寿司"] {
+                let dfa = builder.build_dfa(query);
+                let mut lazy_dfa = LazyDfa::new(query, max_distance, transposition_cost_one);
+                for text in &["xab", "x寿司", "xb", "xyab", "x", "xa"] {
+                    assert_eq!(lazy_dfa.eval(text), dfa.eval(text), "{} {}", query, text);
+                }
+            }
+        }
+        assert_eq!(LazyDfa::new("ab", 1, false).eval("xab"), Distance::Exact(1));
+    }
+
+    #[test]
+    fn test_lazy_dfas_share_the_distance_limit() {
+        let query = "It was the best of times, it was the worst of times";
+        let text = "It was the worst of times, it was the best of times";
+        let concurrent_lazy_dfa = ConcurrentLazyDfa::new(query, 31, true);
+        let mut lazy_dfa = LazyDfa::new(query, 31, true);
+        let distance = lazy_dfa.eval(text);
+        assert_eq!(
+            distance,
+            Distance::Exact(damerau_levenshtein(query, text) as u8)
+        );
+        assert_eq!(concurrent_lazy_dfa.eval(text), distance);
+        assert_eq!(concurrent_lazy_dfa.num_states(), lazy_dfa.num_states());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_concurrent_lazy_dfa_distance_limit() {
+        ConcurrentLazyDfa::new("abc", 32, false);
+    }
+
+    #[test]
+    fn test_single_threaded_lazy_dfa() {
+        let dfa = LevenshteinAutomatonBuilder::new(2, true).build_dfa("abcabc");
+        let mut lazy_dfa = LazyDfa::new("abcabc", 2, true);
+        assert_eq!(lazy_dfa.num_states(), 2);
+        for text in &[
+            "", "a", "abc", "acbabc", "bacabc", "abcabc", "寿司", "abcxyz",
+        ] {
+            assert_eq!(lazy_dfa.eval(text), dfa.eval(text));
+        }
+        let num_states = lazy_dfa.num_states();
+        assert!(num_states < dfa.num_states());
+        assert_eq!(lazy_dfa.eval("abcxyz"), dfa.eval("abcxyz"));
+        assert_eq!(lazy_dfa.num_states(), num_states);
+    }
+
+    #[test]
+    fn test_lazy_dfa_shared_across_threads() {
+        let lazy_dfa = Arc::new(ConcurrentLazyDfa::new("levenshtein", 2, true));
+        let dfa = LevenshteinAutomatonBuilder::new(2, true).build_dfa("levenshtein");
+        let texts: Vec<String> = vec!["levenshtein", "lveenshtein", "levenstein", "einstein", "l"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let lazy_dfa = lazy_dfa.clone();
+                let texts = texts.clone();
+                thread::spawn(move || {
+                    texts
+                        .iter()
+                        .map(|text| lazy_dfa.eval(text))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        let expected: Vec<_> = texts.iter().map(|text| dfa.eval(text)).collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), expected);
+        }
+        let num_states = lazy_dfa.num_states();
+        for text in &texts {
+            lazy_dfa.eval(text);
+        }
+        assert_eq!(lazy_dfa.num_states(), num_states);
+    }
+}
@@ -1,4 +1,5 @@
-use std::cmp::Ordering;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
 
 #[cfg(test)]
 pub fn compute_characteristic_vector(query: &[char], c: char) -> u64 {
@@ -11,6 +12,16 @@ pub fn compute_characteristic_vector(query: &[char], c: char) -> u64 {
     chi
 }
 
+// Bit `i` is set iff the char at `offset + i` in the query is `c`.
+#[cfg(feature = "std")]
+pub(crate) fn shifted_characteristic_vector(query: &[char], offset: u32, c: char) -> u64 {
+    let window = query.iter().skip(offset as usize).take(64);
+    window
+        .enumerate()
+        .filter(|&(_, &query_char)| query_char == c)
+        .fold(0u64, |chi, (i, _)| chi | (1u64 << i))
+}
+
 #[derive(Debug, Hash, Eq, PartialEq, Clone)]
 pub struct MultiState {
     states: Vec<NFAState>,
@@ -70,6 +81,8 @@ impl MultiState {
 /// Over this distance, the automaton will invariably
 /// return `Distance::AtLeast(max_distance + 1)`.
 #[derive(Eq, PartialEq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Distance {
     Exact(u8),
     AtLeast(u8),
@@ -151,13 +164,24 @@ impl LevenshteinNFA {
     }
 
     pub fn multistate_distance(&self, multistate: &MultiState, query_len: u32) -> Distance {
+        self.shifted_multistate_distance(multistate, 0, query_len)
+    }
+
+    // Same as `multistate_distance`, for a multistate whose offsets
+    // are relative to `offset`.
+    pub(crate) fn shifted_multistate_distance(
+        &self,
+        multistate: &MultiState,
+        offset: u32,
+        query_len: u32,
+    ) -> Distance {
         multistate
             .states()
             .iter()
-            .map(|state| state.distance + dist(query_len, state.offset) as u8)
-            .filter(|d| *d <= self.max_distance)
+            .map(|state| u32::from(state.distance) + dist(query_len, offset + state.offset))
+            .filter(|d| *d <= u32::from(self.max_distance))
             .min()
-            .map(Distance::Exact)
+            .map(|d| Distance::Exact(d as u8))
             .unwrap_or_else(|| Distance::AtLeast(self.max_distance + 1u8))
     }
 
@@ -30,36 +30,293 @@ The implementation is based on the following paper
 **Fast String Correction with Levenshtein-Automata (2002)** by by Klaus Schulz and Stoyan Mihov.
 I also tried to explain it in the following [blog post](https://fulmicoton.com/posts/levenshtein/).
 
+Without the default `std` feature, the crate is `no_std` and only relies
+on `alloc`: the builder, the `DFA` and its evaluation are available, while
+the other modules require `std`.
+
 !*/
 
-#![cfg_attr(test, feature(test))]
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(all(test, feature = "unstable"), feature(test))]
 
-#[cfg(test)]
+extern crate alloc;
+
+#[cfg(all(test, feature = "unstable"))]
 extern crate test;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "unstable"))]
 mod bench;
 #[cfg(test)]
 mod tests;
 
+#[cfg(feature = "std")]
+mod acceptor;
 mod alphabet;
+#[cfg(feature = "std")]
+mod art;
+#[cfg(feature = "std")]
+mod batch;
+#[cfg(feature = "std")]
+mod best_first;
+#[cfg(feature = "std")]
+mod byte_classes;
+mod case_folding;
+mod char_automaton;
+#[cfg(feature = "std")]
+mod chunked;
+#[cfg(feature = "std")]
+mod cluster;
+#[cfg(feature = "std")]
+mod codegen;
+#[cfg(feature = "std")]
+mod completion;
+#[cfg(feature = "std")]
+mod continuation;
+#[cfg(feature = "std")]
+mod dawg;
+#[cfg(feature = "std")]
+mod dedup;
 mod dfa;
+#[cfg(feature = "std")]
+mod dictionary;
+#[cfg(feature = "std")]
+mod distance;
+#[cfg(feature = "std")]
+mod double_array;
+mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "arbitrary")]
+mod fuzz;
+#[cfg(feature = "fuzzy_matcher_adapter")]
+mod fuzzy_matcher;
+#[cfg(feature = "std")]
+mod graph;
+#[cfg(feature = "unicode-segmentation")]
+mod grapheme;
+#[cfg(feature = "std")]
+mod hamming;
+#[cfg(feature = "std")]
+mod indel;
 mod index;
+#[cfg(feature = "std")]
+mod join;
+#[cfg(feature = "std")]
+mod keyboard;
+#[cfg(feature = "std")]
+mod language;
+#[cfg(feature = "std")]
+mod lazy_dfa;
 mod levenshtein_nfa;
+#[cfg(feature = "std")]
+mod lucene;
+#[cfg(feature = "std")]
+mod matcher;
+#[cfg(feature = "minhash")]
+mod minhash;
+#[cfg(feature = "std")]
+mod multi_pattern;
+#[cfg(feature = "std")]
+mod myers;
+#[cfg(feature = "std")]
+mod narrow;
+#[cfg(feature = "std")]
+mod nfa;
+#[cfg(feature = "std")]
+mod ngram;
+#[cfg(feature = "unicode-normalization")]
+mod normalization;
 mod parametric_dfa;
+#[cfg(feature = "std")]
+mod phonetic;
+#[cfg(feature = "std")]
+mod prefix_evaluator;
+#[cfg(feature = "std")]
+mod product;
+#[cfg(feature = "std")]
+mod random;
+#[cfg(feature = "regex_automata_export")]
+mod regex_export;
+#[cfg(feature = "rand")]
+mod sample;
+#[cfg(feature = "std")]
+mod scan;
+#[cfg(feature = "serde")]
+mod serde_support;
+#[cfg(feature = "std")]
+mod serialize;
+#[cfg(feature = "std")]
+mod session;
+#[cfg(feature = "std")]
+mod snapshot;
+#[cfg(feature = "std")]
+mod sparse;
+#[cfg(feature = "std")]
+mod stats;
+#[cfg(feature = "std")]
+mod stress;
+mod suffix;
+#[cfg(feature = "std")]
+mod suffix_automaton;
+#[cfg(feature = "std")]
+mod suggest;
+mod tables;
+#[cfg(feature = "std")]
+mod transposition_window;
+#[cfg(feature = "std")]
+mod trigram;
+#[cfg(feature = "std")]
+mod typo_rules;
+#[cfg(feature = "std")]
+mod verify;
+#[cfg(feature = "std")]
+mod visualize;
+#[cfg(feature = "std")]
+mod walker;
+#[cfg(feature = "wasm")]
+mod wasm;
+#[cfg(feature = "std")]
+mod weighted;
 
-pub use self::dfa::{DFA, SINK_STATE};
+#[cfg(feature = "std")]
+pub use self::acceptor::Acceptor;
+#[cfg(feature = "std")]
+pub use self::art::ArtDictionary;
+#[cfg(feature = "std")]
+pub use self::best_first::{best_first_search, Trie};
+#[cfg(feature = "std")]
+pub use self::byte_classes::ByteClassDfa;
+pub use self::case_folding::CaseFolding;
+pub use self::char_automaton::CharAutomaton;
+#[cfg(feature = "std")]
+pub use self::chunked::ChunkedMatches;
+#[cfg(feature = "std")]
+pub use self::cluster::{ClusterPrefilter, Clusterer};
+#[cfg(feature = "std")]
+pub use self::completion::{identifier_tokens, CompletionQuery, CompletionRanker, CompletionScore};
+#[cfg(feature = "std")]
+pub use self::continuation::Continuation;
+#[cfg(feature = "std")]
+pub use self::dawg::{Dawg, DawgBuilder, OutOfOrderWord};
+#[cfg(feature = "std")]
+pub use self::dedup::{dedup_within, Deduplicator};
+pub use self::dfa::{
+    DefaultSuccessorRange, DfaRef, InvalidUtf8Sequence, Utf8DFABuilder, Utf8DFAStateBuilder, DFA,
+    SINK_STATE,
+};
+#[cfg(feature = "std")]
+pub use self::dictionary::{find_top_k, fuzzy_lookup, fuzzy_lookup_iter, NearestNeighbors};
+#[cfg(feature = "std")]
+pub use self::distance::{
+    damerau_levenshtein, damerau_levenshtein_within, distance_within, edit_script, hamming, indel,
+    levenshtein, levenshtein_within, Edit, EditOp,
+};
+#[cfg(feature = "std")]
+pub use self::double_array::DoubleArrayDfa;
+pub use self::error::Error;
+#[cfg(feature = "arbitrary")]
+pub use self::fuzz::{BuilderConfig, QueryInput};
+#[cfg(feature = "fuzzy_matcher_adapter")]
+pub use self::fuzzy_matcher::LevenshteinMatcher;
+#[cfg(feature = "std")]
+pub use self::graph::{Edge, Edges};
+#[cfg(feature = "unicode-segmentation")]
+pub use self::grapheme::GraphemeDfa;
+#[cfg(feature = "std")]
+pub use self::hamming::HammingAutomatonBuilder;
+#[cfg(feature = "std")]
+pub use self::indel::IndelAutomatonBuilder;
 use self::index::Index;
+#[cfg(feature = "std")]
+pub use self::join::{fuzzy_join, FuzzyJoiner};
+#[cfg(feature = "std")]
+pub use self::keyboard::KeyboardCosts;
+#[cfg(feature = "std")]
+pub use self::lazy_dfa::{ConcurrentLazyDfa, LazyDfa};
 pub use self::levenshtein_nfa::Distance;
 use self::levenshtein_nfa::LevenshteinNFA;
+#[cfg(feature = "std")]
+pub use self::lucene::LuceneLevenshteinAutomata;
+#[cfg(feature = "std")]
+pub use self::matcher::{Matcher, Strategy};
+#[cfg(feature = "minhash")]
+pub use self::minhash::MinHashLsh;
+#[cfg(feature = "std")]
+pub use self::multi_pattern::{MultiPatternBuilder, MultiPatternDfa, PatternId};
+#[cfg(feature = "std")]
+pub use self::myers::MyersEvaluator;
+#[cfg(feature = "std")]
+pub use self::narrow::{CompactDfa, NarrowDfa, MAX_NARROW_NUM_STATES};
+#[cfg(feature = "std")]
+pub use self::nfa::{LevenshteinNfa, NfaState};
+#[cfg(feature = "std")]
+pub use self::ngram::{NgramFilter, NgramQuery};
 use self::parametric_dfa::ParametricDFA;
+#[cfg(feature = "std")]
+pub use self::phonetic::{
+    DoubleMetaphone, PhoneticAutomatonBuilder, PhoneticEncoder, PhoneticQuery, Soundex,
+};
+#[cfg(feature = "std")]
+pub use self::prefix_evaluator::PrefixEvaluator;
+#[cfg(feature = "std")]
+pub use self::random::{RandomDfaGenerator, ReferenceDfa};
+#[cfg(feature = "regex_automata_export")]
+pub use self::regex_export::RegexExportError;
+#[cfg(feature = "std")]
+pub use self::serialize::{InvalidSerializedDfa, SerializedDfa};
+#[cfg(feature = "std")]
+pub use self::session::Session;
+#[cfg(feature = "std")]
+pub use self::sparse::SparseDfa;
+#[cfg(feature = "std")]
+pub use self::stats::DfaStats;
+#[cfg(feature = "std")]
+pub use self::stress::{worst_case_query, StressPattern, StressReport};
+#[cfg(feature = "std")]
+pub use self::suffix_automaton::SuffixAutomatonIndex;
+#[cfg(feature = "std")]
+pub use self::suggest::{did_you_mean, Suggester};
+pub use self::tables::ParametricTables;
+#[cfg(feature = "std")]
+pub use self::transposition_window::TranspositionWindowBuilder;
+#[cfg(feature = "std")]
+pub use self::trigram::TrigramIndex;
+#[cfg(feature = "std")]
+pub use self::typo_rules::{Fuzziness, TypoAutomatonBuilder, TypoRules};
+#[cfg(feature = "std")]
+pub use self::verify::{Counterexample, ExhaustiveVerifier};
+#[cfg(feature = "std")]
+pub use self::visualize::render_alignment;
+#[cfg(feature = "std")]
+pub use self::walker::DfaWalker;
+#[cfg(feature = "wasm")]
+pub use self::wasm::{WasmAutomatonBuilder, WasmDfa};
+#[cfg(feature = "std")]
+pub use self::weighted::{EditCosts, WeightedAutomatonBuilder};
 
 /// Builder for Levenshtein Automata.
 ///
 /// It wraps a precomputed datastructure that allows to
 /// produce small (but not minimal) DFA.
+///
+/// The parametric tables only depend on the distance and on whether
+/// transpositions are allowed, so that a single builder can be reused for
+/// all of the queries.
+///
+/// ```rust
+/// # use levenshtein_automata::{Distance, LevenshteinAutomatonBuilder};
+/// let builder = LevenshteinAutomatonBuilder::new(1, true);
+/// let dfa = builder.build_dfa("automaton");
+/// assert_eq!(dfa.eval("automaton"), Distance::Exact(0));
+/// assert_eq!(dfa.eval("autoamton"), Distance::Exact(1));
+/// assert_eq!(dfa.eval("automata"), Distance::AtLeast(2));
+/// ```
 pub struct LevenshteinAutomatonBuilder {
     parametric_dfa: ParametricDFA,
+    max_distance: u8,
+    transposition_cost_one: bool,
+    case_folding: CaseFolding,
+    max_num_states: Option<usize>,
 }
 
 impl LevenshteinAutomatonBuilder {
@@ -76,10 +333,62 @@ impl LevenshteinAutomatonBuilder {
         let levenshtein_nfa = LevenshteinNFA::levenshtein(max_distance, transposition_cost_one);
         let parametric_dfa = ParametricDFA::from_nfa(&levenshtein_nfa);
         LevenshteinAutomatonBuilder {
-            parametric_dfa: parametric_dfa,
+            parametric_dfa,
+            max_distance,
+            transposition_cost_one,
+            case_folding: CaseFolding::None,
+            max_num_states: None,
         }
     }
 
+    /// Sets the case folding applied to the query and to the texts by the
+    /// automata built afterwards. Defaults to [CaseFolding::None](./enum.CaseFolding.html#variant.None).
+    ///
+    /// This spares folding the texts beforehand, which is not possible
+    /// when the automaton drives the traversal of an index.
+    /// Suffix automata are not affected.
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::{CaseFolding, Distance, LevenshteinAutomatonBuilder};
+    /// let builder = LevenshteinAutomatonBuilder::new(1, true).case_folding(CaseFolding::Ascii);
+    /// let dfa = builder.build_dfa("Hello");
+    /// assert_eq!(dfa.eval("hello"), Distance::Exact(0));
+    /// assert_eq!(dfa.eval("HELO"), Distance::Exact(1));
+    /// let builder = LevenshteinAutomatonBuilder::new(1, true).case_folding(CaseFolding::Unicode);
+    /// let dfa = builder.build_dfa("Straße");
+    /// assert_eq!(dfa.eval("STRAẞE"), Distance::Exact(0));
+    /// assert_eq!(dfa.eval("ΣTRAßE"), Distance::Exact(1));
+    /// ```
+    pub fn case_folding(mut self, case_folding: CaseFolding) -> LevenshteinAutomatonBuilder {
+        self.case_folding = case_folding;
+        self
+    }
+
+    /// Limits the number of states of the automata built afterwards from the
+    /// precomputed tables, so that untrusted queries cannot use an unbounded
+    /// amount of memory. There is no limit by default.
+    ///
+    /// The construction is aborted as soon as the limit is exceeded:
+    /// the `try_build_*` methods then return
+    /// [Error::StateBudgetExceeded](./enum.Error.html#variant.StateBudgetExceeded),
+    /// and the other methods panic. The automata built on the fly,
+    /// e.g. suffix automata, are not affected.
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::{Error, LevenshteinAutomatonBuilder};
+    /// let builder = LevenshteinAutomatonBuilder::new(2, true).max_num_states(100);
+    /// assert!(builder.try_build_dfa("abc").is_ok());
+    /// let query = "abcdefghijklmnopqrstuvwxyz";
+    /// assert_eq!(
+    ///     builder.try_build_dfa(query).err(),
+    ///     Some(Error::StateBudgetExceeded { max_num_states: 100 })
+    /// );
+    /// ```
+    pub fn max_num_states(mut self, max_num_states: usize) -> LevenshteinAutomatonBuilder {
+        self.max_num_states = Some(max_num_states);
+        self
+    }
+
     /// Builds a Finite Determinstic Automaton to compute
     /// the levenshtein distance to a fixed given `query`.
     ///
@@ -91,7 +400,31 @@ impl LevenshteinAutomatonBuilder {
     ///
     /// For instance for `d=2` and with transposition, `C=68`.
     pub fn build_dfa(&self, query: &str) -> DFA {
-        self.parametric_dfa.build_dfa(query, false)
+        self.parametric_dfa
+            .try_build_dfa_ignoring(query, false, 0, &[], self.case_folding, self.max_num_states)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as [.build_dfa(...)](#method.build_dfa), but returns an error
+    /// instead of panicking if the query is so long that the states of the
+    /// automaton cannot be addressed with `u32` state ids, or if the automaton
+    /// exceeds the [maximum number of states](#method.max_num_states).
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::{Distance, LevenshteinAutomatonBuilder};
+    /// let builder = LevenshteinAutomatonBuilder::new(1, false);
+    /// let dfa = builder.try_build_dfa("abc").unwrap();
+    /// assert_eq!(dfa.eval("abd"), Distance::Exact(1));
+    /// ```
+    pub fn try_build_dfa(&self, query: &str) -> Result<DFA, Error> {
+        self.parametric_dfa.try_build_dfa_ignoring(
+            query,
+            false,
+            0,
+            &[],
+            self.case_folding,
+            self.max_num_states,
+        )
     }
 
     /// Builds a Finite Determinstic Automaton that computes
@@ -107,6 +440,143 @@ impl LevenshteinAutomatonBuilder {
     ///
     /// See also [.build_dfa(...)](./struct.LevenshteinAutomatonBuilder.html#method.build_dfa).
     pub fn build_prefix_dfa(&self, query: &str) -> DFA {
-        self.parametric_dfa.build_dfa(query, true)
+        self.parametric_dfa
+            .try_build_dfa_ignoring(query, true, 0, &[], self.case_folding, self.max_num_states)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as [.build_prefix_dfa(...)](#method.build_prefix_dfa), but returns
+    /// an error instead of panicking if the query is too long.
+    pub fn try_build_prefix_dfa(&self, query: &str) -> Result<DFA, Error> {
+        self.parametric_dfa.try_build_dfa_ignoring(
+            query,
+            true,
+            0,
+            &[],
+            self.case_folding,
+            self.max_num_states,
+        )
+    }
+
+    /// Same as [.build_prefix_dfa(...)](#method.build_prefix_dfa), but the
+    /// distance is always the one of the closest prefix of the text: the chars
+    /// following it are ignored, which makes it suitable for fuzzy autocompletion.
+    ///
+    /// A prefix automaton stops once no later char can decrease the
+    /// distance, which may miss a closer prefix for distances of `2` or more.
+    /// This one tracks the smallest distance of the prefixes in its states,
+    /// so that it may have up to `max_distance + 2` times as many states.
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::{Distance, LevenshteinAutomatonBuilder};
+    /// let dfa = LevenshteinAutomatonBuilder::new(2, true).build_closest_prefix_dfa("abb");
+    /// assert_eq!(dfa.eval("abbey"), Distance::Exact(0));
+    /// // The prefix "b" is closer than "baa".
+    /// assert_eq!(dfa.eval("baa"), Distance::Exact(2));
+    /// ```
+    pub fn build_closest_prefix_dfa(&self, query: &str) -> DFA {
+        self.try_build_closest_prefix_dfa(query)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as [.build_closest_prefix_dfa(...)](#method.build_closest_prefix_dfa),
+    /// but returns an error instead of panicking if the query is too long.
+    pub fn try_build_closest_prefix_dfa(&self, query: &str) -> Result<DFA, Error> {
+        self.parametric_dfa.try_build_closest_prefix_dfa(
+            query,
+            self.case_folding,
+            self.max_num_states,
+        )
+    }
+
+    /// Same as [.build_dfa(...)](#method.build_dfa), but the automaton is
+    /// built in `dfa_builder`, after [resetting](./struct.Utf8DFABuilder.html#method.reset)
+    /// it, and copied out of it.
+    ///
+    /// Reusing the same builder for a stream of queries saves the allocation
+    /// of its buffers, which keep the capacity required by the largest query.
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::{Distance, LevenshteinAutomatonBuilder, Utf8DFABuilder};
+    /// let builder = LevenshteinAutomatonBuilder::new(1, true);
+    /// let mut dfa_builder = Utf8DFABuilder::with_max_num_states(0);
+    /// for query in &["kitten", "sitting", "mitten"] {
+    ///     let dfa = builder.build_dfa_reusing(query, &mut dfa_builder);
+    ///     assert_eq!(dfa.eval(query), Distance::Exact(0));
+    /// }
+    /// ```
+    pub fn build_dfa_reusing(&self, query: &str, dfa_builder: &mut Utf8DFABuilder) -> DFA {
+        self.parametric_dfa
+            .try_build_dfa_reusing(
+                dfa_builder,
+                query,
+                false,
+                self.case_folding,
+                self.max_num_states,
+            )
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as [.build_prefix_dfa(...)](#method.build_prefix_dfa), reusing
+    /// `dfa_builder` as [.build_dfa_reusing(...)](#method.build_dfa_reusing).
+    pub fn build_prefix_dfa_reusing(&self, query: &str, dfa_builder: &mut Utf8DFABuilder) -> DFA {
+        self.parametric_dfa
+            .try_build_dfa_reusing(
+                dfa_builder,
+                query,
+                true,
+                self.case_folding,
+                self.max_num_states,
+            )
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as [.build_dfa(...)](./struct.LevenshteinAutomatonBuilder.html#method.build_dfa),
+    /// but the `ignorable` chars (e.g. whitespaces, hyphens or punctuation)
+    /// are skipped at no cost.
+    ///
+    /// They are removed from the query, and the automaton stays in the same state
+    /// when reading one of them, so that the corpus does not need to be cleaned
+    /// beforehand.
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::{Distance, LevenshteinAutomatonBuilder};
+    /// let builder = LevenshteinAutomatonBuilder::new(1, true);
+    /// let dfa = builder.build_dfa_ignoring("email", &['-', ' ']);
+    /// assert_eq!(dfa.eval("e-mail"), Distance::Exact(0));
+    /// assert_eq!(dfa.eval("e- mial"), Distance::Exact(1));
+    /// let dfa = builder.build_dfa_ignoring("New York", &['-', ' ']);
+    /// assert_eq!(dfa.eval("NewYork"), Distance::Exact(0));
+    /// ```
+    pub fn build_dfa_ignoring(&self, query: &str, ignorable: &[char]) -> DFA {
+        self.parametric_dfa
+            .try_build_dfa_ignoring(
+                query,
+                false,
+                0,
+                ignorable,
+                self.case_folding,
+                self.max_num_states,
+            )
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Builds a Finite Determinstic Automaton that computes
+    /// the suffix levenshtein distance to a given `query`.
+    ///
+    /// Given a test string, the resulting distance is defined as
+    ///
+    /// ```formula
+    ///     min( levenshtein(&test_string[i..], query } for i in 0..test_string.len() )
+    /// ```
+    ///
+    /// Which translates as *the minimum distance of the suffixes of `test_strings`*,
+    /// i.e. the match has to be aligned with the end of the test string.
+    /// This is handy to match file extensions or identifier suffixes.
+    ///
+    /// Unlike the other automata, it does not rely on the precomputed tables of
+    /// the builder: its states are built on the fly, and are more numerous.
+    pub fn build_suffix_dfa(&self, query: &str) -> DFA {
+        suffix::build_suffix_dfa(query, self.max_distance, self.transposition_cost_one)
     }
 }
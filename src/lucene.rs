@@ -0,0 +1,166 @@
+use super::{LevenshteinAutomatonBuilder, DFA};
+
+/// Compatibility layer with Lucene's `LevenshteinAutomata`.
+///
+/// The automata it produces accept exactly the same strings as the
+/// automata of `LevenshteinAutomata.toAutomaton(n, prefix)`:
+///
+/// * distances are computed over code points,
+/// * transpositions, if enabled, are those of the optimal string alignment
+///   distance (Lucene's `Lev1T` and `Lev2T` tables),
+/// * the `prefix` has to match exactly, and edits only apply to the input,
+/// * as in Lucene, `n` cannot exceed 2.
+///
+/// This makes it possible to cross-validate the two implementations, or to
+/// serve the same fuzzy queries from a mixed stack.
+///
+/// ```rust
+/// # use levenshtein_automata::{Distance, LuceneLevenshteinAutomata};
+/// let lucene = LuceneLevenshteinAutomata::new(true);
+/// let dfa = lucene.to_automaton_with_prefix("stein", 1, "leven").unwrap();
+/// assert_eq!(dfa.eval("levenstien"), Distance::Exact(1));
+/// assert_eq!(dfa.eval("lavenstein"), Distance::AtLeast(2));
+/// ```
+pub struct LuceneLevenshteinAutomata {
+    builders: Vec<LevenshteinAutomatonBuilder>,
+}
+
+impl LuceneLevenshteinAutomata {
+    /// Maximum distance supported by Lucene.
+    pub const MAXIMUM_SUPPORTED_DISTANCE: u8 = 2;
+
+    /// Creates the tables for all of the distances supported by Lucene.
+    pub fn new(with_transpositions: bool) -> LuceneLevenshteinAutomata {
+        let builders = (0..=Self::MAXIMUM_SUPPORTED_DISTANCE)
+            .map(|n| LevenshteinAutomatonBuilder::new(n, with_transpositions))
+            .collect();
+        LuceneLevenshteinAutomata { builders }
+    }
+
+    /// Equivalent of `toAutomaton(n)`.
+    ///
+    /// Returns `None` if `n` is not supported.
+    pub fn to_automaton(&self, input: &str, n: u8) -> Option<DFA> {
+        self.to_automaton_with_prefix(input, n, "")
+    }
+
+    /// Equivalent of `toAutomaton(n, prefix)`.
+    ///
+    /// Returns `None` if `n` is not supported.
+    pub fn to_automaton_with_prefix(&self, input: &str, n: u8, prefix: &str) -> Option<DFA> {
+        let builder = self.builders.get(n as usize)?;
+        let query = format!("{}{}", prefix, input);
+        Some(builder.parametric_dfa.build_dfa_with_exact_prefix(
+            &query,
+            false,
+            prefix.chars().count(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LuceneLevenshteinAutomata;
+    use crate::{Distance, ExhaustiveVerifier, DFA};
+
+    fn accepts(dfa: &DFA, text: &str) -> bool {
+        matches!(dfa.eval(text), Distance::Exact(_))
+    }
+
+    // Optimal string alignment distance, over code points.
+    fn osa_distance(left: &str, right: &str, transpositions: bool) -> usize {
+        let left: Vec<char> = left.chars().collect();
+        let right: Vec<char> = right.chars().collect();
+        let width = right.len() + 1;
+        let mut table: Vec<usize> = vec![0; (left.len() + 1) * width];
+        for i in 0..=left.len() {
+            for j in 0..=right.len() {
+                table[i * width + j] = if i == 0 || j == 0 {
+                    i + j
+                } else {
+                    let substitution = usize::from(left[i - 1] != right[j - 1]);
+                    let mut d = (table[(i - 1) * width + j - 1] + substitution)
+                        .min(table[(i - 1) * width + j] + 1)
+                        .min(table[i * width + j - 1] + 1);
+                    if transpositions
+                        && i > 1
+                        && j > 1
+                        && left[i - 1] == right[j - 2]
+                        && left[i - 2] == right[j - 1]
+                    {
+                        d = d.min(table[(i - 2) * width + j - 2] + 1);
+                    }
+                    d
+                };
+            }
+        }
+        table[left.len() * width + right.len()]
+    }
+
+    #[test]
+    fn test_lucene_acceptance() {
+        let candidates: Vec<String> = ExhaustiveVerifier::new("abé", 5, 0, false)
+            .strings()
+            .collect();
+        for &transpositions in &[false, true] {
+            let lucene = LuceneLevenshteinAutomata::new(transpositions);
+            for input in &["", "ab", "aéb", "abba"] {
+                for n in 0..=2u8 {
+                    let dfa = lucene.to_automaton(input, n).unwrap();
+                    for candidate in &candidates {
+                        let expected = osa_distance(input, candidate, transpositions) <= n as usize;
+                        assert_eq!(accepts(&dfa, candidate), expected);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lucene_prefix() {
+        let lucene = LuceneLevenshteinAutomata::new(false);
+        let dfa = lucene.to_automaton_with_prefix("ba", 1, "ab").unwrap();
+        assert!(accepts(&dfa, "abba"));
+        assert!(accepts(&dfa, "abb"));
+        assert!(accepts(&dfa, "abbab"));
+        assert!(!accepts(&dfa, "baba"));
+        assert!(!accepts(&dfa, "a"));
+        assert!(lucene.to_automaton("abc", 3).is_none());
+        let dfa = lucene.to_automaton_with_prefix("c", 2, "ab").unwrap();
+        assert!(!accepts(&dfa, "a"));
+        assert!(accepts(&dfa, "ab"));
+        let dfa = lucene.to_automaton_with_prefix("", 2, "ab").unwrap();
+        assert!(!accepts(&dfa, ""));
+        assert!(!accepts(&dfa, "a"));
+        assert!(accepts(&dfa, "ab"));
+    }
+
+    #[test]
+    fn test_lucene_short_prefix_acceptance() {
+        let candidates: Vec<String> = ExhaustiveVerifier::new("abé", 5, 0, false)
+            .strings()
+            .collect();
+        let lucene = LuceneLevenshteinAutomata::new(true);
+        for prefix in &["a", "ab", "éa"] {
+            for input in &["", "b", "ab"] {
+                for n in 0..=2u8 {
+                    let dfa = lucene.to_automaton_with_prefix(input, n, prefix).unwrap();
+                    for candidate in &candidates {
+                        let expected = match candidate.strip_prefix(prefix) {
+                            Some(rest) => osa_distance(input, rest, true) <= n as usize,
+                            None => false,
+                        };
+                        assert_eq!(
+                            accepts(&dfa, candidate),
+                            expected,
+                            "{} {} {}",
+                            prefix,
+                            input,
+                            candidate
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
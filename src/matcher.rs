@@ -0,0 +1,180 @@
+use super::{
+    ConcurrentLazyDfa, Distance, LevenshteinAutomatonBuilder, LevenshteinNfa, MyersEvaluator, DFA,
+};
+
+/// Engine used by a [Matcher](./struct.Matcher.html) to compute distances.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Strategy {
+    /// A [DFA](./struct.DFA.html) built up front: the fastest evaluation,
+    /// but the number of states grows with the query and exponentially
+    /// with the distance.
+    Dfa,
+    /// A [ConcurrentLazyDfa](./struct.ConcurrentLazyDfa.html), only
+    /// determinizing the states reached by the texts.
+    LazyDfa,
+    /// A [LevenshteinNfa](./struct.LevenshteinNfa.html) simulation, whose
+    /// memory only grows with the query.
+    Nfa,
+    /// A [MyersEvaluator](./struct.MyersEvaluator.html), whose cost per
+    /// char grows with the query but not with the distance.
+    BitParallel,
+}
+
+enum Engine {
+    Dfa(DFA),
+    LazyDfa(ConcurrentLazyDfa),
+    Nfa(LevenshteinNfa),
+    BitParallel(MyersEvaluator),
+}
+
+/// Computes the distance to a query with the engine best suited to its
+/// length and to the maximum distance.
+///
+/// [Matcher::new](#method.new) picks the [Strategy](./enum.Strategy.html):
+/// - a [DFA](./struct.DFA.html) for distances up to `2` and queries of up to
+///   64 chars,
+/// - a lazy DFA for distances up to `4` and queries of up to 256 chars,
+/// - an NFA simulation for distances up to `4` and longer queries,
+/// - the bit-parallel algorithm of Myers for larger distances.
+///
+/// All of the strategies compute the same distances.
+///
+/// ```rust
+/// # use levenshtein_automata::{Distance, Matcher, Strategy};
+/// let matcher = Matcher::new("kitten", 1, true);
+/// assert_eq!(matcher.strategy(), Strategy::Dfa);
+/// assert_eq!(matcher.eval("sitten"), Distance::Exact(1));
+///
+/// let matcher = Matcher::new("kitten", 8, true);
+/// assert_eq!(matcher.strategy(), Strategy::BitParallel);
+/// assert_eq!(matcher.eval("sitting"), Distance::Exact(3));
+/// ```
+pub struct Matcher {
+    engine: Engine,
+}
+
+impl Matcher {
+    /// Creates the matcher of `query`, up to `max_distance`, choosing the
+    /// strategy from the length of the query and the distance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_distance` is `255`.
+    pub fn new(query: &str, max_distance: u8, transposition_cost_one: bool) -> Matcher {
+        let strategy = Matcher::choose_strategy(query.chars().count(), max_distance);
+        Matcher::with_strategy(query, max_distance, transposition_cost_one, strategy)
+    }
+
+    /// Creates the matcher of `query`, up to `max_distance`, with the given
+    /// strategy.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the engine of the strategy does not support `max_distance`,
+    /// as documented by its constructor.
+    pub fn with_strategy(
+        query: &str,
+        max_distance: u8,
+        transposition_cost_one: bool,
+        strategy: Strategy,
+    ) -> Matcher {
+        let engine = match strategy {
+            Strategy::Dfa => Engine::Dfa(
+                LevenshteinAutomatonBuilder::new(max_distance, transposition_cost_one)
+                    .build_dfa(query),
+            ),
+            Strategy::LazyDfa => Engine::LazyDfa(ConcurrentLazyDfa::new(
+                query,
+                max_distance,
+                transposition_cost_one,
+            )),
+            Strategy::Nfa => Engine::Nfa(LevenshteinNfa::new(
+                query,
+                max_distance,
+                transposition_cost_one,
+            )),
+            Strategy::BitParallel => Engine::BitParallel(MyersEvaluator::new(
+                query,
+                max_distance,
+                transposition_cost_one,
+            )),
+        };
+        Matcher { engine }
+    }
+
+    /// Returns the strategy [Matcher::new](#method.new) picks for a query
+    /// of `num_chars` chars.
+    pub fn choose_strategy(num_chars: usize, max_distance: u8) -> Strategy {
+        match max_distance {
+            0..=2 if num_chars <= 64 => Strategy::Dfa,
+            0..=4 if num_chars <= 256 => Strategy::LazyDfa,
+            0..=4 => Strategy::Nfa,
+            _ => Strategy::BitParallel,
+        }
+    }
+
+    /// Returns the strategy of the matcher.
+    pub fn strategy(&self) -> Strategy {
+        match self.engine {
+            Engine::Dfa(_) => Strategy::Dfa,
+            Engine::LazyDfa(_) => Strategy::LazyDfa,
+            Engine::Nfa(_) => Strategy::Nfa,
+            Engine::BitParallel(_) => Strategy::BitParallel,
+        }
+    }
+
+    /// Returns the distance between the query and `text`.
+    pub fn eval(&self, text: &str) -> Distance {
+        match &self.engine {
+            Engine::Dfa(dfa) => dfa.eval(text),
+            Engine::LazyDfa(dfa) => dfa.eval(text),
+            Engine::Nfa(nfa) => nfa.eval(text),
+            Engine::BitParallel(evaluator) => evaluator.eval(text),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Matcher, Strategy};
+    use crate::damerau_levenshtein_within;
+
+    #[test]
+    fn test_choose_strategy() {
+        assert_eq!(Matcher::choose_strategy(10, 1), Strategy::Dfa);
+        assert_eq!(Matcher::choose_strategy(100, 2), Strategy::LazyDfa);
+        assert_eq!(Matcher::choose_strategy(10, 3), Strategy::LazyDfa);
+        assert_eq!(Matcher::choose_strategy(1000, 3), Strategy::Nfa);
+        assert_eq!(Matcher::choose_strategy(10, 5), Strategy::BitParallel);
+        assert_eq!(Matcher::choose_strategy(1000, 30), Strategy::BitParallel);
+    }
+
+    #[test]
+    fn test_strategies_agree() {
+        let strategies = [
+            Strategy::Dfa,
+            Strategy::LazyDfa,
+            Strategy::Nfa,
+            Strategy::BitParallel,
+        ];
+        let texts = ["", "abc", "acb", "寿司abc", "abcabc", "xyz", "bca"];
+        for max_distance in 0..=2u8 {
+            for query in &["", "abc", "寿司", "abcab"] {
+                for &strategy in &strategies {
+                    let matcher = Matcher::with_strategy(query, max_distance, true, strategy);
+                    assert_eq!(matcher.strategy(), strategy);
+                    for text in &texts {
+                        assert_eq!(
+                            matcher.eval(text),
+                            damerau_levenshtein_within(query, text, max_distance),
+                            "{:?} {} {}",
+                            strategy,
+                            query,
+                            text
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use super::{Distance, LevenshteinAutomatonBuilder};
+use std::collections::HashMap;
+
+/// Near-duplicate candidate generation with MinHash signatures
+/// and locality-sensitive hashing (LSH).
+///
+/// Each string is represented by the set of its shingles (substrings of
+/// `shingle_len` chars). Its signature keeps, for each of
+/// `num_bands * rows_per_band` hash functions, the minimum hash of its shingles.
+/// Signatures are split into bands, and strings sharing a band are candidates.
+/// Candidates are then verified exactly with a Levenshtein automaton.
+///
+/// Unlike [TrigramIndex](./struct.TrigramIndex.html),
+/// candidate generation is probabilistic: a string within the distance may be
+/// missed if its shingles are too different from those of the query.
+/// The probability for two strings of Jaccard similarity `s` to share a band
+/// is `1 - (1 - s^rows_per_band)^num_bands`. More bands increase the recall,
+/// more rows per band increase the precision.
+///
+/// ```rust
+/// # use levenshtein_automata::{LevenshteinAutomatonBuilder, MinHashLsh};
+/// let mut lsh = MinHashLsh::new(3, 16, 2);
+/// for title in &["the quick brown fox", "a lazy dog", "the quick brown fix"] {
+///     lsh.insert(title);
+/// }
+/// let builder = LevenshteinAutomatonBuilder::new(1, false);
+/// assert_eq!(lsh.near_duplicates(&builder), vec![(0, 2, 1)]);
+/// ```
+pub struct MinHashLsh {
+    shingle_len: usize,
+    num_bands: usize,
+    rows_per_band: usize,
+    strings: Vec<String>,
+    // Ids of the strings, indexed by band and band hash.
+    buckets: Vec<HashMap<u64, Vec<u32>>>,
+}
+
+impl MinHashLsh {
+    /// Creates an empty index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the parameters is `0`.
+    pub fn new(shingle_len: usize, num_bands: usize, rows_per_band: usize) -> MinHashLsh {
+        assert!(
+            shingle_len > 0 && num_bands > 0 && rows_per_band > 0,
+            "MinHash parameters should be positive"
+        );
+        MinHashLsh {
+            shingle_len,
+            num_bands,
+            rows_per_band,
+            strings: Vec::new(),
+            buckets: vec![HashMap::new(); num_bands],
+        }
+    }
+
+    /// Returns the number of strings in the index.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Returns true if the index is empty.
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    /// Returns the string associated to an id.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id >= self.len()`.
+    pub fn string(&self, id: u32) -> &str {
+        &self.strings[id as usize]
+    }
+
+    /// Adds a string to the index, and returns its id.
+    ///
+    /// Ids are assigned in insertion order, starting from `0`.
+    pub fn insert(&mut self, string: &str) -> u32 {
+        let id = self.strings.len() as u32;
+        let band_hashes = self.band_hashes(string);
+        for (buckets, band_hash) in self.buckets.iter_mut().zip(band_hashes) {
+            buckets.entry(band_hash).or_default().push(id);
+        }
+        self.strings.push(string.to_string());
+        id
+    }
+
+    /// Returns the MinHash signature of a string.
+    ///
+    /// The signature of the empty string is made of `u64::MAX`.
+    pub fn signature(&self, string: &str) -> Vec<u64> {
+        let chars: Vec<char> = string.chars().collect();
+        let shingle_hashes: Vec<u64> = if chars.len() <= self.shingle_len {
+            if chars.is_empty() {
+                Vec::new()
+            } else {
+                vec![hash_chars(&chars)]
+            }
+        } else {
+            chars.windows(self.shingle_len).map(hash_chars).collect()
+        };
+        (0..(self.num_bands * self.rows_per_band) as u64)
+            .map(|seed| {
+                shingle_hashes
+                    .iter()
+                    .map(|&shingle_hash| mix64(shingle_hash ^ mix64(seed)))
+                    .min()
+                    .unwrap_or(u64::MAX)
+            })
+            .collect()
+    }
+
+    fn band_hashes(&self, string: &str) -> Vec<u64> {
+        self.signature(string)
+            .chunks(self.rows_per_band)
+            .map(|band| band.iter().fold(0u64, |hash, &row| mix64(hash ^ row)))
+            .collect()
+    }
+
+    /// Returns the ids of the strings sharing at least one band with `query`, sorted.
+    pub fn candidates(&self, query: &str) -> Vec<u32> {
+        let mut candidates: Vec<u32> = self
+            .buckets
+            .iter()
+            .zip(self.band_hashes(query))
+            .filter_map(|(buckets, band_hash)| buckets.get(&band_hash))
+            .flatten()
+            .cloned()
+            .collect();
+        candidates.sort_unstable();
+        candidates.dedup();
+        candidates
+    }
+
+    /// Returns the candidates within the distance of `query` accepted by
+    /// `builder`, along with their distance, sorted by id.
+    pub fn lookup(&self, builder: &LevenshteinAutomatonBuilder, query: &str) -> Vec<(u32, u8)> {
+        let dfa = builder.build_dfa(query);
+        self.candidates(query)
+            .into_iter()
+            .filter_map(|id| match dfa.eval(self.string(id)) {
+                Distance::Exact(d) => Some((id, d)),
+                Distance::AtLeast(_) => None,
+            })
+            .collect()
+    }
+
+    /// Returns the `(id, other_id, distance)` of the pairs of strings sharing
+    /// a band and within the distance accepted by `builder`, with `id < other_id`.
+    ///
+    /// Pairs are sorted.
+    pub fn near_duplicates(&self, builder: &LevenshteinAutomatonBuilder) -> Vec<(u32, u32, u8)> {
+        let mut pairs = Vec::new();
+        for (id, string) in self.strings.iter().enumerate() {
+            let id = id as u32;
+            for (other_id, d) in self.lookup(builder, string) {
+                if id < other_id {
+                    pairs.push((id, other_id, d));
+                }
+            }
+        }
+        pairs
+    }
+}
+
+// FNV-1a, over the code points of the shingle.
+fn hash_chars(chars: &[char]) -> u64 {
+    chars.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &c| {
+        (hash ^ c as u64).wrapping_mul(0x100_0000_01b3)
+    })
+}
+
+// Finalizer of SplitMix64.
+fn mix64(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MinHashLsh;
+    use crate::LevenshteinAutomatonBuilder;
+
+    #[test]
+    fn test_minhash_lsh() {
+        let mut lsh = MinHashLsh::new(2, 32, 1);
+        let strings = [
+            "levenshtein automaton",
+            "levenshtein automata",
+            "deterministic finite automaton",
+            "levenshtein automaton",
+            "",
+            "寿司屋",
+            "寿司家",
+        ];
+        for (id, string) in strings.iter().enumerate() {
+            assert_eq!(lsh.insert(string), id as u32);
+        }
+        assert_eq!(lsh.len(), strings.len());
+        assert_eq!(lsh.signature("abc"), lsh.signature("abc"));
+        assert_eq!(lsh.signature("abab"), lsh.signature("ababab"));
+        assert!(lsh.signature("").iter().all(|&h| h == u64::MAX));
+        let builder = LevenshteinAutomatonBuilder::new(2, false);
+        // Exact duplicates always share all of their bands.
+        assert!(lsh.candidates("levenshtein automaton").contains(&3));
+        assert_eq!(
+            lsh.lookup(&builder, "levenshtein automaton"),
+            vec![(0, 0), (1, 2), (3, 0)]
+        );
+        assert_eq!(lsh.lookup(&builder, ""), vec![(4, 0)]);
+        assert_eq!(
+            lsh.near_duplicates(&builder),
+            vec![(0, 1, 2), (0, 3, 0), (1, 3, 2), (5, 6, 1)]
+        );
+    }
+}
@@ -0,0 +1,241 @@
+use super::product::{product, union_distance};
+use super::{Distance, LevenshteinAutomatonBuilder, DFA};
+
+/// Builds a single DFA matching several queries at once,
+/// each query with its own maximum distance.
+///
+/// Evaluating the union automaton costs one pass over the candidate,
+/// instead of one pass per query.
+///
+/// ```rust
+/// # use levenshtein_automata::{Distance, MultiPatternBuilder};
+/// let builder = MultiPatternBuilder::new(2, true);
+/// let dfa = builder.build_dfa(&[("cat", 1), ("elephant", 2)]);
+/// assert_eq!(dfa.eval("cat"), Distance::Exact(0));
+/// assert_eq!(dfa.eval("cart"), Distance::Exact(1));
+/// assert_eq!(dfa.eval("elepant"), Distance::Exact(1));
+/// assert_eq!(dfa.eval("olephamt"), Distance::Exact(2));
+/// assert_eq!(dfa.eval("dog"), Distance::AtLeast(2));
+/// ```
+pub struct MultiPatternBuilder {
+    builders: Vec<LevenshteinAutomatonBuilder>,
+}
+
+impl MultiPatternBuilder {
+    /// Creates a builder for queries with a maximum distance of up to
+    /// `max_distance`.
+    ///
+    /// Building the builder is as expensive as building a
+    /// [LevenshteinAutomatonBuilder] for each distance up to `max_distance`,
+    /// so it should be reused.
+    pub fn new(max_distance: u8, transposition_cost_one: bool) -> MultiPatternBuilder {
+        MultiPatternBuilder {
+            builders: (0..=max_distance)
+                .map(|distance| LevenshteinAutomatonBuilder::new(distance, transposition_cost_one))
+                .collect(),
+        }
+    }
+
+    /// Returns the maximum distance supported by this builder.
+    pub fn max_distance(&self) -> u8 {
+        (self.builders.len() - 1) as u8
+    }
+
+    /// Builds the DFA accepting the strings within the given distance of
+    /// any of the `(query, max_distance)` patterns.
+    ///
+    /// A string within the distance of several queries is evaluated to
+    /// its smallest distance to them. The distance of a rejected string is a
+    /// lower bound of its distance to the queries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the distance of a pattern exceeds the maximum distance of
+    /// the builder.
+    pub fn build_dfa(&self, patterns: &[(&str, u8)]) -> DFA {
+        self.build(patterns).0
+    }
+
+    /// Builds the automaton accepting the same strings as
+    /// [build_dfa](#method.build_dfa), which also reports the patterns
+    /// each string matches.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the distance of a pattern exceeds the maximum distance of
+    /// the builder.
+    pub fn build_multi_dfa(&self, patterns: &[(&str, u8)]) -> MultiPatternDfa {
+        let (dfa, matches) = self.build(patterns);
+        MultiPatternDfa { dfa, matches }
+    }
+
+    fn build(&self, patterns: &[(&str, u8)]) -> (DFA, Vec<Vec<(PatternId, Distance)>>) {
+        let dfas: Vec<DFA> = patterns
+            .iter()
+            .map(|&(query, max_distance)| {
+                assert!(
+                    max_distance <= self.max_distance(),
+                    "the distance {} of {:?} exceeds the maximum distance {}",
+                    max_distance,
+                    query,
+                    self.max_distance()
+                );
+                self.builders[max_distance as usize].build_dfa(query)
+            })
+            .collect();
+        let dfas: Vec<&DFA> = dfas.iter().collect();
+        let (dfa, tuples) = product(
+            &dfas,
+            |states| union_distance(&dfas, states),
+            |states| {
+                dfas.iter()
+                    .zip(states)
+                    .all(|(dfa, &state)| !dfa.can_match(state))
+            },
+        );
+        let matches = tuples
+            .iter()
+            .map(|states| {
+                dfas.iter()
+                    .zip(states)
+                    .enumerate()
+                    .filter_map(|(id, (dfa, &state))| match dfa.distance(state) {
+                        distance @ Distance::Exact(_) => Some((PatternId(id), distance)),
+                        Distance::AtLeast(_) => None,
+                    })
+                    .collect()
+            })
+            .collect();
+        (dfa, matches)
+    }
+}
+
+/// Identifies a pattern of a [MultiPatternDfa], by its index in the
+/// patterns given to the builder.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct PatternId(pub usize);
+
+/// Union automaton of several patterns, built with
+/// [MultiPatternBuilder::build_multi_dfa], that keeps track of the
+/// patterns matched by each state.
+///
+/// ```rust
+/// # use levenshtein_automata::{Distance, MultiPatternBuilder, PatternId};
+/// let builder = MultiPatternBuilder::new(2, true);
+/// let dfa = builder.build_multi_dfa(&[("cat", 1), ("cart", 0), ("chart", 2)]);
+/// assert_eq!(
+///     dfa.eval_multi("cart"),
+///     vec![
+///         (PatternId(0), Distance::Exact(1)),
+///         (PatternId(1), Distance::Exact(0)),
+///         (PatternId(2), Distance::Exact(1)),
+///     ]
+/// );
+/// assert_eq!(dfa.eval_multi("dog"), vec![]);
+/// ```
+pub struct MultiPatternDfa {
+    dfa: DFA,
+    // Patterns matched by each state, sorted by id.
+    matches: Vec<Vec<(PatternId, Distance)>>,
+}
+
+impl MultiPatternDfa {
+    /// Returns the union automaton, evaluating strings to their smallest
+    /// distance to the patterns.
+    pub fn dfa(&self) -> &DFA {
+        &self.dfa
+    }
+
+    /// Returns the patterns matched by a state of the automaton,
+    /// along with their distance, sorted by id.
+    pub fn patterns(&self, state_id: u32) -> &[(PatternId, Distance)] {
+        &self.matches[state_id as usize]
+    }
+
+    /// Returns the patterns matching `text`, along with their distance,
+    /// sorted by id.
+    pub fn eval_multi<B: AsRef<[u8]>>(&self, text: B) -> Vec<(PatternId, Distance)> {
+        let mut state = self.dfa.initial_state();
+        for &b in text.as_ref() {
+            state = self.dfa.transition(state, b);
+        }
+        self.patterns(state).to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MultiPatternBuilder, PatternId};
+    use crate::{Distance, LevenshteinAutomatonBuilder};
+
+    #[test]
+    fn test_multi_pattern_matches_each_pattern() {
+        let patterns = [("abc", 1), ("abd", 0), ("寿司", 2), ("", 1), ("xyzzy", 2)];
+        let builder = MultiPatternBuilder::new(2, true);
+        let dfa = builder.build_dfa(&patterns);
+        let dfas: Vec<_> = patterns
+            .iter()
+            .map(|&(query, d)| LevenshteinAutomatonBuilder::new(d, true).build_dfa(query))
+            .collect();
+        for candidate in &[
+            "",
+            "a",
+            "ab",
+            "abc",
+            "abd",
+            "bacd",
+            "寿",
+            "寿司",
+            "寿司屋",
+            "xyzy",
+            "xzyzy",
+            "zzz",
+            "abcdef",
+        ] {
+            let expected = dfas
+                .iter()
+                .filter_map(|dfa| match dfa.eval(candidate) {
+                    Distance::Exact(d) => Some(d),
+                    Distance::AtLeast(_) => None,
+                })
+                .min();
+            match expected {
+                Some(d) => assert_eq!(dfa.eval(candidate), Distance::Exact(d), "{}", candidate),
+                None => assert!(
+                    matches!(dfa.eval(candidate), Distance::AtLeast(_)),
+                    "{}",
+                    candidate
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn test_multi_pattern_empty() {
+        let dfa = MultiPatternBuilder::new(1, false).build_dfa(&[]);
+        assert_eq!(dfa.eval(""), Distance::AtLeast(0));
+        assert_eq!(dfa.eval("abc"), Distance::AtLeast(0));
+    }
+
+    #[test]
+    fn test_multi_pattern_ids() {
+        let patterns = [("abc", 1), ("abd", 0), ("寿司", 2), ("", 1), ("abc", 2)];
+        let builder = MultiPatternBuilder::new(2, false);
+        let dfa = builder.build_multi_dfa(&patterns);
+        let dfas: Vec<_> = patterns
+            .iter()
+            .map(|&(query, d)| LevenshteinAutomatonBuilder::new(d, false).build_dfa(query))
+            .collect();
+        for candidate in &["", "a", "abc", "abd", "bad", "寿", "寿司屋", "abcde"] {
+            let expected: Vec<(PatternId, Distance)> = dfas
+                .iter()
+                .enumerate()
+                .filter_map(|(id, dfa)| match dfa.eval(candidate) {
+                    distance @ Distance::Exact(_) => Some((PatternId(id), distance)),
+                    Distance::AtLeast(_) => None,
+                })
+                .collect();
+            assert_eq!(dfa.eval_multi(candidate), expected, "{}", candidate);
+        }
+    }
+}
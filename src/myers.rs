@@ -0,0 +1,218 @@
+use super::Distance;
+use std::collections::HashMap;
+
+/// Evaluator of the distance to a query with the bit-parallel algorithm of
+/// Myers, for queries too long or distances too large to build automata.
+///
+/// Nothing depends on the distance: the query is split into blocks of 64
+/// chars, and each char of the text costs a handful of operations per
+/// block, whatever the maximum distance. It computes the same distances as
+/// the automata of a [LevenshteinAutomatonBuilder](./struct.LevenshteinAutomatonBuilder.html)
+/// with the same parameters, transpositions being handled as in the
+/// *optimal string alignment* distance.
+///
+/// ```rust
+/// # use levenshtein_automata::{Distance, MyersEvaluator};
+/// let query = "It was the best of times, it was the worst of times, it was the age of wisdom";
+/// let evaluator = MyersEvaluator::new(query, 8, true);
+/// let text = "It was the best of times, it was the worts of times, it was teh age of wisdom";
+/// assert_eq!(evaluator.eval(query), Distance::Exact(0));
+/// assert_eq!(evaluator.eval(text), Distance::Exact(2));
+/// assert_eq!(evaluator.eval("It was the best of times"), Distance::AtLeast(9));
+/// ```
+pub struct MyersEvaluator {
+    num_chars: usize,
+    max_distance: u8,
+    transposition_cost_one: bool,
+    // Bit `i % 64` of block `i / 64` is set iff the `i`-th char of the
+    // query is the char.
+    masks: HashMap<char, Vec<u64>>,
+}
+
+impl MyersEvaluator {
+    /// Creates the evaluator of the distance to `query`, up to `max_distance`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_distance` is `255`.
+    pub fn new(query: &str, max_distance: u8, transposition_cost_one: bool) -> MyersEvaluator {
+        assert!(
+            max_distance < u8::MAX,
+            "The maximum distance should be at most 254"
+        );
+        let num_chars = query.chars().count();
+        let num_blocks = num_chars.div_ceil(64);
+        let mut masks: HashMap<char, Vec<u64>> = HashMap::new();
+        for (i, chr) in query.chars().enumerate() {
+            masks.entry(chr).or_insert_with(|| vec![0u64; num_blocks])[i / 64] |= 1u64 << (i % 64);
+        }
+        MyersEvaluator {
+            num_chars,
+            max_distance,
+            transposition_cost_one,
+            masks,
+        }
+    }
+
+    /// Returns the maximum distance computed by the evaluator.
+    pub fn max_distance(&self) -> u8 {
+        self.max_distance
+    }
+
+    /// Returns the distance between the query and `text`.
+    ///
+    /// The evaluation stops as soon as the distance is known to exceed
+    /// `max_distance`.
+    pub fn eval(&self, text: &str) -> Distance {
+        let rejected = Distance::AtLeast(self.max_distance + 1);
+        let max_distance = self.max_distance as usize;
+        let text_len = text.chars().count();
+        if self.num_chars.abs_diff(text_len) > max_distance {
+            return rejected;
+        }
+        if self.num_chars == 0 {
+            return Distance::Exact(text_len as u8);
+        }
+        let num_blocks = self.num_chars.div_ceil(64);
+        // The distance is read on the bit of the last char of the query.
+        let last_bit = 1u64 << ((self.num_chars - 1) % 64);
+        let no_match = vec![0u64; num_blocks];
+        // Vertical deltas of the current column: `positive[i]` and
+        // `negative[i]` mark the rows whose distance is one more, or one
+        // less, than the distance of the row above.
+        let mut positive = vec![u64::MAX; num_blocks];
+        let mut negative = vec![0u64; num_blocks];
+        // Diagonal zeros and matches of the previous column, for transpositions.
+        let mut previous_zeros = vec![u64::MAX; num_blocks];
+        let mut previous_matches: &[u64] = &no_match;
+        let mut score = self.num_chars;
+        for (j, chr) in text.chars().enumerate() {
+            let matches: &[u64] = self.masks.get(&chr).unwrap_or(&no_match);
+            // Horizontal delta entering the block, +1 for the first row.
+            let mut carry_in: i8 = 1;
+            let mut transposition_carry = 0u64;
+            for block in 0..num_blocks {
+                let (vp, vn) = (positive[block], negative[block]);
+                let mut eq = matches[block];
+                let mut transpositions = 0u64;
+                if self.transposition_cost_one {
+                    let candidates = !previous_zeros[block] & eq;
+                    transpositions =
+                        ((candidates << 1) | transposition_carry) & previous_matches[block];
+                    transposition_carry = candidates >> 63;
+                }
+                if carry_in < 0 {
+                    eq |= 1;
+                }
+                let zeros = (((eq & vp).wrapping_add(vp)) ^ vp) | eq | vn | transpositions;
+                let mut hp = vn | !(zeros | vp);
+                let mut hn = vp & zeros;
+                let out_bit = if block == num_blocks - 1 {
+                    last_bit
+                } else {
+                    1u64 << 63
+                };
+                let carry_out: i8 = if hp & out_bit != 0 {
+                    1
+                } else if hn & out_bit != 0 {
+                    -1
+                } else {
+                    0
+                };
+                hp = (hp << 1) | u64::from(carry_in > 0);
+                hn = (hn << 1) | u64::from(carry_in < 0);
+                positive[block] = hn | !(zeros | hp);
+                negative[block] = hp & zeros;
+                previous_zeros[block] = zeros;
+                carry_in = carry_out;
+            }
+            previous_matches = matches;
+            score = (score as isize + carry_in as isize) as usize;
+            // The distance decreases by at most one per remaining char.
+            if score > max_distance + (text_len - j - 1) {
+                return rejected;
+            }
+        }
+        if score <= max_distance {
+            Distance::Exact(score as u8)
+        } else {
+            rejected
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MyersEvaluator;
+    use crate::{
+        damerau_levenshtein, damerau_levenshtein_within, levenshtein, levenshtein_within, Distance,
+        ExhaustiveVerifier,
+    };
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn test_myers_short_queries() {
+        let texts: Vec<String> = ExhaustiveVerifier::new("abé", 5, 0, false)
+            .strings()
+            .collect();
+        for max_distance in 0..=3u8 {
+            for query in &["", "a", "abé", "abéé", "ébab"] {
+                let evaluator = MyersEvaluator::new(query, max_distance, false);
+                let damerau_evaluator = MyersEvaluator::new(query, max_distance, true);
+                for text in &texts {
+                    assert_eq!(
+                        evaluator.eval(text),
+                        levenshtein_within(query, text, max_distance)
+                    );
+                    assert_eq!(
+                        damerau_evaluator.eval(text),
+                        damerau_levenshtein_within(query, text, max_distance)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_myers_long_queries() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let alphabet: Vec<char> = "abcé".chars().collect();
+        for _ in 0..300 {
+            let len = rng.gen_range(50..200);
+            let query: String = (0..len)
+                .map(|_| alphabet[rng.gen_range(0..alphabet.len())])
+                .collect();
+            let mut text: Vec<char> = query.chars().collect();
+            for _ in 0..rng.gen_range(0..20) {
+                let i = rng.gen_range(0..text.len());
+                match rng.gen_range(0..4) {
+                    0 => {
+                        text.remove(i);
+                    }
+                    1 => text.insert(i, alphabet[rng.gen_range(0..alphabet.len())]),
+                    2 => text[i] = alphabet[rng.gen_range(0..alphabet.len())],
+                    _ if i + 1 < text.len() => text.swap(i, i + 1),
+                    _ => {}
+                }
+            }
+            let text: String = text.into_iter().collect();
+            for &transposition_cost_one in &[false, true] {
+                let expected = if transposition_cost_one {
+                    damerau_levenshtein(&query, &text)
+                } else {
+                    levenshtein(&query, &text)
+                };
+                let evaluator = MyersEvaluator::new(&query, 254, transposition_cost_one);
+                assert_eq!(evaluator.eval(&text), Distance::Exact(expected as u8));
+                let evaluator = MyersEvaluator::new(&query, 5, transposition_cost_one);
+                let expected = if expected <= 5 {
+                    Distance::Exact(expected as u8)
+                } else {
+                    Distance::AtLeast(6)
+                };
+                assert_eq!(evaluator.eval(&text), expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use super::{Distance, LevenshteinAutomatonBuilder, DFA};
+use std::mem;
+
+/// Largest number of states of a [NarrowDfa].
+pub const MAX_NARROW_NUM_STATES: usize = 1 << 16;
+
+/// Copy of a [DFA](./struct.DFA.html) with `u16` state ids, which halves the
+/// size of the transition table.
+///
+/// It can only represent automata with at most
+/// [MAX_NARROW_NUM_STATES](./constant.MAX_NARROW_NUM_STATES.html) states,
+/// which is the case of most single-query automata. State ids are left
+/// untouched, and exposed as `u32` like in the other representations.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NarrowDfa {
+    // Flat transition table, as in `DFA`.
+    transitions: Vec<u16>,
+    distances: Vec<Distance>,
+    initial_state: u16,
+}
+
+impl DFA {
+    /// Builds the [NarrowDfa](./struct.NarrowDfa.html) equivalent to the automaton,
+    /// or returns `None` if it has too many states.
+    pub fn to_narrow(&self) -> Option<NarrowDfa> {
+        if self.num_states() > MAX_NARROW_NUM_STATES {
+            return None;
+        }
+        Some(NarrowDfa {
+            transitions: self
+                .states()
+                .flat_map(|state| (0..=255u8).map(move |b| self.transition(state, b) as u16))
+                .collect(),
+            distances: self.states().map(|state| self.distance(state)).collect(),
+            initial_state: self.initial_state() as u16,
+        })
+    }
+
+    /// Converts the automaton into a [CompactDfa](./enum.CompactDfa.html),
+    /// using `u16` state ids if it is small enough.
+    pub fn into_compact(self) -> CompactDfa {
+        match self.to_narrow() {
+            Some(narrow) => CompactDfa::Narrow(narrow),
+            None => CompactDfa::Wide(self),
+        }
+    }
+}
+
+impl NarrowDfa {
+    /// Returns the initial state
+    pub fn initial_state(&self) -> u32 {
+        u32::from(self.initial_state)
+    }
+
+    /// Returns the number of states.
+    pub fn num_states(&self) -> usize {
+        self.distances.len()
+    }
+
+    /// Returns the Levenshtein distance associated to the
+    /// current state.
+    pub fn distance(&self, state_id: u32) -> Distance {
+        self.distances[state_id as usize]
+    }
+
+    /// Returns the destination state reached after consuming a given byte.
+    pub fn transition(&self, from_state_id: u32, b: u8) -> u32 {
+        u32::from(self.transitions[from_state_id as usize * 256 + b as usize])
+    }
+
+    /// Returns the Levenshtein distance computed by the automaton on `text`.
+    pub fn eval<B: AsRef<[u8]>>(&self, text: B) -> Distance {
+        let mut state = self.initial_state as usize;
+        for &b in text.as_ref() {
+            state = self.transitions[state * 256 + b as usize] as usize;
+        }
+        self.distances[state]
+    }
+
+    /// Returns the number of bytes allocated on the heap by the tables.
+    pub fn heap_size(&self) -> usize {
+        self.transitions.capacity() * mem::size_of::<u16>()
+            + self.distances.capacity() * mem::size_of::<Distance>()
+    }
+}
+
+/// Automaton whose state id width is picked according to its size.
+///
+/// ```rust
+/// # use levenshtein_automata::{CompactDfa, Distance, LevenshteinAutomatonBuilder};
+/// let builder = LevenshteinAutomatonBuilder::new(2, true);
+/// let dfa = builder.build_compact_dfa("Levenshtein");
+/// assert!(matches!(dfa, CompactDfa::Narrow(_)));
+/// assert_eq!(dfa.eval("Levenshtain"), Distance::Exact(1));
+/// ```
+pub enum CompactDfa {
+    /// Automaton with `u16` state ids.
+    Narrow(NarrowDfa),
+    /// Automaton with too many states for `u16` state ids.
+    Wide(DFA),
+}
+
+impl CompactDfa {
+    /// Returns the initial state
+    pub fn initial_state(&self) -> u32 {
+        match self {
+            CompactDfa::Narrow(dfa) => dfa.initial_state(),
+            CompactDfa::Wide(dfa) => dfa.initial_state(),
+        }
+    }
+
+    /// Returns the number of states.
+    pub fn num_states(&self) -> usize {
+        match self {
+            CompactDfa::Narrow(dfa) => dfa.num_states(),
+            CompactDfa::Wide(dfa) => dfa.num_states(),
+        }
+    }
+
+    /// Returns the Levenshtein distance associated to the
+    /// current state.
+    pub fn distance(&self, state_id: u32) -> Distance {
+        match self {
+            CompactDfa::Narrow(dfa) => dfa.distance(state_id),
+            CompactDfa::Wide(dfa) => dfa.distance(state_id),
+        }
+    }
+
+    /// Returns the destination state reached after consuming a given byte.
+    pub fn transition(&self, from_state_id: u32, b: u8) -> u32 {
+        match self {
+            CompactDfa::Narrow(dfa) => dfa.transition(from_state_id, b),
+            CompactDfa::Wide(dfa) => dfa.transition(from_state_id, b),
+        }
+    }
+
+    /// Returns the Levenshtein distance computed by the automaton on `text`.
+    pub fn eval<B: AsRef<[u8]>>(&self, text: B) -> Distance {
+        match self {
+            CompactDfa::Narrow(dfa) => dfa.eval(text),
+            CompactDfa::Wide(dfa) => dfa.eval(text),
+        }
+    }
+
+    /// Returns the number of bytes allocated on the heap by the tables.
+    pub fn heap_size(&self) -> usize {
+        match self {
+            CompactDfa::Narrow(dfa) => dfa.heap_size(),
+            CompactDfa::Wide(dfa) => dfa.heap_size(),
+        }
+    }
+}
+
+impl LevenshteinAutomatonBuilder {
+    /// Builds the automaton of `query` like [.build_dfa()](#method.build_dfa),
+    /// with `u16` state ids if it has few enough states.
+    pub fn build_compact_dfa(&self, query: &str) -> CompactDfa {
+        self.build_dfa(query).into_compact()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CompactDfa, MAX_NARROW_NUM_STATES};
+    use crate::{Distance, LevenshteinAutomatonBuilder, DFA};
+
+    #[test]
+    fn test_narrow_transitions() {
+        let builder = LevenshteinAutomatonBuilder::new(2, true);
+        for query in &["", "abc", "寿司", "levenshtein"] {
+            let dfa = builder.build_dfa(query);
+            let narrow = dfa.to_narrow().unwrap();
+            assert_eq!(narrow.num_states(), dfa.num_states());
+            assert_eq!(narrow.initial_state(), dfa.initial_state());
+            for state in dfa.states() {
+                assert_eq!(narrow.distance(state), dfa.distance(state));
+                for b in 0..=255u8 {
+                    assert_eq!(narrow.transition(state, b), dfa.transition(state, b));
+                }
+            }
+            assert!(narrow.heap_size() < dfa.heap_size());
+            assert_eq!(narrow.eval("levenstein"), dfa.eval("levenstein"));
+        }
+    }
+
+    #[test]
+    fn test_compact_width() {
+        let distances = vec![Distance::AtLeast(1); MAX_NARROW_NUM_STATES + 1];
+        let transitions = vec![[0u32; 256]; MAX_NARROW_NUM_STATES + 1];
+        let large = DFA::from_parts(transitions, distances, 0);
+        assert!(large.to_narrow().is_none());
+        assert!(matches!(large.into_compact(), CompactDfa::Wide(_)));
+        let small = LevenshteinAutomatonBuilder::new(1, false).build_compact_dfa("abc");
+        assert!(matches!(small, CompactDfa::Narrow(_)));
+        assert_eq!(small.eval("abd"), Distance::Exact(1));
+    }
+}
@@ -0,0 +1,203 @@
+use super::levenshtein_nfa::{shifted_characteristic_vector, LevenshteinNFA, MultiState};
+use super::Distance;
+use std::mem;
+
+/// Levenshtein automaton simulated on the fly, for distances too large
+/// to build a [DFA](./struct.DFA.html).
+///
+/// The number of states of the parametric DFA grows exponentially with the
+/// distance, so that building automata beyond a distance of `2` or `3` is
+/// unpractical. `LevenshteinNfa` instead keeps track of the set of active
+/// NFA states while reading the text. Nothing is precomputed: each char
+/// costs `O(max_distance²)` operations, which is slower than a DFA
+/// transition, but memory stays proportional to the query.
+///
+/// ```rust
+/// # use levenshtein_automata::{Distance, LevenshteinNfa};
+/// let nfa = LevenshteinNfa::new("Les Misérables", 4, false);
+/// assert_eq!(nfa.eval("Les Miserabels"), Distance::Exact(3));
+/// assert_eq!(nfa.eval("Les Misérables"), Distance::Exact(0));
+/// assert_eq!(nfa.eval("Misérables"), Distance::Exact(4));
+/// assert_eq!(nfa.eval("Notre-Dame de Paris"), Distance::AtLeast(5));
+/// ```
+///
+/// The automaton can also be run one char at a time, from its
+/// [initial_state](#method.initial_state).
+///
+/// ```rust
+/// # use levenshtein_automata::{Distance, LevenshteinNfa};
+/// let nfa = LevenshteinNfa::new("abc", 1, false);
+/// let mut state = nfa.initial_state();
+/// for chr in "abd".chars() {
+///     state = nfa.transition(&state, chr);
+/// }
+/// assert_eq!(nfa.distance(&state), Distance::Exact(1));
+/// state = nfa.transition(&state, 'e');
+/// assert!(state.is_dead());
+/// ```
+pub struct LevenshteinNfa {
+    query: Vec<char>,
+    nfa: LevenshteinNFA,
+}
+
+/// Set of the active states of a [LevenshteinNfa](./struct.LevenshteinNfa.html)
+/// after reading some text.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct NfaState {
+    // Offsets of `multistate` are relative to `offset` in the query.
+    offset: u32,
+    multistate: MultiState,
+}
+
+impl NfaState {
+    /// Returns true if no state is active anymore, i.e. if the
+    /// distance exceeds the maximum distance whatever the next chars are.
+    pub fn is_dead(&self) -> bool {
+        self.multistate.states().is_empty()
+    }
+
+    // State without any active NFA state, reached once the distance exceeds
+    // the maximum distance.
+    pub(crate) fn dead() -> NfaState {
+        NfaState {
+            offset: 0,
+            multistate: MultiState::empty(),
+        }
+    }
+}
+
+impl LevenshteinNfa {
+    /// Creates the automaton computing the distance to `query`,
+    /// up to `max_distance`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_distance` is greater than `31`.
+    pub fn new(query: &str, max_distance: u8, transposition_cost_one: bool) -> LevenshteinNfa {
+        assert!(
+            max_distance <= 31,
+            "Levenshtein NFAs only support distances up to 31"
+        );
+        LevenshteinNfa {
+            query: query.chars().collect(),
+            nfa: LevenshteinNFA::levenshtein(max_distance, transposition_cost_one),
+        }
+    }
+
+    /// Returns the maximum distance computed by the automaton.
+    pub fn max_distance(&self) -> u8 {
+        self.nfa.max_distance()
+    }
+
+    /// Returns the state before reading any char.
+    pub fn initial_state(&self) -> NfaState {
+        NfaState {
+            offset: 0,
+            multistate: self.nfa.initial_states(),
+        }
+    }
+
+    /// Returns the state reached after consuming a given char.
+    pub fn transition(&self, state: &NfaState, chr: char) -> NfaState {
+        self.transition_chi(state, self.characteristic_vector(state, chr))
+    }
+
+    /// Returns the Levenshtein distance between the query and the text
+    /// read to reach `state`.
+    pub fn distance(&self, state: &NfaState) -> Distance {
+        self.nfa.shifted_multistate_distance(
+            &state.multistate,
+            state.offset,
+            self.query.len() as u32,
+        )
+    }
+
+    // Characters with the same characteristic vector lead to the same state.
+    pub(crate) fn characteristic_vector(&self, state: &NfaState, chr: char) -> u64 {
+        shifted_characteristic_vector(&self.query, state.offset, chr)
+    }
+
+    pub(crate) fn transition_chi(&self, state: &NfaState, chi: u64) -> NfaState {
+        let mut multistate = MultiState::empty();
+        self.nfa.transition(&state.multistate, &mut multistate, chi);
+        if multistate.states().is_empty() {
+            return NfaState::dead();
+        }
+        let offset = state.offset + multistate.normalize();
+        NfaState { offset, multistate }
+    }
+
+    /// Returns the Levenshtein distance between the query and `text`.
+    ///
+    /// The evaluation stops as soon as the distance exceeds `max_distance`.
+    pub fn eval(&self, text: &str) -> Distance {
+        // Offsets of `multistate` are relative to `offset` in the query.
+        let mut offset = 0u32;
+        let mut multistate = self.nfa.initial_states();
+        let mut next_multistate = MultiState::empty();
+        for chr in text.chars() {
+            let chi = shifted_characteristic_vector(&self.query, offset, chr);
+            self.nfa.transition(&multistate, &mut next_multistate, chi);
+            if next_multistate.states().is_empty() {
+                return Distance::AtLeast(self.max_distance() + 1);
+            }
+            offset += next_multistate.normalize();
+            mem::swap(&mut multistate, &mut next_multistate);
+        }
+        self.nfa
+            .shifted_multistate_distance(&multistate, offset, self.query.len() as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LevenshteinNfa;
+    use crate::{
+        damerau_levenshtein_within, levenshtein_within, Distance, LevenshteinAutomatonBuilder,
+    };
+
+    #[test]
+    fn test_nfa_matches_dfa() {
+        for &(max_distance, transposition_cost_one) in &[(0, false), (1, true), (2, false)] {
+            let builder = LevenshteinAutomatonBuilder::new(max_distance, transposition_cost_one);
+            for query in &["", "abc", "寿司", "abcabc"] {
+                let dfa = builder.build_dfa(query);
+                let nfa = LevenshteinNfa::new(query, max_distance, transposition_cost_one);
+                for text in &["", "a", "acb", "bac", "abcabc", "寿a司", "xyz"] {
+                    assert_eq!(nfa.eval(text), dfa.eval(text));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_nfa_large_distances() {
+        let query = "the quick brown fox jumps over the lazy dog";
+        let texts = [
+            "the quick brown fox jumps over the lazy dog",
+            "teh quick brwn fox jumsp over the lazzy dgo",
+            "the quikc brown fx jumps ovr the lazy dog!",
+            "a quick brown dog jumps over the lazy fox",
+            "the lazy dog",
+            "",
+        ];
+        for max_distance in 3..=8 {
+            for &transposition_cost_one in &[false, true] {
+                let nfa = LevenshteinNfa::new(query, max_distance, transposition_cost_one);
+                for text in &texts {
+                    let expected = if transposition_cost_one {
+                        damerau_levenshtein_within(query, text, max_distance)
+                    } else {
+                        levenshtein_within(query, text, max_distance)
+                    };
+                    assert_eq!(nfa.eval(text), expected);
+                }
+            }
+        }
+        let long_query = "a".repeat(256);
+        assert_eq!(
+            LevenshteinNfa::new(&long_query, 3, false).eval(""),
+            Distance::AtLeast(4)
+        );
+    }
+}
@@ -0,0 +1,202 @@
+/// Prefilter discarding the candidates that cannot be within
+/// a given distance of a query, based on their common n-grams.
+///
+/// Strings are padded with `n - 1` sentinels on each side, so that a
+/// string of `len` chars has `len + n - 1` n-grams. An edit modifies at
+/// most `n` of them (`n + 1` for a transposition), hence two strings within
+/// distance `k` share at least `max_len + n - 1 - k * n` n-grams.
+/// Candidates with fewer common n-grams, or whose length differs too much,
+/// can be discarded before running the automaton.
+///
+/// The filter is conservative: it never discards a candidate within
+/// the distance. It only pays off for large dictionaries, and is most selective
+/// for long queries and small distances.
+///
+/// ```rust
+/// # use levenshtein_automata::NgramFilter;
+/// let dictionary = ["levenshtein", "einstein", "levitation", "levenstien"];
+/// let query = NgramFilter::new(2, 1, true).prepare("levenstein");
+/// let candidates: Vec<&str> = query.filter(dictionary.iter().cloned()).collect();
+/// assert_eq!(candidates, vec!["levenshtein", "levenstien"]);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct NgramFilter {
+    n: usize,
+    max_distance: u8,
+    transposition_cost_one: bool,
+}
+
+impl NgramFilter {
+    /// Creates a filter based on the n-grams of `n` chars.
+    ///
+    /// `max_distance` and `transposition_cost_one` should be those of
+    /// the automata run on the remaining candidates.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is `0`.
+    pub fn new(n: usize, max_distance: u8, transposition_cost_one: bool) -> NgramFilter {
+        assert!(n > 0, "n-grams should contain at least one char");
+        NgramFilter {
+            n,
+            max_distance,
+            transposition_cost_one,
+        }
+    }
+
+    /// Computes the n-grams of a query.
+    pub fn prepare(&self, query: &str) -> NgramQuery {
+        let chars: Vec<char> = query.chars().collect();
+        NgramQuery {
+            filter: *self,
+            len: chars.len(),
+            ngrams: ngrams(&chars, self.n),
+        }
+    }
+
+    /// Returns the minimum number of n-grams that two strings of `left_len`
+    /// and `right_len` chars share if they are within the distance,
+    /// or `None` if their lengths are too different.
+    pub(crate) fn min_common_ngrams(&self, left_len: usize, right_len: usize) -> Option<usize> {
+        let max_len = left_len.max(right_len);
+        if max_len - left_len.min(right_len) > self.max_distance as usize {
+            return None;
+        }
+        let max_lost_ngrams = self.max_distance as usize * self.ngrams_per_edit();
+        Some((max_len + self.n - 1).saturating_sub(max_lost_ngrams))
+    }
+
+    // Maximum number of n-grams modified by a single edit.
+    fn ngrams_per_edit(&self) -> usize {
+        if self.transposition_cost_one {
+            self.n + 1
+        } else {
+            self.n
+        }
+    }
+}
+
+/// A query prepared by a [NgramFilter].
+#[derive(Clone, Debug)]
+pub struct NgramQuery {
+    filter: NgramFilter,
+    len: usize,
+    ngrams: Vec<u64>,
+}
+
+impl NgramQuery {
+    /// Returns false if `candidate` is certainly not within the distance
+    /// of the query.
+    pub fn may_match(&self, candidate: &str) -> bool {
+        let chars: Vec<char> = candidate.chars().collect();
+        match self.filter.min_common_ngrams(self.len, chars.len()) {
+            None => false,
+            Some(0) => true,
+            Some(min_common_ngrams) => {
+                common_ngrams(&self.ngrams, &ngrams(&chars, self.filter.n)) >= min_common_ngrams
+            }
+        }
+    }
+
+    /// Returns the candidates that may be within the distance of the query.
+    pub fn filter<'a, I>(&'a self, candidates: I) -> impl Iterator<Item = &'a str> + 'a
+    where
+        I: IntoIterator<Item = &'a str>,
+        I::IntoIter: 'a,
+    {
+        candidates
+            .into_iter()
+            .filter(move |candidate| self.may_match(candidate))
+    }
+}
+
+// Returns the sorted n-grams of `chars`, padded with `n - 1` sentinels on each side.
+//
+// N-grams are packed into a `u64`, using 21 bits per char. This is exact up to
+// `n = 3`; longer n-grams may collide, which only makes the filter less selective.
+pub(crate) fn ngrams(chars: &[char], n: usize) -> Vec<u64> {
+    // `0` is the sentinel, chars are shifted by one.
+    let mut padded: Vec<u64> = vec![0; n - 1];
+    padded.extend(chars.iter().map(|&c| c as u64 + 1));
+    padded.resize(padded.len() + n - 1, 0);
+    let mut ngrams: Vec<u64> = padded
+        .windows(n)
+        .map(|window| {
+            window
+                .iter()
+                .fold(0u64, |ngram, &c| ngram.wrapping_shl(21) ^ c)
+        })
+        .collect();
+    ngrams.sort_unstable();
+    ngrams
+}
+
+// Returns the size of the multiset intersection of two sorted lists of n-grams.
+fn common_ngrams(left: &[u64], right: &[u64]) -> usize {
+    let mut common = 0;
+    let (mut i, mut j) = (0, 0);
+    while i < left.len() && j < right.len() {
+        if left[i] < right[j] {
+            i += 1;
+        } else if left[i] > right[j] {
+            j += 1;
+        } else {
+            common += 1;
+            i += 1;
+            j += 1;
+        }
+    }
+    common
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NgramFilter;
+    use crate::distance::edit_distance_chars;
+    use crate::ExhaustiveVerifier;
+
+    #[test]
+    fn test_ngram_filter_is_conservative() {
+        let strings: Vec<String> = ExhaustiveVerifier::new("abé", 5, 0, false)
+            .strings()
+            .collect();
+        for &transposition_cost_one in &[false, true] {
+            for n in 1..=3 {
+                for max_distance in 0..=2u8 {
+                    let filter = NgramFilter::new(n, max_distance, transposition_cost_one);
+                    for query in &["", "ab", "aéb", "abba", "babab"] {
+                        let prepared = filter.prepare(query);
+                        let query_chars: Vec<char> = query.chars().collect();
+                        for candidate in &strings {
+                            let candidate_chars: Vec<char> = candidate.chars().collect();
+                            let distance = edit_distance_chars(
+                                &query_chars,
+                                &candidate_chars,
+                                transposition_cost_one,
+                                max_distance as usize,
+                                false,
+                            );
+                            if distance <= max_distance as usize {
+                                assert!(prepared.may_match(candidate));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_ngram_filter_selectivity() {
+        let query = NgramFilter::new(3, 1, false).prepare("automaton");
+        assert!(query.may_match("automaton"));
+        assert!(query.may_match("automatn"));
+        assert!(query.may_match("autonaton"));
+        assert!(!query.may_match("matonauto"));
+        assert!(!query.may_match("automatic"));
+        assert!(!query.may_match("auto"));
+        let dictionary = ["automata", "automaton", "automation", "autumn", "photon"];
+        let candidates: Vec<&str> = query.filter(dictionary.iter().cloned()).collect();
+        assert_eq!(candidates, vec!["automaton", "automation"]);
+    }
+}
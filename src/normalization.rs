@@ -0,0 +1,82 @@
+use super::{Distance, LevenshteinAutomatonBuilder, DFA};
+use unicode_normalization::UnicodeNormalization;
+
+impl LevenshteinAutomatonBuilder {
+    /// Same as [.build_dfa(...)](#method.build_dfa), for the NFC normalized
+    /// form of `query`.
+    ///
+    /// Accented letters have a composed form (`é`) and a decomposed form
+    /// (`e` followed by a combining accent), which are two edits apart.
+    /// Evaluating texts with [DFA::eval_normalized](./struct.DFA.html#method.eval_normalized)
+    /// makes both forms equivalent.
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::{Distance, LevenshteinAutomatonBuilder};
+    /// let builder = LevenshteinAutomatonBuilder::new(1, true);
+    /// let decomposed = "Cafe\u{301}";
+    /// let dfa = builder.build_normalized_dfa(decomposed);
+    /// assert_eq!(dfa.eval("Café"), Distance::Exact(0));
+    /// assert_eq!(dfa.eval_normalized(decomposed), Distance::Exact(0));
+    /// assert_eq!(dfa.eval_normalized("Cafe\u{300}"), Distance::Exact(1));
+    /// ```
+    pub fn build_normalized_dfa(&self, query: &str) -> DFA {
+        self.build_dfa(&query.nfc().collect::<String>())
+    }
+
+    /// Same as [.build_prefix_dfa(...)](#method.build_prefix_dfa), for the
+    /// NFC normalized form of `query`.
+    pub fn build_normalized_prefix_dfa(&self, query: &str) -> DFA {
+        self.build_prefix_dfa(&query.nfc().collect::<String>())
+    }
+}
+
+impl DFA {
+    /// Same as [.eval(...)](#method.eval), for the NFC normalized form of `text`.
+    ///
+    /// The text is normalized on the fly, without allocating.
+    pub fn eval_normalized(&self, text: &str) -> Distance {
+        let mut buffer = [0u8; 4];
+        let mut state = self.initial_state();
+        for chr in text.nfc() {
+            for &b in chr.encode_utf8(&mut buffer).as_bytes() {
+                state = self.transition(state, b);
+            }
+            if !self.can_match(state) {
+                break;
+            }
+        }
+        self.distance(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Distance, LevenshteinAutomatonBuilder};
+    use unicode_normalization::UnicodeNormalization;
+
+    #[test]
+    fn test_normalized_dfa() {
+        let builder = LevenshteinAutomatonBuilder::new(1, false);
+        let texts = [
+            "Ame\u{301}lie",
+            "Amélie",
+            "Amelie",
+            "A\u{30a}mélie",
+            "Åmélie",
+            "Amélie!",
+        ];
+        for query in &["Amélie", "Ame\u{301}lie", "Åmélie"] {
+            let dfa = builder.build_normalized_dfa(query);
+            let reference_dfa = builder.build_dfa(&query.nfc().collect::<String>());
+            for text in &texts {
+                let normalized_text: String = text.nfc().collect();
+                assert_eq!(
+                    dfa.eval_normalized(text),
+                    reference_dfa.eval(&normalized_text)
+                );
+            }
+        }
+        let dfa = builder.build_normalized_prefix_dfa("Ame\u{301}");
+        assert_eq!(dfa.eval_normalized("Ame\u{301}lie"), Distance::Exact(0));
+    }
+}
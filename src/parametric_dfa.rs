@@ -1,13 +1,21 @@
 use super::alphabet::Alphabet;
+use super::case_folding::CaseFolding;
 use super::dfa::{Utf8DFABuilder, DFA};
 use super::levenshtein_nfa::Distance;
 use super::levenshtein_nfa::{LevenshteinNFA, MultiState};
+use super::Error;
 use super::Index;
+use super::ParametricTables;
+use alloc::vec;
+use alloc::vec::Vec;
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct ParametricState {
     shape_id: u32,
     offset: u32,
+    // In closest prefix automata, smallest distance within `max_distance` of
+    // the prefixes of the text read so far, excluding the whole text.
+    prefix_distance: Option<u8>,
 }
 
 impl ParametricState {
@@ -15,6 +23,7 @@ impl ParametricState {
         ParametricState {
             shape_id: 0u32,
             offset: 0u32,
+            prefix_distance: None,
         }
     }
     fn is_dead_end(&self) -> bool {
@@ -22,6 +31,29 @@ impl ParametricState {
     }
 }
 
+// How the texts are compared to the query.
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum Matching {
+    // The whole text is compared to the query.
+    Whole,
+    // The evaluation stops once no later char can decrease the distance,
+    // see `ParametricDFA::is_prefix_sink`.
+    Prefix,
+    // The smallest distance of the prefixes of the text is tracked in the
+    // states, and kept whatever the chars that follow.
+    ClosestPrefix,
+}
+
+impl Matching {
+    fn from_prefix(prefix: bool) -> Matching {
+        if prefix {
+            Matching::Prefix
+        } else {
+            Matching::Whole
+        }
+    }
+}
+
 #[derive(Clone, Copy)]
 pub struct Transition {
     dest_shape_id: u32,
@@ -38,6 +70,7 @@ impl Transition {
             } else {
                 state.offset + self.delta_offset
             },
+            prefix_distance: state.prefix_distance,
         }
     }
 }
@@ -46,16 +79,22 @@ struct ParametricStateIndex {
     state_index: Vec<Option<u32>>,
     state_queue: Vec<ParametricState>,
     num_offsets: usize,
+    num_prefix_distances: usize,
 }
 
 impl ParametricStateIndex {
-    fn new(query_len: usize, num_param_states: usize) -> ParametricStateIndex {
+    fn new(
+        query_len: usize,
+        num_param_states: usize,
+        num_prefix_distances: usize,
+    ) -> ParametricStateIndex {
         let num_offsets = query_len + 1;
-        let max_num_states = num_param_states * num_offsets;
+        let max_num_states = num_param_states * num_offsets * num_prefix_distances;
         ParametricStateIndex {
             state_index: vec![None; max_num_states],
             state_queue: Vec::with_capacity(100),
-            num_offsets: num_offsets,
+            num_offsets,
+            num_prefix_distances,
         }
     }
 
@@ -68,8 +107,15 @@ impl ParametricStateIndex {
     }
 
     fn get_or_allocate(&mut self, parametric_state: ParametricState) -> u32 {
-        let bucket = (parametric_state.shape_id as usize) * self.num_offsets
-            + parametric_state.offset as usize;
+        let prefix_distance_id = match parametric_state.prefix_distance {
+            None => 0,
+            Some(d) => d as usize + 1,
+        };
+        debug_assert!(prefix_distance_id < self.num_prefix_distances);
+        let bucket = ((parametric_state.shape_id as usize) * self.num_offsets
+            + parametric_state.offset as usize)
+            * self.num_prefix_distances
+            + prefix_distance_id;
         if let Some(state_id) = self.state_index[bucket] {
             return state_id;
         }
@@ -97,6 +143,7 @@ impl ParametricDFA {
         ParametricState {
             shape_id: 1,
             offset: 0,
+            prefix_distance: None,
         }
     }
 
@@ -106,13 +153,13 @@ impl ParametricDFA {
         if state.is_dead_end() {
             return true;
         }
+        let prefix_distance = match self.prefix_distance(state, query_len) {
+            Distance::Exact(d) => d,
+            Distance::AtLeast(_) => return false,
+        };
         let remaining_offset: usize = query_len - state.offset as usize;
         if remaining_offset < self.diameter {
             let state_distances = &self.distance[(self.diameter * state.shape_id as usize)..];
-            let prefix_distance = state_distances[remaining_offset];
-            if prefix_distance > self.max_distance {
-                return false;
-            }
             for potential_distance in state_distances[..remaining_offset].iter().cloned() {
                 if potential_distance < prefix_distance {
                     return false;
@@ -124,12 +171,170 @@ impl ParametricDFA {
         }
     }
 
+    #[cfg(test)]
     pub fn build_dfa(&self, query: &str, prefix: bool) -> DFA {
-        let query_chars: Vec<char> = query.chars().collect();
+        self.build_dfa_with_exact_prefix(query, prefix, 0)
+    }
+
+    #[cfg(test)]
+    pub fn build_closest_prefix_dfa(&self, query: &str) -> DFA {
+        self.try_build_closest_prefix_dfa(query, CaseFolding::None, None)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Builds a DFA in which the first `exact_prefix_len` chars of the query
+    /// have to match exactly. Edits are only allowed on the rest of the query.
+    #[cfg(feature = "std")]
+    pub fn build_dfa_with_exact_prefix(
+        &self,
+        query: &str,
+        prefix: bool,
+        exact_prefix_len: usize,
+    ) -> DFA {
+        self.build_dfa_ignoring(query, prefix, exact_prefix_len, &[], CaseFolding::None)
+    }
+
+    /// Builds a DFA in which the `ignorable` chars are skipped,
+    /// both in the query and in the text, and chars are compared
+    /// after `case_folding`.
+    #[cfg(feature = "std")]
+    pub fn build_dfa_ignoring(
+        &self,
+        query: &str,
+        prefix: bool,
+        exact_prefix_len: usize,
+        ignorable: &[char],
+        case_folding: CaseFolding,
+    ) -> DFA {
+        self.try_build_dfa_ignoring(
+            query,
+            prefix,
+            exact_prefix_len,
+            ignorable,
+            case_folding,
+            None,
+        )
+        .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as [ParametricDFA::build_dfa_ignoring], but returns an error
+    /// if the automaton could have too many states, or has more than
+    /// `state_budget` states.
+    pub fn try_build_dfa_ignoring(
+        &self,
+        query: &str,
+        prefix: bool,
+        exact_prefix_len: usize,
+        ignorable: &[char],
+        case_folding: CaseFolding,
+        state_budget: Option<usize>,
+    ) -> Result<DFA, Error> {
+        let mut dfa_builder = Utf8DFABuilder::try_with_max_num_states(0)?;
+        self.try_fill_dfa_builder(
+            &mut dfa_builder,
+            query,
+            Matching::from_prefix(prefix),
+            exact_prefix_len,
+            ignorable,
+            case_folding,
+            state_budget,
+        )?;
+        dfa_builder.try_build()
+    }
+
+    /// Same as [ParametricDFA::try_build_dfa_ignoring], but the states are
+    /// added to `dfa_builder` after resetting it, so that its buffers are
+    /// reused. The automaton is then copied out of the builder.
+    pub fn try_build_dfa_reusing(
+        &self,
+        dfa_builder: &mut Utf8DFABuilder,
+        query: &str,
+        prefix: bool,
+        case_folding: CaseFolding,
+        state_budget: Option<usize>,
+    ) -> Result<DFA, Error> {
+        self.try_fill_dfa_builder(
+            dfa_builder,
+            query,
+            Matching::from_prefix(prefix),
+            0,
+            &[],
+            case_folding,
+            state_budget,
+        )?;
+        dfa_builder.try_build_by_ref()
+    }
+
+    /// Builds a prefix DFA keeping the smallest distance of the prefixes of
+    /// the texts, whatever the chars that follow. Its states also track that
+    /// distance, so that it may have up to `max_distance + 2` times as many
+    /// states as the DFA built with `prefix`.
+    pub fn try_build_closest_prefix_dfa(
+        &self,
+        query: &str,
+        case_folding: CaseFolding,
+        state_budget: Option<usize>,
+    ) -> Result<DFA, Error> {
+        let mut dfa_builder = Utf8DFABuilder::try_with_max_num_states(0)?;
+        self.try_fill_dfa_builder(
+            &mut dfa_builder,
+            query,
+            Matching::ClosestPrefix,
+            0,
+            &[],
+            case_folding,
+            state_budget,
+        )?;
+        dfa_builder.try_build()
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn try_fill_dfa_builder(
+        &self,
+        dfa_builder: &mut Utf8DFABuilder,
+        query: &str,
+        matching: Matching,
+        exact_prefix_len: usize,
+        ignorable: &[char],
+        case_folding: CaseFolding,
+        state_budget: Option<usize>,
+    ) -> Result<(), Error> {
+        let check_budget = |dfa_builder: &Utf8DFABuilder| match state_budget {
+            Some(max_num_states) if dfa_builder.num_states() > max_num_states => {
+                Err(Error::StateBudgetExceeded { max_num_states })
+            }
+            _ => Ok(()),
+        };
+        let all_query_chars: Vec<char> = query
+            .chars()
+            .filter(|c| !ignorable.contains(c))
+            .map(|c| case_folding.fold(c))
+            .collect();
+        let exact_prefix_len = exact_prefix_len.min(all_query_chars.len());
+        let (exact_prefix, query_chars) = all_query_chars.split_at(exact_prefix_len);
         let query_len = query_chars.len();
-        let alphabet = Alphabet::for_query_chars(&query_chars);
+        let alphabet = Alphabet::for_query_chars(query_chars);
+        // The other chars folded to each char of the alphabet, computed once
+        // as they can be expensive to enumerate.
+        let alphabet_variants: Vec<Vec<char>> = alphabet
+            .iter()
+            .map(|(chr, _)| case_folding.variants(*chr))
+            .collect();
 
-        let mut parametric_state_index = ParametricStateIndex::new(query_len, self.num_states());
+        let num_prefix_distances = if matching == Matching::ClosestPrefix {
+            self.max_distance as usize + 2
+        } else {
+            1
+        };
+        let max_num_states = (query_len + 1)
+            .checked_mul(self.num_states() * num_prefix_distances)
+            .and_then(|max_num_states| max_num_states.checked_add(exact_prefix_len))
+            .ok_or(Error::TooManyStates {
+                max_num_states: usize::MAX,
+            })?;
+        dfa_builder.try_reset(max_num_states)?;
+        let mut parametric_state_index =
+            ParametricStateIndex::new(query_len, self.num_states(), num_prefix_distances);
         let max_num_states = parametric_state_index.max_num_states();
 
         let dead_end_state_id = parametric_state_index.get_or_allocate(ParametricState::empty());
@@ -137,7 +342,6 @@ impl ParametricDFA {
         let initial_state_id =
             parametric_state_index.get_or_allocate(ParametricDFA::initial_state());
 
-        let mut dfa_builder = Utf8DFABuilder::with_max_num_states(max_num_states);
         let mask = (1 << self.diameter) - 1;
 
         for state_id in 0u32.. {
@@ -145,28 +349,67 @@ impl ParametricDFA {
                 break;
             }
             let state = parametric_state_index.get(state_id);
-            if prefix && self.is_prefix_sink(state, query_len) {
+            let distance = self.prefix_distance(state, query_len);
+            // Distance of the prefixes read so far, for the successors.
+            let successor_prefix_distance = match distance {
+                Distance::Exact(d) if matching == Matching::ClosestPrefix => Some(d),
+                _ => None,
+            };
+            if state.is_dead_end() {
+                dfa_builder.add_sink_state(state_id, distance);
+            } else if matching != Matching::Whole && self.is_prefix_sink(state, query_len) {
                 let default_successor_id = state_id;
-                let distance = self.distance(state, query_len);
-                dfa_builder.add_state(state_id, distance, default_successor_id);
+                let mut state_builder =
+                    dfa_builder.add_state(state_id, distance, default_successor_id);
+                for &chr in ignorable {
+                    state_builder.add_transition(chr, state_id);
+                }
             } else {
-                let default_successor = self.transition(state, 0u32).apply(state);
+                let mut default_successor = self.transition(state, 0u32).apply(state);
+                default_successor.prefix_distance = successor_prefix_distance;
                 let default_successor_id =
                     parametric_state_index.get_or_allocate(default_successor);
-                let distance = self.distance(state, query_len);
                 let mut state_builder =
                     dfa_builder.add_state(state_id, distance, default_successor_id);
-                for &(ref chr, ref characteristic_vec) in alphabet.iter() {
+                for &chr in ignorable {
+                    state_builder.add_transition(chr, state_id);
+                }
+                for ((chr, characteristic_vec), variants) in alphabet.iter().zip(&alphabet_variants)
+                {
                     let chi = characteristic_vec.shift_and_mask(state.offset as usize, mask);
-                    let dest_state: ParametricState = self.transition(state, chi).apply(state);
+                    let mut dest_state: ParametricState = self.transition(state, chi).apply(state);
+                    dest_state.prefix_distance = successor_prefix_distance;
                     let dest_state_id = parametric_state_index.get_or_allocate(dest_state);
                     state_builder.add_transition(*chr, dest_state_id);
+                    for &variant in variants {
+                        state_builder.add_transition(variant, dest_state_id);
+                    }
                 }
             }
+            check_budget(dfa_builder)?;
         }
 
-        dfa_builder.set_initial_state(initial_state_id);
-        dfa_builder.build()
+        // The states consuming the exact prefix are added last, so that the
+        // dead end state keeps the id `SINK_STATE`. The texts ending inside
+        // the exact prefix do not match it, and are rejected.
+        let mut next_state_id = initial_state_id;
+        for (i, &chr) in exact_prefix.iter().enumerate().rev() {
+            let state_id = (max_num_states + i) as u32;
+            let distance = Distance::AtLeast(self.max_distance + 1u8);
+            let mut state_builder = dfa_builder.add_state(state_id, distance, dead_end_state_id);
+            state_builder.add_transition(chr, next_state_id);
+            for variant in case_folding.variants(chr) {
+                state_builder.add_transition(variant, next_state_id);
+            }
+            for &ignorable_chr in ignorable {
+                state_builder.add_transition(ignorable_chr, state_id);
+            }
+            next_state_id = state_id;
+        }
+
+        dfa_builder.set_initial_state(next_state_id);
+        check_budget(dfa_builder)?;
+        Ok(())
     }
 
     pub fn num_states(&self) -> usize {
@@ -206,11 +449,55 @@ impl ParametricDFA {
         }
     }
 
+    // Distance of the state, taking into account the prefixes
+    // read before reaching it in prefix automata.
+    fn prefix_distance(&self, state: ParametricState, query_len: usize) -> Distance {
+        match (self.distance(state, query_len), state.prefix_distance) {
+            (Distance::Exact(d), Some(prefix_distance)) if prefix_distance < d => {
+                Distance::Exact(prefix_distance)
+            }
+            (Distance::AtLeast(_), Some(prefix_distance)) => Distance::Exact(prefix_distance),
+            (distance, _) => distance,
+        }
+    }
+
     pub fn transition(&self, state: ParametricState, chi: u32) -> Transition {
         assert!((chi as usize) < self.transition_stride);
         self.transitions[self.transition_stride * state.shape_id as usize + chi as usize]
     }
 
+    pub(crate) fn from_tables(tables: &ParametricTables<'_>) -> ParametricDFA {
+        ParametricDFA {
+            distance: tables.distances.to_vec(),
+            transitions: tables
+                .dest_shape_ids
+                .iter()
+                .zip(tables.delta_offsets)
+                .map(|(&dest_shape_id, &delta_offset)| Transition {
+                    dest_shape_id,
+                    delta_offset,
+                })
+                .collect(),
+            max_distance: tables.max_distance,
+            transition_stride: 1 << tables.diameter,
+            diameter: tables.diameter as usize,
+        }
+    }
+
+    pub(crate) fn distances(&self) -> &[u8] {
+        &self.distance
+    }
+
+    pub(crate) fn diameter(&self) -> u32 {
+        self.diameter as u32
+    }
+
+    pub(crate) fn transitions(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.transitions
+            .iter()
+            .map(|transition| (transition.dest_shape_id, transition.delta_offset))
+    }
+
     pub fn from_nfa(nfa: &LevenshteinNFA) -> ParametricDFA {
         let mut index: Index<MultiState> = Index::new();
         index.get_or_allocate(&MultiState::empty());
@@ -0,0 +1,850 @@
+use super::{Distance, LevenshteinAutomatonBuilder, DFA};
+
+/// Phonetic encoding of words, used as a pre-transform by
+/// [PhoneticAutomatonBuilder].
+///
+/// Words sounding alike are expected to get identical, or close, codes.
+pub trait PhoneticEncoder {
+    /// Returns the codes of a word.
+    ///
+    /// Some encodings return several codes for ambiguous pronunciations.
+    /// The result is empty if the word cannot be encoded.
+    fn encode(&self, word: &str) -> Vec<String>;
+}
+
+/// American Soundex.
+///
+/// Codes are made of the first letter of the word followed by three
+/// digits, e.g. `R163` for both `Robert` and `Rupert`.
+/// Non ascii letters are ignored.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Soundex;
+
+fn soundex_digit(letter: u8) -> Option<u8> {
+    match letter {
+        b'B' | b'F' | b'P' | b'V' => Some(b'1'),
+        b'C' | b'G' | b'J' | b'K' | b'Q' | b'S' | b'X' | b'Z' => Some(b'2'),
+        b'D' | b'T' => Some(b'3'),
+        b'L' => Some(b'4'),
+        b'M' | b'N' => Some(b'5'),
+        b'R' => Some(b'6'),
+        _ => None,
+    }
+}
+
+impl PhoneticEncoder for Soundex {
+    fn encode(&self, word: &str) -> Vec<String> {
+        let mut letters = word
+            .bytes()
+            .filter(u8::is_ascii_alphabetic)
+            .map(|b| b.to_ascii_uppercase());
+        let first_letter = match letters.next() {
+            Some(first_letter) => first_letter,
+            None => return Vec::new(),
+        };
+        let mut code = vec![first_letter];
+        let mut previous_digit = soundex_digit(first_letter);
+        for letter in letters {
+            if code.len() == 4 {
+                break;
+            }
+            match soundex_digit(letter) {
+                Some(digit) => {
+                    if previous_digit != Some(digit) {
+                        code.push(digit);
+                    }
+                    previous_digit = Some(digit);
+                }
+                // `H` and `W` do not separate letters having the same digit.
+                None if letter == b'H' || letter == b'W' => {}
+                None => previous_digit = None,
+            }
+        }
+        code.resize(4, b'0');
+        vec![String::from_utf8(code).unwrap()]
+    }
+}
+
+/// Double Metaphone, by Lawrence Philips.
+///
+/// Returns a primary code and, if it differs, an alternate code
+/// for another plausible pronunciation, e.g. `SM0` and `XMT` for `Smith`.
+/// Codes are truncated to `max_len`, which is `4` by default.
+#[derive(Clone, Copy, Debug)]
+pub struct DoubleMetaphone {
+    /// Maximum length of the codes.
+    pub max_len: usize,
+}
+
+impl Default for DoubleMetaphone {
+    fn default() -> DoubleMetaphone {
+        DoubleMetaphone { max_len: 4 }
+    }
+}
+
+impl PhoneticEncoder for DoubleMetaphone {
+    fn encode(&self, word: &str) -> Vec<String> {
+        let (primary, alternate) = DoubleMetaphoneEncoder::new(word, self.max_len).encode();
+        if primary.is_empty() {
+            Vec::new()
+        } else if primary == alternate {
+            vec![primary]
+        } else {
+            vec![primary, alternate]
+        }
+    }
+}
+
+struct DoubleMetaphoneEncoder {
+    // Uppercased word, padded with spaces.
+    chars: Vec<char>,
+    len: usize,
+    max_len: usize,
+    slavo_germanic: bool,
+    primary: String,
+    alternate: String,
+}
+
+impl DoubleMetaphoneEncoder {
+    fn new(word: &str, max_len: usize) -> DoubleMetaphoneEncoder {
+        let mut chars: Vec<char> = word.chars().flat_map(char::to_uppercase).collect();
+        let len = chars.len();
+        let upper: String = chars.iter().collect();
+        let slavo_germanic = upper.contains('W') || upper.contains('K') || upper.contains("CZ");
+        chars.extend("     ".chars());
+        DoubleMetaphoneEncoder {
+            chars,
+            len,
+            max_len,
+            slavo_germanic,
+            primary: String::new(),
+            alternate: String::new(),
+        }
+    }
+
+    fn at(&self, position: usize) -> char {
+        self.chars.get(position).cloned().unwrap_or('\0')
+    }
+
+    fn is_vowel(&self, position: usize) -> bool {
+        matches!(self.at(position), 'A' | 'E' | 'I' | 'O' | 'U' | 'Y')
+    }
+
+    // Returns true if the chars starting at `start` are one of `patterns`,
+    // which all have the same length.
+    fn string_at(&self, start: isize, patterns: &[&str]) -> bool {
+        if start < 0 {
+            return false;
+        }
+        let start = start as usize;
+        patterns.iter().any(|pattern| {
+            pattern
+                .chars()
+                .enumerate()
+                .all(|(i, c)| self.at(start + i) == c)
+        })
+    }
+
+    fn add(&mut self, code: &str) {
+        self.add_alternate(code, code);
+    }
+
+    fn add_alternate(&mut self, primary: &str, alternate: &str) {
+        self.primary.push_str(primary);
+        self.alternate.push_str(alternate);
+    }
+
+    fn is_germanic(&self) -> bool {
+        self.string_at(0, &["VAN ", "VON "]) || self.string_at(0, &["SCH"])
+    }
+
+    fn encode(mut self) -> (String, String) {
+        let len = self.len;
+        let last = len as isize - 1;
+        let mut current = 0usize;
+        // Skip these when at the start of the word.
+        if self.string_at(0, &["GN", "KN", "PN", "WR", "PS"]) {
+            current += 1;
+        }
+        // Initial `X` is pronounced `Z`, e.g. `Xavier`.
+        if self.at(0) == 'X' {
+            self.add("S");
+            current += 1;
+        }
+        while (self.primary.len() < self.max_len || self.alternate.len() < self.max_len)
+            && current < len
+        {
+            let c = current as isize;
+            current += match self.at(current) {
+                'A' | 'E' | 'I' | 'O' | 'U' | 'Y' => {
+                    // All of the initial vowels map to `A`.
+                    if current == 0 {
+                        self.add("A");
+                    }
+                    1
+                }
+                'B' => {
+                    self.add("P");
+                    if self.at(current + 1) == 'B' {
+                        2
+                    } else {
+                        1
+                    }
+                }
+                'Ç' => {
+                    self.add("S");
+                    1
+                }
+                'C' => self.encode_c(current),
+                'D' => {
+                    if self.string_at(c, &["DG"]) {
+                        if self.string_at(c + 2, &["I", "E", "Y"]) {
+                            // `edge`
+                            self.add("J");
+                            3
+                        } else {
+                            // `edgar`
+                            self.add("TK");
+                            2
+                        }
+                    } else if self.string_at(c, &["DT", "DD"]) {
+                        self.add("T");
+                        2
+                    } else {
+                        self.add("T");
+                        1
+                    }
+                }
+                'F' => self.encode_simple(current, 'F', "F"),
+                'G' => self.encode_g(current),
+                // Only kept if first or between two vowels.
+                'H' if (current == 0 || self.is_vowel(current - 1))
+                    && self.is_vowel(current + 1) =>
+                {
+                    self.add("H");
+                    2
+                }
+                'H' => 1,
+                'J' => self.encode_j(current),
+                'K' => self.encode_simple(current, 'K', "K"),
+                'L' => {
+                    if self.at(current + 1) == 'L' {
+                        // Spanish, e.g. `cabrillo`, `gallegos`.
+                        if (c == len as isize - 3
+                            && self.string_at(c - 1, &["ILLO", "ILLA", "ALLE"]))
+                            || ((self.string_at(last - 1, &["AS", "OS"])
+                                || self.string_at(last, &["A", "O"]))
+                                && self.string_at(c - 1, &["ALLE"]))
+                        {
+                            self.add_alternate("L", "");
+                        } else {
+                            self.add("L");
+                        }
+                        2
+                    } else {
+                        self.add("L");
+                        1
+                    }
+                }
+                'M' => {
+                    self.add("M");
+                    // `dumb`, `thumb`
+                    if (self.string_at(c - 1, &["UMB"])
+                        && (c + 1 == last || self.string_at(c + 2, &["ER"])))
+                        || self.at(current + 1) == 'M'
+                    {
+                        2
+                    } else {
+                        1
+                    }
+                }
+                'N' => self.encode_simple(current, 'N', "N"),
+                'Ñ' => {
+                    self.add("N");
+                    1
+                }
+                'P' => {
+                    if self.at(current + 1) == 'H' {
+                        self.add("F");
+                        2
+                    } else {
+                        self.add("P");
+                        // `campbell`, `raspberry`
+                        if self.string_at(c + 1, &["P", "B"]) {
+                            2
+                        } else {
+                            1
+                        }
+                    }
+                }
+                'Q' => self.encode_simple(current, 'Q', "K"),
+                'R' => {
+                    // French, e.g. `rogier`, but not `hochmeier`.
+                    if c == last
+                        && !self.slavo_germanic
+                        && self.string_at(c - 2, &["IE"])
+                        && !self.string_at(c - 4, &["ME", "MA"])
+                    {
+                        self.add_alternate("", "R");
+                    } else {
+                        self.add("R");
+                    }
+                    if self.at(current + 1) == 'R' {
+                        2
+                    } else {
+                        1
+                    }
+                }
+                'S' => self.encode_s(current),
+                'T' => {
+                    if self.string_at(c, &["TION"]) || self.string_at(c, &["TIA", "TCH"]) {
+                        self.add("X");
+                        3
+                    } else if self.string_at(c, &["TH"]) || self.string_at(c, &["TTH"]) {
+                        // `thomas`, `thames`, or germanic.
+                        if self.string_at(c + 2, &["OM", "AM"]) || self.is_germanic() {
+                            self.add("T");
+                        } else {
+                            self.add_alternate("0", "T");
+                        }
+                        2
+                    } else {
+                        self.add("T");
+                        if self.string_at(c + 1, &["T", "D"]) {
+                            2
+                        } else {
+                            1
+                        }
+                    }
+                }
+                'V' => self.encode_simple(current, 'V', "F"),
+                'W' => self.encode_w(current),
+                'X' => {
+                    // French, e.g. `breaux`.
+                    if !(c == last
+                        && (self.string_at(c - 3, &["IAU", "EAU"])
+                            || self.string_at(c - 2, &["AU", "OU"])))
+                    {
+                        self.add("KS");
+                    }
+                    if self.string_at(c + 1, &["C", "X"]) {
+                        2
+                    } else {
+                        1
+                    }
+                }
+                'Z' => {
+                    if self.at(current + 1) == 'H' {
+                        // Chinese pinyin, e.g. `zhao`.
+                        self.add("J");
+                        2
+                    } else {
+                        if self.string_at(c + 1, &["ZO", "ZI", "ZA"])
+                            || (self.slavo_germanic && current > 0 && self.at(current - 1) != 'T')
+                        {
+                            self.add_alternate("S", "TS");
+                        } else {
+                            self.add("S");
+                        }
+                        if self.at(current + 1) == 'Z' {
+                            2
+                        } else {
+                            1
+                        }
+                    }
+                }
+                _ => 1,
+            };
+        }
+        self.primary.truncate(self.max_len);
+        self.alternate.truncate(self.max_len);
+        (self.primary, self.alternate)
+    }
+
+    // Letters that are encoded the same way whether they are doubled or not.
+    fn encode_simple(&mut self, current: usize, letter: char, code: &str) -> usize {
+        self.add(code);
+        if self.at(current + 1) == letter {
+            2
+        } else {
+            1
+        }
+    }
+
+    fn encode_c(&mut self, current: usize) -> usize {
+        let c = current as isize;
+        // Various germanic.
+        if current > 1
+            && !self.is_vowel(current - 2)
+            && self.string_at(c - 1, &["ACH"])
+            && self.at(current + 2) != 'I'
+            && (self.at(current + 2) != 'E' || self.string_at(c - 2, &["BACHER", "MACHER"]))
+        {
+            self.add("K");
+            return 2;
+        }
+        if current == 0 && self.string_at(c, &["CAESAR"]) {
+            self.add("S");
+            return 2;
+        }
+        // Italian `chianti`.
+        if self.string_at(c, &["CHIA"]) {
+            self.add("K");
+            return 2;
+        }
+        if self.string_at(c, &["CH"]) {
+            // `michael`
+            if current > 0 && self.string_at(c, &["CHAE"]) {
+                self.add_alternate("K", "X");
+                return 2;
+            }
+            // Greek roots, e.g. `chemistry`, `chorus`.
+            if current == 0
+                && (self.string_at(c + 1, &["HARAC", "HARIS"])
+                    || self.string_at(c + 1, &["HOR", "HYM", "HIA", "HEM"]))
+                && !self.string_at(0, &["CHORE"])
+            {
+                self.add("K");
+                return 2;
+            }
+            // Germanic, greek, or otherwise `ch` for `kh` sound.
+            if self.is_germanic()
+                || self.string_at(c - 2, &["ORCHES", "ARCHIT", "ORCHID"])
+                || self.string_at(c + 2, &["T", "S"])
+                || ((current == 0 || self.string_at(c - 1, &["A", "O", "U", "E"]))
+                    && self.string_at(c + 2, &["L", "R", "N", "M", "B", "H", "F", "V", "W", " "]))
+            {
+                self.add("K");
+            } else if current > 0 {
+                if self.string_at(0, &["MC"]) {
+                    self.add("K");
+                } else {
+                    self.add_alternate("X", "K");
+                }
+            } else {
+                self.add("X");
+            }
+            return 2;
+        }
+        // `czerny`
+        if self.string_at(c, &["CZ"]) && !self.string_at(c - 2, &["WICZ"]) {
+            self.add_alternate("S", "X");
+            return 2;
+        }
+        // `focaccia`
+        if self.string_at(c + 1, &["CIA"]) {
+            self.add("X");
+            return 3;
+        }
+        // Double `C`, but not in `McClellan`.
+        if self.string_at(c, &["CC"]) && !(current == 1 && self.at(0) == 'M') {
+            // `bellocchio`, but not `bacchus`.
+            if self.string_at(c + 2, &["I", "E", "H"]) && !self.string_at(c + 2, &["HU"]) {
+                // `accident`, `accede`, `succeed`
+                if (current == 1 && self.at(0) == 'A') || self.string_at(c - 1, &["UCCEE", "UCCES"])
+                {
+                    self.add("KS");
+                } else {
+                    // `bacci`, `bertucci`, other italian
+                    self.add("X");
+                }
+                return 3;
+            }
+            // Pierce's rule.
+            self.add("K");
+            return 2;
+        }
+        if self.string_at(c, &["CK", "CG", "CQ"]) {
+            self.add("K");
+            return 2;
+        }
+        if self.string_at(c, &["CI", "CE", "CY"]) {
+            // Italian vs. english.
+            if self.string_at(c, &["CIO", "CIE", "CIA"]) {
+                self.add_alternate("S", "X");
+            } else {
+                self.add("S");
+            }
+            return 2;
+        }
+        self.add("K");
+        // `mac caffrey`, `mac gregor`
+        if self.string_at(c + 1, &[" C", " Q", " G"]) {
+            3
+        } else if self.string_at(c + 1, &["C", "K", "Q"]) && !self.string_at(c + 1, &["CE", "CI"]) {
+            2
+        } else {
+            1
+        }
+    }
+
+    fn encode_g(&mut self, current: usize) -> usize {
+        let c = current as isize;
+        if self.at(current + 1) == 'H' {
+            if current > 0 && !self.is_vowel(current - 1) {
+                self.add("K");
+                return 2;
+            }
+            // `ghislane`, `ghiradelli`
+            if current == 0 {
+                if self.at(current + 2) == 'I' {
+                    self.add("J");
+                } else {
+                    self.add("K");
+                }
+                return 2;
+            }
+            // Parker's rule (with some further refinements), e.g. `hugh`.
+            if (current > 1 && self.string_at(c - 2, &["B", "H", "D"]))
+                || (current > 2 && self.string_at(c - 3, &["B", "H", "D"]))
+                || (current > 3 && self.string_at(c - 4, &["B", "H"]))
+            {
+                return 2;
+            }
+            // `laugh`, `mclaughlin`, `cough`, `gough`, `rough`, `tough`
+            if current > 2
+                && self.at(current - 1) == 'U'
+                && self.string_at(c - 3, &["C", "G", "L", "R", "T"])
+            {
+                self.add("F");
+            } else if self.at(current - 1) != 'I' {
+                self.add("K");
+            }
+            return 2;
+        }
+        if self.at(current + 1) == 'N' {
+            if current == 1 && self.is_vowel(0) && !self.slavo_germanic {
+                self.add_alternate("KN", "N");
+            } else if !self.string_at(c + 2, &["EY"])
+                && self.at(current + 1) != 'Y'
+                && !self.slavo_germanic
+            {
+                // Not `cagney`.
+                self.add_alternate("N", "KN");
+            } else {
+                self.add("KN");
+            }
+            return 2;
+        }
+        // `tagliaro`
+        if self.string_at(c + 1, &["LI"]) && !self.slavo_germanic {
+            self.add_alternate("KL", "L");
+            return 2;
+        }
+        // -ges-, -gep-, -gel-, -gie- at the beginning.
+        if current == 0
+            && (self.at(current + 1) == 'Y'
+                || self.string_at(
+                    c + 1,
+                    &[
+                        "ES", "EP", "EB", "EL", "EY", "IB", "IL", "IN", "IE", "EI", "ER",
+                    ],
+                ))
+        {
+            self.add_alternate("K", "J");
+            return 2;
+        }
+        // -ger-, -gy-
+        if (self.string_at(c + 1, &["ER"]) || self.at(current + 1) == 'Y')
+            && !self.string_at(0, &["DANGER", "RANGER", "MANGER"])
+            && !self.string_at(c - 1, &["E", "I"])
+            && !self.string_at(c - 1, &["RGY", "OGY"])
+        {
+            self.add_alternate("K", "J");
+            return 2;
+        }
+        // Italian, e.g. `biaggi`.
+        if self.string_at(c + 1, &["E", "I", "Y"]) || self.string_at(c - 1, &["AGGI", "OGGI"]) {
+            if self.is_germanic() || self.string_at(c + 1, &["ET"]) {
+                self.add("K");
+            } else if self.string_at(c + 1, &["IER "]) {
+                // Always soft with a french ending.
+                self.add("J");
+            } else {
+                self.add_alternate("J", "K");
+            }
+            return 2;
+        }
+        self.encode_simple(current, 'G', "K")
+    }
+
+    fn encode_j(&mut self, current: usize) -> usize {
+        let c = current as isize;
+        // Obvious spanish, `jose`, `san jacinto`.
+        if self.string_at(c, &["JOSE"]) || self.string_at(0, &["SAN "]) {
+            if (current == 0 && self.at(current + 4) == ' ') || self.string_at(0, &["SAN "]) {
+                self.add("H");
+            } else {
+                self.add_alternate("J", "H");
+            }
+            return 1;
+        }
+        if current == 0 {
+            // `Yankelovich`, `Jankelowicz`
+            self.add_alternate("J", "A");
+        } else if self.is_vowel(current - 1)
+            && !self.slavo_germanic
+            && matches!(self.at(current + 1), 'A' | 'O')
+        {
+            // Spanish pronunciation of e.g. `bajador`.
+            self.add_alternate("J", "H");
+        } else if current + 1 == self.len {
+            self.add_alternate("J", "");
+        } else if !self.string_at(c + 1, &["L", "T", "K", "S", "N", "M", "B", "Z"])
+            && !self.string_at(c - 1, &["S", "K", "L"])
+        {
+            self.add("J");
+        }
+        if self.at(current + 1) == 'J' {
+            2
+        } else {
+            1
+        }
+    }
+
+    fn encode_s(&mut self, current: usize) -> usize {
+        let c = current as isize;
+        // `island`, `isle`, `carlisle`, `carlysle`
+        if self.string_at(c - 1, &["ISL", "YSL"]) {
+            return 1;
+        }
+        // `sugar-`
+        if current == 0 && self.string_at(c, &["SUGAR"]) {
+            self.add_alternate("X", "S");
+            return 1;
+        }
+        if self.string_at(c, &["SH"]) {
+            // Germanic.
+            if self.string_at(c + 1, &["HEIM", "HOEK", "HOLM", "HOLZ"]) {
+                self.add("S");
+            } else {
+                self.add("X");
+            }
+            return 2;
+        }
+        // Italian and armenian.
+        if self.string_at(c, &["SIO", "SIA"]) || self.string_at(c, &["SIAN"]) {
+            if self.slavo_germanic {
+                self.add("S");
+            } else {
+                self.add_alternate("S", "X");
+            }
+            return 3;
+        }
+        // German and anglicisations, e.g. `smith` matching `schmidt`,
+        // `snider` matching `schneider`. Also -sz- in slavic languages.
+        if (current == 0 && self.string_at(c + 1, &["M", "N", "L", "W"]))
+            || self.string_at(c + 1, &["Z"])
+        {
+            self.add_alternate("S", "X");
+            return if self.string_at(c + 1, &["Z"]) { 2 } else { 1 };
+        }
+        if self.string_at(c, &["SC"]) {
+            // Schlesinger's rule.
+            if self.at(current + 2) == 'H' {
+                // Dutch origin, e.g. `school`, `schooner`.
+                if self.string_at(c + 3, &["OO", "ER", "EN", "UY", "ED", "EM"]) {
+                    // `schermerhorn`, `schenker`
+                    if self.string_at(c + 3, &["ER", "EN"]) {
+                        self.add_alternate("X", "SK");
+                    } else {
+                        self.add("SK");
+                    }
+                } else if current == 0 && !self.is_vowel(3) && self.at(3) != 'W' {
+                    self.add_alternate("X", "S");
+                } else {
+                    self.add("X");
+                }
+                return 3;
+            }
+            if self.string_at(c + 2, &["I", "E", "Y"]) {
+                self.add("S");
+            } else {
+                self.add("SK");
+            }
+            return 3;
+        }
+        // French, e.g. `resnais`, `artois`.
+        if current + 1 == self.len && self.string_at(c - 2, &["AI", "OI"]) {
+            self.add_alternate("", "S");
+        } else {
+            self.add("S");
+        }
+        if self.string_at(c + 1, &["S", "Z"]) {
+            2
+        } else {
+            1
+        }
+    }
+
+    fn encode_w(&mut self, current: usize) -> usize {
+        let c = current as isize;
+        if self.string_at(c, &["WR"]) {
+            self.add("R");
+            return 2;
+        }
+        if current == 0 && (self.is_vowel(current + 1) || self.string_at(c, &["WH"])) {
+            // `Wasserman` should match `Vasserman`.
+            if self.is_vowel(current + 1) {
+                self.add_alternate("A", "F");
+            } else {
+                self.add("A");
+            }
+        }
+        // `Arnow` should match `Arnoff`.
+        if (current + 1 == self.len && current > 0 && self.is_vowel(current - 1))
+            || self.string_at(c - 1, &["EWSKI", "EWSKY", "OWSKI", "OWSKY"])
+            || self.string_at(0, &["SCH"])
+        {
+            self.add_alternate("", "F");
+            return 1;
+        }
+        // Polish, e.g. `filipowicz`.
+        if self.string_at(c, &["WICZ", "WITZ"]) {
+            self.add_alternate("TS", "FX");
+            return 4;
+        }
+        1
+    }
+}
+
+/// Builds automata matching words whose phonetic codes are within
+/// a given distance of the codes of a query.
+///
+/// Pure edit distance misses homophones such as `Smith` and `Schmidt`.
+/// Comparing their phonetic codes instead catches them, and the automaton
+/// on top of the codes tolerates the small discrepancies of the encodings.
+///
+/// ```rust
+/// # use levenshtein_automata::{DoubleMetaphone, LevenshteinAutomatonBuilder, PhoneticAutomatonBuilder};
+/// let builder = PhoneticAutomatonBuilder::new(DoubleMetaphone::default(), LevenshteinAutomatonBuilder::new(1, true));
+/// let query = builder.build_query("Schmidt");
+/// assert!(query.is_match("Smith"));
+/// assert!(query.is_match("Schmitt"));
+/// assert!(!query.is_match("Simpson"));
+/// ```
+pub struct PhoneticAutomatonBuilder<E> {
+    encoder: E,
+    builder: LevenshteinAutomatonBuilder,
+}
+
+impl<E: PhoneticEncoder> PhoneticAutomatonBuilder<E> {
+    /// Creates a builder comparing the codes produced by `encoder`
+    /// with the automata of `builder`.
+    pub fn new(encoder: E, builder: LevenshteinAutomatonBuilder) -> PhoneticAutomatonBuilder<E> {
+        PhoneticAutomatonBuilder { encoder, builder }
+    }
+
+    /// Returns the encoder.
+    pub fn encoder(&self) -> &E {
+        &self.encoder
+    }
+
+    /// Builds the automata for all of the codes of `query`.
+    pub fn build_query(&self, query: &str) -> PhoneticQuery<'_, E> {
+        let codes = self.encoder.encode(query);
+        let dfas = codes
+            .iter()
+            .map(|code| self.builder.build_dfa(code))
+            .collect();
+        PhoneticQuery {
+            encoder: &self.encoder,
+            codes,
+            dfas,
+        }
+    }
+}
+
+/// Phonetic query, built by [PhoneticAutomatonBuilder::build_query].
+pub struct PhoneticQuery<'a, E> {
+    encoder: &'a E,
+    codes: Vec<String>,
+    dfas: Vec<DFA>,
+}
+
+impl<'a, E: PhoneticEncoder> PhoneticQuery<'a, E> {
+    /// Returns the codes of the query.
+    pub fn codes(&self) -> &[String] {
+        &self.codes
+    }
+
+    /// Returns the smallest distance between the codes of the query
+    /// and the codes of `word`.
+    ///
+    /// If no pair of codes is within the maximum distance, or if either
+    /// word cannot be encoded, the result is an `AtLeast` distance.
+    pub fn eval(&self, word: &str) -> Distance {
+        let mut best: Option<u8> = None;
+        let mut lower_bound = Distance::AtLeast(0);
+        for code in self.encoder.encode(word) {
+            for dfa in &self.dfas {
+                match dfa.eval(&code) {
+                    Distance::Exact(d) => best = Some(best.map_or(d, |best| best.min(d))),
+                    at_least => lower_bound = at_least,
+                }
+            }
+        }
+        best.map(Distance::Exact).unwrap_or(lower_bound)
+    }
+
+    /// Returns true if `word` sounds like the query.
+    pub fn is_match(&self, word: &str) -> bool {
+        matches!(self.eval(word), Distance::Exact(_))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DoubleMetaphone, PhoneticAutomatonBuilder, PhoneticEncoder, Soundex};
+    use crate::{Distance, LevenshteinAutomatonBuilder};
+
+    #[test]
+    fn test_soundex() {
+        let codes = |word: &str| Soundex.encode(word);
+        assert_eq!(codes("Robert"), vec!["R163"]);
+        assert_eq!(codes("Rupert"), vec!["R163"]);
+        assert_eq!(codes("Rubin"), vec!["R150"]);
+        assert_eq!(codes("Ashcraft"), vec!["A261"]);
+        assert_eq!(codes("Tymczak"), vec!["T522"]);
+        assert_eq!(codes("Pfister"), vec!["P236"]);
+        assert_eq!(codes("Lee"), vec!["L000"]);
+        assert!(codes("").is_empty());
+        assert!(codes("寿司").is_empty());
+    }
+
+    #[test]
+    fn test_double_metaphone() {
+        let codes = |word: &str| DoubleMetaphone::default().encode(word);
+        assert_eq!(codes("Smith"), vec!["SM0", "XMT"]);
+        assert_eq!(codes("Schmidt"), vec!["XMT", "SMT"]);
+        assert_eq!(codes("Aubrey"), vec!["APR"]);
+        assert_eq!(codes("Richard"), vec!["RXRT", "RKRT"]);
+        assert_eq!(codes("Katherine"), vec!["K0RN", "KTRN"]);
+        assert_eq!(codes("Catherine"), vec!["K0RN", "KTRN"]);
+        assert_eq!(codes("Michael"), vec!["MKL", "MXL"]);
+        assert_eq!(codes("Jose"), vec!["HS"]);
+        assert_eq!(codes("Xavier"), vec!["SF", "SFR"]);
+        assert_eq!(codes("Knight"), vec!["NT"]);
+        assert!(codes("").is_empty());
+    }
+
+    #[test]
+    fn test_phonetic_query() {
+        let builder =
+            PhoneticAutomatonBuilder::new(Soundex, LevenshteinAutomatonBuilder::new(0, false));
+        let query = builder.build_query("Robert");
+        assert_eq!(query.codes(), &["R163".to_string()]);
+        assert_eq!(query.eval("Rupert"), Distance::Exact(0));
+        assert_eq!(query.eval("Rubin"), Distance::AtLeast(1));
+        assert_eq!(query.eval(""), Distance::AtLeast(0));
+        let builder = PhoneticAutomatonBuilder::new(
+            DoubleMetaphone::default(),
+            LevenshteinAutomatonBuilder::new(1, false),
+        );
+        let query = builder.build_query("Katherine");
+        assert_eq!(query.eval("Catherine"), Distance::Exact(0));
+        assert_eq!(query.eval("Kathryn"), Distance::Exact(0));
+        assert_eq!(query.eval("Karine"), Distance::Exact(1));
+        assert!(!query.is_match("Margaret"));
+    }
+}
@@ -0,0 +1,128 @@
+use super::{Distance, DFA};
+
+/// Evaluates a [DFA](./struct.DFA.html) on a sequence of strings, reusing
+/// the states reached on the prefix each string shares with the previous one.
+///
+/// Any sequence of strings can be evaluated, but the evaluator shines when
+/// they are sorted, e.g. when they are streamed from a term dictionary:
+/// consecutive strings then share long prefixes, and only their differing
+/// suffixes are walked.
+///
+/// ```rust
+/// # use levenshtein_automata::{Distance, LevenshteinAutomatonBuilder};
+/// let dfa = LevenshteinAutomatonBuilder::new(1, false).build_dfa("banana");
+/// let mut evaluator = dfa.prefix_evaluator();
+/// let distances: Vec<Distance> = ["banana", "bananas", "bandana", "bank"]
+///     .iter()
+///     .map(|term| evaluator.eval(term))
+///     .collect();
+/// assert_eq!(
+///     distances,
+///     vec![
+///         Distance::Exact(0),
+///         Distance::Exact(1),
+///         Distance::Exact(1),
+///         Distance::AtLeast(2)
+///     ]
+/// );
+/// ```
+pub struct PrefixEvaluator<'a> {
+    dfa: &'a DFA,
+    // Bytes of the previous string walked through the automaton.
+    previous: Vec<u8>,
+    // `states[i]` is the state reached on the first `i` bytes of `previous`.
+    states: Vec<u32>,
+}
+
+impl DFA {
+    /// Returns an evaluator sharing the work on the common prefixes of
+    /// consecutive strings.
+    pub fn prefix_evaluator(&self) -> PrefixEvaluator<'_> {
+        PrefixEvaluator {
+            dfa: self,
+            previous: Vec::new(),
+            states: vec![self.initial_state()],
+        }
+    }
+}
+
+impl<'a> PrefixEvaluator<'a> {
+    /// Returns the distance computed by the automaton on `text`.
+    ///
+    /// Once no accepting state can be reached, the rest of `text` is skipped.
+    pub fn eval<B: AsRef<[u8]>>(&mut self, text: B) -> Distance {
+        let text = text.as_ref();
+        let common_prefix_len = self
+            .previous
+            .iter()
+            .zip(text)
+            .take_while(|(left, right)| left == right)
+            .count();
+        self.previous.truncate(common_prefix_len);
+        self.states.truncate(common_prefix_len + 1);
+        let mut state = self.states[common_prefix_len];
+        for &b in &text[common_prefix_len..] {
+            if self.dfa.is_sink(state) {
+                break;
+            }
+            state = self.dfa.transition(state, b);
+            self.previous.push(b);
+            self.states.push(state);
+        }
+        self.dfa.distance(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Distance, LevenshteinAutomatonBuilder, Utf8DFABuilder};
+
+    #[test]
+    fn test_prefix_evaluator() {
+        let mut terms = vec![
+            "",
+            "a",
+            "ab",
+            "abc",
+            "abcd",
+            "abd",
+            "b",
+            "bcd",
+            "levenshtein",
+            "levenstein",
+            "lev",
+            "寿司",
+            "寿司屋",
+            "寿",
+            "xyzxyzxyz",
+            "xyzxyzxy",
+        ];
+        let builder = LevenshteinAutomatonBuilder::new(1, true);
+        for query in &["abc", "levenshtein", "寿司"] {
+            let dfa = builder.build_dfa(query);
+            // Unsorted, then sorted.
+            for _ in 0..2 {
+                let mut evaluator = dfa.prefix_evaluator();
+                for term in &terms {
+                    assert_eq!(evaluator.eval(term), dfa.eval(term), "{}", term);
+                }
+                terms.sort_unstable();
+            }
+        }
+    }
+
+    #[test]
+    fn test_prefix_evaluator_through_accepting_state_zero() {
+        // Parity of the number of chars, whose state `0` accepts.
+        let mut dfa_builder = Utf8DFABuilder::with_max_num_states(2);
+        dfa_builder.add_state(0, Distance::Exact(0), 1);
+        dfa_builder.add_state(1, Distance::AtLeast(1), 0);
+        dfa_builder.set_initial_state(1);
+        let dfa = dfa_builder.build();
+        let mut evaluator = dfa.prefix_evaluator();
+        for term in &["a", "aa", "aab", "ab", "b"] {
+            assert_eq!(evaluator.eval(term), dfa.eval(term), "{}", term);
+        }
+        assert_eq!(evaluator.eval("aab"), Distance::Exact(0));
+    }
+}
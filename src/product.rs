@@ -0,0 +1,323 @@
+use super::byte_classes::byte_classes;
+use super::{Distance, DFA, SINK_STATE};
+use std::collections::HashMap;
+
+impl DFA {
+    /// Returns the automaton accepting the strings accepted by both `self`
+    /// and `other`.
+    ///
+    /// The distance of an accepted string is the largest of its two
+    /// distances, and rejected strings are evaluated to
+    /// `Distance::AtLeast(_)`.
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::{Distance, LevenshteinAutomatonBuilder};
+    /// let fuzzy = LevenshteinAutomatonBuilder::new(1, false).build_dfa("abcd");
+    /// let starts_with_ab = LevenshteinAutomatonBuilder::new(0, false).build_prefix_dfa("ab");
+    /// let dfa = fuzzy.intersect(&starts_with_ab);
+    /// assert_eq!(dfa.eval("abed"), Distance::Exact(1));
+    /// assert!(matches!(dfa.eval("xbcd"), Distance::AtLeast(_)));
+    /// ```
+    pub fn intersect(&self, other: &DFA) -> DFA {
+        let dfas = [self, other];
+        product(
+            &dfas,
+            |states| match (self.distance(states[0]), other.distance(states[1])) {
+                (Distance::Exact(left), Distance::Exact(right)) => Distance::Exact(left.max(right)),
+                (left, right) => Distance::AtLeast(left.to_u8().max(right.to_u8())),
+            },
+            |states| !self.can_match(states[0]) || !other.can_match(states[1]),
+        )
+        .0
+    }
+
+    /// Returns the automaton accepting the strings accepted by `self` or
+    /// `other`.
+    ///
+    /// The distance of a string accepted by both automata is the smallest
+    /// of its two distances. The distance of a rejected string is a lower
+    /// bound of its distances.
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::{Distance, LevenshteinAutomatonBuilder};
+    /// let builder = LevenshteinAutomatonBuilder::new(1, false);
+    /// let dfa = builder.build_dfa("color").union(&builder.build_dfa("colour"));
+    /// assert_eq!(dfa.eval("colour"), Distance::Exact(0));
+    /// assert_eq!(dfa.eval("colr"), Distance::Exact(1));
+    /// assert_eq!(dfa.eval("coloures"), Distance::AtLeast(2));
+    /// ```
+    pub fn union(&self, other: &DFA) -> DFA {
+        let dfas = [self, other];
+        product(
+            &dfas,
+            |states| union_distance(&dfas, states),
+            |states| !self.can_match(states[0]) && !other.can_match(states[1]),
+        )
+        .0
+    }
+
+    /// Returns the automaton accepting the strings that are not within
+    /// `threshold` of `self`.
+    ///
+    /// Accepted strings are evaluated to `Distance::Exact(0)`, so that
+    /// intersecting the negation with another automaton keeps the distances
+    /// of the latter, and rejected strings to `Distance::AtLeast(1)`.
+    ///
+    /// The strings evaluated to `Distance::AtLeast(_)` by `self` are
+    /// considered beyond the threshold, which should therefore not exceed
+    /// the maximum distance of `self`.
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::{Distance, LevenshteinAutomatonBuilder};
+    /// let dfa = LevenshteinAutomatonBuilder::new(2, false).build_dfa("apple");
+    /// let not_apple = dfa.negate(1);
+    /// assert_eq!(not_apple.eval("apple"), Distance::AtLeast(1));
+    /// assert_eq!(not_apple.eval("aple"), Distance::AtLeast(1));
+    /// assert_eq!(not_apple.eval("ale"), Distance::Exact(0));
+    /// assert_eq!(not_apple.eval("banana"), Distance::Exact(0));
+    /// ```
+    pub fn negate(&self, threshold: u8) -> DFA {
+        // Every state is shifted by one, to insert a sink state that
+        // does not accept anything.
+        let mut transitions: Vec<[u32; 256]> = vec![[SINK_STATE; 256]];
+        let mut distances: Vec<Distance> = vec![Distance::AtLeast(1)];
+        for state_id in 0..self.num_states() as u32 {
+            let mut row = [SINK_STATE; 256];
+            for (b, to_state_id) in row.iter_mut().enumerate() {
+                *to_state_id = self.transition(state_id, b as u8) + 1;
+            }
+            transitions.push(row);
+            distances.push(match self.distance(state_id) {
+                Distance::Exact(d) if d <= threshold => Distance::AtLeast(1),
+                _ => Distance::Exact(0),
+            });
+        }
+        DFA::from_parts(transitions, distances, self.initial_state() + 1)
+    }
+}
+
+// Builds the product of several automata, restricted to the tuples of states
+// reachable from their initial states.
+//
+// `distance` returns the distance of a tuple of states, and `is_dead` tells
+// whether no accepting tuple can be reached from it; dead tuples are all
+// merged into the sink state `0`, whose distance is the distance of the tuple
+// of the states `0` if it is dead, and of the first dead tuple reached
+// otherwise.
+//
+// Returns the automaton, along with the tuple of each of its states.
+// The tuple of the sink state is only meaningful if it was reached.
+pub(crate) fn product<D, F>(dfas: &[&DFA], mut distance: D, mut is_dead: F) -> (DFA, Vec<Vec<u32>>)
+where
+    D: FnMut(&[u32]) -> Distance,
+    F: FnMut(&[u32]) -> bool,
+{
+    let representatives = joint_representatives(dfas);
+    let sink: Vec<u32> = vec![SINK_STATE; dfas.len()];
+    let initial: Vec<u32> = dfas.iter().map(|dfa| dfa.initial_state()).collect();
+    let mut tuples: Vec<Vec<u32>> = vec![sink.clone()];
+    let mut ids: HashMap<Vec<u32>, u32> = HashMap::new();
+    // The states `0` of the automata are not necessarily sink states, in
+    // which case the sink state stands for the first dead tuple reached.
+    let mut is_sink_set = is_dead(&sink);
+    let initial_state = if is_dead(&initial) {
+        if !is_sink_set {
+            tuples[0] = initial.clone();
+            is_sink_set = true;
+        }
+        SINK_STATE
+    } else {
+        tuples.push(initial.clone());
+        ids.insert(initial, 1);
+        1
+    };
+    let mut transitions: Vec<[u32; 256]> = vec![[SINK_STATE; 256]];
+    let mut state = 1;
+    while state < tuples.len() {
+        let tuple = tuples[state].clone();
+        let mut row = [SINK_STATE; 256];
+        for &(b, ref bytes) in &representatives {
+            let next: Vec<u32> = dfas
+                .iter()
+                .zip(&tuple)
+                .map(|(dfa, &from)| dfa.transition(from, b))
+                .collect();
+            let next_state = if is_dead(&next) {
+                if !is_sink_set {
+                    tuples[0] = next;
+                    is_sink_set = true;
+                }
+                SINK_STATE
+            } else {
+                let num_states = tuples.len() as u32;
+                *ids.entry(next).or_insert_with_key(|next| {
+                    tuples.push(next.clone());
+                    num_states
+                })
+            };
+            for &b in bytes {
+                row[b as usize] = next_state;
+            }
+        }
+        transitions.push(row);
+        state += 1;
+    }
+    let mut distances: Vec<Distance> = tuples.iter().map(|tuple| distance(tuple)).collect();
+    if !is_sink_set {
+        // The sink state is unreachable, but should still reject.
+        distances[0] = Distance::AtLeast(distances[0].to_u8());
+    }
+    (
+        DFA::from_parts(transitions, distances, initial_state),
+        tuples,
+    )
+}
+
+// Smallest distance of the states matching, or lower bound of the distances
+// if none of them match.
+pub(crate) fn union_distance(dfas: &[&DFA], states: &[u32]) -> Distance {
+    let distances = dfas
+        .iter()
+        .zip(states)
+        .map(|(dfa, &state)| dfa.distance(state));
+    let exact = distances
+        .clone()
+        .filter_map(|distance| match distance {
+            Distance::Exact(d) => Some(d),
+            Distance::AtLeast(_) => None,
+        })
+        .min();
+    match exact {
+        Some(d) => Distance::Exact(d),
+        None => Distance::AtLeast(
+            distances
+                .map(|distance| distance.to_u8())
+                .min()
+                .unwrap_or(0),
+        ),
+    }
+}
+
+// Groups the bytes on which all of the automata behave the same way,
+// as `(representative, bytes)` pairs.
+fn joint_representatives(dfas: &[&DFA]) -> Vec<(u8, Vec<u8>)> {
+    let classes: Vec<Box<[u8; 256]>> = dfas.iter().map(|dfa| byte_classes(dfa).0).collect();
+    let mut groups: Vec<(u8, Vec<u8>)> = Vec::new();
+    let mut ids: HashMap<Vec<u8>, usize> = HashMap::new();
+    for b in 0..=255u8 {
+        let key: Vec<u8> = classes.iter().map(|classes| classes[b as usize]).collect();
+        let num_groups = groups.len();
+        let id = *ids.entry(key).or_insert(num_groups);
+        if id == num_groups {
+            groups.push((b, Vec::new()));
+        }
+        groups[id].1.push(b);
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Distance, LevenshteinAutomatonBuilder, Utf8DFABuilder, SINK_STATE};
+
+    #[test]
+    fn test_negate() {
+        let dfa = LevenshteinAutomatonBuilder::new(2, true).build_dfa("abcd");
+        for &threshold in &[0, 1, 2] {
+            let negation = dfa.negate(threshold);
+            for candidate in &[
+                "", "a", "ab", "abcd", "abdc", "bacd", "abcdef", "xyz", "寿司",
+            ] {
+                let expected = match dfa.eval(candidate) {
+                    Distance::Exact(d) if d <= threshold => Distance::AtLeast(1),
+                    _ => Distance::Exact(0),
+                };
+                assert_eq!(negation.eval(candidate), expected, "{}", candidate);
+            }
+            assert!(!negation.can_match(SINK_STATE));
+        }
+    }
+
+    #[test]
+    fn test_union() {
+        let left = LevenshteinAutomatonBuilder::new(2, true).build_dfa("levenshtein");
+        let right = LevenshteinAutomatonBuilder::new(1, false).build_dfa("damerau");
+        let dfa = left.union(&right);
+        for candidate in &[
+            "",
+            "levenshtein",
+            "levenstein",
+            "levenstain",
+            "damerau",
+            "damrau",
+            "dameaur",
+            "abc",
+        ] {
+            let expected = match (left.eval(candidate), right.eval(candidate)) {
+                (Distance::Exact(l), Distance::Exact(r)) => Some(l.min(r)),
+                (Distance::Exact(d), _) | (_, Distance::Exact(d)) => Some(d),
+                _ => None,
+            };
+            match expected {
+                Some(d) => assert_eq!(dfa.eval(candidate), Distance::Exact(d), "{}", candidate),
+                None => assert!(
+                    matches!(dfa.eval(candidate), Distance::AtLeast(_)),
+                    "{}",
+                    candidate
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn test_intersect() {
+        let left = LevenshteinAutomatonBuilder::new(2, true).build_dfa("levenshtein");
+        let right = LevenshteinAutomatonBuilder::new(1, false).build_dfa("levenstein");
+        let dfa = left.intersect(&right);
+        for candidate in &[
+            "",
+            "levenshtein",
+            "levenstein",
+            "levenstain",
+            "levensthein",
+            "lebenshtein",
+            "leveshtein",
+            "abc",
+        ] {
+            let expected = match (left.eval(candidate), right.eval(candidate)) {
+                (Distance::Exact(l), Distance::Exact(r)) => Some(l.max(r)),
+                _ => None,
+            };
+            match expected {
+                Some(d) => assert_eq!(dfa.eval(candidate), Distance::Exact(d), "{}", candidate),
+                None => assert!(
+                    matches!(dfa.eval(candidate), Distance::AtLeast(_)),
+                    "{}",
+                    candidate
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn test_product_with_accepting_state_zero() {
+        // Parity of the number of chars, whose state `0` accepts.
+        let mut dfa_builder = Utf8DFABuilder::with_max_num_states(2);
+        dfa_builder.add_state(0, Distance::Exact(0), 1);
+        dfa_builder.add_state(1, Distance::AtLeast(1), 0);
+        dfa_builder.set_initial_state(0);
+        let even = dfa_builder.build();
+        let fuzzy = LevenshteinAutomatonBuilder::new(1, false).build_dfa("abcd");
+        let intersection = even.intersect(&fuzzy);
+        let union = even.union(&fuzzy);
+        for candidate in &["", "ab", "abc", "abcd", "abcde", "xyzw", "xyz"] {
+            let is_even = matches!(even.eval(candidate), Distance::Exact(_));
+            let is_fuzzy = matches!(fuzzy.eval(candidate), Distance::Exact(_));
+            let accepts = |dfa: &crate::DFA| matches!(dfa.eval(candidate), Distance::Exact(_));
+            assert_eq!(accepts(&intersection), is_even && is_fuzzy, "{}", candidate);
+            assert_eq!(accepts(&union), is_even || is_fuzzy, "{}", candidate);
+        }
+        assert!(!intersection.can_match(SINK_STATE));
+        assert!(!union.can_match(SINK_STATE));
+    }
+}
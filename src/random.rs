@@ -0,0 +1,233 @@
+use super::{DfaRef, Distance, DFA, SINK_STATE};
+
+/// Seeded generator of random automata, to test code storing
+/// or walking automata against diverse shapes.
+///
+/// Each generated [DFA](./struct.DFA.html) comes with a
+/// [ReferenceDfa](./struct.ReferenceDfa.html) describing the same
+/// automaton in a deliberately naive way.
+///
+/// Generated automata are valid: all of the transitions lead to existing
+/// states, and the state `SINK_STATE` is a sink. The same seed always
+/// yields the same sequence of automata.
+///
+/// ```rust
+/// # use levenshtein_automata::RandomDfaGenerator;
+/// let mut generator = RandomDfaGenerator::new(42);
+/// for _ in 0..10 {
+///     let (dfa, reference) = generator.generate();
+///     let text = generator.random_text(&reference, 20);
+///     assert_eq!(dfa.eval(&text), reference.eval(&text));
+/// }
+/// ```
+pub struct RandomDfaGenerator {
+    rng: SplitMix64,
+    max_num_states: u32,
+    max_num_edges: usize,
+    max_distance: u8,
+}
+
+/// Naive model of an automaton generated by a
+/// [RandomDfaGenerator](./struct.RandomDfaGenerator.html).
+///
+/// Each state is described by a sorted list of byte ranges
+/// and their destination.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReferenceDfa {
+    /// Outgoing edges of each state, as `(start, end, destination)`.
+    /// The ranges are inclusive and cover all of the bytes.
+    pub edges: Vec<Vec<(u8, u8, u32)>>,
+    /// Distance of each state.
+    pub distances: Vec<Distance>,
+    /// Initial state.
+    pub initial_state: u32,
+}
+
+impl ReferenceDfa {
+    /// Returns the destination state reached after consuming a given byte.
+    pub fn transition(&self, from_state_id: u32, b: u8) -> u32 {
+        self.edges[from_state_id as usize]
+            .iter()
+            .find(|&&(start, end, _)| start <= b && b <= end)
+            .map(|&(_, _, to)| to)
+            .expect("The edges of a state cover all of the bytes")
+    }
+
+    /// Consumes all of the bytes and returns the resulting distance.
+    pub fn eval<B: AsRef<[u8]>>(&self, text: B) -> Distance {
+        let state = text
+            .as_ref()
+            .iter()
+            .fold(self.initial_state, |state, &b| self.transition(state, b));
+        self.distances[state as usize]
+    }
+}
+
+impl RandomDfaGenerator {
+    /// Creates a generator producing automata of up to 16 states,
+    /// with up to 8 edges per state and distances up to 4.
+    pub fn new(seed: u64) -> RandomDfaGenerator {
+        RandomDfaGenerator {
+            rng: SplitMix64(seed),
+            max_num_states: 16,
+            max_num_edges: 8,
+            max_distance: 4,
+        }
+    }
+
+    /// Sets the maximum number of states of the generated automata.
+    pub fn max_num_states(mut self, max_num_states: u32) -> RandomDfaGenerator {
+        assert!(max_num_states > 0, "Automata have at least one state");
+        self.max_num_states = max_num_states;
+        self
+    }
+
+    /// Sets the maximum number of edges of each state.
+    pub fn max_num_edges(mut self, max_num_edges: usize) -> RandomDfaGenerator {
+        assert!(
+            (1..=256).contains(&max_num_edges),
+            "A state has between 1 and 256 edges"
+        );
+        self.max_num_edges = max_num_edges;
+        self
+    }
+
+    /// Sets the maximum distance of the states.
+    pub fn max_distance(mut self, max_distance: u8) -> RandomDfaGenerator {
+        self.max_distance = max_distance;
+        self
+    }
+
+    /// Generates a random automaton, and its reference model.
+    pub fn generate(&mut self) -> (DFA, ReferenceDfa) {
+        let num_states = 1 + self.rng.below(self.max_num_states as u64) as u32;
+        let mut edges: Vec<Vec<(u8, u8, u32)>> = Vec::with_capacity(num_states as usize);
+        let mut distances: Vec<Distance> = Vec::with_capacity(num_states as usize);
+        for state_id in 0..num_states {
+            let distance = self.rng.below(u64::from(self.max_distance) + 1) as u8;
+            if state_id == SINK_STATE {
+                edges.push(vec![(0, 255, SINK_STATE)]);
+                distances.push(Distance::AtLeast(distance));
+                continue;
+            }
+            distances.push(if self.rng.below(2) == 0 {
+                Distance::Exact(distance)
+            } else {
+                Distance::AtLeast(distance)
+            });
+            let num_edges = 1 + self.rng.below(self.max_num_edges as u64) as usize;
+            let mut starts: Vec<u8> = (1..num_edges)
+                .map(|_| 1 + self.rng.below(255) as u8)
+                .collect();
+            starts.push(0);
+            starts.sort_unstable();
+            starts.dedup();
+            let state_edges = starts
+                .iter()
+                .enumerate()
+                .map(|(i, &start)| {
+                    let end = starts.get(i + 1).map(|&next| next - 1).unwrap_or(255);
+                    (start, end, self.rng.below(u64::from(num_states)) as u32)
+                })
+                .collect();
+            edges.push(state_edges);
+        }
+        let initial_state = self.rng.below(u64::from(num_states)) as u32;
+        let reference = ReferenceDfa {
+            edges,
+            distances,
+            initial_state,
+        };
+        let mut transitions: Vec<u32> = Vec::with_capacity(num_states as usize * 256);
+        for state_edges in &reference.edges {
+            for &(start, end, to) in state_edges {
+                transitions.extend((start..=end).map(|_| to));
+            }
+        }
+        let dfa = DfaRef::from_parts(&transitions, &reference.distances, initial_state)
+            .expect("Generated tables are consistent")
+            .to_dfa();
+        (dfa, reference)
+    }
+
+    /// Generates a random text of at most `max_len` bytes.
+    ///
+    /// Bytes are biased towards the bounds of the ranges of the
+    /// `reference` automaton, where bugs typically hide.
+    pub fn random_text(&mut self, reference: &ReferenceDfa, max_len: usize) -> Vec<u8> {
+        let len = self.rng.below(max_len as u64 + 1) as usize;
+        let mut bounds: Vec<u8> = reference
+            .edges
+            .iter()
+            .flat_map(|state_edges| {
+                state_edges
+                    .iter()
+                    .flat_map(|&(start, end, _)| vec![start, end])
+            })
+            .collect();
+        bounds.sort_unstable();
+        bounds.dedup();
+        (0..len)
+            .map(|_| {
+                if self.rng.below(4) == 0 {
+                    self.rng.below(256) as u8
+                } else {
+                    bounds[self.rng.below(bounds.len() as u64) as usize]
+                }
+            })
+            .collect()
+    }
+}
+
+// SplitMix64, which is small, fast, and good enough for generating test cases.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Returns a number in `0..n`. The bias is negligible for the small `n` used here.
+    fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RandomDfaGenerator;
+    use crate::SINK_STATE;
+
+    #[test]
+    fn test_random_dfas_match_reference() {
+        let mut generator = RandomDfaGenerator::new(7).max_num_states(8);
+        for _ in 0..200 {
+            let (dfa, reference) = generator.generate();
+            assert!(dfa.num_states() <= 8);
+            assert_eq!(dfa.successors(SINK_STATE), vec![SINK_STATE]);
+            for _ in 0..10 {
+                let text = generator.random_text(&reference, 16);
+                assert_eq!(dfa.eval(&text), reference.eval(&text));
+                assert_eq!(dfa.eval_iter(text.iter().cloned()), reference.eval(&text));
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_dfas_are_deterministic() {
+        let mut left = RandomDfaGenerator::new(3);
+        let mut right = RandomDfaGenerator::new(3);
+        for _ in 0..20 {
+            let (left_dfa, left_reference) = left.generate();
+            let (right_dfa, right_reference) = right.generate();
+            assert_eq!(left_reference, right_reference);
+            assert_eq!(left_dfa.to_canonical_text(), right_dfa.to_canonical_text());
+        }
+        let (_, other_reference) = RandomDfaGenerator::new(4).generate();
+        assert_ne!(other_reference, RandomDfaGenerator::new(3).generate().1);
+    }
+}
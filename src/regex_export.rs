@@ -0,0 +1,173 @@
+use super::{Distance, DFA};
+use regex_automata::dfa::{dense, StartKind};
+use regex_automata::nfa::thompson::{self, Transition};
+use regex_automata::util::primitives::StateID;
+use regex_automata::MatchKind;
+use std::error;
+use std::fmt;
+
+/// Error returned by [DFA::to_regex_automata](./struct.DFA.html#method.to_regex_automata)
+/// when the automaton exceeds the limits of `regex-automata`.
+#[derive(Clone, Debug)]
+pub enum RegexExportError {
+    /// The intermediary Thompson NFA could not be built.
+    Nfa(thompson::BuildError),
+    /// The dense DFA could not be built from the Thompson NFA.
+    Dfa(dense::BuildError),
+}
+
+impl fmt::Display for RegexExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegexExportError::Nfa(err) => write!(f, "cannot build the Thompson NFA: {}", err),
+            RegexExportError::Dfa(err) => write!(f, "cannot build the dense DFA: {}", err),
+        }
+    }
+}
+
+impl error::Error for RegexExportError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            RegexExportError::Nfa(err) => Some(err),
+            RegexExportError::Dfa(err) => Some(err),
+        }
+    }
+}
+
+impl From<thompson::BuildError> for RegexExportError {
+    fn from(err: thompson::BuildError) -> RegexExportError {
+        RegexExportError::Nfa(err)
+    }
+}
+
+impl From<dense::BuildError> for RegexExportError {
+    fn from(err: dense::BuildError) -> RegexExportError {
+        RegexExportError::Dfa(err)
+    }
+}
+
+impl DFA {
+    /// Converts the automaton into a dense DFA of the `regex-automata` crate,
+    /// so that it can be used with its search runtimes.
+    ///
+    /// The dense DFA has one pattern per distance: the pattern `d` matches
+    /// the texts at a distance of `d`. Since the automaton matches whole
+    /// texts, searches should be anchored and only the matches ending at
+    /// the end of the haystack are meaningful.
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::LevenshteinAutomatonBuilder;
+    /// use regex_automata::dfa::Automaton;
+    /// use regex_automata::{Anchored, Input};
+    ///
+    /// let dfa = LevenshteinAutomatonBuilder::new(1, true).build_dfa("kitten");
+    /// let dense = dfa.to_regex_automata().unwrap();
+    /// let input = Input::new("sitten").anchored(Anchored::Yes);
+    /// let half_match = dense.try_search_fwd(&input).unwrap().unwrap();
+    /// assert_eq!(half_match.offset(), 6);
+    /// assert_eq!(half_match.pattern().as_usize(), 1);
+    /// ```
+    ///
+    /// Returns an error if the automaton exceeds the limits of `regex-automata`.
+    pub fn to_regex_automata(&self) -> Result<dense::DFA<Vec<u32>>, RegexExportError> {
+        let num_patterns = self
+            .states()
+            .filter_map(|state_id| match self.distance(state_id) {
+                Distance::Exact(d) => Some(d as usize + 1),
+                Distance::AtLeast(_) => None,
+            })
+            .max()
+            .unwrap_or(1);
+        let nfa = self.to_thompson_nfa(num_patterns)?;
+        let dense = dense::Builder::new()
+            .configure(
+                dense::Config::new()
+                    .match_kind(MatchKind::All)
+                    .start_kind(StartKind::Anchored),
+            )
+            .build_from_nfa(&nfa)?;
+        Ok(dense)
+    }
+
+    // Builds a Thompson NFA made of one copy of the automaton per pattern,
+    // the pattern `d` matching at the states at a distance of `d`.
+    fn to_thompson_nfa(&self, num_patterns: usize) -> Result<thompson::NFA, thompson::BuildError> {
+        let mut builder = thompson::Builder::new();
+        builder.set_utf8(false);
+        let mut starts: Vec<StateID> = Vec::with_capacity(num_patterns);
+        for d in 0..num_patterns {
+            builder.start_pattern()?;
+            // Each state is a union of its transitions and possibly of a match,
+            // so that its id is known before its transitions are added.
+            let unions = self
+                .states()
+                .map(|_| builder.add_union(Vec::new()))
+                .collect::<Result<Vec<StateID>, _>>()?;
+            for state_id in self.states().filter(|&state_id| self.can_match(state_id)) {
+                let transitions: Vec<Transition> = self
+                    .edges(state_id)
+                    .filter(|edge| self.can_match(edge.to))
+                    .map(|edge| Transition {
+                        start: edge.start,
+                        end: edge.end,
+                        next: unions[edge.to as usize],
+                    })
+                    .collect();
+                let union = unions[state_id as usize];
+                let sparse = builder.add_sparse(transitions)?;
+                builder.patch(union, sparse)?;
+                if self.distance(state_id) == Distance::Exact(d as u8) {
+                    let match_state = builder.add_match()?;
+                    builder.patch(union, match_state)?;
+                }
+            }
+            let start = unions[self.initial_state() as usize];
+            builder.finish_pattern(start)?;
+            starts.push(start);
+        }
+        let start = builder.add_union(starts)?;
+        builder.build(start, start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Distance, LevenshteinAutomatonBuilder};
+    use regex_automata::dfa::Automaton;
+    use regex_automata::{Anchored, Input};
+
+    #[test]
+    fn test_to_regex_automata() {
+        let dfa = LevenshteinAutomatonBuilder::new(2, true).build_dfa("寿司abc");
+        let dense = dfa.to_regex_automata().unwrap();
+        assert_eq!(dense.pattern_len(), 3);
+        for candidate in &[
+            "",
+            "寿司abc",
+            "寿司acb",
+            "寿abc",
+            "司abc",
+            "寿司",
+            "寿司abcdef",
+            "xyz",
+        ] {
+            let input = Input::new(candidate).anchored(Anchored::Yes);
+            let mut state = dense.start_state_forward(&input).unwrap();
+            for &b in candidate.as_bytes() {
+                state = dense.next_state(state, b);
+            }
+            state = dense.next_eoi_state(state);
+            let distance = if dense.is_match_state(state) {
+                assert_eq!(dense.match_len(state), 1);
+                Some(dense.match_pattern(state, 0).as_usize() as u8)
+            } else {
+                None
+            };
+            let expected = match dfa.eval(candidate) {
+                Distance::Exact(d) => Some(d),
+                Distance::AtLeast(_) => None,
+            };
+            assert_eq!(distance, expected, "{}", candidate);
+        }
+    }
+}
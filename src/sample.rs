@@ -0,0 +1,210 @@
+use super::dfa::utf8_sequences;
+use super::{Distance, DFA};
+use rand::Rng;
+use std::collections::VecDeque;
+
+// Set of chars leading from a state to the same destination, as the
+// sequences of utf-8 byte ranges of their encoding.
+struct CharClass {
+    to: u32,
+    sequences: Vec<Vec<(u8, u8)>>,
+    num_chars: u32,
+}
+
+impl CharClass {
+    // Picks a char of the class uniformly, and appends its utf-8 encoding to `bytes`.
+    fn push_random_char<R: Rng + ?Sized>(&self, rng: &mut R, bytes: &mut Vec<u8>) {
+        let mut i = rng.gen_range(0..self.num_chars);
+        for sequence in &self.sequences {
+            let num_chars = sequence_num_chars(sequence);
+            if i < num_chars {
+                bytes.extend(
+                    sequence
+                        .iter()
+                        .map(|&(start, end)| rng.gen_range(start..=end)),
+                );
+                return;
+            }
+            i -= num_chars;
+        }
+    }
+}
+
+fn sequence_num_chars(sequence: &[(u8, u8)]) -> u32 {
+    sequence
+        .iter()
+        .map(|&(start, end)| u32::from(end - start) + 1)
+        .product()
+}
+
+impl DFA {
+    /// Returns `n` random strings accepted by the automaton, of at most `max_len` chars.
+    ///
+    /// Strings are built char by char. At each step, all of the successor
+    /// states that can still reach an accepting state in time are equally likely,
+    /// and the char is then picked uniformly among the chars leading to that state.
+    /// As a result, the chars of the query are much more frequent than
+    /// arbitrary chars. The same string may be returned several times.
+    ///
+    /// Returns an empty vector if the automaton accepts no string of
+    /// at most `max_len` chars.
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::{Distance, LevenshteinAutomatonBuilder};
+    /// # use rand::SeedableRng;
+    /// let dfa = LevenshteinAutomatonBuilder::new(1, true).build_dfa("cat");
+    /// let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+    /// for text in dfa.sample_accepted(&mut rng, 10, 4) {
+    ///     assert!(matches!(dfa.eval(&text), Distance::Exact(_)));
+    /// }
+    /// ```
+    pub fn sample_accepted<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        n: usize,
+        max_len: usize,
+    ) -> Vec<String> {
+        let classes: Vec<Vec<CharClass>> = self
+            .states()
+            .map(|state| self.char_classes(state))
+            .collect();
+        let min_lens = min_accepted_lens(self, &classes);
+        let initial_state = self.initial_state();
+        match min_lens[initial_state as usize] {
+            Some(min_len) if min_len <= max_len => {}
+            _ => return Vec::new(),
+        }
+        let mut samples = Vec::with_capacity(n);
+        for _ in 0..n {
+            let mut bytes: Vec<u8> = Vec::new();
+            let mut state = initial_state;
+            let mut remaining = max_len;
+            loop {
+                let candidates: Vec<&CharClass> = classes[state as usize]
+                    .iter()
+                    .filter(|class| matches!(min_lens[class.to as usize], Some(min_len) if min_len < remaining))
+                    .collect();
+                if let Distance::Exact(_) = self.distance(state) {
+                    if candidates.is_empty() || rng.gen_range(0..=remaining) == 0 {
+                        break;
+                    }
+                }
+                let class = candidates[rng.gen_range(0..candidates.len())];
+                class.push_random_char(rng, &mut bytes);
+                state = class.to;
+                remaining -= 1;
+            }
+            samples.push(String::from_utf8(bytes).expect("Char classes only contain valid utf-8"));
+        }
+        samples
+    }
+
+    // Partitions the chars according to the state they lead to from `state`.
+    fn char_classes(&self, state: u32) -> Vec<CharClass> {
+        let mut sequences = Vec::new();
+        for sequence in utf8_sequences(0, 0x10_FFFF) {
+            self.split_byte_ranges(state, &sequence, &mut Vec::new(), &mut sequences);
+        }
+        let mut classes: Vec<CharClass> = Vec::new();
+        for (to, sequence) in sequences {
+            let num_chars = sequence_num_chars(&sequence);
+            match classes.iter_mut().find(|class| class.to == to) {
+                Some(class) => {
+                    class.sequences.push(sequence);
+                    class.num_chars += num_chars;
+                }
+                None => classes.push(CharClass {
+                    to,
+                    sequences: vec![sequence],
+                    num_chars,
+                }),
+            }
+        }
+        classes
+    }
+
+    fn split_byte_ranges(
+        &self,
+        state: u32,
+        byte_ranges: &[(u8, u8)],
+        prefix: &mut Vec<(u8, u8)>,
+        sequences: &mut Vec<(u32, Vec<(u8, u8)>)>,
+    ) {
+        let (start, end) = match byte_ranges.first() {
+            Some(&byte_range) => byte_range,
+            None => {
+                sequences.push((state, prefix.clone()));
+                return;
+            }
+        };
+        for edge in self.edges(state) {
+            if edge.end < start || edge.start > end {
+                continue;
+            }
+            prefix.push((edge.start.max(start), edge.end.min(end)));
+            self.split_byte_ranges(edge.to, &byte_ranges[1..], prefix, sequences);
+            prefix.pop();
+        }
+    }
+}
+
+// Returns, for each state, the minimum number of chars to consume
+// to reach an accepting state, if any.
+fn min_accepted_lens(dfa: &DFA, classes: &[Vec<CharClass>]) -> Vec<Option<usize>> {
+    let mut predecessors: Vec<Vec<u32>> = vec![Vec::new(); dfa.num_states()];
+    for (state, state_classes) in classes.iter().enumerate() {
+        for class in state_classes {
+            predecessors[class.to as usize].push(state as u32);
+        }
+    }
+    let mut min_lens: Vec<Option<usize>> = vec![None; dfa.num_states()];
+    let mut queue: VecDeque<u32> = VecDeque::new();
+    for state in dfa.states() {
+        if let Distance::Exact(_) = dfa.distance(state) {
+            min_lens[state as usize] = Some(0);
+            queue.push_back(state);
+        }
+    }
+    while let Some(state) = queue.pop_front() {
+        let len = min_lens[state as usize].unwrap() + 1;
+        for &predecessor in &predecessors[state as usize] {
+            if min_lens[predecessor as usize].is_none() {
+                min_lens[predecessor as usize] = Some(len);
+                queue.push_back(predecessor);
+            }
+        }
+    }
+    min_lens
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Distance, LevenshteinAutomatonBuilder};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_sample_accepted() {
+        let dfa = LevenshteinAutomatonBuilder::new(2, true).build_dfa("寿司abc");
+        let mut rng = StdRng::seed_from_u64(3);
+        let samples = dfa.sample_accepted(&mut rng, 200, 8);
+        assert_eq!(samples.len(), 200);
+        for sample in &samples {
+            assert!(sample.chars().count() <= 8);
+            assert!(matches!(dfa.eval(sample), Distance::Exact(_)));
+        }
+        assert!(samples.iter().any(|sample| sample != &samples[0]));
+        let mut other_rng = StdRng::seed_from_u64(3);
+        assert_eq!(dfa.sample_accepted(&mut other_rng, 200, 8), samples);
+    }
+
+    #[test]
+    fn test_sample_accepted_too_short() {
+        let dfa = LevenshteinAutomatonBuilder::new(1, false).build_dfa("abcdef");
+        let mut rng = StdRng::seed_from_u64(0);
+        assert!(dfa.sample_accepted(&mut rng, 10, 4).is_empty());
+        let samples = dfa.sample_accepted(&mut rng, 10, 5);
+        assert_eq!(samples.len(), 10);
+        assert!(samples.iter().all(|sample| sample.chars().count() == 5));
+    }
+}
@@ -0,0 +1,180 @@
+use super::{Distance, DFA};
+use std::collections::HashSet;
+
+/// Set of bytes on which an automaton leaves its initial state.
+///
+/// This is the skip loop of [DFA::find_iter](./struct.DFA.html#method.find_iter):
+/// when searching a text with a suffix automaton, most positions are read
+/// in the initial state, and can be skipped at once if only a handful of
+/// bytes leave it (this is typically the case with a distance of 0). The
+/// skip loop relies on `memchr` when the `memchr` feature is enabled, and
+/// on a byte by byte search otherwise.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum StartBytes {
+    All,
+    None,
+    One(u8),
+    Two(u8, u8),
+    Three(u8, u8, u8),
+}
+
+impl StartBytes {
+    /// Returns the first bytes of the chars on which the automaton leaves
+    /// its initial state, or `StartBytes::All` if its initial state is
+    /// accepting.
+    ///
+    /// In a valid utf-8 text, all of the chars before the next position
+    /// returned by [StartBytes::next_start] keep the automaton in its
+    /// initial state.
+    pub fn leaving_initial_state(dfa: &DFA) -> StartBytes {
+        let initial_state = dfa.initial_state();
+        if let Distance::Exact(_) = dfa.distance(initial_state) {
+            return StartBytes::All;
+        }
+        let mut returning: HashSet<(u32, u8)> = HashSet::new();
+        StartBytes::from_bytes((0..=255u8).filter(|&b| {
+            let num_continuation_bytes = match b {
+                0x00..=0x7F => 0,
+                0xC0..=0xDF => 1,
+                0xE0..=0xEF => 2,
+                0xF0..=0xF7 => 3,
+                // These bytes never start a char.
+                _ => return false,
+            };
+            let state = dfa.transition(initial_state, b);
+            !returns_to(
+                dfa,
+                state,
+                num_continuation_bytes,
+                initial_state,
+                &mut returning,
+            )
+        }))
+    }
+
+    fn from_bytes<I: Iterator<Item = u8>>(bytes: I) -> StartBytes {
+        let mut start_bytes = Vec::with_capacity(4);
+        for b in bytes {
+            if start_bytes.len() == 3 {
+                return StartBytes::All;
+            }
+            start_bytes.push(b);
+        }
+        match start_bytes[..] {
+            [] => StartBytes::None,
+            [b1] => StartBytes::One(b1),
+            [b1, b2] => StartBytes::Two(b1, b2),
+            [b1, b2, b3] => StartBytes::Three(b1, b2, b3),
+            _ => StartBytes::All,
+        }
+    }
+
+    /// Returns the first position `>= from` at which a match may start.
+    pub fn next_start(&self, text: &[u8], from: usize) -> Option<usize> {
+        if from > text.len() {
+            return None;
+        }
+        let haystack = &text[from..];
+        let position = match *self {
+            StartBytes::All => Some(0),
+            StartBytes::None => None,
+            StartBytes::One(b1) => find_one(b1, haystack),
+            StartBytes::Two(b1, b2) => find_two(b1, b2, haystack),
+            StartBytes::Three(b1, b2, b3) => find_three(b1, b2, b3, haystack),
+        };
+        position.map(|position| from + position)
+    }
+}
+
+// Returns true iff all of the sequences of `num_continuation_bytes`
+// continuation bytes lead from `state` to `target`. `returning` holds the
+// pairs of states and numbers of bytes for which this is known to hold.
+fn returns_to(
+    dfa: &DFA,
+    state: u32,
+    num_continuation_bytes: u8,
+    target: u32,
+    returning: &mut HashSet<(u32, u8)>,
+) -> bool {
+    if num_continuation_bytes == 0 {
+        return state == target;
+    }
+    if returning.contains(&(state, num_continuation_bytes)) {
+        return true;
+    }
+    for b in 0x80..=0xBFu8 {
+        let successor = dfa.transition(state, b);
+        if !returns_to(
+            dfa,
+            successor,
+            num_continuation_bytes - 1,
+            target,
+            returning,
+        ) {
+            return false;
+        }
+    }
+    returning.insert((state, num_continuation_bytes));
+    true
+}
+
+#[cfg(feature = "memchr")]
+fn find_one(b1: u8, haystack: &[u8]) -> Option<usize> {
+    memchr::memchr(b1, haystack)
+}
+
+#[cfg(feature = "memchr")]
+fn find_two(b1: u8, b2: u8, haystack: &[u8]) -> Option<usize> {
+    memchr::memchr2(b1, b2, haystack)
+}
+
+#[cfg(feature = "memchr")]
+fn find_three(b1: u8, b2: u8, b3: u8, haystack: &[u8]) -> Option<usize> {
+    memchr::memchr3(b1, b2, b3, haystack)
+}
+
+#[cfg(not(feature = "memchr"))]
+fn find_one(b1: u8, haystack: &[u8]) -> Option<usize> {
+    haystack.iter().position(|&b| b == b1)
+}
+
+#[cfg(not(feature = "memchr"))]
+fn find_two(b1: u8, b2: u8, haystack: &[u8]) -> Option<usize> {
+    haystack.iter().position(|&b| b == b1 || b == b2)
+}
+
+#[cfg(not(feature = "memchr"))]
+fn find_three(b1: u8, b2: u8, b3: u8, haystack: &[u8]) -> Option<usize> {
+    haystack.iter().position(|&b| b == b1 || b == b2 || b == b3)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StartBytes;
+    use crate::LevenshteinAutomatonBuilder;
+
+    #[test]
+    fn test_start_bytes_leaving_initial_state() {
+        let exact = LevenshteinAutomatonBuilder::new(0, false);
+        let start_bytes = StartBytes::leaving_initial_state(&exact.build_suffix_dfa("needle"));
+        assert_eq!(start_bytes, StartBytes::One(b'n'));
+        assert_eq!(start_bytes.next_start(b"a needle", 0), Some(2));
+        assert_eq!(start_bytes.next_start(b"a needle", 3), None);
+        assert_eq!(start_bytes.next_start(b"a needle", 9), None);
+        let start_bytes = StartBytes::leaving_initial_state(&exact.build_suffix_dfa("éa"));
+        assert_eq!(start_bytes, StartBytes::One(0xC3));
+        let start_bytes = StartBytes::leaving_initial_state(&exact.build_suffix_dfa("ab寿"));
+        assert_eq!(start_bytes, StartBytes::One(b'a'));
+        let start_bytes = StartBytes::leaving_initial_state(&exact.build_suffix_dfa(""));
+        assert_eq!(start_bytes, StartBytes::All);
+        assert_eq!(start_bytes.next_start(b"abc", 1), Some(1));
+        let fuzzy = LevenshteinAutomatonBuilder::new(1, false);
+        let start_bytes = StartBytes::leaving_initial_state(&fuzzy.build_suffix_dfa("needle"));
+        assert_eq!(start_bytes, StartBytes::Two(b'e', b'n'));
+        let start_bytes = StartBytes::leaving_initial_state(&fuzzy.build_suffix_dfa("abcd"));
+        assert_eq!(start_bytes, StartBytes::Two(b'a', b'b'));
+        let fuzzier = LevenshteinAutomatonBuilder::new(3, false);
+        let start_bytes = StartBytes::leaving_initial_state(&fuzzier.build_suffix_dfa("abcd"));
+        assert_eq!(start_bytes, StartBytes::All);
+    }
+}
@@ -0,0 +1,110 @@
+use super::dfa::DfaRef;
+use super::{Distance, DFA};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+// Serialized form of a `DFA`, with a flat transition table.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "DFA")]
+struct SerdeDfa {
+    transitions: Vec<u32>,
+    distances: Vec<Distance>,
+    initial_state: u32,
+}
+
+/// The transition table is serialized as a flat sequence of `256` state ids
+/// per state. Deserialization fails if the tables are inconsistent.
+impl Serialize for DFA {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (transitions, distances) = {
+            let dfa_ref = self.as_dfa_ref();
+            let transitions: Vec<u32> = self
+                .states()
+                .flat_map(|state| (0..=255u8).map(move |b| dfa_ref.transition(state, b)))
+                .collect();
+            let distances: Vec<Distance> =
+                self.states().map(|state| self.distance(state)).collect();
+            (transitions, distances)
+        };
+        SerdeDfa {
+            transitions,
+            distances,
+            initial_state: self.initial_state(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DFA {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<DFA, D::Error> {
+        let dfa = SerdeDfa::deserialize(deserializer)?;
+        DfaRef::from_parts(&dfa.transitions, &dfa.distances, dfa.initial_state)
+            .map(|dfa_ref| dfa_ref.to_dfa())
+            .ok_or_else(|| D::Error::custom("inconsistent automaton tables"))
+    }
+}
+
+// Serializes a table of rows of `256` state ids as a flat sequence.
+pub(crate) mod flat_rows {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(rows: &[[u32; 256]], serializer: S) -> Result<S::Ok, S::Error> {
+        let flat: Vec<u32> = rows.iter().flat_map(|row| row.iter().cloned()).collect();
+        flat.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<[u32; 256]>, D::Error> {
+        let flat: Vec<u32> = Vec::deserialize(deserializer)?;
+        let rows = flat.chunks_exact(256);
+        if !rows.remainder().is_empty() {
+            return Err(D::Error::custom(
+                "the number of transitions is not a multiple of 256",
+            ));
+        }
+        Ok(rows
+            .map(|chunk| {
+                let mut row = [0u32; 256];
+                row.copy_from_slice(chunk);
+                row
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Distance, LevenshteinAutomatonBuilder, Utf8DFABuilder, DFA};
+
+    #[test]
+    fn test_serde_dfa() {
+        let dfa = LevenshteinAutomatonBuilder::new(1, true).build_dfa("寿司");
+        let json = serde_json::to_string(&dfa).unwrap();
+        let deserialized: DFA = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.to_bytes(), dfa.to_bytes());
+        assert_eq!(
+            serde_json::to_string(&Distance::Exact(1)).unwrap(),
+            r#"{"Exact":1}"#
+        );
+        let invalid = r#"{"transitions":[1],"distances":[{"Exact":0}],"initial_state":0}"#;
+        assert!(serde_json::from_str::<DFA>(invalid).is_err());
+    }
+
+    #[test]
+    fn test_serde_utf8_dfa_builder() {
+        let mut dfa_builder = Utf8DFABuilder::with_max_num_states(3);
+        dfa_builder.add_sink_state(0, Distance::AtLeast(1));
+        dfa_builder.set_initial_state(1);
+        dfa_builder
+            .add_state(1, Distance::AtLeast(1), 0)
+            .add_transition('é', 2);
+        let json = serde_json::to_string(&dfa_builder).unwrap();
+        let mut deserialized: Utf8DFABuilder = serde_json::from_str(&json).unwrap();
+        deserialized.add_sink_state(2, Distance::Exact(0));
+        let dfa = deserialized.build();
+        assert_eq!(dfa.eval("é"), Distance::Exact(0));
+        assert_eq!(dfa.eval("e"), Distance::AtLeast(1));
+    }
+}
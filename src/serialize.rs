@@ -0,0 +1,238 @@
+use super::{Distance, DFA};
+use std::fmt;
+
+const MAGIC: &[u8; 4] = b"LADF";
+const VERSION: u32 = 1;
+// Magic, version, number of states and initial state.
+const HEADER_LEN: usize = 16;
+
+/// Error returned when a byte buffer is not a serialized [DFA](./struct.DFA.html).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct InvalidSerializedDfa;
+
+impl fmt::Display for InvalidSerializedDfa {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("the bytes are not a serialized automaton")
+    }
+}
+
+impl std::error::Error for InvalidSerializedDfa {}
+
+impl DFA {
+    /// Serializes the automaton into a compact binary format.
+    ///
+    /// The format is a sequence of little endian integers: a header,
+    /// the distance of each state on two bytes, padding up to a multiple of
+    /// four bytes, and the transition table. It can be loaded back
+    /// without copy with [DFA::from_bytes](#method.from_bytes).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let num_states = self.num_states();
+        // The tables of the automaton are in memory: their sizes cannot overflow.
+        let distances_len = padded_distances_len(num_states).unwrap();
+        let mut bytes = Vec::with_capacity(HEADER_LEN + distances_len + num_states * 1024);
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(num_states as u32).to_le_bytes());
+        bytes.extend_from_slice(&self.initial_state().to_le_bytes());
+        for state in self.states() {
+            bytes.extend_from_slice(&match self.distance(state) {
+                Distance::Exact(d) => [0, d],
+                Distance::AtLeast(d) => [1, d],
+            });
+        }
+        bytes.resize(HEADER_LEN + distances_len, 0);
+        for state in self.states() {
+            for b in 0..=255u8 {
+                bytes.extend_from_slice(&self.transition(state, b).to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    /// Loads an automaton serialized by [DFA::to_bytes](#method.to_bytes).
+    ///
+    /// The returned automaton borrows `bytes`: nothing is allocated or copied,
+    /// the buffer is only validated, so that loading is about as fast as reading
+    /// the buffer once. The buffer does not need to be aligned.
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::{Distance, LevenshteinAutomatonBuilder, DFA};
+    /// let dfa = LevenshteinAutomatonBuilder::new(2, true).build_dfa("Levenshtein");
+    /// let bytes = dfa.to_bytes();
+    /// let loaded = DFA::from_bytes(&bytes).unwrap();
+    /// assert_eq!(loaded.eval("Levenshtain"), Distance::Exact(1));
+    /// assert_eq!(loaded.to_dfa().to_bytes(), bytes);
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Result<SerializedDfa<'_>, InvalidSerializedDfa> {
+        if bytes.len() < HEADER_LEN || &bytes[..4] != MAGIC || read_u32(bytes, 4) != VERSION {
+            return Err(InvalidSerializedDfa);
+        }
+        let num_states = read_u32(bytes, 8) as usize;
+        let initial_state = read_u32(bytes, 12);
+        // The number of states is read from the buffer: the sizes it implies
+        // may overflow on 32 bits targets.
+        let distances_end = padded_distances_len(num_states)
+            .and_then(|distances_len| distances_len.checked_add(HEADER_LEN))
+            .ok_or(InvalidSerializedDfa)?;
+        let len = num_states
+            .checked_mul(1024)
+            .and_then(|transitions_len| transitions_len.checked_add(distances_end))
+            .ok_or(InvalidSerializedDfa)?;
+        if initial_state as usize >= num_states || bytes.len() != len {
+            return Err(InvalidSerializedDfa);
+        }
+        let distances = &bytes[HEADER_LEN..HEADER_LEN + 2 * num_states];
+        if distances.chunks_exact(2).any(|distance| distance[0] > 1) {
+            return Err(InvalidSerializedDfa);
+        }
+        let transitions = &bytes[distances_end..];
+        if transitions
+            .chunks_exact(4)
+            .any(|state_id| read_u32(state_id, 0) as usize >= num_states)
+        {
+            return Err(InvalidSerializedDfa);
+        }
+        Ok(SerializedDfa {
+            transitions,
+            distances,
+            initial_state,
+        })
+    }
+}
+
+// Returns `None` if the length overflows.
+fn padded_distances_len(num_states: usize) -> Option<usize> {
+    num_states.checked_mul(2)?.div_ceil(4).checked_mul(4)
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    let mut buffer = [0u8; 4];
+    buffer.copy_from_slice(&bytes[offset..offset + 4]);
+    u32::from_le_bytes(buffer)
+}
+
+/// Automaton loaded by [DFA::from_bytes](./struct.DFA.html#method.from_bytes),
+/// borrowing the serialized buffer.
+#[derive(Clone, Copy, Debug)]
+pub struct SerializedDfa<'a> {
+    transitions: &'a [u8],
+    distances: &'a [u8],
+    initial_state: u32,
+}
+
+impl<'a> SerializedDfa<'a> {
+    /// Returns the initial state
+    pub fn initial_state(&self) -> u32 {
+        self.initial_state
+    }
+
+    /// Returns the number of states.
+    pub fn num_states(&self) -> usize {
+        self.distances.len() / 2
+    }
+
+    /// Returns the Levenshtein distance associated to the
+    /// current state.
+    pub fn distance(&self, state_id: u32) -> Distance {
+        let offset = 2 * state_id as usize;
+        match self.distances[offset] {
+            0 => Distance::Exact(self.distances[offset + 1]),
+            _ => Distance::AtLeast(self.distances[offset + 1]),
+        }
+    }
+
+    /// Returns the destination state reached after consuming a given byte.
+    pub fn transition(&self, from_state_id: u32, b: u8) -> u32 {
+        read_u32(
+            self.transitions,
+            (from_state_id as usize * 256 + b as usize) * 4,
+        )
+    }
+
+    /// See [DFA::eval](./struct.DFA.html#method.eval).
+    pub fn eval<B: AsRef<[u8]>>(&self, text: B) -> Distance {
+        let mut state = self.initial_state();
+        for &b in text.as_ref() {
+            state = self.transition(state, b);
+        }
+        self.distance(state)
+    }
+
+    /// Copies the automaton into an owned [DFA](./struct.DFA.html).
+    pub fn to_dfa(&self) -> DFA {
+        let transitions = (0..self.num_states() as u32)
+            .map(|state| {
+                let mut transitions = [0u32; 256];
+                for (b, transition) in transitions.iter_mut().enumerate() {
+                    *transition = self.transition(state, b as u8);
+                }
+                transitions
+            })
+            .collect();
+        let distances = (0..self.num_states() as u32)
+            .map(|state| self.distance(state))
+            .collect();
+        DFA::from_parts(transitions, distances, self.initial_state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{padded_distances_len, InvalidSerializedDfa, HEADER_LEN, MAGIC, VERSION};
+    use crate::{LevenshteinAutomatonBuilder, DFA};
+
+    #[test]
+    fn test_serialization_roundtrip() {
+        let builder = LevenshteinAutomatonBuilder::new(1, true);
+        for query in &["", "abc", "寿司"] {
+            let dfa = builder.build_prefix_dfa(query);
+            let bytes = dfa.to_bytes();
+            let loaded = DFA::from_bytes(&bytes).unwrap();
+            assert_eq!(loaded.num_states(), dfa.num_states());
+            for state in dfa.states() {
+                assert_eq!(loaded.distance(state), dfa.distance(state));
+                for b in 0..=255u8 {
+                    assert_eq!(loaded.transition(state, b), dfa.transition(state, b));
+                }
+            }
+            // The buffer does not need to be aligned.
+            let mut unaligned = vec![0u8];
+            unaligned.extend_from_slice(&bytes);
+            assert_eq!(
+                DFA::from_bytes(&unaligned[1..]).unwrap().eval("abd"),
+                dfa.eval("abd")
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_bytes() {
+        let bytes = LevenshteinAutomatonBuilder::new(1, false)
+            .build_dfa("ab")
+            .to_bytes();
+        assert_eq!(
+            DFA::from_bytes(&bytes[..bytes.len() - 1]).unwrap_err(),
+            InvalidSerializedDfa
+        );
+        assert!(DFA::from_bytes(&[]).is_err());
+        let mut corrupted = bytes.clone();
+        corrupted[0] = b'X';
+        assert!(DFA::from_bytes(&corrupted).is_err());
+        let mut corrupted = bytes.clone();
+        let len = corrupted.len();
+        corrupted[len - 1] = 0xFF;
+        assert!(DFA::from_bytes(&corrupted).is_err());
+    }
+
+    #[test]
+    fn test_invalid_num_states() {
+        assert_eq!(padded_distances_len(usize::MAX), None);
+        assert_eq!(padded_distances_len(3), Some(8));
+        let mut bytes = Vec::with_capacity(HEADER_LEN);
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        assert_eq!(DFA::from_bytes(&bytes).unwrap_err(), InvalidSerializedDfa);
+    }
+}
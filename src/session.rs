@@ -0,0 +1,127 @@
+use super::{LevenshteinAutomatonBuilder, Utf8DFABuilder, DFA};
+
+/// Query edited one char at a time, e.g. in a search-as-you-type UI.
+///
+/// Each edit builds the automaton of the new query from scratch, as
+/// [LevenshteinAutomatonBuilder::build_dfa](./struct.LevenshteinAutomatonBuilder.html#method.build_dfa)
+/// does: nothing is shared with the automaton of the previous query. What
+/// the session saves is the allocations: the buffers of the previous builds
+/// are reused, as with
+/// [.build_dfa_reusing(...)](./struct.LevenshteinAutomatonBuilder.html#method.build_dfa_reusing),
+/// and only the automaton of the current query is kept.
+///
+/// ```rust
+/// # use levenshtein_automata::{Distance, LevenshteinAutomatonBuilder};
+/// let builder = LevenshteinAutomatonBuilder::new(1, true);
+/// let mut session = builder.session(true);
+/// for chr in "lev".chars() {
+///     session.push_char(chr);
+/// }
+/// assert_eq!(session.dfa().eval("levenshtein"), Distance::Exact(0));
+/// session.push_char('i');
+/// assert_eq!(session.dfa().eval("levenshtein"), Distance::Exact(1));
+/// assert_eq!(session.pop_char(), Some('i'));
+/// assert_eq!(session.query(), "lev");
+/// assert_eq!(session.dfa().eval("levenshtein"), Distance::Exact(0));
+/// ```
+pub struct Session<'a> {
+    builder: &'a LevenshteinAutomatonBuilder,
+    prefix: bool,
+    query: String,
+    dfa: DFA,
+    dfa_builder: Utf8DFABuilder,
+}
+
+impl LevenshteinAutomatonBuilder {
+    /// Starts a session with an empty query.
+    ///
+    /// If `prefix` is true, the automata are built with
+    /// [.build_prefix_dfa(...)](#method.build_prefix_dfa), and otherwise
+    /// with [.build_dfa(...)](#method.build_dfa).
+    pub fn session(&self, prefix: bool) -> Session<'_> {
+        let mut dfa_builder = Utf8DFABuilder::with_max_num_states(0);
+        let dfa = if prefix {
+            self.build_prefix_dfa_reusing("", &mut dfa_builder)
+        } else {
+            self.build_dfa_reusing("", &mut dfa_builder)
+        };
+        Session {
+            builder: self,
+            prefix,
+            query: String::new(),
+            dfa,
+            dfa_builder,
+        }
+    }
+}
+
+impl<'a> Session<'a> {
+    fn rebuild(&mut self) {
+        self.dfa = if self.prefix {
+            self.builder
+                .build_prefix_dfa_reusing(&self.query, &mut self.dfa_builder)
+        } else {
+            self.builder
+                .build_dfa_reusing(&self.query, &mut self.dfa_builder)
+        };
+    }
+
+    /// Returns the current query.
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Returns the automaton of the current query.
+    pub fn dfa(&self) -> &DFA {
+        &self.dfa
+    }
+
+    /// Appends a char to the query, and returns the updated automaton.
+    pub fn push_char(&mut self, chr: char) -> &DFA {
+        self.query.push(chr);
+        self.rebuild();
+        &self.dfa
+    }
+
+    /// Removes the last char of the query and returns it, or returns `None`
+    /// if the query is empty.
+    ///
+    /// The automaton of the shortened query is rebuilt, as when a char is
+    /// appended.
+    pub fn pop_char(&mut self) -> Option<char> {
+        let chr = self.query.pop()?;
+        self.rebuild();
+        Some(chr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::LevenshteinAutomatonBuilder;
+
+    #[test]
+    fn test_session() {
+        let builder = LevenshteinAutomatonBuilder::new(1, false);
+        for &prefix in &[false, true] {
+            let mut session = builder.session(prefix);
+            assert_eq!(session.pop_char(), None);
+            for edit in &["a", "ab", "abc", "ab", "ab寿", "ab寿司", "ab", "", "x"] {
+                while !edit.starts_with(session.query()) {
+                    session.pop_char();
+                }
+                for chr in edit[session.query().len()..].chars() {
+                    session.push_char(chr);
+                }
+                assert_eq!(session.query(), *edit);
+                let expected = if prefix {
+                    builder.build_prefix_dfa(edit)
+                } else {
+                    builder.build_dfa(edit)
+                };
+                for text in &["", "a", "abc", "ab寿司", "abd", "x", "xyz"] {
+                    assert_eq!(session.dfa().eval(text), expected.eval(text));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use super::{Distance, DFA};
+use std::collections::VecDeque;
+use std::fmt::Write;
+
+impl DFA {
+    /// Returns a canonical text dump of the automaton, meant to be
+    /// compared to golden files in snapshot tests.
+    ///
+    /// States are renumbered in the order of a breadth-first traversal
+    /// from the initial state, visiting bytes in increasing order.
+    /// Unreachable states are dropped. The dump therefore only depends on
+    /// the language and the distances computed by the automaton, and
+    /// on its shape, not on the internal numbering of its states.
+    ///
+    /// Each state is followed by its outgoing edges, coalesced
+    /// into byte ranges. This format will remain stable across releases.
+    ///
+    /// For instance, the automaton of `"a"` with a distance of `0` starts with:
+    ///
+    /// ```text
+    /// initial 0
+    /// state 0 at_least 1
+    ///   [00-60] 1
+    ///   [61] 2
+    ///   [62-bf] 1
+    ///   [c0-df] 3
+    ///   ...
+    /// ```
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::LevenshteinAutomatonBuilder;
+    /// let dfa = LevenshteinAutomatonBuilder::new(0, false).build_dfa("a");
+    /// let text = dfa.to_canonical_text();
+    /// assert!(text.starts_with("initial 0\nstate 0 at_least 1\n  [00-60] 1\n  [61] 2\n"));
+    /// ```
+    pub fn to_canonical_text(&self) -> String {
+        let mut canonical_ids: Vec<Option<u32>> = vec![None; self.num_states()];
+        let mut order: Vec<u32> = Vec::new();
+        let mut queue: VecDeque<u32> = VecDeque::new();
+        canonical_ids[self.initial_state() as usize] = Some(0);
+        order.push(self.initial_state());
+        queue.push_back(self.initial_state());
+        while let Some(state_id) = queue.pop_front() {
+            for edge in self.edges(state_id) {
+                if canonical_ids[edge.to as usize].is_none() {
+                    canonical_ids[edge.to as usize] = Some(order.len() as u32);
+                    order.push(edge.to);
+                    queue.push_back(edge.to);
+                }
+            }
+        }
+        let mut text = String::from("initial 0\n");
+        for (canonical_id, &state_id) in order.iter().enumerate() {
+            let distance = match self.distance(state_id) {
+                Distance::Exact(d) => format!("exact {}", d),
+                Distance::AtLeast(d) => format!("at_least {}", d),
+            };
+            writeln!(text, "state {} {}", canonical_id, distance).unwrap();
+            for edge in self.edges(state_id) {
+                let to = canonical_ids[edge.to as usize].unwrap();
+                if edge.start == edge.end {
+                    writeln!(text, "  [{:02x}] {}", edge.start, to).unwrap();
+                } else {
+                    writeln!(text, "  [{:02x}-{:02x}] {}", edge.start, edge.end, to).unwrap();
+                }
+            }
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Distance, LevenshteinAutomatonBuilder, Utf8DFABuilder};
+
+    #[test]
+    fn test_canonical_text_ignores_numbering() {
+        // The same automaton, defined in two different orders.
+        let mut left = Utf8DFABuilder::with_max_num_states(3);
+        left.add_sink_state(0, Distance::AtLeast(1));
+        left.add_state(1, Distance::AtLeast(1), 0)
+            .add_transition('a', 2);
+        left.add_state(2, Distance::Exact(0), 0);
+        left.set_initial_state(1);
+        let mut right = Utf8DFABuilder::with_max_num_states(3);
+        right.add_state(2, Distance::Exact(0), 1);
+        right.add_sink_state(1, Distance::AtLeast(1));
+        right
+            .add_state(0, Distance::AtLeast(1), 1)
+            .add_transition('a', 2);
+        right.set_initial_state(0);
+        let left = left.build().to_canonical_text();
+        assert_eq!(left, right.build().to_canonical_text());
+        assert_eq!(
+            left,
+            LevenshteinAutomatonBuilder::new(0, false)
+                .build_dfa("a")
+                .to_canonical_text()
+        );
+    }
+
+    #[test]
+    fn test_canonical_text_is_deterministic() {
+        let builder = LevenshteinAutomatonBuilder::new(2, true);
+        let text = builder.build_dfa("寿司abc").to_canonical_text();
+        assert_eq!(text, builder.build_dfa("寿司abc").to_canonical_text());
+        assert!(text.starts_with("initial 0\nstate 0 at_least 3\n"));
+        assert_ne!(
+            text,
+            builder.build_prefix_dfa("寿司abc").to_canonical_text()
+        );
+    }
+}
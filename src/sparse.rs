@@ -0,0 +1,142 @@
+use super::{Distance, DFA};
+use std::mem;
+
+/// Representation of a [DFA](./struct.DFA.html) where each state only
+/// stores the transitions that differ from its default destination.
+///
+/// The default destination of a state is the most frequent one among its
+/// transitions. The other transitions are stored as `(byte, state)` pairs
+/// sorted by byte, and looked up by binary search.
+///
+/// Lookups are a bit slower than in a dense `DFA`, but most states of a
+/// Levenshtein automaton only have a handful of transitions differing from
+/// their default, so that large automata, e.g. union automata built with a
+/// [MultiPatternBuilder](./struct.MultiPatternBuilder.html), become much
+/// smaller. State ids are left untouched.
+///
+/// ```rust
+/// # use levenshtein_automata::{Distance, MultiPatternBuilder};
+/// let builder = MultiPatternBuilder::new(2, true);
+/// let dfa = builder.build_dfa(&[("levenshtein", 2), ("damerau", 1)]);
+/// let sparse = dfa.to_sparse();
+/// assert_eq!(sparse.eval("levenstein"), Distance::Exact(1));
+/// assert_eq!(sparse.eval("damrau"), Distance::Exact(1));
+/// assert!(sparse.heap_size() * 10 < dfa.heap_size());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SparseDfa {
+    defaults: Vec<u32>,
+    // The exceptions of `state` are at `offsets[state]..offsets[state + 1]`.
+    offsets: Vec<u32>,
+    bytes: Vec<u8>,
+    destinations: Vec<u32>,
+    distances: Vec<Distance>,
+    initial_state: u32,
+}
+
+impl DFA {
+    /// Builds the [SparseDfa](./struct.SparseDfa.html) equivalent to the automaton.
+    pub fn to_sparse(&self) -> SparseDfa {
+        let mut sparse = SparseDfa {
+            defaults: Vec::with_capacity(self.num_states()),
+            offsets: Vec::with_capacity(self.num_states() + 1),
+            bytes: Vec::new(),
+            destinations: Vec::new(),
+            distances: self.states().map(|state| self.distance(state)).collect(),
+            initial_state: self.initial_state(),
+        };
+        sparse.offsets.push(0);
+        let mut counts: Vec<u32> = vec![0; self.num_states()];
+        for state in self.states() {
+            for b in 0..=255u8 {
+                counts[self.transition(state, b) as usize] += 1;
+            }
+            let default = (0..=255u8)
+                .map(|b| self.transition(state, b))
+                .max_by_key(|&to_state| (counts[to_state as usize], std::cmp::Reverse(to_state)))
+                .unwrap();
+            for b in 0..=255u8 {
+                let to_state = self.transition(state, b);
+                counts[to_state as usize] = 0;
+                if to_state != default {
+                    sparse.bytes.push(b);
+                    sparse.destinations.push(to_state);
+                }
+            }
+            sparse.defaults.push(default);
+            sparse.offsets.push(sparse.bytes.len() as u32);
+        }
+        sparse
+    }
+}
+
+impl SparseDfa {
+    /// Returns the initial state
+    pub fn initial_state(&self) -> u32 {
+        self.initial_state
+    }
+
+    /// Returns the number of states.
+    pub fn num_states(&self) -> usize {
+        self.defaults.len()
+    }
+
+    /// Returns the Levenshtein distance associated to the
+    /// current state.
+    pub fn distance(&self, state_id: u32) -> Distance {
+        self.distances[state_id as usize]
+    }
+
+    /// Returns the destination state reached after consuming a given byte.
+    pub fn transition(&self, from_state_id: u32, b: u8) -> u32 {
+        let start = self.offsets[from_state_id as usize] as usize;
+        let end = self.offsets[from_state_id as usize + 1] as usize;
+        match self.bytes[start..end].binary_search(&b) {
+            Ok(i) => self.destinations[start + i],
+            Err(_) => self.defaults[from_state_id as usize],
+        }
+    }
+
+    /// Returns the Levenshtein distance computed by the automaton on `text`.
+    pub fn eval<B: AsRef<[u8]>>(&self, text: B) -> Distance {
+        let mut state = self.initial_state();
+        for &b in text.as_ref() {
+            state = self.transition(state, b);
+        }
+        self.distance(state)
+    }
+
+    /// Returns the number of bytes allocated on the heap by the tables.
+    pub fn heap_size(&self) -> usize {
+        (self.defaults.capacity() + self.offsets.capacity() + self.destinations.capacity())
+            * mem::size_of::<u32>()
+            + self.bytes.capacity()
+            + self.distances.capacity() * mem::size_of::<Distance>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::LevenshteinAutomatonBuilder;
+
+    #[test]
+    fn test_sparse_transitions() {
+        for &(max_distance, transposition_cost_one) in &[(0, false), (1, true), (2, false)] {
+            let builder = LevenshteinAutomatonBuilder::new(max_distance, transposition_cost_one);
+            for query in &["", "abc", "寿司", "levenshtein"] {
+                for dfa in &[builder.build_dfa(query), builder.build_prefix_dfa(query)] {
+                    let sparse = dfa.to_sparse();
+                    assert_eq!(sparse.num_states(), dfa.num_states());
+                    assert_eq!(sparse.initial_state(), dfa.initial_state());
+                    for state in dfa.states() {
+                        assert_eq!(sparse.distance(state), dfa.distance(state));
+                        for b in 0..=255u8 {
+                            assert_eq!(sparse.transition(state, b), dfa.transition(state, b));
+                        }
+                    }
+                    assert!(sparse.heap_size() < dfa.heap_size());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use super::{Distance, DFA};
+use std::collections::HashSet;
+
+/// Size and shape of a [DFA](./struct.DFA.html), as returned by
+/// [DFA::stats](./struct.DFA.html#method.stats).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DfaStats {
+    /// Number of bytes allocated on the heap by the tables.
+    pub heap_size: usize,
+    /// Number of states, the sink state included.
+    pub num_states: usize,
+    /// Number of distinct transition rows.
+    pub num_distinct_rows: usize,
+    /// Number of accepting states.
+    pub num_accepting_states: usize,
+    /// Number of accepting states of each distance, indexed by distance.
+    pub num_accepting_states_by_distance: Vec<usize>,
+}
+
+impl DFA {
+    /// Returns the size and shape of the automaton.
+    ///
+    /// ```rust
+    /// # use levenshtein_automata::LevenshteinAutomatonBuilder;
+    /// let dfa = LevenshteinAutomatonBuilder::new(1, false).build_dfa("abc");
+    /// let stats = dfa.stats();
+    /// assert_eq!(stats.num_states, dfa.num_states());
+    /// assert!(stats.num_distinct_rows <= stats.num_states);
+    /// assert_eq!(stats.num_accepting_states_by_distance.len(), 2);
+    /// ```
+    pub fn stats(&self) -> DfaStats {
+        let mut rows: HashSet<Vec<u32>> = HashSet::new();
+        let mut num_accepting_states_by_distance: Vec<usize> = Vec::new();
+        for state_id in 0..self.num_states() as u32 {
+            rows.insert((0..=255u8).map(|b| self.transition(state_id, b)).collect());
+            if let Distance::Exact(d) = self.distance(state_id) {
+                let d = d as usize;
+                if num_accepting_states_by_distance.len() <= d {
+                    num_accepting_states_by_distance.resize(d + 1, 0);
+                }
+                num_accepting_states_by_distance[d] += 1;
+            }
+        }
+        DfaStats {
+            heap_size: self.heap_size(),
+            num_states: self.num_states(),
+            num_distinct_rows: rows.len(),
+            num_accepting_states: num_accepting_states_by_distance.iter().sum(),
+            num_accepting_states_by_distance,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Distance, LevenshteinAutomatonBuilder};
+
+    #[test]
+    fn test_stats() {
+        let dfa = LevenshteinAutomatonBuilder::new(2, true).build_dfa("levenshtein");
+        let stats = dfa.stats();
+        assert_eq!(stats.num_states, dfa.num_states());
+        assert!(stats.heap_size >= dfa.num_states() * 256 * 4);
+        let num_accepting_states = (0..dfa.num_states() as u32)
+            .filter(|&state_id| matches!(dfa.distance(state_id), Distance::Exact(_)))
+            .count();
+        assert_eq!(stats.num_accepting_states, num_accepting_states);
+        assert_eq!(stats.num_accepting_states_by_distance.len(), 3);
+        assert!(stats
+            .num_accepting_states_by_distance
+            .iter()
+            .all(|&count| count > 0));
+        // Some states only differ by their distance.
+        assert!(stats.num_distinct_rows < stats.num_states);
+    }
+}
@@ -0,0 +1,168 @@
+use super::{Distance, Error, LevenshteinAutomatonBuilder};
+use std::mem;
+
+/// Family of adversarial queries, producing large automata.
+///
+/// There are two sources of blowup:
+///
+/// * the layout of the query, which drives the number of Levenshtein states,
+/// * its multibyte chars, which require intermediary states in the utf-8 automaton.
+///   Chars that do not share their leading bytes are the most expensive.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum StressPattern {
+    /// Distinct ascii chars, e.g. `"abcdef"`.
+    DistinctAscii,
+    /// Two alternating ascii chars, e.g. `"ababab"`.
+    PeriodicAscii,
+    /// Distinct 4-bytes chars, with as few common leading bytes as possible.
+    DistinctFourBytes,
+    /// Two alternating 4-bytes chars.
+    PeriodicFourBytes,
+}
+
+impl StressPattern {
+    /// All of the patterns.
+    pub const ALL: [StressPattern; 4] = [
+        StressPattern::DistinctAscii,
+        StressPattern::PeriodicAscii,
+        StressPattern::DistinctFourBytes,
+        StressPattern::PeriodicFourBytes,
+    ];
+
+    /// Returns the query of `len` chars following the pattern.
+    pub fn query(self, len: usize) -> String {
+        match self {
+            StressPattern::DistinctAscii => (0..len)
+                .map(|i| char::from(b'!' + (i % 94) as u8))
+                .collect(),
+            StressPattern::PeriodicAscii => (0..len)
+                .map(|i| if i % 2 == 0 { 'a' } else { 'b' })
+                .collect(),
+            StressPattern::DistinctFourBytes => (0..len).map(four_bytes_char).collect(),
+            StressPattern::PeriodicFourBytes => (0..len).map(|i| four_bytes_char(i % 2)).collect(),
+        }
+    }
+}
+
+// Returns a 4-bytes char. Distinct `i` (up to `2^20`) give distinct chars,
+// scattered over the supplementary planes so that they rarely share leading bytes.
+fn four_bytes_char(i: usize) -> char {
+    // multiplying by an odd number is a bijection modulo `2^20`.
+    let offset = (i as u32).wrapping_mul(0x9E37_79B1) & 0xF_FFFF;
+    char::from_u32(0x1_0000 + offset).expect("Supplementary planes only contain valid chars")
+}
+
+/// Size of the automaton of an adversarial query.
+///
+/// See [worst_case_query](./fn.worst_case_query.html).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StressReport {
+    /// Pattern of the query.
+    pub pattern: StressPattern,
+    /// Query producing the largest automaton.
+    pub query: String,
+    /// Number of states of its automaton, or the error returned by
+    /// [LevenshteinAutomatonBuilder::try_build_dfa](./struct.LevenshteinAutomatonBuilder.html#method.try_build_dfa),
+    /// e.g. `Error::StateBudgetExceeded` if the builder has a
+    /// [maximum number of states](./struct.LevenshteinAutomatonBuilder.html#method.max_num_states).
+    pub num_states: Result<usize, Error>,
+}
+
+impl StressReport {
+    /// Returns an estimate of the memory used by the tables of the automaton,
+    /// in bytes, or `None` if it could not be built.
+    pub fn memory_usage(&self) -> Option<usize> {
+        let num_states = *self.num_states.as_ref().ok()?;
+        Some(num_states * (256 * mem::size_of::<u32>() + mem::size_of::<Distance>()))
+    }
+}
+
+/// Builds the automata of all of the [StressPattern](./enum.StressPattern.html)s
+/// for queries of `len` chars, and reports the largest one.
+///
+/// The automata that could not be built, e.g. because they exceed the
+/// maximum number of states of the builder, are reported first.
+///
+/// This makes it possible to validate construction limits, or to plan capacity,
+/// for a given builder and a maximum query length.
+///
+/// ```rust
+/// # use levenshtein_automata::{worst_case_query, Error, LevenshteinAutomatonBuilder};
+/// let builder = LevenshteinAutomatonBuilder::new(1, true);
+/// let report = worst_case_query(&builder, 8);
+/// assert_eq!(report.query.chars().count(), 8);
+/// assert!(report.num_states.unwrap() >= builder.build_dfa("abcdefgh").num_states());
+///
+/// let report = worst_case_query(&builder.max_num_states(100), 8);
+/// assert_eq!(
+///     report.num_states,
+///     Err(Error::StateBudgetExceeded { max_num_states: 100 })
+/// );
+/// ```
+pub fn worst_case_query(builder: &LevenshteinAutomatonBuilder, len: usize) -> StressReport {
+    StressPattern::ALL
+        .iter()
+        .map(|&pattern| {
+            let query = pattern.query(len);
+            let num_states = builder.try_build_dfa(&query).map(|dfa| dfa.num_states());
+            StressReport {
+                pattern,
+                query,
+                num_states,
+            }
+        })
+        .max_by_key(|report| *report.num_states.as_ref().unwrap_or(&usize::MAX))
+        .expect("There is at least one pattern")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{worst_case_query, StressPattern};
+    use crate::{Error, LevenshteinAutomatonBuilder};
+
+    #[test]
+    fn test_stress_patterns() {
+        for &pattern in &StressPattern::ALL {
+            assert_eq!(pattern.query(0), "");
+            assert_eq!(pattern.query(10).chars().count(), 10);
+        }
+        let query = StressPattern::DistinctFourBytes.query(100);
+        let mut chars: Vec<char> = query.chars().collect();
+        assert!(chars.iter().all(|c| c.len_utf8() == 4));
+        chars.sort_unstable();
+        chars.dedup();
+        assert_eq!(chars.len(), 100);
+        assert_eq!(StressPattern::PeriodicAscii.query(5), "ababa");
+    }
+
+    #[test]
+    fn test_worst_case_query() {
+        let builder = LevenshteinAutomatonBuilder::new(2, true);
+        let report = worst_case_query(&builder, 10);
+        assert_eq!(report.pattern, StressPattern::DistinctFourBytes);
+        let num_states = builder.build_dfa(&report.query).num_states();
+        assert_eq!(report.num_states, Ok(num_states));
+        for &pattern in &StressPattern::ALL {
+            assert!(builder.build_dfa(&pattern.query(10)).num_states() <= num_states);
+        }
+        assert!(report.memory_usage().unwrap() > num_states * 1024);
+    }
+
+    #[test]
+    fn test_worst_case_query_budget_exceeded() {
+        let builder = LevenshteinAutomatonBuilder::new(2, true);
+        let ascii_num_states = builder
+            .build_dfa(&StressPattern::DistinctAscii.query(10))
+            .num_states();
+        let builder = builder.max_num_states(ascii_num_states);
+        let report = worst_case_query(&builder, 10);
+        assert!(builder.try_build_dfa(&report.query).is_err());
+        assert_eq!(
+            report.num_states,
+            Err(Error::StateBudgetExceeded {
+                max_num_states: ascii_num_states
+            })
+        );
+        assert_eq!(report.memory_usage(), None);
+    }
+}
@@ -0,0 +1,189 @@
+use super::{CharAutomaton, Distance, DFA};
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+use hashbrown::HashMap;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+// State of the suffix automaton: the last column of the dynamic programming
+// table of approximate string matching (Sellers), where `column[i]` is the
+// smallest distance between `query[..i]` and a suffix of the text read so far,
+// capped at `max_distance + 1`.
+//
+// With transpositions, the previous column and char are also needed.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+struct SuffixState {
+    column: Vec<u8>,
+    previous_column: Vec<u8>,
+    previous_char: Option<char>,
+}
+
+// Char-level automaton computing the distance between a query and the
+// closest suffix of the text. The state `0` is a sink, only kept so that
+// the compiled `DFA` follows the convention of `SINK_STATE`.
+struct SuffixAutomaton {
+    distances: Vec<Distance>,
+    transitions: Vec<Vec<(char, u32)>>,
+    default_transitions: Vec<u32>,
+}
+
+impl CharAutomaton for SuffixAutomaton {
+    fn num_states(&self) -> u32 {
+        self.distances.len() as u32
+    }
+
+    fn initial_state(&self) -> u32 {
+        1
+    }
+
+    fn distance(&self, state: u32) -> Distance {
+        self.distances[state as usize]
+    }
+
+    fn default_transition(&self, state: u32) -> u32 {
+        self.default_transitions[state as usize]
+    }
+
+    fn transitions(&self, state: u32) -> Vec<(char, u32)> {
+        self.transitions[state as usize].clone()
+    }
+}
+
+fn next_state(
+    state: &SuffixState,
+    query: &[char],
+    chr: Option<char>,
+    max_distance: u8,
+    transposition_cost_one: bool,
+) -> SuffixState {
+    let cap = max_distance + 1;
+    let mut column = vec![0u8; query.len() + 1];
+    for i in 1..=query.len() {
+        let substitution = state.column[i - 1] + u8::from(Some(query[i - 1]) != chr);
+        let mut d = substitution.min(state.column[i] + 1).min(column[i - 1] + 1);
+        if transposition_cost_one
+            && i >= 2
+            && !state.previous_column.is_empty()
+            && state.previous_char == Some(query[i - 1])
+            && chr == Some(query[i - 2])
+        {
+            d = d.min(state.previous_column[i - 2] + 1);
+        }
+        column[i] = d.min(cap);
+    }
+    if transposition_cost_one {
+        SuffixState {
+            previous_column: state.column.clone(),
+            previous_char: chr,
+            column,
+        }
+    } else {
+        SuffixState {
+            column,
+            previous_column: Vec::new(),
+            previous_char: None,
+        }
+    }
+}
+
+pub(crate) fn build_suffix_dfa(query: &str, max_distance: u8, transposition_cost_one: bool) -> DFA {
+    let query: Vec<char> = query.chars().collect();
+    let mut alphabet = query.clone();
+    alphabet.sort_unstable();
+    alphabet.dedup();
+    let cap = max_distance + 1;
+    let initial = SuffixState {
+        column: (0..=query.len())
+            .map(|i| i.min(cap as usize) as u8)
+            .collect(),
+        previous_column: Vec::new(),
+        previous_char: None,
+    };
+    let mut automaton = SuffixAutomaton {
+        distances: vec![Distance::AtLeast(cap)],
+        transitions: vec![Vec::new()],
+        default_transitions: vec![0],
+    };
+    let mut ids: HashMap<SuffixState, u32> = HashMap::new();
+    let mut states: Vec<SuffixState> = vec![initial.clone()];
+    ids.insert(initial, 1);
+    let mut i = 0;
+    while i < states.len() {
+        let state = states[i].clone();
+        let mut state_id = |next: SuffixState| -> u32 {
+            let next_id = ids.len() as u32 + 1;
+            *ids.entry(next).or_insert_with_key(|next| {
+                states.push(next.clone());
+                next_id
+            })
+        };
+        let transitions: Vec<(char, u32)> = alphabet
+            .iter()
+            .map(|&chr| {
+                let next = next_state(
+                    &state,
+                    &query,
+                    Some(chr),
+                    max_distance,
+                    transposition_cost_one,
+                );
+                (chr, state_id(next))
+            })
+            .collect();
+        let next = next_state(&state, &query, None, max_distance, transposition_cost_one);
+        let default_transition = state_id(next);
+        let d = state.column[query.len()];
+        automaton.distances.push(if d < cap {
+            Distance::Exact(d)
+        } else {
+            Distance::AtLeast(cap)
+        });
+        automaton.transitions.push(transitions);
+        automaton.default_transitions.push(default_transition);
+        i += 1;
+    }
+    DFA::from_char_automaton(&automaton)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::distance::edit_distance_chars;
+    use crate::{Distance, ExhaustiveVerifier, LevenshteinAutomatonBuilder, SINK_STATE};
+
+    // Distance between the query and the closest suffix of the text.
+    fn suffix_distance(query: &str, text: &str, transposition_cost_one: bool) -> usize {
+        let query: Vec<char> = query.chars().rev().collect();
+        let text: Vec<char> = text.chars().rev().collect();
+        edit_distance_chars(&query, &text, transposition_cost_one, usize::MAX, true)
+    }
+
+    #[test]
+    fn test_suffix_dfa() {
+        for &transposition_cost_one in &[false, true] {
+            for max_distance in 0..=2u8 {
+                let builder =
+                    LevenshteinAutomatonBuilder::new(max_distance, transposition_cost_one);
+                let verifier =
+                    ExhaustiveVerifier::new("aé", 5, max_distance, transposition_cost_one);
+                for query in &["", "a", "aé", "éaa", "aéaé"] {
+                    let dfa = builder.build_suffix_dfa(query);
+                    let result = verifier.verify_dfa_with(query, &dfa, |query, text| {
+                        suffix_distance(query, text, transposition_cost_one)
+                    });
+                    assert_eq!(result, Ok(()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_suffix_dfa_extensions() {
+        let dfa = LevenshteinAutomatonBuilder::new(1, true).build_suffix_dfa(".tar.gz");
+        assert_eq!(dfa.eval("archive.tar.gz"), Distance::Exact(0));
+        assert_eq!(dfa.eval("archive.tra.gz"), Distance::Exact(1));
+        assert_eq!(dfa.eval("archive.tar.gz.bak"), Distance::AtLeast(2));
+        assert_eq!(dfa.eval("寿司.tar.g"), Distance::Exact(1));
+        assert_ne!(dfa.initial_state(), SINK_STATE);
+    }
+}
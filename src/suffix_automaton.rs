@@ -0,0 +1,236 @@
+use super::{Distance, DFA};
+use std::ops::Range;
+
+struct State {
+    // Length of the longest string of the state.
+    len: usize,
+    // Suffix link, `None` for the root.
+    link: Option<u32>,
+    // Outgoing transitions, sorted by byte.
+    transitions: Vec<(u8, u32)>,
+    // End of the first occurrence of the strings of the state, exclusive.
+    first_end: usize,
+    // Clones, as well as the root, do not correspond to a prefix of the text.
+    is_clone: bool,
+}
+
+impl State {
+    fn transition(&self, b: u8) -> Option<u32> {
+        self.transitions
+            .binary_search_by_key(&b, |&(b, _)| b)
+            .ok()
+            .map(|i| self.transitions[i].1)
+    }
+
+    fn set_transition(&mut self, b: u8, to: u32) {
+        match self.transitions.binary_search_by_key(&b, |&(b, _)| b) {
+            Ok(i) => self.transitions[i].1 = to,
+            Err(i) => self.transitions.insert(i, (b, to)),
+        }
+    }
+}
+
+/// Index over a text, answering "find all of the substrings within
+/// distance `k` of a pattern" without scanning the whole text.
+///
+/// The index is the suffix automaton of the text, i.e. the minimal automaton
+/// recognizing its substrings. Its size is linear in the length of the text.
+/// A query walks the suffix automaton and the Levenshtein DFA of the pattern
+/// together, and stops as soon as the DFA cannot match anymore. The cost of a
+/// query therefore depends on the number of substrings close to the pattern,
+/// rather than on the length of the text.
+///
+/// ```rust
+/// # use levenshtein_automata::{LevenshteinAutomatonBuilder, SuffixAutomatonIndex};
+/// let index = SuffixAutomatonIndex::new("the cat sat on the mat");
+/// let dfa = LevenshteinAutomatonBuilder::new(1, false).build_dfa("hat");
+/// let substrings: Vec<&str> = index
+///     .find(&dfa)
+///     .into_iter()
+///     .filter(|(_, d)| *d == 1)
+///     .map(|(range, _)| &index.text()[range])
+///     .filter(|substring| substring.len() == 3)
+///     .collect();
+/// assert_eq!(substrings, vec!["cat", "sat", "mat"]);
+/// ```
+pub struct SuffixAutomatonIndex {
+    text: String,
+    states: Vec<State>,
+    // Children of the states in the tree of suffix links.
+    link_children: Vec<Vec<u32>>,
+}
+
+impl SuffixAutomatonIndex {
+    /// Builds the index of a text.
+    pub fn new(text: &str) -> SuffixAutomatonIndex {
+        let mut states = vec![State {
+            len: 0,
+            link: None,
+            transitions: Vec::new(),
+            first_end: 0,
+            is_clone: true,
+        }];
+        let mut last = 0u32;
+        for (i, b) in text.bytes().enumerate() {
+            let current = states.len() as u32;
+            states.push(State {
+                len: states[last as usize].len + 1,
+                link: None,
+                transitions: Vec::new(),
+                first_end: i + 1,
+                is_clone: false,
+            });
+            let mut p = Some(last);
+            while let Some(p_id) = p {
+                if states[p_id as usize].transition(b).is_some() {
+                    break;
+                }
+                states[p_id as usize].set_transition(b, current);
+                p = states[p_id as usize].link;
+            }
+            let link = match p {
+                None => 0,
+                Some(p_id) => {
+                    let q = states[p_id as usize].transition(b).unwrap();
+                    if states[p_id as usize].len + 1 == states[q as usize].len {
+                        q
+                    } else {
+                        let clone = states.len() as u32;
+                        let q_state = &states[q as usize];
+                        states.push(State {
+                            len: states[p_id as usize].len + 1,
+                            link: q_state.link,
+                            transitions: q_state.transitions.clone(),
+                            first_end: q_state.first_end,
+                            is_clone: true,
+                        });
+                        let mut p = Some(p_id);
+                        while let Some(p_id) = p {
+                            if states[p_id as usize].transition(b) != Some(q) {
+                                break;
+                            }
+                            states[p_id as usize].set_transition(b, clone);
+                            p = states[p_id as usize].link;
+                        }
+                        states[q as usize].link = Some(clone);
+                        clone
+                    }
+                }
+            };
+            states[current as usize].link = Some(link);
+            last = current;
+        }
+        let mut link_children = vec![Vec::new(); states.len()];
+        for (state_id, state) in states.iter().enumerate() {
+            if let Some(link) = state.link {
+                link_children[link as usize].push(state_id as u32);
+            }
+        }
+        SuffixAutomatonIndex {
+            text: text.to_string(),
+            states,
+            link_children,
+        }
+    }
+
+    /// Returns the indexed text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the number of states of the suffix automaton.
+    pub fn num_states(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Returns the byte ranges of all of the non-empty substrings of the text
+    /// accepted by `dfa`, along with their distance.
+    ///
+    /// Overlapping occurrences are all reported. Results are sorted by range.
+    pub fn find(&self, dfa: &DFA) -> Vec<(Range<usize>, u8)> {
+        let live_states = dfa.live_states();
+        let text = self.text.as_bytes();
+        let mut matches = Vec::new();
+        let initial_state = dfa.initial_state();
+        if !live_states[initial_state as usize] {
+            return matches;
+        }
+        // Pairs of a state of the suffix automaton and a state of the DFA,
+        // along with the length of the substring leading to them.
+        let mut stack: Vec<(u32, u32, usize)> = vec![(0, initial_state, 0)];
+        while let Some((state_id, dfa_state, len)) = stack.pop() {
+            if len > 0 {
+                if let Distance::Exact(d) = dfa.distance(dfa_state) {
+                    for end in self.end_positions(state_id) {
+                        let start = end - len;
+                        // Substrings may start or end in the middle of a char.
+                        if std::str::from_utf8(&text[start..end]).is_ok() {
+                            matches.push((start..end, d));
+                        }
+                    }
+                }
+            }
+            for &(b, to) in &self.states[state_id as usize].transitions {
+                let next_dfa_state = dfa.transition(dfa_state, b);
+                if live_states[next_dfa_state as usize] {
+                    stack.push((to, next_dfa_state, len + 1));
+                }
+            }
+        }
+        matches.sort_by_key(|(range, _)| (range.start, range.end));
+        matches
+    }
+
+    // Returns the end positions of the occurrences of the strings of a state.
+    fn end_positions(&self, state_id: u32) -> Vec<usize> {
+        let mut end_positions = Vec::new();
+        let mut stack = vec![state_id];
+        while let Some(state_id) = stack.pop() {
+            let state = &self.states[state_id as usize];
+            if !state.is_clone {
+                end_positions.push(state.first_end);
+            }
+            stack.extend(&self.link_children[state_id as usize]);
+        }
+        end_positions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SuffixAutomatonIndex;
+    use crate::{Distance, LevenshteinAutomatonBuilder};
+
+    #[test]
+    fn test_suffix_automaton_size() {
+        // A suffix automaton has at most `2n - 1` states.
+        for text in &["", "a", "abcbc", "aaaaaa", "abracadabra"] {
+            let index = SuffixAutomatonIndex::new(text);
+            assert!(index.num_states() <= (2 * text.len()).max(1));
+        }
+    }
+
+    #[test]
+    fn test_find_matches_scan() {
+        let text = "abracadabra 寿司 abba cadabra";
+        let index = SuffixAutomatonIndex::new(text);
+        for &(max_distance, transposition_cost_one) in &[(0, false), (1, true), (2, false)] {
+            let builder = LevenshteinAutomatonBuilder::new(max_distance, transposition_cost_one);
+            for pattern in &["abra", "cad", "寿司", "abab", "", "xyz"] {
+                let dfa = builder.build_dfa(pattern);
+                let mut expected = Vec::new();
+                for (start, _) in text.char_indices() {
+                    for end in start + 1..=text.len() {
+                        if !text.is_char_boundary(end) {
+                            continue;
+                        }
+                        if let Distance::Exact(d) = dfa.eval(&text[start..end]) {
+                            expected.push((start..end, d));
+                        }
+                    }
+                }
+                assert_eq!(index.find(&dfa), expected);
+            }
+        }
+    }
+}
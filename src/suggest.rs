@@ -0,0 +1,104 @@
+use super::{Distance, TypoAutomatonBuilder, TypoRules};
+
+/// Suggests the valid commands or flags closest to a mistyped token,
+/// for "did you mean ...?" hints in command line interfaces.
+///
+/// The default policy accepts one typo for tokens of 2 to 5 chars,
+/// and two typos for longer tokens. Transpositions count as one typo.
+///
+/// ```rust
+/// # use levenshtein_automata::Suggester;
+/// let suggester = Suggester::default();
+/// let commands = ["commit", "checkout", "cherry-pick", "clone"];
+/// assert_eq!(suggester.suggest("comit", commands.iter().cloned()), vec!["commit"]);
+/// assert!(suggester.suggest("push", commands.iter().cloned()).is_empty());
+/// ```
+pub struct Suggester {
+    builder: TypoAutomatonBuilder,
+}
+
+impl Default for Suggester {
+    fn default() -> Suggester {
+        Suggester::with_rules(TypoRules {
+            one_typo_min_len: 2,
+            two_typos_min_len: 6,
+            exact_first_char: false,
+            transposition_cost_one: true,
+        })
+    }
+}
+
+impl Suggester {
+    /// Creates a suggester with a custom distance policy.
+    pub fn with_rules(rules: TypoRules) -> Suggester {
+        Suggester {
+            builder: rules.builder(),
+        }
+    }
+
+    /// Returns the candidates within the allowed distance of `token`,
+    /// closest first. Candidates at the same distance keep their original order.
+    pub fn suggest<'a, I>(&self, token: &str, candidates: I) -> Vec<&'a str>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let dfa = self.builder.build_dfa(token);
+        let mut suggestions: Vec<(u8, &'a str)> = candidates
+            .into_iter()
+            .filter_map(|candidate| match dfa.eval(candidate) {
+                Distance::Exact(d) => Some((d, candidate)),
+                Distance::AtLeast(_) => None,
+            })
+            .collect();
+        suggestions.sort_by_key(|&(d, _)| d);
+        suggestions
+            .into_iter()
+            .map(|(_, candidate)| candidate)
+            .collect()
+    }
+}
+
+/// Shortcut for [Suggester::suggest] with the default policy.
+///
+/// Creating a [Suggester] is not free: reuse one if you need
+/// several suggestions.
+pub fn did_you_mean<'a, I>(token: &str, candidates: I) -> Vec<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    Suggester::default().suggest(token, candidates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{did_you_mean, Suggester};
+
+    #[test]
+    fn test_did_you_mean() {
+        let flags = ["--verbose", "--version", "--vertical", "--quiet"];
+        assert_eq!(
+            did_you_mean("--verison", flags.iter().cloned()),
+            vec!["--version"]
+        );
+        assert_eq!(
+            did_you_mean("--qiuet", flags.iter().cloned()),
+            vec!["--quiet"]
+        );
+        assert_eq!(
+            did_you_mean("--verbse", flags.iter().cloned()),
+            vec!["--verbose"]
+        );
+        assert!(did_you_mean("--help", flags.iter().cloned()).is_empty());
+    }
+
+    #[test]
+    fn test_short_tokens() {
+        let suggester = Suggester::default();
+        let commands = ["ls", "cd", "rm"];
+        assert_eq!(
+            suggester.suggest("sl", commands.iter().cloned()),
+            vec!["ls"]
+        );
+        assert!(suggester.suggest("l", commands.iter().cloned()).is_empty());
+    }
+}
@@ -0,0 +1,209 @@
+use super::parametric_dfa::ParametricDFA;
+use super::{CaseFolding, LevenshteinAutomatonBuilder};
+use alloc::string::String;
+use core::fmt::Write;
+
+/// Precomputed tables of a [LevenshteinAutomatonBuilder], which only depend
+/// on its maximum distance and on whether transpositions cost `1`.
+///
+/// Computing the tables is the expensive part of
+/// [LevenshteinAutomatonBuilder::new]. They can instead be generated once,
+/// e.g. by a build script, with
+/// [LevenshteinAutomatonBuilder::tables_source], and embedded as static
+/// data, so that creating the builder is a mere copy.
+///
+/// In `build.rs`, with `levenshtein_automata` as a build dependency:
+///
+/// ```rust,no_run
+/// # use levenshtein_automata::LevenshteinAutomatonBuilder;
+/// # use std::path::Path;
+/// let out_dir = std::env::var("OUT_DIR").unwrap();
+/// let source = LevenshteinAutomatonBuilder::new(2, true).tables_source("LEV2_TABLES");
+/// std::fs::write(Path::new(&out_dir).join("lev2_tables.rs"), source).unwrap();
+/// ```
+///
+/// In the crate:
+///
+/// ```rust,ignore
+/// include!(concat!(env!("OUT_DIR"), "/lev2_tables.rs"));
+///
+/// let builder = LevenshteinAutomatonBuilder::from_tables(&LEV2_TABLES);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct ParametricTables<'a> {
+    pub(crate) max_distance: u8,
+    pub(crate) transposition_cost_one: bool,
+    pub(crate) diameter: u32,
+    pub(crate) distances: &'a [u8],
+    pub(crate) dest_shape_ids: &'a [u32],
+    pub(crate) delta_offsets: &'a [u32],
+}
+
+impl<'a> ParametricTables<'a> {
+    /// Creates the tables from their parts, as emitted by
+    /// [LevenshteinAutomatonBuilder::tables_source].
+    ///
+    /// The parts are not checked: inconsistent tables result in
+    /// panics or in wrong automata.
+    pub const fn from_raw_parts(
+        max_distance: u8,
+        transposition_cost_one: bool,
+        diameter: u32,
+        distances: &'a [u8],
+        dest_shape_ids: &'a [u32],
+        delta_offsets: &'a [u32],
+    ) -> ParametricTables<'a> {
+        ParametricTables {
+            max_distance,
+            transposition_cost_one,
+            diameter,
+            distances,
+            dest_shape_ids,
+            delta_offsets,
+        }
+    }
+
+    /// Returns the maximum distance of the automata built from the tables.
+    pub fn max_distance(&self) -> u8 {
+        self.max_distance
+    }
+
+    /// Returns true if transpositions cost `1` in the automata built from
+    /// the tables.
+    pub fn transposition_cost_one(&self) -> bool {
+        self.transposition_cost_one
+    }
+}
+
+impl LevenshteinAutomatonBuilder {
+    /// Creates a builder from precomputed tables, without the cost of
+    /// [LevenshteinAutomatonBuilder::new].
+    ///
+    /// See [ParametricTables] for how to generate the tables.
+    pub fn from_tables(tables: &ParametricTables<'_>) -> LevenshteinAutomatonBuilder {
+        LevenshteinAutomatonBuilder {
+            parametric_dfa: ParametricDFA::from_tables(tables),
+            max_distance: tables.max_distance,
+            transposition_cost_one: tables.transposition_cost_one,
+            case_folding: CaseFolding::None,
+            max_num_states: None,
+        }
+    }
+
+    /// Emits the Rust source of a static [ParametricTables] named `name`,
+    /// holding the tables of the builder.
+    ///
+    /// `name` must be a valid Rust identifier. The source refers to the
+    /// crate as `levenshtein_automata`.
+    pub fn tables_source(&self, name: &str) -> String {
+        let parametric_dfa = &self.parametric_dfa;
+        let mut source = String::new();
+        writeln!(
+            source,
+            "// Generated by levenshtein_automata: parametric tables for a maximum distance of {}{}.",
+            self.max_distance,
+            if self.transposition_cost_one {
+                ", with transpositions costing 1"
+            } else {
+                ""
+            }
+        )
+        .unwrap();
+        writeln!(
+            source,
+            "pub static {}: levenshtein_automata::ParametricTables<'static> =",
+            name
+        )
+        .unwrap();
+        writeln!(
+            source,
+            "    levenshtein_automata::ParametricTables::from_raw_parts("
+        )
+        .unwrap();
+        writeln!(source, "        {},", self.max_distance).unwrap();
+        writeln!(source, "        {},", self.transposition_cost_one).unwrap();
+        writeln!(source, "        {},", parametric_dfa.diameter()).unwrap();
+        write_slice(&mut source, parametric_dfa.distances().iter());
+        write_slice(
+            &mut source,
+            parametric_dfa
+                .transitions()
+                .map(|(dest_shape_id, _)| dest_shape_id),
+        );
+        write_slice(
+            &mut source,
+            parametric_dfa
+                .transitions()
+                .map(|(_, delta_offset)| delta_offset),
+        );
+        writeln!(source, "    );").unwrap();
+        source
+    }
+}
+
+// Writes a slice literal argument, with 16 values per line.
+fn write_slice<T: core::fmt::Display, I: Iterator<Item = T>>(source: &mut String, values: I) {
+    source.push_str("        &[");
+    for (i, value) in values.enumerate() {
+        if i % 16 == 0 {
+            source.push_str("\n            ");
+        } else {
+            source.push(' ');
+        }
+        write!(source, "{},", value).unwrap();
+    }
+    source.push_str("\n        ],\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ParametricTables;
+    use crate::LevenshteinAutomatonBuilder;
+
+    #[test]
+    fn test_from_tables() {
+        for &(max_distance, transposition_cost_one) in &[(0, false), (1, true), (2, false)] {
+            let builder = LevenshteinAutomatonBuilder::new(max_distance, transposition_cost_one);
+            let parametric_dfa = &builder.parametric_dfa;
+            let dest_shape_ids: Vec<u32> = parametric_dfa.transitions().map(|(id, _)| id).collect();
+            let delta_offsets: Vec<u32> = parametric_dfa
+                .transitions()
+                .map(|(_, offset)| offset)
+                .collect();
+            let tables = ParametricTables::from_raw_parts(
+                max_distance,
+                transposition_cost_one,
+                parametric_dfa.diameter(),
+                parametric_dfa.distances(),
+                &dest_shape_ids,
+                &delta_offsets,
+            );
+            assert_eq!(tables.max_distance(), max_distance);
+            let copy = LevenshteinAutomatonBuilder::from_tables(&tables);
+            for query in &["", "abc", "寿司", "levenshtein"] {
+                let (expected, dfa) = (builder.build_dfa(query), copy.build_dfa(query));
+                let (expected_prefix, prefix) = (
+                    builder.build_prefix_dfa(query),
+                    copy.build_prefix_dfa(query),
+                );
+                for candidate in &["", "ab", "abd", "寿司屋", "levenstein", "xyz"] {
+                    assert_eq!(dfa.eval(candidate), expected.eval(candidate));
+                    assert_eq!(prefix.eval(candidate), expected_prefix.eval(candidate));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_tables_source() {
+        let source = LevenshteinAutomatonBuilder::new(1, true).tables_source("LEV1");
+        assert!(source.starts_with("// Generated by levenshtein_automata"));
+        assert!(
+            source.contains("pub static LEV1: levenshtein_automata::ParametricTables<'static> =")
+        );
+        assert!(source.contains(
+            "ParametricTables::from_raw_parts(\n        1,\n        true,\n        3,\n"
+        ));
+        assert!(source.trim_end().ends_with(");"));
+    }
+}
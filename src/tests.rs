@@ -1,4 +1,4 @@
-use crate::{Distance, LevenshteinNFA, ParametricDFA};
+use crate::{CaseFolding, Distance, LevenshteinNFA, ParametricDFA};
 use std::collections::HashSet;
 
 fn make_distance(n: u8, max_distance: u8) -> Distance {
@@ -383,6 +383,22 @@ fn test_prefix_dfa_2_lev() {
     );
 }
 
+#[test]
+fn test_prefix_dfa_keeps_closest_prefix() {
+    for &transposition in &[false, true] {
+        let nfa = LevenshteinNFA::levenshtein(2, transposition);
+        let parametric_dfa = ParametricDFA::from_nfa(&nfa);
+        let dfa = parametric_dfa.build_closest_prefix_dfa("abb");
+        assert_eq!(dfa.eval("b"), Distance::Exact(2));
+        assert_eq!(dfa.eval("baa"), Distance::Exact(2));
+        assert_eq!(dfa.eval("baaaaa"), Distance::Exact(2));
+        let swapped_distance = if transposition { 1 } else { 2 };
+        assert_eq!(dfa.eval("bab"), Distance::Exact(swapped_distance));
+        assert_eq!(dfa.eval("babb"), Distance::Exact(1));
+        assert_eq!(dfa.eval("cc"), Distance::AtLeast(3));
+    }
+}
+
 #[test]
 fn test_prefix_dfa_1_damerau() {
     let nfa = LevenshteinNFA::levenshtein(1, true);
@@ -415,3 +431,223 @@ fn test_prefix_dfa_1_damerau() {
         Distance::Exact(1),
     );
 }
+
+#[test]
+fn test_eval_iter() {
+    let nfa = LevenshteinNFA::levenshtein(1, false);
+    let parametric_dfa = ParametricDFA::from_nfa(&nfa);
+    let dfa = parametric_dfa.build_dfa("寿司", false);
+    let chunks: Vec<&[u8]> = vec!["寿".as_bytes(), "司".as_bytes()];
+    assert_eq!(
+        dfa.eval_iter(chunks.iter().flat_map(|chunk| chunk.iter().cloned())),
+        Distance::Exact(0)
+    );
+    assert_eq!(dfa.eval_iter("寿".bytes()), Distance::Exact(1));
+    assert_eq!(dfa.eval_iter("abc".bytes()), Distance::AtLeast(2));
+    let bytes: Vec<Result<u8, &str>> = "寿司".bytes().map(Ok).collect();
+    assert_eq!(dfa.try_eval_iter(bytes), Ok(Distance::Exact(0)));
+    let bytes = vec![Ok(b'a'), Err("truncated"), Ok(b'b')];
+    assert_eq!(dfa.try_eval_iter(bytes), Err("truncated"));
+}
+
+#[test]
+fn test_matcher() {
+    let nfa = LevenshteinNFA::levenshtein(1, false);
+    let parametric_dfa = ParametricDFA::from_nfa(&nfa);
+    let dfa = parametric_dfa.build_dfa("abc", false);
+    let words = ["abc", "abd", "acb", "xyz", "ab"];
+    let matches: Vec<&str> = words.iter().cloned().filter(dfa.matcher()).collect();
+    assert_eq!(matches, vec!["abc", "abd", "ab"]);
+    let mut owned: Vec<String> = words.iter().map(|word| word.to_string()).collect();
+    owned.retain(dfa.matcher());
+    assert_eq!(owned, vec!["abc", "abd", "ab"]);
+    assert!(words.iter().any(dfa.matcher()));
+}
+
+#[test]
+fn test_eval_unchecked() {
+    let nfa = LevenshteinNFA::levenshtein(1, true);
+    let parametric_dfa = ParametricDFA::from_nfa(&nfa);
+    let dfa = parametric_dfa.build_dfa("寿司abc", false);
+    for text in &["寿司abc", "寿司acb", "司abc", "寿司", "", "寿司abcd"] {
+        assert_eq!(unsafe { dfa.eval_unchecked(text) }, dfa.eval(text));
+        assert_eq!(
+            unsafe { dfa.as_dfa_ref().eval_unchecked(text) },
+            dfa.eval(text)
+        );
+    }
+    let state = dfa.initial_state();
+    assert_eq!(
+        unsafe { dfa.transition_unchecked(state, b'a') },
+        dfa.transition(state, b'a')
+    );
+}
+
+#[test]
+fn test_ignorable_chars() {
+    let ignorable = ['-', ' ', '・'];
+    let texts = [
+        "",
+        "-",
+        "abc",
+        "a-bc",
+        "a - b・c",
+        "--abc--",
+        "acb",
+        "a-c-b",
+        "ab",
+        "寿-司",
+        "寿 司・abc",
+    ];
+    for &transposition in &[false, true] {
+        let nfa = LevenshteinNFA::levenshtein(1, transposition);
+        let parametric_dfa = ParametricDFA::from_nfa(&nfa);
+        for query in &["abc", "a-bc", "寿司", "寿・司abc", ""] {
+            let dfa =
+                parametric_dfa.build_dfa_ignoring(query, false, 0, &ignorable, CaseFolding::None);
+            let cleaned_query: String = query.chars().filter(|c| !ignorable.contains(c)).collect();
+            let reference_dfa = parametric_dfa.build_dfa(&cleaned_query, false);
+            for text in &texts {
+                let cleaned_text: String =
+                    text.chars().filter(|c| !ignorable.contains(c)).collect();
+                assert_eq!(dfa.eval(text), reference_dfa.eval(&cleaned_text));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_ascii_case_folding() {
+    let texts = [
+        "",
+        "abc",
+        "ABC",
+        "aBc",
+        "Acb",
+        "AB",
+        "ÀBC",
+        "àbc",
+        "abcD",
+        "寿司ABC",
+    ];
+    for &transposition in &[false, true] {
+        let nfa = LevenshteinNFA::levenshtein(1, transposition);
+        let parametric_dfa = ParametricDFA::from_nfa(&nfa);
+        for query in &["abc", "ABc", "Àbc", "寿司Abc", ""] {
+            for &(prefix, exact_prefix_len) in &[(false, 0), (true, 0), (false, 1)] {
+                let dfa = parametric_dfa.build_dfa_ignoring(
+                    query,
+                    prefix,
+                    exact_prefix_len,
+                    &[],
+                    CaseFolding::Ascii,
+                );
+                let reference_dfa = parametric_dfa.build_dfa_with_exact_prefix(
+                    &query.to_ascii_lowercase(),
+                    prefix,
+                    exact_prefix_len,
+                );
+                for text in &texts {
+                    assert_eq!(
+                        dfa.eval(text),
+                        reference_dfa.eval(text.to_ascii_lowercase())
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_unicode_case_folding() {
+    let fold = |text: &str| -> String {
+        text.chars()
+            .map(|chr| CaseFolding::Unicode.fold(chr))
+            .collect()
+    };
+    let texts = [
+        "",
+        "ΣΟΦΙΑ",
+        "σοφιας",
+        "ΣοφιαΣ",
+        "Kelvin",
+        "\u{212A}ELVIN",
+        "kelvim",
+        "İi",
+    ];
+    for &prefix in &[false, true] {
+        let nfa = LevenshteinNFA::levenshtein(1, true);
+        let parametric_dfa = ParametricDFA::from_nfa(&nfa);
+        for query in &["σοφιας", "ΣΟΦΙΑΣ", "kelvin", "İ"] {
+            let dfa =
+                parametric_dfa.build_dfa_ignoring(query, prefix, 0, &[], CaseFolding::Unicode);
+            let reference_dfa = parametric_dfa.build_dfa(&fold(query), prefix);
+            for text in &texts {
+                assert_eq!(dfa.eval(text), reference_dfa.eval(fold(text)));
+            }
+        }
+    }
+}
+
+// The API of the published `levenshtein_automata` 0.2 crate must keep compiling
+// unchanged, so that its users can upgrade without code changes.
+#[test]
+fn test_published_api() {
+    use crate::{LevenshteinAutomatonBuilder, DFA, SINK_STATE};
+
+    let new: fn(u8, bool) -> LevenshteinAutomatonBuilder = LevenshteinAutomatonBuilder::new;
+    let build_dfa: fn(&LevenshteinAutomatonBuilder, &str) -> DFA =
+        LevenshteinAutomatonBuilder::build_dfa;
+    let build_prefix_dfa: fn(&LevenshteinAutomatonBuilder, &str) -> DFA =
+        LevenshteinAutomatonBuilder::build_prefix_dfa;
+    let initial_state: fn(&DFA) -> u32 = DFA::initial_state;
+    let transition: fn(&DFA, u32, u8) -> u32 = DFA::transition;
+    let distance: fn(&DFA, u32) -> Distance = DFA::distance;
+    let num_states: fn(&DFA) -> usize = DFA::num_states;
+    let eval: fn(&DFA, &'static str) -> Distance = DFA::eval::<&'static str>;
+    let to_u8: fn(&Distance) -> u8 = Distance::to_u8;
+    let sink_state: u32 = SINK_STATE;
+
+    let builder = new(1, true);
+    let dfa = build_dfa(&builder, "abc");
+    let mut state = initial_state(&dfa);
+    for &b in b"abd" {
+        state = transition(&dfa, state, b);
+    }
+    assert_eq!(distance(&dfa, state), Distance::Exact(1));
+    assert_eq!(eval(&dfa, "xyz"), Distance::AtLeast(2));
+    assert_eq!(to_u8(&Distance::AtLeast(2)), 2);
+    assert_eq!(transition(&dfa, sink_state, b'a'), sink_state);
+    assert!(num_states(&dfa) > 0);
+    let prefix_dfa = build_prefix_dfa(&builder, "abc");
+    assert_eq!(prefix_dfa.eval("abcdef"), Distance::Exact(0));
+}
+
+#[test]
+fn test_state_budget() {
+    use crate::{Error, LevenshteinAutomatonBuilder};
+
+    for query in &["", "abc", "levenshtein", "寿司"] {
+        for &prefix in &[false, true] {
+            let build = |builder: &LevenshteinAutomatonBuilder| {
+                if prefix {
+                    builder.try_build_prefix_dfa(query)
+                } else {
+                    builder.try_build_dfa(query)
+                }
+            };
+            let num_states = build(&LevenshteinAutomatonBuilder::new(2, true))
+                .unwrap()
+                .num_states();
+            let builder = LevenshteinAutomatonBuilder::new(2, true).max_num_states(num_states);
+            assert_eq!(build(&builder).unwrap().num_states(), num_states);
+            let builder = LevenshteinAutomatonBuilder::new(2, true).max_num_states(num_states - 1);
+            assert_eq!(
+                build(&builder).err(),
+                Some(Error::StateBudgetExceeded {
+                    max_num_states: num_states - 1
+                })
+            );
+        }
+    }
+}
@@ -0,0 +1,280 @@
+use super::{CharAutomaton, Distance, DFA, SINK_STATE};
+use std::collections::{HashMap, VecDeque};
+
+/// Builds automata in which two chars can be swapped at a fixed cost, as long
+/// as they are at most `window` positions apart and the chars between them
+/// are left untouched.
+///
+/// With a window of `1` and a cost of `1`, this is the distance computed by
+/// the automata of a [LevenshteinAutomatonBuilder](./struct.LevenshteinAutomatonBuilder.html)
+/// with `transposition_cost_one`. Larger windows help with dyslexic-style
+/// typos or OCR column swaps, e.g. "celandar" / "calendar" has a distance of 1
+/// with a window of `2`, instead of 2.
+///
+/// Insertions, deletions and substitutions still cost `1`.
+///
+/// ```rust
+/// # use levenshtein_automata::{Distance, TranspositionWindowBuilder};
+/// let dfa = TranspositionWindowBuilder::new(1, 2, 1).build_dfa("form");
+/// assert_eq!(dfa.eval("from"), Distance::Exact(1));
+/// assert_eq!(dfa.eval("mrof"), Distance::AtLeast(2));
+/// assert_eq!(dfa.eval("fmro"), Distance::Exact(1));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TranspositionWindowBuilder {
+    max_distance: u8,
+    window: usize,
+    transposition_cost: u8,
+}
+
+// State of the automaton: the last `window + 1` columns of the dynamic
+// programming table, where `columns[y][x]` is the distance between the first
+// `x` chars of the query and the first `y` chars of the text, capped at
+// `max_distance + 1`, along with the last `window` chars of the text, as
+// their index in the alphabet of the query.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+struct WindowState {
+    columns: VecDeque<Vec<u8>>,
+    chars: VecDeque<Option<usize>>,
+}
+
+struct WindowAutomaton {
+    distances: Vec<Distance>,
+    transitions: Vec<Vec<(char, u32)>>,
+    default_transitions: Vec<u32>,
+}
+
+impl CharAutomaton for WindowAutomaton {
+    fn num_states(&self) -> u32 {
+        self.distances.len() as u32
+    }
+
+    fn initial_state(&self) -> u32 {
+        1
+    }
+
+    fn distance(&self, state: u32) -> Distance {
+        self.distances[state as usize]
+    }
+
+    fn default_transition(&self, state: u32) -> u32 {
+        self.default_transitions[state as usize]
+    }
+
+    fn transitions(&self, state: u32) -> Vec<(char, u32)> {
+        self.transitions[state as usize].clone()
+    }
+}
+
+impl TranspositionWindowBuilder {
+    /// Creates a builder.
+    ///
+    /// * `max_distance` - maximum distance considered by the automaton.
+    /// * `window` - maximum distance between two swapped chars.
+    /// * `transposition_cost` - cost of swapping two chars.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window` or `transposition_cost` is `0`, or if
+    /// `max_distance` is greater than `253`.
+    pub fn new(
+        max_distance: u8,
+        window: usize,
+        transposition_cost: u8,
+    ) -> TranspositionWindowBuilder {
+        assert!(
+            max_distance < u8::MAX - 1,
+            "The maximum distance should be at most 253"
+        );
+        assert!(window > 0, "The transposition window should be at least 1");
+        assert!(transposition_cost > 0, "Transpositions should have a cost");
+        TranspositionWindowBuilder {
+            max_distance,
+            window,
+            transposition_cost,
+        }
+    }
+
+    /// Builds the automaton computing the distance to `query`.
+    ///
+    /// States are built on the fly, and their number grows quickly
+    /// with the window and the distance.
+    pub fn build_dfa(&self, query: &str) -> DFA {
+        let query: Vec<char> = query.chars().collect();
+        let mut alphabet = query.clone();
+        alphabet.sort_unstable();
+        alphabet.dedup();
+        let query_classes: Vec<usize> = query
+            .iter()
+            .map(|chr| alphabet.binary_search(chr).unwrap())
+            .collect();
+        let cap = self.max_distance + 1;
+        let initial = WindowState {
+            columns: vec![(0..=query.len())
+                .map(|x| x.min(cap as usize) as u8)
+                .collect()]
+            .into(),
+            chars: VecDeque::new(),
+        };
+        let mut automaton = WindowAutomaton {
+            distances: vec![Distance::AtLeast(cap)],
+            transitions: vec![Vec::new()],
+            default_transitions: vec![SINK_STATE],
+        };
+        let mut ids: HashMap<WindowState, u32> = HashMap::new();
+        let mut states: Vec<WindowState> = vec![initial.clone()];
+        ids.insert(initial, 1);
+        let mut i = 0;
+        while i < states.len() {
+            let state = states[i].clone();
+            let mut state_id = |next: WindowState| -> u32 {
+                if next.columns.iter().flatten().all(|&d| d == cap) {
+                    return SINK_STATE;
+                }
+                let next_id = ids.len() as u32 + 1;
+                *ids.entry(next).or_insert_with_key(|next| {
+                    states.push(next.clone());
+                    next_id
+                })
+            };
+            let transitions: Vec<(char, u32)> = alphabet
+                .iter()
+                .enumerate()
+                .map(|(class, &chr)| {
+                    (
+                        chr,
+                        state_id(self.next_state(&state, &query_classes, Some(class))),
+                    )
+                })
+                .collect();
+            let default_transition = state_id(self.next_state(&state, &query_classes, None));
+            let d = state.columns.back().unwrap()[query.len()];
+            automaton.distances.push(if d < cap {
+                Distance::Exact(d)
+            } else {
+                Distance::AtLeast(cap)
+            });
+            automaton.transitions.push(transitions);
+            automaton.default_transitions.push(default_transition);
+            i += 1;
+        }
+        DFA::from_char_automaton(&automaton)
+    }
+
+    fn next_state(
+        &self,
+        state: &WindowState,
+        query: &[usize],
+        class: Option<usize>,
+    ) -> WindowState {
+        let cap = self.max_distance + 1;
+        // `text[m]` is the `m`-th char before the end of the text, including `class`.
+        let mut text: Vec<Option<usize>> = vec![class];
+        text.extend(state.chars.iter().rev().cloned());
+        // `previous[m]` is the column `m + 1` chars before the new one.
+        let previous: Vec<&Vec<u8>> = state.columns.iter().rev().collect();
+        let mut column = vec![0u8; query.len() + 1];
+        column[0] = (previous[0][0] + 1).min(cap);
+        for x in 1..=query.len() {
+            let substitution = previous[0][x - 1] + u8::from(Some(query[x - 1]) != class);
+            let mut d = substitution.min(previous[0][x] + 1).min(column[x - 1] + 1);
+            // Swaps of `query[x - j - 1]` and `query[x - 1]`.
+            for j in 1..=self.window.min(x - 1) {
+                if j >= previous.len() {
+                    break;
+                }
+                let is_swap = text[0] == Some(query[x - j - 1])
+                    && text[j] == Some(query[x - 1])
+                    && (1..j).all(|m| text[m] == Some(query[x - 1 - m]));
+                if is_swap {
+                    d = d.min(previous[j][x - j - 1].saturating_add(self.transposition_cost));
+                }
+            }
+            column[x] = d.min(cap);
+        }
+        let mut next = state.clone();
+        next.columns.push_back(column);
+        if next.columns.len() > self.window + 1 {
+            next.columns.pop_front();
+        }
+        next.chars.push_back(class);
+        if next.chars.len() > self.window {
+            next.chars.pop_front();
+        }
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TranspositionWindowBuilder;
+    use crate::{Distance, ExhaustiveVerifier};
+
+    // Reference implementation of the distance with windowed swaps.
+    fn window_distance(query: &str, text: &str, window: usize, cost: usize) -> usize {
+        let query: Vec<char> = query.chars().collect();
+        let text: Vec<char> = text.chars().collect();
+        let mut table = vec![vec![0usize; text.len() + 1]; query.len() + 1];
+        for x in 0..=query.len() {
+            for y in 0..=text.len() {
+                table[x][y] = if x == 0 || y == 0 {
+                    x + y
+                } else {
+                    let substitution =
+                        table[x - 1][y - 1] + usize::from(query[x - 1] != text[y - 1]);
+                    let mut d = substitution
+                        .min(table[x - 1][y] + 1)
+                        .min(table[x][y - 1] + 1);
+                    for j in 1..=window {
+                        if x > j
+                            && y > j
+                            && query[x - j - 1] == text[y - 1]
+                            && query[x - 1] == text[y - j - 1]
+                            && query[x - j..x - 1] == text[y - j..y - 1]
+                        {
+                            d = d.min(table[x - j - 1][y - j - 1] + cost);
+                        }
+                    }
+                    d
+                };
+            }
+        }
+        table[query.len()][text.len()]
+    }
+
+    #[test]
+    fn test_window_of_one_is_damerau() {
+        for max_distance in 0..=2u8 {
+            let window_builder = TranspositionWindowBuilder::new(max_distance, 1, 1);
+            let verifier = ExhaustiveVerifier::new("abé", 4, max_distance, true);
+            for query in &["", "ab", "abé", "aéba"] {
+                let dfa = window_builder.build_dfa(query);
+                assert_eq!(verifier.verify_dfa(query, &dfa), Ok(()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_transposition_window() {
+        for &(max_distance, window, cost) in &[(1, 2, 1), (2, 2, 1), (2, 3, 2), (1, 3, 1)] {
+            let builder = TranspositionWindowBuilder::new(max_distance, window, cost);
+            let verifier = ExhaustiveVerifier::new("abé", 5, max_distance, true);
+            for query in &["", "abé", "abéé", "ébab"] {
+                let dfa = builder.build_dfa(query);
+                let result = verifier.verify_dfa_with(query, &dfa, |query, text| {
+                    window_distance(query, text, window, cost as usize)
+                });
+                assert_eq!(result, Ok(()));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "The maximum distance should be at most 253")]
+    fn test_max_distance_254() {
+        let dfa = TranspositionWindowBuilder::new(253, 1, 1).build_dfa("ab");
+        assert_eq!(dfa.eval("ba"), Distance::Exact(1));
+        assert_eq!(dfa.eval(""), Distance::Exact(2));
+        TranspositionWindowBuilder::new(254, 1, 1);
+    }
+}
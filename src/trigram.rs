@@ -0,0 +1,319 @@
+use super::ngram::{ngrams, NgramFilter};
+use super::{Distance, LevenshteinAutomatonBuilder};
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+
+const MAGIC: &[u8; 4] = b"LATI";
+const VERSION: u32 = 1;
+
+/// Dictionary index mapping trigrams to the ids of the words containing them.
+///
+/// Fuzzy lookups first generate candidates sharing enough trigrams with the
+/// query (see [NgramFilter]), and then verify them with a Levenshtein automaton.
+/// Only a small fraction of the dictionary is therefore evaluated by the automaton.
+///
+/// Word ids are their position in the dictionary the index was built from.
+/// The index can be written to, and read back from, any `Write` / `Read`.
+///
+/// ```rust
+/// # use levenshtein_automata::{LevenshteinAutomatonBuilder, TrigramIndex};
+/// let index = TrigramIndex::new(&["apple", "maple", "ample", "application"]);
+/// let builder = LevenshteinAutomatonBuilder::new(1, true);
+/// let matches: Vec<&str> = index
+///     .lookup(&builder, "aple")
+///     .into_iter()
+///     .map(|(id, _)| index.word(id))
+///     .collect();
+/// assert_eq!(matches, vec!["apple", "maple", "ample"]);
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TrigramIndex {
+    words: Vec<String>,
+    // Length of the words, in chars.
+    lens: Vec<u32>,
+    // Sorted word ids, each id being repeated once per occurrence of the trigram.
+    postings: HashMap<u64, Vec<u32>>,
+}
+
+impl TrigramIndex {
+    /// Indexes a dictionary.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the dictionary contains more than `u32::MAX` words.
+    pub fn new<S: AsRef<str>>(words: &[S]) -> TrigramIndex {
+        let mut index = TrigramIndex::default();
+        for word in words {
+            index.insert(word.as_ref());
+        }
+        index
+    }
+
+    fn from_parts(words: Vec<String>, postings: HashMap<u64, Vec<u32>>) -> TrigramIndex {
+        let lens = words
+            .iter()
+            .map(|word| word.chars().count() as u32)
+            .collect();
+        TrigramIndex {
+            words,
+            lens,
+            postings,
+        }
+    }
+
+    /// Appends a word to the dictionary, and returns its id.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the dictionary already contains `u32::MAX` words.
+    pub fn insert(&mut self, word: &str) -> u32 {
+        assert!(
+            self.words.len() < u32::MAX as usize,
+            "Too many words in the dictionary"
+        );
+        let id = self.words.len() as u32;
+        let chars: Vec<char> = word.chars().collect();
+        for trigram in ngrams(&chars, 3) {
+            self.postings.entry(trigram).or_default().push(id);
+        }
+        self.words.push(word.to_string());
+        self.lens.push(chars.len() as u32);
+        id
+    }
+
+    /// Returns the number of words in the dictionary.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Returns true if the dictionary is empty.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Returns the word associated to an id.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id >= self.len()`.
+    pub fn word(&self, id: u32) -> &str {
+        &self.words[id as usize]
+    }
+
+    /// Returns the ids of the words within the distance of `query`
+    /// accepted by `builder`, along with their distance.
+    ///
+    /// Results are sorted by distance, and then by id.
+    pub fn lookup(&self, builder: &LevenshteinAutomatonBuilder, query: &str) -> Vec<(u32, u8)> {
+        let filter = NgramFilter::new(3, builder.max_distance, builder.transposition_cost_one);
+        let query_chars: Vec<char> = query.chars().collect();
+        let query_len = query_chars.len();
+        let min_common_trigrams =
+            |id: u32| filter.min_common_ngrams(query_len, self.lens[id as usize] as usize);
+        let candidates: Vec<u32> = if (0..=query_len + builder.max_distance as usize)
+            .any(|len| filter.min_common_ngrams(query_len, len) == Some(0))
+        {
+            // Short queries: some words may match without sharing any trigram.
+            (0..self.words.len() as u32)
+                .filter(|&id| min_common_trigrams(id).is_some())
+                .collect()
+        } else {
+            self.common_trigram_counts(&query_chars)
+                .into_iter()
+                .filter(|&(id, count)| matches!(min_common_trigrams(id), Some(min_count) if count >= min_count))
+                .map(|(id, _)| id)
+                .collect()
+        };
+        let dfa = builder.build_dfa(query);
+        let mut matches: Vec<(u32, u8)> = candidates
+            .into_iter()
+            .filter_map(|id| match dfa.eval(&self.words[id as usize]) {
+                Distance::Exact(d) => Some((id, d)),
+                Distance::AtLeast(_) => None,
+            })
+            .collect();
+        matches.sort_by_key(|&(id, d)| (d, id));
+        matches
+    }
+
+    // Returns the number of trigrams shared by the query and the words
+    // sharing at least one trigram with it.
+    fn common_trigram_counts(&self, query_chars: &[char]) -> HashMap<u32, usize> {
+        let mut query_trigrams: HashMap<u64, usize> = HashMap::new();
+        for trigram in ngrams(query_chars, 3) {
+            *query_trigrams.entry(trigram).or_insert(0) += 1;
+        }
+        let mut counts: HashMap<u32, usize> = HashMap::new();
+        for (trigram, query_count) in query_trigrams {
+            let posting = match self.postings.get(&trigram) {
+                Some(posting) => posting,
+                None => continue,
+            };
+            let mut start = 0;
+            while start < posting.len() {
+                let id = posting[start];
+                let mut end = start + 1;
+                while end < posting.len() && posting[end] == id {
+                    end += 1;
+                }
+                *counts.entry(id).or_insert(0) += query_count.min(end - start);
+                start = end;
+            }
+        }
+        counts
+    }
+
+    /// Serializes the index.
+    ///
+    /// The format is a sequence of little endian integers: a header, the words
+    /// as length-prefixed utf-8 strings, and the posting lists ordered by trigram.
+    pub fn write_to<W: Write>(&self, wrt: &mut W) -> io::Result<()> {
+        wrt.write_all(MAGIC)?;
+        write_u32(wrt, VERSION)?;
+        write_u32(wrt, self.words.len() as u32)?;
+        for word in &self.words {
+            write_u32(wrt, word.len() as u32)?;
+            wrt.write_all(word.as_bytes())?;
+        }
+        let mut trigrams: Vec<u64> = self.postings.keys().cloned().collect();
+        trigrams.sort_unstable();
+        write_u32(wrt, trigrams.len() as u32)?;
+        for trigram in trigrams {
+            let posting = &self.postings[&trigram];
+            wrt.write_all(&trigram.to_le_bytes())?;
+            write_u32(wrt, posting.len() as u32)?;
+            for &id in posting {
+                write_u32(wrt, id)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Deserializes an index written by [TrigramIndex::write_to].
+    ///
+    /// Returns an error of kind `InvalidData` if the data is not a valid index.
+    pub fn read_from<R: Read>(rdr: &mut R) -> io::Result<TrigramIndex> {
+        let mut magic = [0u8; 4];
+        rdr.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("Not a trigram index"));
+        }
+        if read_u32(rdr)? != VERSION {
+            return Err(invalid_data("Unsupported trigram index version"));
+        }
+        let num_words = read_u32(rdr)?;
+        let mut words = Vec::new();
+        for _ in 0..num_words {
+            let len = read_u32(rdr)? as usize;
+            let mut bytes = Vec::new();
+            rdr.take(len as u64).read_to_end(&mut bytes)?;
+            if bytes.len() != len {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            let word = String::from_utf8(bytes).map_err(|_| invalid_data("Invalid utf-8 word"))?;
+            words.push(word);
+        }
+        let num_trigrams = read_u32(rdr)?;
+        let mut postings = HashMap::new();
+        for _ in 0..num_trigrams {
+            let mut trigram = [0u8; 8];
+            rdr.read_exact(&mut trigram)?;
+            let len = read_u32(rdr)?;
+            let mut posting = Vec::new();
+            for _ in 0..len {
+                let id = read_u32(rdr)?;
+                if id >= num_words || matches!(posting.last(), Some(&last) if id < last) {
+                    return Err(invalid_data("Invalid posting list"));
+                }
+                posting.push(id);
+            }
+            postings.insert(u64::from_le_bytes(trigram), posting);
+        }
+        Ok(TrigramIndex::from_parts(words, postings))
+    }
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn write_u32<W: Write>(wrt: &mut W, val: u32) -> io::Result<()> {
+    wrt.write_all(&val.to_le_bytes())
+}
+
+fn read_u32<R: Read>(rdr: &mut R) -> io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    rdr.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TrigramIndex;
+    use crate::{Distance, LevenshteinAutomatonBuilder};
+    use std::io;
+
+    const WORDS: &[&str] = &[
+        "",
+        "a",
+        "ab",
+        "abc",
+        "abcd",
+        "abcde",
+        "bcde",
+        "levenshtein",
+        "levenstein",
+        "lowenstein",
+        "einstein",
+        "寿司",
+        "寿司屋",
+        "abcabc",
+    ];
+
+    #[test]
+    fn test_lookup_matches_scan() {
+        let index = TrigramIndex::new(WORDS);
+        for &(max_distance, transposition_cost_one) in &[(0, false), (1, true), (2, false)] {
+            let builder = LevenshteinAutomatonBuilder::new(max_distance, transposition_cost_one);
+            for query in &[
+                "",
+                "b",
+                "abd",
+                "bacd",
+                "levenstien",
+                "寿",
+                "cabcab",
+                "einstien",
+            ] {
+                let dfa = builder.build_dfa(query);
+                let mut expected: Vec<(u32, u8)> = WORDS
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(id, word)| match dfa.eval(word) {
+                        Distance::Exact(d) => Some((id as u32, d)),
+                        Distance::AtLeast(_) => None,
+                    })
+                    .collect();
+                expected.sort_by_key(|&(id, d)| (d, id));
+                assert_eq!(index.lookup(&builder, query), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_serialization() {
+        let index = TrigramIndex::new(WORDS);
+        let mut buffer: Vec<u8> = Vec::new();
+        index.write_to(&mut buffer).unwrap();
+        let deserialized = TrigramIndex::read_from(&mut &buffer[..]).unwrap();
+        assert_eq!(deserialized, index);
+        let mut other_buffer: Vec<u8> = Vec::new();
+        deserialized.write_to(&mut other_buffer).unwrap();
+        assert_eq!(other_buffer, buffer);
+        let truncated = TrigramIndex::read_from(&mut &buffer[..buffer.len() - 1]);
+        assert_eq!(truncated.unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+        buffer[0] = b'X';
+        let corrupted = TrigramIndex::read_from(&mut &buffer[..]);
+        assert_eq!(corrupted.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
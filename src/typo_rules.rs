@@ -0,0 +1,241 @@
+use super::{LevenshteinAutomatonBuilder, DFA};
+
+/// Typo tolerance rules depending on the length of the term,
+/// in the style of Meilisearch.
+///
+/// With the default rules, terms shorter than 5 chars must match exactly,
+/// terms of 5 to 8 chars accept one typo, and longer terms accept two typos.
+/// The first char of a term never accepts a typo.
+///
+/// Lengths are expressed in chars.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct TypoRules {
+    /// Minimum length of a term for it to accept one typo.
+    pub one_typo_min_len: usize,
+    /// Minimum length of a term for it to accept two typos.
+    pub two_typos_min_len: usize,
+    /// If true, the first char of the term has to match exactly.
+    pub exact_first_char: bool,
+    /// If true, a transposition counts as a single typo.
+    pub transposition_cost_one: bool,
+}
+
+impl Default for TypoRules {
+    fn default() -> TypoRules {
+        TypoRules {
+            one_typo_min_len: 5,
+            two_typos_min_len: 9,
+            exact_first_char: true,
+            transposition_cost_one: true,
+        }
+    }
+}
+
+impl TypoRules {
+    /// Returns the number of typos allowed for `term`.
+    pub fn max_distance(&self, term: &str) -> u8 {
+        let term_len = term.chars().count();
+        if term_len >= self.two_typos_min_len {
+            2
+        } else if term_len >= self.one_typo_min_len {
+            1
+        } else {
+            0
+        }
+    }
+
+    /// Creates the builder producing the automata for these rules.
+    ///
+    /// This is computationally intensive, the resulting builder
+    /// should be reused.
+    pub fn builder(self) -> TypoAutomatonBuilder {
+        let builders = [0u8, 1u8, 2u8]
+            .iter()
+            .map(|&d| LevenshteinAutomatonBuilder::new(d, self.transposition_cost_one))
+            .collect();
+        TypoAutomatonBuilder {
+            rules: self,
+            builders,
+        }
+    }
+}
+
+/// Fuzziness policy, mirroring the `fuzziness` parameter of Elasticsearch.
+///
+/// Converting it into [TypoRules] gives the same behavior as
+/// Elasticsearch's defaults: no exact prefix, and transpositions
+/// counting as a single edit.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum Fuzziness {
+    /// The same maximum distance for all terms.
+    ///
+    /// Like in Elasticsearch, distances above 2 are treated as 2.
+    Fixed(u8),
+    /// Equivalent to `AutoRange { low: 3, high: 6 }`: 0 edit for terms of
+    /// at most 2 chars, 1 edit for 3 to 5 chars, 2 edits above.
+    Auto,
+    /// 0 edit for terms shorter than `low` chars, 1 edit for terms shorter
+    /// than `high` chars, and 2 edits otherwise.
+    AutoRange {
+        /// Minimum length of a term for it to accept one edit.
+        low: usize,
+        /// Minimum length of a term for it to accept two edits.
+        high: usize,
+    },
+}
+
+impl Fuzziness {
+    /// Returns the maximum distance allowed for `term`.
+    pub fn max_distance(&self, term: &str) -> u8 {
+        TypoRules::from(*self).max_distance(term)
+    }
+}
+
+impl From<Fuzziness> for TypoRules {
+    fn from(fuzziness: Fuzziness) -> TypoRules {
+        let (one_typo_min_len, two_typos_min_len) = match fuzziness {
+            Fuzziness::Fixed(0) => (usize::MAX, usize::MAX),
+            Fuzziness::Fixed(1) => (0, usize::MAX),
+            Fuzziness::Fixed(_) => (0, 0),
+            Fuzziness::Auto => (3, 6),
+            Fuzziness::AutoRange { low, high } => (low, high),
+        };
+        TypoRules {
+            one_typo_min_len,
+            two_typos_min_len,
+            exact_first_char: false,
+            transposition_cost_one: true,
+        }
+    }
+}
+
+/// Builds the automata matching a term according to some [TypoRules].
+pub struct TypoAutomatonBuilder {
+    rules: TypoRules,
+    builders: Vec<LevenshteinAutomatonBuilder>,
+}
+
+impl TypoAutomatonBuilder {
+    /// Returns the rules used by this builder.
+    pub fn rules(&self) -> &TypoRules {
+        &self.rules
+    }
+
+    fn build(&self, term: &str, prefix: bool) -> DFA {
+        let max_distance = self.rules.max_distance(term) as usize;
+        let exact_prefix_len = if self.rules.exact_first_char { 1 } else { 0 };
+        self.builders[max_distance]
+            .parametric_dfa
+            .build_dfa_with_exact_prefix(term, prefix, exact_prefix_len)
+    }
+
+    /// Builds the DFA matching `term` with the number of typos
+    /// allowed for its length.
+    pub fn build_dfa(&self, term: &str) -> DFA {
+        self.build(term, false)
+    }
+
+    /// Builds the DFA matching the strings starting with `term`,
+    /// with the number of typos allowed for its length.
+    ///
+    /// See also [LevenshteinAutomatonBuilder::build_prefix_dfa](./struct.LevenshteinAutomatonBuilder.html#method.build_prefix_dfa).
+    pub fn build_prefix_dfa(&self, term: &str) -> DFA {
+        self.build(term, true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Fuzziness, TypoRules};
+    use crate::Distance;
+
+    #[test]
+    fn test_fuzziness_auto() {
+        let lengths: Vec<u8> = ["", "a", "ab", "abc", "abcde", "abcdef", "abcdefghij"]
+            .iter()
+            .map(|term| Fuzziness::Auto.max_distance(term))
+            .collect();
+        assert_eq!(lengths, vec![0, 0, 0, 1, 1, 2, 2]);
+        let auto_range = Fuzziness::AutoRange { low: 2, high: 4 };
+        assert_eq!(auto_range.max_distance("a"), 0);
+        assert_eq!(auto_range.max_distance("ab"), 1);
+        assert_eq!(auto_range.max_distance("abcd"), 2);
+        assert_eq!(Fuzziness::Fixed(0).max_distance("abcdefghij"), 0);
+        assert_eq!(Fuzziness::Fixed(1).max_distance(""), 1);
+        assert_eq!(Fuzziness::Fixed(5).max_distance("a"), 2);
+    }
+
+    #[test]
+    fn test_fuzziness_automaton() {
+        let builder = TypoRules::from(Fuzziness::Auto).builder();
+        assert_eq!(builder.build_dfa("ab").eval("ac"), Distance::AtLeast(1));
+        assert_eq!(builder.build_dfa("abc").eval("bac"), Distance::Exact(1));
+        assert_eq!(
+            builder.build_dfa("abcdef").eval("bcdefg"),
+            Distance::Exact(2)
+        );
+    }
+
+    #[test]
+    fn test_max_distance() {
+        let rules = TypoRules::default();
+        assert_eq!(rules.max_distance("cat"), 0);
+        assert_eq!(rules.max_distance("horse"), 1);
+        assert_eq!(rules.max_distance("elephants"), 2);
+        assert_eq!(rules.max_distance("寿司は焦げられ"), 1);
+    }
+
+    #[test]
+    fn test_typo_automaton() {
+        let builder = TypoRules::default().builder();
+        let dfa = builder.build_dfa("cat");
+        assert_eq!(dfa.eval("cat"), Distance::Exact(0));
+        assert_eq!(dfa.eval("cut"), Distance::AtLeast(1));
+        let dfa = builder.build_dfa("horse");
+        assert_eq!(dfa.eval("hrose"), Distance::Exact(1));
+        assert_eq!(dfa.eval("horsey"), Distance::Exact(1));
+        assert_eq!(dfa.eval("morse"), Distance::AtLeast(2));
+        assert_eq!(dfa.eval("hors"), Distance::Exact(1));
+        assert_eq!(dfa.eval("h"), Distance::AtLeast(2));
+        let dfa = builder.build_dfa("elephants");
+        assert_eq!(dfa.eval("elefants"), Distance::Exact(2));
+        assert_eq!(dfa.eval("olephants"), Distance::AtLeast(3));
+        assert_eq!(dfa.eval("lephants"), Distance::AtLeast(3));
+    }
+
+    #[test]
+    fn test_texts_shorter_than_exact_prefix() {
+        let rules = TypoRules {
+            one_typo_min_len: 1,
+            two_typos_min_len: 2,
+            exact_first_char: true,
+            transposition_cost_one: true,
+        };
+        let builder = rules.builder();
+        let dfa = builder.build_dfa("ab");
+        assert_eq!(dfa.eval(""), Distance::AtLeast(3));
+        assert_eq!(dfa.eval("b"), Distance::AtLeast(3));
+        assert_eq!(dfa.eval("a"), Distance::Exact(1));
+        let dfa = builder.build_prefix_dfa("ab");
+        assert_eq!(dfa.eval(""), Distance::AtLeast(3));
+        assert_eq!(dfa.eval("a"), Distance::Exact(1));
+        assert_eq!(dfa.eval("abc"), Distance::Exact(0));
+    }
+
+    #[test]
+    fn test_typo_prefix_automaton() {
+        let builder = TypoRules::default().builder();
+        let dfa = builder.build_prefix_dfa("horse");
+        assert_eq!(dfa.eval("horseback"), Distance::Exact(0));
+        assert_eq!(dfa.eval("hrseback"), Distance::Exact(1));
+        assert_eq!(dfa.eval("norseman"), Distance::AtLeast(2));
+        let rules = TypoRules {
+            exact_first_char: false,
+            ..TypoRules::default()
+        };
+        let dfa = rules.builder().build_prefix_dfa("horse");
+        assert_eq!(dfa.eval("norseman"), Distance::Exact(1));
+    }
+}
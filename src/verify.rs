@@ -0,0 +1,243 @@
+use super::distance::edit_distance_chars;
+use super::{Distance, LevenshteinAutomatonBuilder, DFA};
+
+/// Text on which an automaton does not compute the expected distance.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Counterexample {
+    /// Query of the automaton.
+    pub query: String,
+    /// Text evaluated by the automaton.
+    pub text: String,
+    /// Distance computed by the reference implementation.
+    pub expected: Distance,
+    /// Distance computed by the automaton.
+    pub actual: Distance,
+}
+
+/// Exhaustive verification of automata over a small alphabet.
+///
+/// All of the strings of up to `max_len` chars of the alphabet are
+/// evaluated, shortest first, and compared to a straightforward dynamic
+/// programming implementation. The first counterexample is returned.
+///
+/// The number of strings grows exponentially with `max_len`:
+/// keep the alphabet small.
+///
+/// ```rust
+/// # use levenshtein_automata::{ExhaustiveVerifier, LevenshteinAutomatonBuilder};
+/// let verifier = ExhaustiveVerifier::new("abé", 4, 1, true);
+/// let builder = LevenshteinAutomatonBuilder::new(1, true);
+/// assert_eq!(verifier.verify_builder(&builder), Ok(()));
+/// // Transpositions cost 2 without `transposition_cost_one`.
+/// let counterexample = verifier
+///     .verify_dfa("ab", &LevenshteinAutomatonBuilder::new(1, false).build_dfa("ab"))
+///     .unwrap_err();
+/// assert_eq!(counterexample.text, "ba");
+/// ```
+#[derive(Clone, Debug)]
+pub struct ExhaustiveVerifier {
+    alphabet: Vec<char>,
+    max_len: usize,
+    max_distance: u8,
+    transposition_cost_one: bool,
+    prefix: bool,
+}
+
+impl ExhaustiveVerifier {
+    /// Creates a verifier enumerating the strings of up to `max_len` chars of `alphabet`.
+    ///
+    /// Distances are checked up to `max_distance`. If `transposition_cost_one` is true,
+    /// the reference distance is the optimal string alignment distance.
+    pub fn new(
+        alphabet: &str,
+        max_len: usize,
+        max_distance: u8,
+        transposition_cost_one: bool,
+    ) -> ExhaustiveVerifier {
+        let mut alphabet: Vec<char> = alphabet.chars().collect();
+        alphabet.sort_unstable();
+        alphabet.dedup();
+        ExhaustiveVerifier {
+            alphabet,
+            max_len,
+            max_distance,
+            transposition_cost_one,
+            prefix: false,
+        }
+    }
+
+    /// Checks prefix automata instead, as built by
+    /// [LevenshteinAutomatonBuilder::build_prefix_dfa](./struct.LevenshteinAutomatonBuilder.html#method.build_prefix_dfa).
+    pub fn prefix(mut self, prefix: bool) -> ExhaustiveVerifier {
+        self.prefix = prefix;
+        self
+    }
+
+    /// Checks the automata built by `builder` for all of the queries of up to
+    /// `max_len` chars of the alphabet.
+    ///
+    /// The builder is expected to have the `max_distance` and
+    /// `transposition_cost_one` of the verifier.
+    pub fn verify_builder(
+        &self,
+        builder: &LevenshteinAutomatonBuilder,
+    ) -> Result<(), Counterexample> {
+        for query in self.strings() {
+            let dfa = if self.prefix {
+                builder.build_prefix_dfa(&query)
+            } else {
+                builder.build_dfa(&query)
+            };
+            self.verify_dfa(&query, &dfa)?;
+        }
+        Ok(())
+    }
+
+    /// Checks the automaton of `query` on all of the strings of up to
+    /// `max_len` chars of the alphabet.
+    pub fn verify_dfa(&self, query: &str, dfa: &DFA) -> Result<(), Counterexample> {
+        let transposition_cost_one = self.transposition_cost_one;
+        let prefix = self.prefix;
+        self.verify_dfa_with(query, dfa, |query, text| {
+            reference_distance(query, text, transposition_cost_one, prefix)
+        })
+    }
+
+    /// Same as [.verify_dfa(...)](#method.verify_dfa), with a custom reference
+    /// implementation, returning the distance between the query and a text.
+    ///
+    /// This makes it possible to check automata implementing other edit operations.
+    pub fn verify_dfa_with<F>(
+        &self,
+        query: &str,
+        dfa: &DFA,
+        reference: F,
+    ) -> Result<(), Counterexample>
+    where
+        F: Fn(&str, &str) -> usize,
+    {
+        for text in self.strings() {
+            let distance = reference(query, &text);
+            let expected = if distance > self.max_distance as usize {
+                Distance::AtLeast(self.max_distance + 1)
+            } else {
+                Distance::Exact(distance as u8)
+            };
+            let actual = dfa.eval(&text);
+            if actual != expected {
+                return Err(Counterexample {
+                    query: query.to_string(),
+                    text,
+                    expected,
+                    actual,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns all of the strings of up to `max_len` chars, shortest first.
+    pub(crate) fn strings(&self) -> impl Iterator<Item = String> + '_ {
+        let mut indexes: Vec<usize> = Vec::new();
+        let mut done = false;
+        std::iter::from_fn(move || {
+            if done {
+                return None;
+            }
+            let text: String = indexes.iter().map(|&i| self.alphabet[i]).collect();
+            // increments `indexes` as a number written in base `alphabet.len()`.
+            let mut pos = indexes.len();
+            loop {
+                if pos == 0 {
+                    if indexes.len() == self.max_len || self.alphabet.is_empty() {
+                        done = true;
+                    } else {
+                        indexes.push(0);
+                    }
+                    break;
+                }
+                pos -= 1;
+                indexes[pos] += 1;
+                if indexes[pos] < self.alphabet.len() {
+                    break;
+                }
+                indexes[pos] = 0;
+            }
+            Some(text)
+        })
+    }
+}
+
+fn reference_distance(
+    query: &str,
+    text: &str,
+    transposition_cost_one: bool,
+    prefix: bool,
+) -> usize {
+    let query: Vec<char> = query.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    edit_distance_chars(&query, &text, transposition_cost_one, usize::MAX, prefix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ExhaustiveVerifier;
+    use crate::{Distance, LevenshteinAutomatonBuilder};
+
+    #[test]
+    fn test_strings() {
+        let verifier = ExhaustiveVerifier::new("ba", 2, 0, false);
+        let strings: Vec<String> = verifier.strings().collect();
+        assert_eq!(strings, vec!["", "a", "b", "aa", "ab", "ba", "bb"]);
+        assert_eq!(
+            ExhaustiveVerifier::new("", 3, 0, false).strings().count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_verify_builders() {
+        for &transposition_cost_one in &[false, true] {
+            for max_distance in 0..=2u8 {
+                let builder =
+                    LevenshteinAutomatonBuilder::new(max_distance, transposition_cost_one);
+                let verifier =
+                    ExhaustiveVerifier::new("aé", 4, max_distance, transposition_cost_one);
+                assert_eq!(verifier.verify_builder(&builder), Ok(()));
+                // For larger distances, prefix automata may miss a prefix
+                // closer than the last one, e.g. `"b"` in `"bc"` for `"aab"`.
+                if max_distance <= 1 {
+                    assert_eq!(
+                        verifier.clone().prefix(true).verify_builder(&builder),
+                        Ok(())
+                    );
+                }
+                let verifier = verifier.prefix(true);
+                for query in verifier.strings() {
+                    let dfa = builder.build_closest_prefix_dfa(&query);
+                    assert_eq!(verifier.verify_dfa(&query, &dfa), Ok(()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_counterexample() {
+        let verifier = ExhaustiveVerifier::new("abc", 3, 1, false);
+        let dfa = LevenshteinAutomatonBuilder::new(2, false).build_dfa("abc");
+        let counterexample = verifier.verify_dfa("abc", &dfa).unwrap_err();
+        assert_eq!(counterexample.text, "");
+        assert_eq!(counterexample.expected, Distance::AtLeast(2));
+        assert_eq!(counterexample.actual, Distance::AtLeast(3));
+        let dfa = LevenshteinAutomatonBuilder::new(1, false).build_dfa("abc");
+        assert_eq!(
+            verifier.verify_dfa_with("abc", &dfa, |_, _| 0),
+            Err(super::Counterexample {
+                query: "abc".to_string(),
+                text: String::new(),
+                expected: Distance::Exact(0),
+                actual: Distance::AtLeast(2),
+            })
+        );
+    }
+}
@@ -0,0 +1,92 @@
+use super::distance::{edit_script_chars, EditOp};
+
+const RESET: &str = "\u{1b}[0m";
+const RED: &str = "\u{1b}[31m";
+const GREEN: &str = "\u{1b}[32m";
+const YELLOW: &str = "\u{1b}[33m";
+const MAGENTA: &str = "\u{1b}[35m";
+
+/// Renders an optimal alignment of a query and a candidate, for terminals.
+///
+/// The query is displayed on the first line, and the candidate on the second one.
+/// Substitutions are yellow, deletions (chars of the query absent from the candidate)
+/// are red, insertions (chars of the candidate absent from the query) are green,
+/// and transpositions are magenta. Gaps are displayed as `-`.
+///
+/// This is meant for debugging, e.g. while tuning a distance policy:
+/// chars are assumed to be one column wide.
+///
+/// ```rust
+/// # use levenshtein_automata::render_alignment;
+/// let rendered = render_alignment("kitten", "sitting", false);
+/// println!("{}", rendered);
+/// assert_eq!(rendered.lines().count(), 2);
+/// ```
+pub fn render_alignment(query: &str, candidate: &str, transposition_cost_one: bool) -> String {
+    let query: Vec<char> = query.chars().collect();
+    let candidate: Vec<char> = candidate.chars().collect();
+    let mut query_line = String::new();
+    let mut candidate_line = String::new();
+    for edit in edit_script_chars(&query, &candidate, transposition_cost_one) {
+        let (query_chars, candidate_chars, color): (&[char], &[char], Option<&str>) = match edit.op
+        {
+            EditOp::Match => (
+                &query[edit.query_position..][..1],
+                &candidate[edit.candidate_position..][..1],
+                None,
+            ),
+            EditOp::Substitution => (
+                &query[edit.query_position..][..1],
+                &candidate[edit.candidate_position..][..1],
+                Some(YELLOW),
+            ),
+            EditOp::Deletion => (&query[edit.query_position..][..1], &['-'], Some(RED)),
+            EditOp::Insertion => (
+                &['-'],
+                &candidate[edit.candidate_position..][..1],
+                Some(GREEN),
+            ),
+            EditOp::Transposition => (
+                &query[edit.query_position..][..2],
+                &candidate[edit.candidate_position..][..2],
+                Some(MAGENTA),
+            ),
+        };
+        push_colored(&mut query_line, query_chars, color);
+        push_colored(&mut candidate_line, candidate_chars, color);
+    }
+    format!("{}\n{}\n", query_line, candidate_line)
+}
+
+fn push_colored(line: &mut String, chars: &[char], color: Option<&str>) {
+    if let Some(color) = color {
+        line.push_str(color);
+    }
+    line.extend(chars);
+    if color.is_some() {
+        line.push_str(RESET);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render_alignment;
+
+    #[test]
+    fn test_render_alignment() {
+        assert_eq!(
+            render_alignment("kitten", "sitting", false),
+            "\u{1b}[33mk\u{1b}[0mitt\u{1b}[33me\u{1b}[0mn\u{1b}[32m-\u{1b}[0m\n\
+             \u{1b}[33ms\u{1b}[0mitt\u{1b}[33mi\u{1b}[0mn\u{1b}[32mg\u{1b}[0m\n"
+        );
+        assert_eq!(
+            render_alignment("abcd", "acbd", true),
+            "a\u{1b}[35mbc\u{1b}[0md\na\u{1b}[35mcb\u{1b}[0md\n"
+        );
+        assert_eq!(
+            render_alignment("寿司", "司", false),
+            "\u{1b}[31m寿\u{1b}[0m司\n\u{1b}[31m-\u{1b}[0m司\n"
+        );
+        assert_eq!(render_alignment("", "", false), "\n\n");
+    }
+}
@@ -0,0 +1,126 @@
+use super::{Distance, DFA};
+
+/// Cursor feeding bytes to a [DFA](./struct.DFA.html) one at a time.
+///
+/// A walker is a copyable pair of the automaton and its current state:
+/// it can be forked by copying it, e.g. to explore several branches of a
+/// trie from a common prefix.
+///
+/// ```rust
+/// # use levenshtein_automata::{Distance, LevenshteinAutomatonBuilder};
+/// let dfa = LevenshteinAutomatonBuilder::new(1, false).build_dfa("cat");
+/// let mut walker = dfa.walker();
+/// walker.step_bytes(b"ca");
+/// let mut fork = walker;
+/// walker.step(b't');
+/// fork.step_bytes(b"rt");
+/// assert_eq!(walker.distance(), Distance::Exact(0));
+/// assert_eq!(fork.distance(), Distance::Exact(1));
+/// fork.step(b's');
+/// assert!(!fork.can_match());
+/// ```
+#[derive(Clone, Copy)]
+pub struct DfaWalker<'a> {
+    dfa: &'a DFA,
+    state: u32,
+}
+
+impl DFA {
+    /// Returns a walker positioned on the initial state.
+    pub fn walker(&self) -> DfaWalker<'_> {
+        DfaWalker {
+            dfa: self,
+            state: self.initial_state(),
+        }
+    }
+}
+
+impl<'a> DfaWalker<'a> {
+    /// Returns the automaton being walked.
+    pub fn dfa(&self) -> &'a DFA {
+        self.dfa
+    }
+
+    /// Returns the current state.
+    pub fn state(&self) -> u32 {
+        self.state
+    }
+
+    /// Consumes a byte, and returns the new state.
+    pub fn step(&mut self, b: u8) -> u32 {
+        self.state = self.dfa.transition(self.state, b);
+        self.state
+    }
+
+    /// Consumes a sequence of bytes, and returns the new state.
+    ///
+    /// Stops early once no accepting state can be reached.
+    pub fn step_bytes<B: AsRef<[u8]>>(&mut self, bytes: B) -> u32 {
+        for &b in bytes.as_ref() {
+            if self.dfa.is_sink(self.state) {
+                break;
+            }
+            self.step(b);
+        }
+        self.state
+    }
+
+    /// Returns the distance associated to the current state.
+    pub fn distance(&self) -> Distance {
+        self.dfa.distance(self.state)
+    }
+
+    /// Returns true if the bytes consumed so far are accepted.
+    pub fn is_match(&self) -> bool {
+        matches!(self.distance(), Distance::Exact(_))
+    }
+
+    /// Returns true if some continuation of the bytes consumed
+    /// so far is accepted. See [DFA::can_match](./struct.DFA.html#method.can_match).
+    pub fn can_match(&self) -> bool {
+        self.dfa.can_match(self.state)
+    }
+
+    /// Moves the walker back to the initial state.
+    pub fn reset(&mut self) {
+        self.state = self.dfa.initial_state();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Distance, LevenshteinAutomatonBuilder, Utf8DFABuilder, SINK_STATE};
+
+    #[test]
+    fn test_walker_matches_eval() {
+        let dfa = LevenshteinAutomatonBuilder::new(2, true).build_dfa("寿司屋");
+        for text in &["", "寿司", "寿司屋", "司寿屋", "abc", "寿司屋さん"] {
+            let mut walker = dfa.walker();
+            for (i, &b) in text.as_bytes().iter().enumerate() {
+                assert_eq!(walker.distance(), dfa.eval(&text.as_bytes()[..i]));
+                walker.step(b);
+            }
+            assert_eq!(walker.distance(), dfa.eval(text));
+            assert_eq!(walker.is_match(), dfa.eval(text).to_u8() <= 2);
+        }
+        let mut walker = dfa.walker();
+        assert_eq!(walker.step_bytes("xyzw"), SINK_STATE);
+        assert!(!walker.can_match());
+        walker.reset();
+        assert_eq!(walker.state(), dfa.initial_state());
+    }
+
+    #[test]
+    fn test_walker_through_accepting_state_zero() {
+        // Parity of the number of chars, whose state `0` accepts.
+        let mut dfa_builder = Utf8DFABuilder::with_max_num_states(2);
+        dfa_builder.add_state(0, Distance::Exact(0), 1);
+        dfa_builder.add_state(1, Distance::AtLeast(1), 0);
+        dfa_builder.set_initial_state(1);
+        let dfa = dfa_builder.build();
+        let mut walker = dfa.walker();
+        walker.step_bytes("aab");
+        assert_eq!(walker.distance(), dfa.eval("aab"));
+        assert_eq!(walker.distance(), Distance::Exact(0));
+    }
+}
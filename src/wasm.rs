@@ -0,0 +1,131 @@
+use super::{Distance, LevenshteinAutomatonBuilder, DFA};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// JavaScript wrapper of a [LevenshteinAutomatonBuilder], exported as
+/// `LevenshteinAutomatonBuilder`.
+///
+/// ```js
+/// const builder = new LevenshteinAutomatonBuilder(1, true);
+/// const dfa = builder.buildPrefixDfa("autom");
+/// dfa.eval("automaton"); // 0
+/// dfa.eval("banana"); // undefined
+/// ```
+#[wasm_bindgen(js_name = LevenshteinAutomatonBuilder)]
+pub struct WasmAutomatonBuilder {
+    builder: LevenshteinAutomatonBuilder,
+}
+
+#[wasm_bindgen(js_class = LevenshteinAutomatonBuilder)]
+impl WasmAutomatonBuilder {
+    /// Creates a builder, see [LevenshteinAutomatonBuilder::new].
+    #[wasm_bindgen(constructor)]
+    pub fn new(max_distance: u8, transposition_cost_one: bool) -> WasmAutomatonBuilder {
+        WasmAutomatonBuilder {
+            builder: LevenshteinAutomatonBuilder::new(max_distance, transposition_cost_one),
+        }
+    }
+
+    /// Builds the automaton of a query.
+    #[wasm_bindgen(js_name = buildDfa)]
+    pub fn build_dfa(&self, query: &str) -> WasmDfa {
+        WasmDfa {
+            dfa: self.builder.build_dfa(query),
+        }
+    }
+
+    /// Builds the prefix automaton of a query, e.g. for autocompletion.
+    #[wasm_bindgen(js_name = buildPrefixDfa)]
+    pub fn build_prefix_dfa(&self, query: &str) -> WasmDfa {
+        WasmDfa {
+            dfa: self.builder.build_prefix_dfa(query),
+        }
+    }
+}
+
+/// JavaScript wrapper of a [DFA], exported as `Dfa`.
+///
+/// Distances are returned as numbers, or as `undefined` for the texts beyond
+/// the maximum distance. States are stepped with whole strings, which
+/// are encoded in utf-8.
+///
+/// ```js
+/// const dfa = new LevenshteinAutomatonBuilder(1, false).buildDfa("cat");
+/// let state = dfa.initialState();
+/// state = dfa.step(state, "ca");
+/// state = dfa.step(state, "rt");
+/// dfa.distance(state); // 1
+/// dfa.filter(["cat", "dog", "cut"]); // Uint32Array [0, 2]
+/// ```
+#[wasm_bindgen(js_name = Dfa)]
+pub struct WasmDfa {
+    dfa: DFA,
+}
+
+fn exact(distance: Distance) -> Option<u8> {
+    match distance {
+        Distance::Exact(d) => Some(d),
+        Distance::AtLeast(_) => None,
+    }
+}
+
+#[wasm_bindgen(js_class = Dfa)]
+impl WasmDfa {
+    /// Returns the distance of a text.
+    pub fn eval(&self, text: &str) -> Option<u8> {
+        exact(self.dfa.eval(text))
+    }
+
+    /// Returns the indices of the candidates within the maximum distance.
+    pub fn filter(&self, candidates: Vec<String>) -> Vec<u32> {
+        candidates
+            .iter()
+            .enumerate()
+            .filter(|(_, candidate)| matches!(self.dfa.eval(candidate), Distance::Exact(_)))
+            .map(|(id, _)| id as u32)
+            .collect()
+    }
+
+    /// Returns the initial state.
+    #[wasm_bindgen(js_name = initialState)]
+    pub fn initial_state(&self) -> u32 {
+        self.dfa.initial_state()
+    }
+
+    /// Returns the state reached from `state` after reading `text`.
+    pub fn step(&self, state: u32, text: &str) -> u32 {
+        text.bytes()
+            .fold(state, |state, b| self.dfa.transition(state, b))
+    }
+
+    /// Returns the distance of the texts leading to `state`.
+    pub fn distance(&self, state: u32) -> Option<u8> {
+        exact(self.dfa.distance(state))
+    }
+
+    /// Returns false if no text within the maximum distance can be reached
+    /// from `state`.
+    #[wasm_bindgen(js_name = canMatch)]
+    pub fn can_match(&self, state: u32) -> bool {
+        self.dfa.can_match(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WasmAutomatonBuilder;
+
+    #[test]
+    fn test_wasm_wrappers() {
+        let builder = WasmAutomatonBuilder::new(1, false);
+        let dfa = builder.build_dfa("cat");
+        assert_eq!(dfa.eval("cart"), Some(1));
+        assert_eq!(dfa.eval("dog"), None);
+        let state = dfa.step(dfa.initial_state(), "ca");
+        assert!(dfa.can_match(state));
+        assert_eq!(dfa.distance(dfa.step(state, "rt")), Some(1));
+        let candidates = vec!["cat".to_string(), "dog".to_string(), "cut".to_string()];
+        assert_eq!(dfa.filter(candidates), vec![0, 2]);
+        let dfa = builder.build_prefix_dfa("寿司");
+        assert_eq!(dfa.eval("寿司屋"), Some(0));
+    }
+}
@@ -0,0 +1,371 @@
+use super::{CharAutomaton, Distance, EditOp, DFA};
+use std::collections::HashMap;
+
+/// Costs of the edit operations of a weighted Levenshtein automaton.
+///
+/// `cost(op, a, b)` is called with:
+/// - for a [EditOp::Substitution](./enum.EditOp.html#variant.Substitution),
+///   the char `a` of the query replaced by the char `b` of the text,
+/// - for an [EditOp::Insertion](./enum.EditOp.html#variant.Insertion) or an
+///   [EditOp::Deletion](./enum.EditOp.html#variant.Deletion), the char
+///   inserted in the text or deleted from the query, twice,
+/// - for an [EditOp::Transposition](./enum.EditOp.html#variant.Transposition),
+///   the two contiguous chars `a` and `b` of the query, which appear as `b`
+///   then `a` in the text.
+///
+/// Matches never cost anything. An operation can be disabled by giving
+/// it a cost greater than the maximum distance.
+///
+/// Closures implement `EditCosts`.
+pub trait EditCosts {
+    /// Returns the cost of an edit operation.
+    fn cost(&self, op: EditOp, a: char, b: char) -> u8;
+
+    /// Returns the chars whose costs may differ from those of the chars
+    /// absent from the query.
+    ///
+    /// The automaton only distinguishes the chars of the query and of this
+    /// alphabet: all of the other chars are given the costs of one of them.
+    /// Defaults to an empty alphabet.
+    fn alphabet(&self) -> Vec<char> {
+        Vec::new()
+    }
+}
+
+impl<F: Fn(EditOp, char, char) -> u8> EditCosts for F {
+    fn cost(&self, op: EditOp, a: char, b: char) -> u8 {
+        self(op, a, b)
+    }
+}
+
+/// Builder of Levenshtein automata where edits have custom costs.
+///
+/// The [Distance](./enum.Distance.html) of the automata is the total cost
+/// of the cheapest alignment, transpositions being handled as in
+/// the *optimal string alignment* distance.
+///
+/// Unlike the [LevenshteinAutomatonBuilder](./struct.LevenshteinAutomatonBuilder.html),
+/// there are no precomputed tables: the states are the columns of the
+/// dynamic programming table, built for each query.
+///
+/// ```rust
+/// # use levenshtein_automata::{Distance, EditOp, WeightedAutomatonBuilder};
+/// let is_vowel = |c: char| "aeiou".contains(c);
+/// let costs = |op: EditOp, a: char, b: char| match op {
+///     EditOp::Substitution if is_vowel(a) && is_vowel(b) => 1,
+///     _ => 2,
+/// };
+/// let builder = WeightedAutomatonBuilder::new(2, costs).with_alphabet("aeiou".chars());
+/// let dfa = builder.build_dfa("color");
+/// assert_eq!(dfa.eval("colour"), Distance::Exact(2));
+/// assert_eq!(dfa.eval("culor"), Distance::Exact(1));
+/// assert_eq!(dfa.eval("calar"), Distance::Exact(2));
+/// assert_eq!(dfa.eval("cotor"), Distance::Exact(2));
+/// assert_eq!(dfa.eval("clor"), Distance::Exact(2));
+/// assert_eq!(dfa.eval("clr"), Distance::AtLeast(3));
+/// ```
+pub struct WeightedAutomatonBuilder<C> {
+    max_distance: u8,
+    costs: C,
+    alphabet: Vec<char>,
+}
+
+impl<C: EditCosts> WeightedAutomatonBuilder<C> {
+    /// Creates a builder of automata accepting the texts whose total edit cost
+    /// is at most `max_distance`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_distance` is `255`.
+    pub fn new(max_distance: u8, costs: C) -> WeightedAutomatonBuilder<C> {
+        assert!(
+            max_distance < u8::MAX,
+            "The maximum distance should be lower than 255"
+        );
+        let alphabet = costs.alphabet();
+        WeightedAutomatonBuilder {
+            max_distance,
+            costs,
+            alphabet,
+        }
+    }
+
+    /// Adds some chars to the [alphabet](./trait.EditCosts.html#method.alphabet)
+    /// of the costs.
+    pub fn with_alphabet<I: IntoIterator<Item = char>>(
+        mut self,
+        chars: I,
+    ) -> WeightedAutomatonBuilder<C> {
+        self.alphabet.extend(chars);
+        self
+    }
+
+    /// Builds the automaton computing the weighted distance to `query`.
+    pub fn build_dfa(&self, query: &str) -> DFA {
+        let query: Vec<char> = query.chars().collect();
+        let mut alphabet = query.clone();
+        alphabet.extend(self.alphabet.iter().cloned());
+        alphabet.sort_unstable();
+        alphabet.dedup();
+        let other_char = other_char(&alphabet);
+        let columns = Columns {
+            query: &query,
+            costs: &self.costs,
+            cap: self.max_distance + 1,
+        };
+        let initial = columns.initial_state();
+        // The state `0` is a sink, only kept so that the compiled `DFA`
+        // follows the convention of `SINK_STATE`.
+        let mut automaton = WeightedAutomaton {
+            distances: vec![Distance::AtLeast(columns.cap)],
+            transitions: vec![Vec::new()],
+            default_transitions: vec![0],
+        };
+        let mut ids: HashMap<WeightedState, u32> = HashMap::new();
+        let mut states: Vec<WeightedState> = vec![initial.clone()];
+        ids.insert(initial, 1);
+        let mut i = 0;
+        while i < states.len() {
+            let state = states[i].clone();
+            let mut state_id = |next: WeightedState| -> u32 {
+                if next
+                    .column
+                    .iter()
+                    .chain(&next.previous_column)
+                    .all(|&d| d >= columns.cap)
+                {
+                    return 0;
+                }
+                let next_id = ids.len() as u32 + 1;
+                *ids.entry(next).or_insert_with_key(|next| {
+                    states.push(next.clone());
+                    next_id
+                })
+            };
+            let transitions: Vec<(char, u32)> = alphabet
+                .iter()
+                .map(|&chr| (chr, state_id(columns.next_state(&state, chr, true))))
+                .collect();
+            let default_transition = state_id(columns.next_state(&state, other_char, false));
+            let d = state.column[query.len()];
+            automaton.distances.push(if d < columns.cap {
+                Distance::Exact(d)
+            } else {
+                Distance::AtLeast(columns.cap)
+            });
+            automaton.transitions.push(transitions);
+            automaton.default_transitions.push(default_transition);
+            i += 1;
+        }
+        DFA::from_char_automaton(&automaton)
+    }
+}
+
+// Returns a char absent from the alphabet, standing for all of the other chars.
+fn other_char(alphabet: &[char]) -> char {
+    (0xF_0000..=0x10_FFFD)
+        .filter_map(std::char::from_u32)
+        .find(|chr| alphabet.binary_search(chr).is_err())
+        .unwrap()
+}
+
+// State of the weighted automaton: the last column of the dynamic programming
+// table, where `column[i]` is the smallest cost of turning `query[..i]`
+// into the text read so far, capped at `max_distance + 1`.
+//
+// The previous column and char are needed for transpositions. The previous
+// char is only kept if it is distinguished by the automaton.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+struct WeightedState {
+    column: Vec<u8>,
+    previous_column: Vec<u8>,
+    previous_char: Option<char>,
+}
+
+struct Columns<'a, C> {
+    query: &'a [char],
+    costs: &'a C,
+    cap: u8,
+}
+
+impl<'a, C: EditCosts> Columns<'a, C> {
+    fn cost(&self, op: EditOp, a: char, b: char) -> u8 {
+        self.costs.cost(op, a, b).min(self.cap)
+    }
+
+    fn initial_state(&self) -> WeightedState {
+        let mut column = vec![0u8; self.query.len() + 1];
+        for i in 1..=self.query.len() {
+            let deletion = self.cost(EditOp::Deletion, self.query[i - 1], self.query[i - 1]);
+            column[i] = column[i - 1].saturating_add(deletion).min(self.cap);
+        }
+        WeightedState {
+            column,
+            previous_column: Vec::new(),
+            previous_char: None,
+        }
+    }
+
+    fn next_state(&self, state: &WeightedState, chr: char, distinguished: bool) -> WeightedState {
+        let query = self.query;
+        let mut column = vec![0u8; query.len() + 1];
+        column[0] = state.column[0]
+            .saturating_add(self.cost(EditOp::Insertion, chr, chr))
+            .min(self.cap);
+        for i in 1..=query.len() {
+            let substitution = if query[i - 1] == chr {
+                0
+            } else {
+                self.cost(EditOp::Substitution, query[i - 1], chr)
+            };
+            let mut d = state.column[i - 1]
+                .saturating_add(substitution)
+                .min(state.column[i].saturating_add(self.cost(EditOp::Insertion, chr, chr)))
+                .min(column[i - 1].saturating_add(self.cost(
+                    EditOp::Deletion,
+                    query[i - 1],
+                    query[i - 1],
+                )));
+            if i >= 2
+                && !state.previous_column.is_empty()
+                && query[i - 1] != query[i - 2]
+                && state.previous_char == Some(query[i - 1])
+                && chr == query[i - 2]
+            {
+                let transposition = self.cost(EditOp::Transposition, query[i - 2], query[i - 1]);
+                d = d.min(state.previous_column[i - 2].saturating_add(transposition));
+            }
+            column[i] = d.min(self.cap);
+        }
+        WeightedState {
+            previous_column: state.column.clone(),
+            previous_char: if distinguished { Some(chr) } else { None },
+            column,
+        }
+    }
+}
+
+// Char-level automaton built by `WeightedAutomatonBuilder::build_dfa`.
+struct WeightedAutomaton {
+    distances: Vec<Distance>,
+    transitions: Vec<Vec<(char, u32)>>,
+    default_transitions: Vec<u32>,
+}
+
+impl CharAutomaton for WeightedAutomaton {
+    fn num_states(&self) -> u32 {
+        self.distances.len() as u32
+    }
+
+    fn initial_state(&self) -> u32 {
+        1
+    }
+
+    fn distance(&self, state: u32) -> Distance {
+        self.distances[state as usize]
+    }
+
+    fn default_transition(&self, state: u32) -> u32 {
+        self.default_transitions[state as usize]
+    }
+
+    fn transitions(&self, state: u32) -> Vec<(char, u32)> {
+        self.transitions[state as usize].clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EditCosts, WeightedAutomatonBuilder};
+    use crate::{EditOp, ExhaustiveVerifier, LevenshteinAutomatonBuilder};
+
+    // Weighted optimal string alignment distance.
+    fn weighted_distance<C: EditCosts>(costs: &C, query: &str, text: &str) -> usize {
+        let query: Vec<char> = query.chars().collect();
+        let text: Vec<char> = text.chars().collect();
+        let cost = |op, a, b| costs.cost(op, a, b) as usize;
+        let mut table = vec![vec![0usize; text.len() + 1]; query.len() + 1];
+        for i in 0..=query.len() {
+            for j in 0..=text.len() {
+                table[i][j] = match (i, j) {
+                    (0, 0) => 0,
+                    (0, _) => table[0][j - 1] + cost(EditOp::Insertion, text[j - 1], text[j - 1]),
+                    (_, 0) => table[i - 1][0] + cost(EditOp::Deletion, query[i - 1], query[i - 1]),
+                    _ => {
+                        let substitution = if query[i - 1] == text[j - 1] {
+                            0
+                        } else {
+                            cost(EditOp::Substitution, query[i - 1], text[j - 1])
+                        };
+                        let mut d = (table[i - 1][j - 1] + substitution)
+                            .min(
+                                table[i][j - 1] + cost(EditOp::Insertion, text[j - 1], text[j - 1]),
+                            )
+                            .min(
+                                table[i - 1][j]
+                                    + cost(EditOp::Deletion, query[i - 1], query[i - 1]),
+                            );
+                        if i >= 2
+                            && j >= 2
+                            && query[i - 1] != query[i - 2]
+                            && query[i - 1] == text[j - 2]
+                            && query[i - 2] == text[j - 1]
+                        {
+                            d = d.min(
+                                table[i - 2][j - 2]
+                                    + cost(EditOp::Transposition, query[i - 2], query[i - 1]),
+                            );
+                        }
+                        d
+                    }
+                };
+            }
+        }
+        table[query.len()][text.len()]
+    }
+
+    #[test]
+    fn test_weighted_dfa() {
+        // 'x' and 'y' do not appear in the queries, and only 'y' costs the same as the other chars.
+        let costs = |op: EditOp, a: char, b: char| match (op, a, b) {
+            (EditOp::Substitution, 'a', 'b') | (EditOp::Substitution, 'b', 'a') => 1,
+            (EditOp::Substitution, _, 'x') => 1,
+            (EditOp::Insertion, 'x', _) => 3,
+            (EditOp::Deletion, 'é', _) => 1,
+            (EditOp::Transposition, _, _) => 1,
+            _ => 2,
+        };
+        for max_distance in 0..=4u8 {
+            let builder =
+                WeightedAutomatonBuilder::new(max_distance, costs).with_alphabet(vec!['b', 'x']);
+            let verifier = ExhaustiveVerifier::new("abéxy", 4, max_distance, false);
+            for query in &["", "a", "ab", "éa", "abé", "baab"] {
+                let dfa = builder.build_dfa(query);
+                let result = verifier.verify_dfa_with(query, &dfa, |query, text| {
+                    weighted_distance(&costs, query, text)
+                });
+                assert_eq!(result, Ok(()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_unit_costs_match_levenshtein() {
+        for &transposition_cost_one in &[false, true] {
+            let transposition_cost = if transposition_cost_one { 1 } else { 3 };
+            let costs = move |op: EditOp, _: char, _: char| match op {
+                EditOp::Transposition => transposition_cost,
+                _ => 1,
+            };
+            let builder = WeightedAutomatonBuilder::new(2, costs);
+            let levenshtein_builder = LevenshteinAutomatonBuilder::new(2, transposition_cost_one);
+            for query in &["", "abc", "寿司", "abcabc"] {
+                let dfa = builder.build_dfa(query);
+                let expected = levenshtein_builder.build_dfa(query);
+                for text in &["", "a", "acb", "bac", "abcabc", "寿a司", "司寿", "xyz"] {
+                    assert_eq!(dfa.eval(text), expected.eval(text));
+                }
+            }
+        }
+    }
+}